@@ -98,6 +98,46 @@ impl RunStorage {
         self.base_path.join("agent_chat.json")
     }
 
+    /// Path to the workflow's suspended agent-run snapshot. Workflow-level
+    /// rather than per-execution: a resumed run starts a fresh execution
+    /// directory, and at most one suspended run exists per workflow.
+    pub fn suspended_run_path(&self) -> PathBuf {
+        self.base_path.join("suspended_run.json")
+    }
+
+    /// Persist a suspended agent-run snapshot, replacing any previous one.
+    /// Written crash-atomically. No-op when persistence is disabled.
+    pub fn write_suspended_run(&self, snapshot: &impl Serialize) -> Result<()> {
+        if !self.persistent {
+            return Ok(());
+        }
+        write_json_pretty(&self.suspended_run_path(), snapshot)
+    }
+
+    /// Load the suspended agent-run snapshot, if one exists.
+    pub fn load_suspended_run<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        let path = self.suspended_run_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let snapshot = serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(snapshot))
+    }
+
+    /// Delete the suspended agent-run snapshot. Called once a resume has
+    /// taken ownership of it so the same run cannot be resumed twice.
+    pub fn clear_suspended_run(&self) -> Result<()> {
+        let path = self.suspended_run_path();
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to remove {}", path.display())),
+        }
+    }
+
     /// Path to the `artifacts/` directory for the current execution.
     ///
     /// Returns `None` when `begin_execution()` has not yet been called, or
@@ -114,6 +154,29 @@ impl RunStorage {
         Some(self.base_path.join(exec_dir).join("artifacts"))
     }
 
+    /// Path to `cassettes/<name>.jsonl` for the current execution, where
+    /// recorded LLM traffic is kept (one cassette per backend, e.g.
+    /// `agent`, `vision`).
+    ///
+    /// `None` under the same conditions as [`Self::execution_artifacts_dir`]:
+    /// a run recorded with persistence disabled leaves no cassette.
+    pub fn cassette_path(&self, name: &str) -> Option<PathBuf> {
+        let exec_dir = self.execution_dir.as_ref()?;
+        if !self.persistent {
+            return None;
+        }
+        Some(self.cassette_path_in(exec_dir, name))
+    }
+
+    /// Path to cassette `name` of an earlier execution of this workflow,
+    /// identified by its execution directory name. Used to replay a run.
+    pub fn cassette_path_in(&self, execution_dir: &str, name: &str) -> PathBuf {
+        self.base_path
+            .join(execution_dir)
+            .join("cassettes")
+            .join(format!("{name}.jsonl"))
+    }
+
     /// Append a serializable agent event to the execution-level events.jsonl.
     ///
     /// No-op when persistence is disabled — the agent run still requires
@@ -180,6 +243,30 @@ impl RunStorage {
         Ok(dirname)
     }
 
+    /// Storage for a sub-agent spawned by the current execution. Its
+    /// events land in `<execution>/sub_agents/<child_run_id>/events.jsonl`
+    /// so a child run stays nested under the run that delegated to it.
+    ///
+    /// Returns `None` before `begin_execution()`. The child inherits the
+    /// persistence flag; its directory is created here when persistent.
+    pub fn sub_agent_storage(&self, child_run_id: Uuid) -> Result<Option<RunStorage>> {
+        let Some(execution_dir) = self.execution_dir.as_ref() else {
+            return Ok(None);
+        };
+        let base_path = self.base_path.join(execution_dir).join("sub_agents");
+        let child_dir = child_run_id.to_string();
+        if self.persistent {
+            std::fs::create_dir_all(base_path.join(&child_dir))
+                .context("Failed to create sub-agent directory")?;
+        }
+        Ok(Some(RunStorage {
+            base_path,
+            project_skills_path: self.project_skills_path.clone(),
+            execution_dir: Some(child_dir),
+            persistent: self.persistent,
+        }))
+    }
+
     pub fn now_millis() -> u64 {
         now_millis()
     }
@@ -226,6 +313,25 @@ impl RunStorage {
         self.skill_runs_dir(skill_id).join(run_id.to_string())
     }
 
+    /// Path for cassette `name` of a skill run, inside its events
+    /// directory so it is pruned with the run. `None` when persistence
+    /// is disabled.
+    pub fn skill_run_cassette_path(
+        &self,
+        skill_id: &str,
+        run_id: Uuid,
+        name: &str,
+    ) -> Option<PathBuf> {
+        if !self.persistent {
+            return None;
+        }
+        Some(
+            self.skill_run_events_dir(skill_id, run_id)
+                .join("cassettes")
+                .join(format!("{name}.jsonl")),
+        )
+    }
+
     /// Maximum number of historical run records kept per skill (D27).
     pub const SKILL_RUN_HISTORY_LIMIT: usize = 20;
 
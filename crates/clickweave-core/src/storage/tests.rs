@@ -255,6 +255,81 @@ fn set_persistent_true_restores_disk_writes() {
     let _ = std::fs::remove_dir_all(&base);
 }
 
+// ── suspended agent run ─────────────────────────────────────
+
+#[test]
+fn suspended_run_round_trips_and_clears() {
+    let (storage, dir) = temp_storage();
+
+    assert!(
+        storage
+            .load_suspended_run::<Value>()
+            .expect("load")
+            .is_none()
+    );
+    let snapshot = serde_json::json!({"run_id": "r1", "steps": [1, 2]});
+    storage.write_suspended_run(&snapshot).expect("write");
+    assert_eq!(
+        storage.load_suspended_run::<Value>().expect("load"),
+        Some(snapshot)
+    );
+
+    storage.clear_suspended_run().expect("clear");
+    assert!(!storage.suspended_run_path().exists());
+    storage
+        .clear_suspended_run()
+        .expect("clearing twice is a no-op");
+
+    cleanup(&dir);
+}
+
+#[test]
+fn suspended_run_is_not_written_when_persistence_disabled() {
+    let (mut storage, dir) = temp_storage();
+    storage.set_persistent(false);
+
+    storage
+        .write_suspended_run(&serde_json::json!({"k": "v"}))
+        .expect("write");
+    assert!(!storage.suspended_run_path().exists());
+
+    cleanup(&dir);
+}
+
+// ── sub-agent storage ───────────────────────────────────────
+
+#[test]
+fn sub_agent_events_nest_under_the_parent_execution() {
+    let (mut storage, dir) = temp_storage();
+    let child_run_id = Uuid::new_v4();
+    assert!(
+        storage
+            .sub_agent_storage(child_run_id)
+            .expect("no execution yet")
+            .is_none()
+    );
+
+    let exec_dir = storage.begin_execution().expect("begin");
+    let child = storage
+        .sub_agent_storage(child_run_id)
+        .expect("create")
+        .expect("execution started");
+    child
+        .append_agent_event(&serde_json::json!({"type": "goal_complete"}))
+        .expect("append");
+
+    let events = storage
+        .base_path()
+        .join(&exec_dir)
+        .join("sub_agents")
+        .join(child_run_id.to_string())
+        .join("events.jsonl");
+    let text = std::fs::read_to_string(events).expect("child events written");
+    assert!(text.contains("goal_complete"));
+
+    cleanup(&dir);
+}
+
 // ── variant index isolation ─────────────────────────────────
 
 #[test]
@@ -445,3 +520,48 @@ fn write_json_atomic_preserves_existing_file_when_write_fails() {
 
     let _ = std::fs::remove_dir_all(&dir);
 }
+
+#[test]
+fn cassette_path_lives_in_the_execution_dir_and_respects_persistence() {
+    let (mut storage, dir) = temp_storage();
+    assert!(storage.cassette_path("agent").is_none(), "no execution yet");
+
+    let exec_dir = storage.begin_execution().expect("begin execution");
+    assert_eq!(
+        storage.cassette_path("agent"),
+        Some(
+            storage
+                .base_path
+                .join(&exec_dir)
+                .join("cassettes")
+                .join("agent.jsonl")
+        )
+    );
+
+    storage.set_persistent(false);
+    assert!(storage.cassette_path("agent").is_none());
+    cleanup(&dir);
+}
+
+#[test]
+fn skill_run_cassette_path_lives_in_the_run_events_dir() {
+    let (mut storage, dir) = temp_storage();
+    let run_id = Uuid::new_v4();
+    assert_eq!(
+        storage.skill_run_cassette_path("login", run_id, "mcp"),
+        Some(
+            storage
+                .skill_run_events_dir("login", run_id)
+                .join("cassettes")
+                .join("mcp.jsonl")
+        )
+    );
+
+    storage.set_persistent(false);
+    assert!(
+        storage
+            .skill_run_cassette_path("login", run_id, "mcp")
+            .is_none()
+    );
+    cleanup(&dir);
+}
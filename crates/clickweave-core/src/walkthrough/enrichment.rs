@@ -174,17 +174,28 @@ pub fn attach_recording_frames(
 /// Build a VLM prompt for identifying a click/hover target on a screenshot.
 ///
 /// Returns the complete prompt string with context hints from accessibility data,
-/// OCR text, and app name when available.
+/// OCR text, and app name when available. `click_mark` is the number of the
+/// set-of-marks box drawn around the click; without one the screenshot is
+/// expected to carry a crosshair instead.
 pub fn build_vlm_click_prompt(
     ax_label: Option<(&str, Option<&str>)>,
     ocr_text: Option<&str>,
     app_name: Option<&str>,
+    click_mark: Option<u32>,
 ) -> String {
-    let mut prompt = String::from(
-        "This is a screenshot of an application window with a red \
-         crosshair marking where the user clicked. What UI element is at \
-         the crosshair?",
-    );
+    let mut prompt = match click_mark {
+        Some(number) => format!(
+            "This is a screenshot of an application window with a numbered \
+             box, mark {number}, around where the user clicked. What UI \
+             element is inside mark {number}? Name the element, not the \
+             mark."
+        ),
+        None => String::from(
+            "This is a screenshot of an application window with a red \
+             crosshair marking where the user clicked. What UI element is at \
+             the crosshair?",
+        ),
+    };
 
     let mut hints = Vec::new();
     if let Some(app) = app_name {
@@ -14,6 +14,7 @@ use crate::agent::types::ApprovalRequest;
 /// executing a tool call. The runner sends an `ApprovalRequest` paired
 /// with a oneshot reply channel; the UI replies exactly once with
 /// `true` (approve) or `false` (reject).
+#[derive(Clone)]
 pub struct ApprovalGate {
     pub request_tx: mpsc::Sender<(ApprovalRequest, oneshot::Sender<bool>)>,
 }
@@ -0,0 +1,127 @@
+//! `ask_user` pseudo-tool: lets the LLM put a clarifying question to the
+//! operator mid-run instead of guessing or replanning blind.
+//!
+//! The runner sends the question out as a [`RunnerOutput::UserQuestion`]
+//! and waits on its oneshot reply, bounded by
+//! `AgentConfig::ask_user_timeout` and the run deadline, and cut short
+//! by a suspend or stop request. Whatever happens — an answer, a
+//! timeout, or nobody listening — the LLM gets a tool result back, so
+//! the run never stalls on an absent operator.
+//!
+//! [`RunnerOutput::UserQuestion`]: crate::agent::RunnerOutput::UserQuestion
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+pub(crate) const TOOL_NAME: &str = "ask_user";
+
+/// Default time to wait for an operator's answer before falling back.
+pub const DEFAULT_ASK_USER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// Upper bound on offered choices; longer lists are truncated so the
+/// prompt UI stays usable.
+const MAX_CHOICES: usize = 8;
+
+/// How an `ask_user` call was resolved. Recorded in the run trace via
+/// `AgentEvent::UserQuestionAnswered`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AskUserOutcome {
+    /// The operator replied.
+    Answered,
+    /// An operator was reachable but did not reply in time.
+    TimedOut,
+    /// Headless run, or the host dropped the question unanswered.
+    NoHumanAvailable,
+    /// The run was suspended or stopped while the question was open.
+    Interrupted,
+}
+
+/// Extract `(question, choices)` from `ask_user` arguments. `choices` is
+/// optional; blank entries are dropped and the list is capped at
+/// [`MAX_CHOICES`].
+pub(crate) fn parse_args(args: &Value) -> Result<(String, Vec<String>), String> {
+    let question = args
+        .get("question")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .ok_or_else(|| "ask_user requires a non-empty `question`".to_string())?
+        .to_string();
+    let choices = args
+        .get("choices")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .take(MAX_CHOICES)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok((question, choices))
+}
+
+/// Tool-result text handed back to the LLM for a resolved question.
+pub(crate) fn tool_result_body(outcome: AskUserOutcome, answer: Option<&str>) -> String {
+    match outcome {
+        AskUserOutcome::Answered => json!({
+            "outcome": outcome,
+            "answer": answer.unwrap_or_default(),
+        }),
+        AskUserOutcome::TimedOut | AskUserOutcome::NoHumanAvailable => json!({
+            "outcome": outcome,
+            "answer": Value::Null,
+            "note": "No human answered. Do not ask again — proceed with the most \
+                     reasonable assumption, or call agent_replan if the goal cannot \
+                     be completed without this information.",
+        }),
+        AskUserOutcome::Interrupted => json!({
+            "outcome": outcome,
+            "answer": Value::Null,
+            "note": "The run was paused before the operator answered. Ask again \
+                     if you still need this information.",
+        }),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_args_trims_and_caps_choices() {
+        let many: Vec<String> = (0..12).map(|i| format!("opt {i}")).collect();
+        let (question, choices) = parse_args(&json!({
+            "question": "  Which account?  ",
+            "choices": [" ", "work", 3, many[0], many[1], many[2], many[3], many[4], many[5], many[6], many[7]],
+        }))
+        .unwrap();
+        assert_eq!(question, "Which account?");
+        assert_eq!(choices.len(), MAX_CHOICES);
+        assert_eq!(choices[0], "work");
+    }
+
+    #[test]
+    fn parse_args_rejects_missing_question() {
+        assert!(parse_args(&json!({"question": "   "})).is_err());
+        assert!(parse_args(&json!({"choices": ["a"]})).is_err());
+    }
+
+    #[test]
+    fn fallback_body_carries_no_answer() {
+        let body: Value =
+            serde_json::from_str(&tool_result_body(AskUserOutcome::NoHumanAvailable, None))
+                .unwrap();
+        assert_eq!(body["outcome"], "no_human_available");
+        assert!(body["answer"].is_null());
+        let body: Value =
+            serde_json::from_str(&tool_result_body(AskUserOutcome::Answered, Some("work")))
+                .unwrap();
+        assert_eq!(body["answer"], "work");
+    }
+}
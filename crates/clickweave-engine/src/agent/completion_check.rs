@@ -14,6 +14,9 @@
 
 use std::path::Path;
 
+use clickweave_llm::{MarkCandidate, MarkRect, SetOfMarks};
+
+use crate::agent::world_model::ObservedElement;
 use crate::executor::screenshot::ScreenshotScope;
 
 /// The VLM verdict derived from a completion-check reply.
@@ -45,14 +48,58 @@ pub(crate) fn pick_completion_screenshot_scope(
 }
 
 /// Build the user-facing prompt text sent to the VLM alongside the screenshot.
-pub(crate) fn build_completion_prompt(goal: &str, summary: &str) -> String {
-    format!(
+///
+/// When the screenshot carries set-of-marks boxes, their legend is appended
+/// so the explanation can cite the element it is about by number.
+pub(crate) fn build_completion_prompt(goal: &str, summary: &str, marks: &SetOfMarks) -> String {
+    let mut prompt = format!(
         "The goal was: \"{}\".\n\
          The agent believes it is complete: \"{}\".\n\
          Does this screenshot confirm the goal was achieved? \
          Reply with YES or NO and a one-sentence explanation.",
         goal, summary,
-    )
+    );
+    if !marks.is_empty() {
+        prompt.push_str(
+            "\n\nNumbered boxes mark elements we already know about. If your \
+             explanation is about one of them, cite it as \"mark N\".\n",
+        );
+        prompt.push_str(&marks.legend());
+    }
+    prompt
+}
+
+/// Set-of-marks candidates for the completion screenshot.
+///
+/// Only OCR matches are marked: they are in screen points like the
+/// screenshot, while CDP rects are relative to the page viewport inside the
+/// browser window and AX elements carry no frame. `find_text` reports the
+/// centre of each match.
+pub(crate) fn completion_mark_candidates(elements: &[ObservedElement]) -> Vec<MarkCandidate> {
+    elements
+        .iter()
+        .filter_map(|element| match element {
+            ObservedElement::Ocr(m) => Some(MarkCandidate::ocr(
+                m.text.clone(),
+                MarkRect {
+                    x: f64::from(m.x) - f64::from(m.width) / 2.0,
+                    y: f64::from(m.y) - f64::from(m.height) / 2.0,
+                    width: f64::from(m.width),
+                    height: f64::from(m.height),
+                },
+            )),
+            ObservedElement::Cdp(_) | ObservedElement::Ax(_) => None,
+        })
+        .collect()
+}
+
+/// Append what a mark cited in the VLM reply stands for, so a disagreement
+/// names the element rather than just its number.
+pub(crate) fn annotate_cited_mark(reply: String, marks: &SetOfMarks) -> String {
+    match marks.resolve_reply(&reply) {
+        Some(mark) => format!("{reply} ({})", mark.describe()),
+        None => reply,
+    }
 }
 
 /// Write a completion-verification screenshot and metadata JSON to `artifacts_dir`.
@@ -197,10 +244,49 @@ mod tests {
 
     #[test]
     fn prompt_includes_goal_and_summary() {
-        let p = build_completion_prompt("Open the settings page", "I clicked gear icon");
+        let p = build_completion_prompt(
+            "Open the settings page",
+            "I clicked gear icon",
+            &SetOfMarks::default(),
+        );
         assert!(p.contains("Open the settings page"));
         assert!(p.contains("I clicked gear icon"));
         assert!(p.contains("YES or NO"));
+        assert!(!p.contains("mark N"), "no legend without marks");
+    }
+
+    #[test]
+    fn ocr_matches_become_marks_that_replies_can_cite() {
+        use crate::agent::world_model::OcrMatch;
+        use clickweave_llm::{Mark, MarkTarget};
+
+        let elements = [ObservedElement::Ocr(OcrMatch {
+            text: "Saved".into(),
+            x: 100,
+            y: 50,
+            width: 40,
+            height: 20,
+            confidence: 0.9,
+        })];
+        let candidates = completion_mark_candidates(&elements);
+        assert_eq!(candidates[0].rect.center(), (100.0, 50.0));
+
+        let marks = SetOfMarks {
+            marks: vec![Mark {
+                number: 1,
+                target: MarkTarget::Ocr {
+                    text: "Saved".into(),
+                },
+                rect: candidates[0].rect,
+            }],
+        };
+        let prompt = build_completion_prompt("Save the file", "Saved it", &marks);
+        assert!(prompt.ends_with("[1] text \"Saved\""));
+        assert_eq!(
+            annotate_cited_mark("NO, mark 1 is a stale toast.".into(), &marks),
+            "NO, mark 1 is a stale toast. ([1] text \"Saved\")"
+        );
+        assert_eq!(annotate_cited_mark("YES".into(), &marks), "YES");
     }
 
     #[test]
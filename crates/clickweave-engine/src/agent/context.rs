@@ -5,6 +5,8 @@
 //! - `messages[1]` (goal, with prior_turns + variant context inlined) — never compacted.
 //! - Last `recent_n` assistant/tool pairs — preserved verbatim.
 //! - Beyond `recent_n` — collapsed to a brief harness-authored line.
+//! - Optionally, those lines are folded into an LLM-written summary at
+//!   `messages[2]`, which is never compacted either.
 //! - Snapshot tool-result messages older than the current step are dropped.
 //! - Per-turn `<world_model>` / `<task_state>` user messages older than the
 //!   current step are collapsed; the latest user turn carries the live state.
@@ -20,12 +22,25 @@
 use clickweave_llm::{Content, Message, Role};
 use serde_json::Value;
 
-/// Rough token estimate: ~4 characters per token for English text.
-const CHARS_PER_TOKEN: usize = 4;
+/// Framing a chat template adds around every message (role markers,
+/// separators).
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Word-like runs up to this length usually map to a token or two; longer
+/// unbroken runs are ids, hashes or base64, which split much finer.
+const WORD_RUN_MAX: usize = 12;
 
 /// Prefix marking a tool-result body that has already been collapsed by
 /// [`collapse_superseded_snapshots`]. Used to make the pass idempotent.
 const SUPERSEDED_PREFIX: &str = "[superseded ";
+/// Prefix of the one-line stand-in for a pair collapsed out of the
+/// recent-N window.
+const COLLAPSED_PREFIX: &str = "[collapsed] ";
+/// Prefix of a body shortened by the token-budget pass.
+const BUDGET_COLLAPSED_PREFIX: &str = "[collapsed to fit budget] ";
+/// Prefix of the harness message at `messages[2]` carrying an
+/// LLM-written summary of collapsed steps; see [`fold_into_summary`].
+pub(crate) const SUMMARY_PREFIX: &str = "[summary of earlier steps]\n";
 const STALE_STATE_BLOCK_PLACEHOLDER: &str =
     "[stale state block omitted; latest <world_model>/<task_state> is in the current user turn]";
 
@@ -82,12 +97,67 @@ fn resolve_tool_name<'a>(messages: &'a [Message], msg: &'a Message) -> Option<&'
     None
 }
 
-/// Rough token estimate for a single string: ~4 characters per token.
+/// Token estimate for a single string, shaped after how BPE tokenizers
+/// split text: short words are a token or two (a single leading space
+/// rides along), long opaque runs and digits split every few characters,
+/// and punctuation, line breaks and non-ASCII characters cost extra.
 ///
 /// Shared by context compaction and the prior-turn log renderer so the
-/// heuristic stays consistent.
+/// heuristic stays consistent. Still an estimate: [`ContextFit`] scales it
+/// by what the model's tokenizer reports.
 pub(crate) fn estimate_tokens(text: &str) -> usize {
-    text.len().div_ceil(CHARS_PER_TOKEN)
+    let bytes = text.as_bytes();
+    let mut tokens = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let run = |pred: fn(u8) -> bool| bytes[i..].iter().take_while(|&&c| pred(c)).count();
+        if b.is_ascii_alphabetic() {
+            let len = run(|c| c.is_ascii_alphanumeric() || c == b'_');
+            tokens += if len <= WORD_RUN_MAX {
+                len.div_ceil(6)
+            } else {
+                len.div_ceil(3)
+            };
+            i += len;
+        } else if b.is_ascii_digit() {
+            let len = run(|c| c.is_ascii_digit());
+            tokens += len.div_ceil(3);
+            i += len;
+        } else if b.is_ascii_whitespace() {
+            let len = run(|c| c.is_ascii_whitespace());
+            if len > 1 || b != b' ' {
+                tokens += 1;
+            }
+            i += len;
+        } else if b.is_ascii() {
+            let len = run(|c| c.is_ascii_punctuation());
+            tokens += len.max(1).div_ceil(2);
+            i += len.max(1);
+        } else {
+            let ch = text[i..].chars().next().unwrap_or_default();
+            tokens += 1;
+            i += ch.len_utf8().max(1);
+        }
+    }
+    tokens
+}
+
+/// Estimated tokens of one message: content, tool calls and the template
+/// framing around it.
+fn message_tokens(m: &Message) -> usize {
+    let content = m.content_text().map_or(0, estimate_tokens);
+    let tool_calls = m.tool_calls.as_ref().map_or(0, |tcs| {
+        tcs.iter()
+            .map(|tc| {
+                // Arguments are punctuation-dense JSON: ~3 characters
+                // per token.
+                estimate_tokens(&tc.function.name)
+                    + json_value_len(&tc.function.arguments).div_ceil(3)
+            })
+            .sum()
+    });
+    content + tool_calls + MESSAGE_OVERHEAD_TOKENS
 }
 
 /// Estimate the total token count across a list of messages.
@@ -97,18 +167,7 @@ pub(crate) fn estimate_tokens(text: &str) -> usize {
 /// loop-detection / recovery code in `runner.rs`) can still reason about
 /// transcript token pressure.
 pub fn estimate_messages_tokens(messages: &[Message]) -> usize {
-    messages
-        .iter()
-        .map(|m| {
-            let content_len = m.content_text().map_or(0, |t| t.len());
-            let tool_calls_len = m.tool_calls.as_ref().map_or(0, |tcs| {
-                tcs.iter()
-                    .map(|tc| tc.function.name.len() + json_value_len(&tc.function.arguments))
-                    .sum()
-            });
-            (content_len + tool_calls_len).div_ceil(CHARS_PER_TOKEN)
-        })
-        .sum()
+    messages.iter().map(message_tokens).sum()
 }
 
 /// Approximate serialized length of a JSON `Value` without allocating the
@@ -197,25 +256,116 @@ pub struct CompactBudget {
 impl Default for CompactBudget {
     fn default() -> Self {
         Self {
-            // Keep message history below the smallest provider window we
-            // ship against (~40k) with room for tool schemas and the model
-            // response. Tool definitions are sent separately and are not
-            // included in this estimate.
+            // Used when the model's context window is unknown: keep
+            // message history below the smallest provider window we ship
+            // against (~40k) with room for tool schemas and the model
+            // response. A known window is fitted by [`ContextFit`].
             max_tokens: 32_000,
             recent_n: 6,
         }
     }
 }
 
+/// Smallest message budget a fit hands out. Below this even the system
+/// prompt and goal stop fitting, so compaction has nothing left to give.
+const MIN_FIT_TOKENS: usize = 1_024;
+
+/// Sizes [`CompactBudget::max_tokens`] from the model's context window.
+///
+/// The window is what remains of the detected context length after the
+/// tool schemas (sent with every request) and the reserve for the reply.
+/// Budgets are handed out in *estimated* tokens, so the fit keeps a scale
+/// of real tokens per estimated token, measured against the server's
+/// tokenizer or the prompt sizes it reports. After a length rejection the
+/// budget is capped below the transcript that was refused.
+#[derive(Debug, Clone)]
+pub struct ContextFit {
+    /// Real tokens available to messages; `None` when the context length
+    /// is unknown.
+    window: Option<usize>,
+    tool_tokens: usize,
+    /// Budget used while the window is unknown.
+    fallback: usize,
+    scale: f64,
+    cap: Option<usize>,
+}
+
+impl ContextFit {
+    /// A fit for a model of unknown context length: `fallback` estimated
+    /// tokens, until a length rejection says otherwise.
+    pub fn unknown(fallback: usize) -> Self {
+        Self {
+            window: None,
+            tool_tokens: 0,
+            fallback,
+            scale: 1.0,
+            cap: None,
+        }
+    }
+
+    /// A fit for `context_length` tokens, of which `tool_tokens` go to the
+    /// tool schemas and `response_reserve` stay free for the reply. The
+    /// reserve never takes more than a quarter of a small window.
+    pub fn new(context_length: usize, tool_tokens: usize, response_reserve: usize) -> Self {
+        let reserve = response_reserve.min(context_length / 4);
+        Self {
+            window: Some(context_length.saturating_sub(tool_tokens + reserve)),
+            tool_tokens,
+            fallback: 0,
+            scale: 1.0,
+            cap: None,
+        }
+    }
+
+    /// Message budget in estimated tokens.
+    pub fn budget_tokens(&self) -> usize {
+        let fitted = match self.window {
+            Some(window) => ((window as f64 / self.scale) as usize).max(MIN_FIT_TOKENS),
+            None => self.fallback,
+        };
+        self.cap.map_or(fitted, |cap| fitted.min(cap))
+    }
+
+    /// Learn the estimator's error from text the tokenizer counted:
+    /// `estimated` by [`estimate_tokens`], `actual` by the model. Small
+    /// samples are too noisy to learn from.
+    pub fn calibrate(&mut self, estimated: usize, actual: usize) {
+        if estimated >= 64 && actual > 0 {
+            self.scale = (actual as f64 / estimated as f64).clamp(0.5, 3.0);
+        }
+    }
+
+    /// Calibrate from the `prompt_tokens` a response reported for messages
+    /// estimated at `estimated_messages`. Only meaningful with a known
+    /// window, where the tool-schema share can be taken out.
+    pub fn observe_prompt(&mut self, estimated_messages: usize, prompt_tokens: usize) {
+        if self.window.is_some() {
+            self.calibrate(
+                estimated_messages,
+                prompt_tokens.saturating_sub(self.tool_tokens),
+            );
+        }
+    }
+
+    /// The endpoint refused messages estimated at `estimated` tokens as
+    /// too long; cap every later budget a quarter below that.
+    pub fn shrink_below(&mut self, estimated: usize) {
+        let cap = (estimated * 3 / 4).max(MIN_FIT_TOKENS);
+        self.cap = Some(self.cap.map_or(cap, |old| old.min(cap)));
+    }
+}
+
 /// Compact a chat-history vector under the state-spine rules.
 ///
 /// Invariants:
-/// - `messages[0]` (system) and `messages[1]` (goal) are never modified.
+/// - `messages[0]` (system) and `messages[1]` (goal) are never modified,
+///   nor is a summary of earlier steps at `messages[2]`.
 /// - The last `budget.recent_n` assistant/tool pairs are preserved verbatim,
 ///   except that snapshot-family tool-result bodies older than the current
 ///   step are replaced by a short placeholder (body dropped).
 /// - All pairs older than the recent-N window are collapsed to a single
-///   brief assistant-authored summary line.
+///   brief assistant-authored summary line; lines collapsed by an earlier
+///   pass are kept as they are.
 /// - If the total token estimate still exceeds `budget.max_tokens`, the
 ///   largest surviving body is truncated in-place until the estimate fits
 ///   or nothing is left to collapse.
@@ -227,10 +377,12 @@ pub fn compact(messages: Vec<Message>, budget: &CompactBudget) -> Vec<Message> {
     let mut out = Vec::with_capacity(messages.len());
     out.push(messages[0].clone());
     out.push(messages[1].clone());
+    let head = protected_len(&messages);
+    out.extend(messages[2..head].iter().cloned());
 
-    // Pair up assistant + tool-result messages after messages[1].
+    // Pair up assistant + tool-result messages after the protected head.
     // Each "pair" is one assistant message followed by its tool-result(s).
-    let tail = &messages[2..];
+    let tail = &messages[head..];
     let mut pairs = group_into_pairs(tail);
     fold_snapshot_family_in_pairs(&mut pairs);
 
@@ -253,6 +405,10 @@ pub fn compact(messages: Vec<Message>, budget: &CompactBudget) -> Vec<Message> {
                     out.push(m.clone());
                 }
             }
+        } else if is_collapsed_pair(pair) {
+            // Collapsed by an earlier pass; collapsing it again would
+            // lose the line's content.
+            out.push(pair[0].clone());
         } else {
             // Collapse: one brief summary line instead of the full pair.
             out.push(collapse_pair_to_brief(pair));
@@ -266,6 +422,79 @@ pub fn compact(messages: Vec<Message>, budget: &CompactBudget) -> Vec<Message> {
     enforce_token_budget(out, budget)
 }
 
+/// Messages at the front that compaction never touches: system, goal
+/// and the summary of earlier steps, when there is one.
+fn protected_len(messages: &[Message]) -> usize {
+    match messages.get(2) {
+        Some(m) if is_summary(m) => 3,
+        _ => messages.len().min(2),
+    }
+}
+
+fn is_summary(m: &Message) -> bool {
+    m.role == Role::Assistant
+        && m.content_text()
+            .is_some_and(|t| t.starts_with(SUMMARY_PREFIX))
+}
+
+/// A lone assistant line left by an earlier pass: a collapsed pair, or a
+/// bare assistant message the budget pass shortened.
+fn is_collapsed_pair(pair: &[Message]) -> bool {
+    matches!(pair, [m] if m.role == Role::Assistant
+    && m.content_text().is_some_and(|t| {
+        t.starts_with(COLLAPSED_PREFIX) || t.starts_with(BUDGET_COLLAPSED_PREFIX)
+    }))
+}
+
+/// Pairs the next [`compact`] with `budget` collapses to one-liners,
+/// in full and oldest first, so a summarizer can see what the one-liners
+/// leave out.
+pub fn pairs_leaving_window(messages: &[Message], budget: &CompactBudget) -> Vec<Vec<Message>> {
+    if messages.len() <= 2 {
+        return Vec::new();
+    }
+    let pairs = group_into_pairs(&messages[protected_len(messages)..]);
+    let recent_start = pairs.len().saturating_sub(budget.recent_n);
+    pairs
+        .into_iter()
+        .take(recent_start)
+        .filter(|pair| !is_collapsed_pair(pair))
+        .collect()
+}
+
+/// The summary of earlier steps, without its prefix.
+pub fn current_summary(messages: &[Message]) -> Option<&str> {
+    messages
+        .get(2)
+        .filter(|m| is_summary(m))
+        .and_then(|m| m.content_text())
+        .map(|t| &t[SUMMARY_PREFIX.len()..])
+}
+
+/// The one-liners compaction has left after the summary, oldest first.
+/// These are the steps a new summary has to fold in.
+pub fn collapsed_lines(messages: &[Message]) -> Vec<&str> {
+    messages
+        .iter()
+        .skip(protected_len(messages))
+        .take_while(|m| is_collapsed_pair(std::slice::from_ref(*m)))
+        .filter_map(|m| m.content_text())
+        .collect()
+}
+
+/// Replace the summary and the one-liners after it with a single
+/// summary message at `messages[2]`. Everything else is kept as is, so
+/// the transcript after the summary is unchanged.
+pub fn fold_into_summary(messages: Vec<Message>, summary: &str) -> Vec<Message> {
+    let folded = protected_len(&messages) + collapsed_lines(&messages).len();
+    let mut out = Vec::with_capacity(messages.len());
+    let mut rest = messages.into_iter();
+    out.extend(rest.by_ref().take(2));
+    out.push(Message::assistant(format!("{SUMMARY_PREFIX}{summary}")));
+    out.extend(rest.skip(folded.saturating_sub(2)));
+    out
+}
+
 /// Replace a snapshot-family tool-result body with a short placeholder,
 /// preserving `role`, `tool_call_id`, and `name` so OpenAI tool-call
 /// linkage stays intact.
@@ -444,8 +673,8 @@ fn collapse_pair_to_brief(pair: &[Message]) -> Message {
     Message {
         role: Role::Assistant,
         content: Some(Content::Text(format!(
-            "[collapsed] action={} tool={} outcome={}",
-            asst_kind, tool_kind, outcome
+            "{}action={} tool={} outcome={}",
+            COLLAPSED_PREFIX, asst_kind, tool_kind, outcome
         ))),
         reasoning_content: None,
         tool_calls: None,
@@ -454,7 +683,7 @@ fn collapse_pair_to_brief(pair: &[Message]) -> Message {
     }
 }
 
-fn truncate(s: &str, cap: usize) -> String {
+pub(crate) fn truncate(s: &str, cap: usize) -> String {
     if s.len() <= cap {
         return s.to_string();
     }
@@ -483,10 +712,8 @@ fn content_len(m: &Message) -> usize {
 }
 
 fn enforce_token_budget(mut messages: Vec<Message>, budget: &CompactBudget) -> Vec<Message> {
-    // Rough estimate: 4 characters per token (matches `context::estimate_tokens`).
-    let est = |m: &Message| content_len(m) / 4 + 4;
     loop {
-        let total: usize = messages.iter().map(est).sum();
+        let total = estimate_messages_tokens(&messages);
         if total <= budget.max_tokens {
             return messages;
         }
@@ -500,14 +727,15 @@ fn enforce_token_budget(mut messages: Vec<Message>, budget: &CompactBudget) -> V
             .find(|(_, m)| {
                 let text = m.content_text().unwrap_or("");
                 text.len() > 200
-                    && !text.starts_with("[collapsed")
-                    && !text.starts_with("[collapsed to fit budget]")
+                    && !text.starts_with(SUMMARY_PREFIX)
+                    && !text.starts_with(COLLAPSED_PREFIX)
+                    && !text.starts_with(BUDGET_COLLAPSED_PREFIX)
             })
             .map(|(i, _)| i);
         match collapse_idx {
             Some(i) => {
                 let text = messages[i].content_text().unwrap_or("").to_string();
-                let shortened = format!("[collapsed to fit budget] {}", truncate(&text, 80));
+                let shortened = format!("{BUDGET_COLLAPSED_PREFIX}{}", truncate(&text, 80));
                 messages[i].content = Some(Content::Text(shortened));
             }
             None => return messages, // cannot compact further
@@ -654,6 +882,104 @@ mod state_spine_compact_tests {
         assert!(out.iter().any(|m| content_of(m) == "a9"));
     }
 
+    fn clicks(n: usize) -> Vec<Message> {
+        let mut messages = vec![msg(Role::System, "sys"), msg(Role::User, "goal")];
+        for i in 0..n {
+            messages.push(assistant_call("cdp_click", &format!("c{i}")));
+            messages.push(tool_result_with_id(
+                "cdp_click",
+                &format!("c{i}"),
+                &format!("r{i}"),
+            ));
+        }
+        messages
+    }
+
+    #[test]
+    fn collapsed_lines_survive_later_compactions() {
+        let budget = CompactBudget {
+            max_tokens: 2_000,
+            recent_n: 2,
+        };
+        let once = compact(clicks(5), &budget);
+        let twice = compact(once.clone(), &budget);
+
+        assert_eq!(collapsed_lines(&once).len(), 3);
+        assert_eq!(collapsed_lines(&twice), collapsed_lines(&once));
+        assert!(collapsed_lines(&twice)[0].contains("action=cdp_click"));
+    }
+
+    #[test]
+    fn lines_shortened_to_fit_the_budget_are_not_collapsed_again() {
+        let mut messages = clicks(0);
+        messages.push(msg(Role::Assistant, &"thinking it over ".repeat(40)));
+        messages.extend(clicks(1).into_iter().skip(2));
+        let tight = CompactBudget {
+            max_tokens: 20,
+            recent_n: 4,
+        };
+        let narrow = CompactBudget {
+            max_tokens: 2_000,
+            recent_n: 1,
+        };
+
+        let shortened = compact(messages, &tight);
+        assert!(content_of(&shortened[2]).starts_with(BUDGET_COLLAPSED_PREFIX));
+        let later = compact(shortened.clone(), &narrow);
+
+        assert_eq!(content_of(&later[2]), content_of(&shortened[2]));
+        assert_eq!(collapsed_lines(&later).len(), 1);
+    }
+
+    #[test]
+    fn leaving_pairs_are_the_ones_compaction_collapses() {
+        let budget = CompactBudget {
+            max_tokens: 2_000,
+            recent_n: 2,
+        };
+        let leaving = pairs_leaving_window(&clicks(4), &budget);
+        assert_eq!(leaving.len(), 2);
+        assert_eq!(content_of(&leaving[1][1]), "r1");
+
+        // Already collapsed pairs are not reported again.
+        let mut compacted = compact(clicks(4), &budget);
+        assert!(pairs_leaving_window(&compacted, &budget).is_empty());
+        compacted.push(assistant_call("cdp_click", "c4"));
+        compacted.push(tool_result_with_id("cdp_click", "c4", "r4"));
+        let leaving = pairs_leaving_window(&compacted, &budget);
+        assert_eq!(leaving.len(), 1);
+        assert_eq!(content_of(&leaving[0][1]), "r2");
+    }
+
+    #[test]
+    fn summary_replaces_collapsed_lines_and_is_never_compacted() {
+        let budget = CompactBudget {
+            max_tokens: 2_000,
+            recent_n: 2,
+        };
+        let compacted = compact(clicks(5), &budget);
+        let folded = fold_into_summary(compacted.clone(), "clicked three rows");
+
+        assert_eq!(folded.len(), compacted.len() - 3 + 1);
+        assert_eq!(current_summary(&folded), Some("clicked three rows"));
+        assert!(collapsed_lines(&folded).is_empty());
+        let ids = |ms: &[Message]| -> Vec<Option<String>> {
+            ms.iter().map(|m| m.tool_call_id.clone()).collect()
+        };
+        assert_eq!(ids(&folded[3..]), ids(&compacted[5..]));
+
+        let tight = CompactBudget {
+            max_tokens: 1,
+            recent_n: 0,
+        };
+        let squeezed = compact(folded.clone(), &tight);
+        assert_eq!(current_summary(&squeezed), Some("clicked three rows"));
+
+        let refolded = fold_into_summary(squeezed, "clicked five rows");
+        assert_eq!(current_summary(&refolded), Some("clicked five rows"));
+        assert_eq!(refolded.len(), 3);
+    }
+
     #[test]
     fn cross_phase_snapshot_family_does_not_accumulate_bodies() {
         let long = "x".repeat(5_000);
@@ -846,6 +1172,39 @@ mod state_spine_compact_tests {
         assert_eq!(content_of(&out[1]), "goal");
     }
 
+    #[test]
+    fn context_fit_leaves_room_for_tools_and_the_reply() {
+        assert_eq!(
+            ContextFit::new(32_000, 2_000, 4_000).budget_tokens(),
+            26_000
+        );
+        // A small window keeps three quarters of itself for messages.
+        assert_eq!(ContextFit::new(8_000, 0, 4_096).budget_tokens(), 6_000);
+        assert_eq!(ContextFit::unknown(32_000).budget_tokens(), 32_000);
+    }
+
+    #[test]
+    fn context_fit_scales_by_the_measured_tokenizer_ratio() {
+        let mut fit = ContextFit::new(32_000, 2_000, 4_000);
+        fit.calibrate(10, 40);
+        assert_eq!(fit.budget_tokens(), 26_000, "tiny samples are ignored");
+
+        fit.calibrate(1_000, 2_000);
+        assert_eq!(fit.budget_tokens(), 13_000);
+
+        fit.observe_prompt(1_000, 3_000);
+        assert_eq!(fit.budget_tokens(), 26_000, "tool schemas are taken out");
+    }
+
+    #[test]
+    fn context_fit_caps_the_budget_below_a_rejected_transcript() {
+        let mut fit = ContextFit::unknown(32_000);
+        fit.shrink_below(10_000);
+        assert_eq!(fit.budget_tokens(), 7_500);
+        fit.shrink_below(20_000);
+        assert_eq!(fit.budget_tokens(), 7_500, "a cap never loosens");
+    }
+
     #[test]
     fn short_histories_are_returned_unchanged() {
         let messages = vec![msg(Role::System, "sys"), msg(Role::User, "goal")];
@@ -887,7 +1246,7 @@ mod legacy_context_tests {
 
     #[test]
     fn estimate_tokens_basic() {
-        // 12 characters → 3 tokens
+        // two words and "!" → 3 tokens
         assert_eq!(estimate_tokens("hello world!"), 3);
     }
 
@@ -901,15 +1260,24 @@ mod legacy_context_tests {
         assert_eq!(estimate_tokens("a"), 1);
     }
 
+    #[test]
+    fn estimate_tokens_splits_opaque_runs_finer_than_words() {
+        assert_eq!(estimate_tokens("the quick brown fox"), 4);
+        // 24 hex characters read like an id, not a word.
+        assert_eq!(estimate_tokens("f86d081884c7d659a2feaa09"), 8);
+        assert_eq!(estimate_tokens("12345678"), 3);
+        assert_eq!(estimate_tokens("{\"a\": 1}"), 5);
+    }
+
     #[test]
     fn estimate_messages_tokens_sums_content() {
         let messages = vec![
-            Message::system("You are a helper."), // 18 chars → 5 tokens
-            Message::user("Do something."),       // 13 chars → 4 tokens
+            Message::system("You are a helper."), // 4 words + "." → 5 tokens
+            Message::user("Do something."),       // "something" splits → 4 tokens
         ];
         let total = estimate_messages_tokens(&messages);
         assert!(total > 0);
-        assert_eq!(total, 5 + 4);
+        assert_eq!(total, 5 + 4 + 2 * MESSAGE_OVERHEAD_TOKENS);
     }
 
     #[test]
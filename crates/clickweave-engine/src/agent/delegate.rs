@@ -0,0 +1,213 @@
+//! `delegate_subgoal` pseudo-tool: hands one subgoal to a child
+//! `StateRunner` with its own transcript, a restricted tool scope and its
+//! own step budget, so a large goal does not have to fit in one context.
+//!
+//! The child inherits the parent's permission policy and approval gate
+//! as-is, and its tool scope is resolved against the tools the parent
+//! itself may call — a child can only ever see a subset of its parent's
+//! surface. Delegation depth is bounded by
+//! `AgentConfig::max_sub_agent_depth`; each level hands its child one
+//! less.
+
+use serde_json::{Value, json};
+
+use crate::agent::permissions::glob_match;
+use crate::agent::types::{AgentState, StepOutcome, TerminalReason};
+
+pub(crate) const TOOL_NAME: &str = "delegate_subgoal";
+
+/// Step budget when the LLM does not ask for one.
+pub(crate) const DEFAULT_SUB_AGENT_MAX_STEPS: usize = 15;
+
+/// Upper bound on a child's step budget, whatever the LLM asks for.
+const MAX_SUB_AGENT_MAX_STEPS: usize = 40;
+
+/// Most recent successful child steps echoed back to the parent.
+const MAX_REPORTED_OUTPUTS: usize = 5;
+
+/// Per-output truncation for the parent's tool result.
+const OUTPUT_CHARS: usize = 400;
+
+/// Parsed `delegate_subgoal` arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DelegationRequest {
+    pub subgoal: String,
+    /// Tool-name globs (`cdp_*`, `ax_click`) the child may call.
+    pub tools: Vec<String>,
+    pub max_steps: usize,
+}
+
+pub(crate) fn parse_args(args: &Value) -> Result<DelegationRequest, String> {
+    let subgoal = args
+        .get("subgoal")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "delegate_subgoal requires a non-empty `subgoal`".to_string())?
+        .to_string();
+    let tools: Vec<String> = args
+        .get("tools")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    if tools.is_empty() {
+        return Err("delegate_subgoal requires a non-empty `tools` allowlist".into());
+    }
+    let max_steps = args
+        .get("max_steps")
+        .and_then(Value::as_u64)
+        .map(|n| (n as usize).clamp(1, MAX_SUB_AGENT_MAX_STEPS))
+        .unwrap_or(DEFAULT_SUB_AGENT_MAX_STEPS);
+    Ok(DelegationRequest {
+        subgoal,
+        tools,
+        max_steps,
+    })
+}
+
+/// Resolve the requested globs against the tools the parent may call.
+/// Every pattern must match at least one of them, so a typo or a request
+/// for a tool outside the parent's scope fails loudly instead of leaving
+/// the child with a silently narrower surface.
+pub(crate) fn resolve_tool_scope(
+    patterns: &[String],
+    available: &[String],
+) -> Result<Vec<String>, String> {
+    let mut scope: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let matched: Vec<&String> = available
+            .iter()
+            .filter(|name| glob_match(pattern, name))
+            .collect();
+        if matched.is_empty() {
+            return Err(format!(
+                "delegate_subgoal: `{pattern}` matches no tool available to this agent"
+            ));
+        }
+        for name in matched {
+            if !scope.contains(name) {
+                scope.push(name.clone());
+            }
+        }
+    }
+    Ok(scope)
+}
+
+/// Keep only the openai-shaped tool definitions named in `scope`.
+pub(crate) fn scoped_tools(mcp_tools: &[Value], scope: &[String]) -> Vec<Value> {
+    mcp_tools
+        .iter()
+        .filter(|tool| {
+            tool.pointer("/function/name")
+                .and_then(Value::as_str)
+                .is_some_and(|name| scope.iter().any(|s| s == name))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Tool-result text handed back to the parent once the child finished:
+/// its summary, why it stopped, and its last few successful outputs.
+pub(crate) fn tool_result_body(child_run_id: uuid::Uuid, state: &AgentState) -> String {
+    let outputs: Vec<Value> = state
+        .steps
+        .iter()
+        .rev()
+        .filter_map(|step| match (&step.command, &step.outcome) {
+            (
+                crate::agent::types::AgentCommand::ToolCall { tool_name, .. },
+                StepOutcome::Success(body),
+            ) => Some(json!({
+                "tool": tool_name,
+                "result": crate::agent::prompt::truncate_summary(body, OUTPUT_CHARS),
+            })),
+            _ => None,
+        })
+        .take(MAX_REPORTED_OUTPUTS)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    json!({
+        "sub_agent_run_id": child_run_id,
+        "completed": state.completed,
+        "summary": state.summary,
+        "terminal_reason": state.terminal_reason,
+        "steps_executed": state.steps.len(),
+        "outputs": outputs,
+    })
+    .to_string()
+}
+
+/// Tool-result text when the child could not run to a terminal state.
+pub(crate) fn failure_body(child_run_id: uuid::Uuid, error: &str) -> String {
+    json!({
+        "sub_agent_run_id": child_run_id,
+        "completed": false,
+        "error": error,
+    })
+    .to_string()
+}
+
+/// Whether a child's terminal state must also stop the parent: losing
+/// the approval channel cannot be recovered from one level up.
+pub(crate) fn halts_parent(reason: Option<&TerminalReason>) -> bool {
+    matches!(reason, Some(TerminalReason::ApprovalUnavailable))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_args_requires_subgoal_and_tools_and_clamps_budget() {
+        let request = parse_args(&json!({
+            "subgoal": " export contacts ",
+            "tools": ["cdp_*", " "],
+            "max_steps": 500,
+        }))
+        .unwrap();
+        assert_eq!(request.subgoal, "export contacts");
+        assert_eq!(request.tools, names(&["cdp_*"]));
+        assert_eq!(request.max_steps, MAX_SUB_AGENT_MAX_STEPS);
+
+        let defaulted = parse_args(&json!({"subgoal": "x", "tools": ["cdp_click"]})).unwrap();
+        assert_eq!(defaulted.max_steps, DEFAULT_SUB_AGENT_MAX_STEPS);
+
+        assert!(parse_args(&json!({"subgoal": "x", "tools": []})).is_err());
+        assert!(parse_args(&json!({"tools": ["cdp_click"]})).is_err());
+    }
+
+    #[test]
+    fn tool_scope_never_exceeds_the_available_tools() {
+        let available = names(&["cdp_click", "cdp_find_elements", "ax_click"]);
+        let scope = resolve_tool_scope(&names(&["cdp_*", "cdp_click"]), &available).unwrap();
+        assert_eq!(scope, names(&["cdp_click", "cdp_find_elements"]));
+
+        let err = resolve_tool_scope(&names(&["cdp_*", "launch_app"]), &available).unwrap_err();
+        assert!(err.contains("launch_app"));
+    }
+
+    #[test]
+    fn scoped_tools_filters_openai_definitions_by_name() {
+        let tools = vec![
+            json!({"type": "function", "function": {"name": "cdp_click"}}),
+            json!({"type": "function", "function": {"name": "ax_click"}}),
+        ];
+        let kept = scoped_tools(&tools, &names(&["cdp_click"]));
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0]["function"]["name"], "cdp_click");
+    }
+}
@@ -413,5 +413,14 @@ fn event_kind(e: &AgentEvent) -> &'static str {
         AgentEvent::SkillInvoked { .. } => "skill_invoked",
         AgentEvent::SkillExtracted { .. } => "skill_extracted",
         AgentEvent::SkillConfirmed { .. } => "skill_confirmed",
+        AgentEvent::UserQuestionAnswered { .. } => "user_question_answered",
+        AgentEvent::PlanReviewed { .. } => "plan_reviewed",
+        AgentEvent::SubAgentStarted { .. } => "sub_agent_started",
+        AgentEvent::SubAgentFinished { .. } => "sub_agent_finished",
+        AgentEvent::LlmDelta { .. } => "llm_delta",
+        AgentEvent::McpProgress { .. } => "mcp_progress",
+        AgentEvent::McpLog { .. } => "mcp_log",
+        AgentEvent::ToolCallCancelled { .. } => "tool_call_cancelled",
+        AgentEvent::VlmCacheStats { .. } => "vlm_cache_stats",
     }
 }
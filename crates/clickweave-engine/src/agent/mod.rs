@@ -1,6 +1,8 @@
 mod approval;
+pub mod ask_user;
 mod completion_check;
 mod context;
+mod delegate;
 pub mod episodic;
 pub mod permissions;
 pub mod skills;
@@ -8,13 +10,16 @@ pub mod skills;
 // `task_state_at_entry` snapshots in the episodic memory layer's
 // integration tests), so the module surfaces as `pub mod`.
 pub mod phase;
+pub mod plan;
 pub mod prior_turns;
 mod prompt;
 mod recovery;
 mod render;
+mod resources;
 mod runner;
 mod time_oracle;
 pub mod tool_mapping;
+pub mod tool_timeouts;
 pub mod trace_graph;
 // Phase 2 (episodic memory) integration tests construct
 // `WorldModelSnapshot`, `StepRecord`, and `TaskState` values from
@@ -33,10 +38,16 @@ pub mod world_model;
 // without a cyclic dep on the legacy runner. Phase 3b deleted the legacy
 // runner; this re-export keeps external callers pointed at a stable path.
 pub use approval::ApprovalGate;
+pub use ask_user::AskUserOutcome;
 pub use permissions::{PermissionAction, PermissionPolicy, PermissionRule, ToolAnnotations};
+pub use plan::{PlanDecision, PlannedSubgoal};
 pub use prior_turns::{PriorTurn, build_goal_block};
 pub use prompt::truncate_summary;
-pub use runner::{AgentAction, AgentTurn, StateRunner, ToolExecutor, TurnOutcome};
+pub use runner::{
+    AgentAction, AgentTurn, SUSPENDED_RUN_SCHEMA_VERSION, StateRunner, SuspendedRun, ToolExecutor,
+    TurnOutcome,
+};
+pub use tool_timeouts::ToolTimeouts;
 pub use types::*;
 
 use std::path::PathBuf;
@@ -53,6 +64,26 @@ pub struct AgentChannels {
     /// Approval request channel (each request comes with a oneshot response sender).
     pub approval_tx:
         tokio::sync::mpsc::Sender<(ApprovalRequest, tokio::sync::oneshot::Sender<bool>)>,
+    /// Optional suspend request. Cancelling it snapshots the run into
+    /// the attached `RunStorage` and halts with `TerminalReason::Suspended`.
+    pub suspend_signal: Option<tokio_util::sync::CancellationToken>,
+    /// Optional stop request. Cancelling it abandons any in-flight tool
+    /// call and halts with `TerminalReason::Cancelled`.
+    pub cancel_signal: Option<tokio_util::sync::CancellationToken>,
+}
+
+/// Forward `LlmClient` retries and circuit trips onto a run's event
+/// stream as `AgentEvent::Warning`, so a step stalled on a flaky
+/// endpoint explains itself instead of looking hung. Notices are
+/// dropped rather than awaited when the channel is full.
+pub fn llm_retry_warnings(
+    event_tx: tokio::sync::mpsc::Sender<RunnerOutput>,
+) -> clickweave_llm::RetryObserver {
+    Arc::new(move |notice| {
+        let _ = event_tx.try_send(RunnerOutput::Event(AgentEvent::Warning {
+            message: notice.to_string(),
+        }));
+    })
 }
 
 /// Public entry point for running the agent loop from outside the engine crate.
@@ -66,6 +97,8 @@ pub struct AgentChannels {
 /// When `vision` is `Some`, the runner verifies `agent_done` against a
 /// fresh screenshot via the VLM and may halt with a disagreement event
 /// when the VLM rejects completion.
+/// When `summarizer` is `Some`, the steps compaction collapses out of
+/// the transcript are folded into a narrative summary it writes.
 /// When `permissions` is `Some`, the runner consults the policy for every
 /// non-observation tool call — `Allow` skips approval, `Deny` hard-rejects,
 /// `Ask` falls through to the existing approval prompt.
@@ -90,6 +123,7 @@ pub async fn run_agent_workflow<B, M>(
     mcp: &M,
     channels: Option<AgentChannels>,
     vision: Option<Arc<dyn DynChatBackend>>,
+    summarizer: Option<Arc<dyn DynChatBackend>>,
     permissions: Option<PermissionPolicy>,
     run_id: uuid::Uuid,
     anchor_node_id: Option<uuid::Uuid>,
@@ -124,6 +158,7 @@ where
         mcp,
         channels,
         vision,
+        summarizer,
         permissions,
         run_id,
         anchor_node_id,
@@ -132,6 +167,7 @@ where
         episodic_ctx,
         skill_ctx,
         None,
+        None,
     )
     .await
 }
@@ -147,6 +183,7 @@ pub async fn run_agent_workflow_with_prompt_override<B, M>(
     mcp: &M,
     channels: Option<AgentChannels>,
     vision: Option<Arc<dyn DynChatBackend>>,
+    summarizer: Option<Arc<dyn DynChatBackend>>,
     permissions: Option<PermissionPolicy>,
     run_id: uuid::Uuid,
     anchor_node_id: Option<uuid::Uuid>,
@@ -170,6 +207,7 @@ where
         mcp,
         channels,
         vision,
+        summarizer,
         permissions,
         run_id,
         anchor_node_id,
@@ -178,6 +216,7 @@ where
         episodic_ctx,
         skill_ctx,
         agent_system_prompt_override,
+        None,
     )
     .await
 }
@@ -190,6 +229,7 @@ async fn run_agent_workflow_inner<B, M>(
     mcp: &M,
     channels: Option<AgentChannels>,
     vision: Option<Arc<dyn DynChatBackend>>,
+    summarizer: Option<Arc<dyn DynChatBackend>>,
     permissions: Option<PermissionPolicy>,
     run_id: uuid::Uuid,
     anchor_node_id: Option<uuid::Uuid>,
@@ -198,6 +238,7 @@ async fn run_agent_workflow_inner<B, M>(
     episodic_ctx: Option<crate::agent::episodic::EpisodicContext>,
     skill_ctx: Option<crate::agent::skills::SkillContext>,
     agent_system_prompt_override: Option<String>,
+    resume: Option<SuspendedRun>,
 ) -> anyhow::Result<(
     AgentState,
     Option<tokio::sync::mpsc::Sender<crate::agent::episodic::types::WriteRequest>>,
@@ -238,10 +279,19 @@ where
         runner = runner
             .with_events(ch.event_tx)
             .with_approval(ch.approval_tx);
+        if let Some(token) = ch.suspend_signal {
+            runner = runner.with_suspend_signal(token);
+        }
+        if let Some(token) = ch.cancel_signal {
+            runner = runner.with_cancel_signal(token);
+        }
     }
     if let Some(v) = vision {
         runner = runner.with_vision(v);
     }
+    if let Some(summarizer) = summarizer {
+        runner = runner.with_summarizer(summarizer);
+    }
     if let Some(policy) = permissions {
         runner = runner.with_permissions(policy);
     }
@@ -251,6 +301,9 @@ where
     if let Some(s) = storage {
         runner = runner.with_storage(s);
     }
+    if let Some(snapshot) = resume {
+        runner = runner.with_resume(snapshot);
+    }
     // Spawn the episodic writer last so it captures the live `event_tx`
     // and `run_id` already seeded by `with_events` / `with_run_id`. The
     // writer is a no-op when `episodic_active()` is false.
@@ -270,6 +323,68 @@ where
     Ok((state, writer_tx))
 }
 
+/// Continue a run previously halted with `TerminalReason::Suspended`.
+///
+/// Takes the same collaborators as [`run_agent_workflow`]; the goal and
+/// run id come from `snapshot`. The resumed loop re-observes the app
+/// before its first turn, since the UI may have moved on while the run
+/// was suspended. The step budget and `max_duration` carry over: steps
+/// and active time spent before the suspension count against them.
+///
+/// Fails without running anything when the snapshot was written under a
+/// different [`SUSPENDED_RUN_SCHEMA_VERSION`].
+#[allow(clippy::too_many_arguments)]
+pub async fn resume_agent_workflow<B, M>(
+    llm: &B,
+    config: AgentConfig,
+    snapshot: SuspendedRun,
+    mcp: &M,
+    channels: Option<AgentChannels>,
+    vision: Option<Arc<dyn DynChatBackend>>,
+    summarizer: Option<Arc<dyn DynChatBackend>>,
+    permissions: Option<PermissionPolicy>,
+    verification_artifacts_dir: Option<PathBuf>,
+    storage: Option<RunStorageHandle>,
+    episodic_ctx: Option<crate::agent::episodic::EpisodicContext>,
+    skill_ctx: Option<crate::agent::skills::SkillContext>,
+) -> anyhow::Result<(
+    AgentState,
+    Option<tokio::sync::mpsc::Sender<crate::agent::episodic::types::WriteRequest>>,
+)>
+where
+    B: ChatBackend,
+    M: Mcp + ?Sized,
+{
+    anyhow::ensure!(
+        snapshot.schema_version == SUSPENDED_RUN_SCHEMA_VERSION,
+        "suspended run uses schema version {} but this build expects {}",
+        snapshot.schema_version,
+        SUSPENDED_RUN_SCHEMA_VERSION
+    );
+    let goal = snapshot.goal.clone();
+    let run_id = snapshot.run_id;
+    let anchor_node_id = snapshot.last_node_id;
+    run_agent_workflow_inner(
+        llm,
+        config,
+        goal,
+        mcp,
+        channels,
+        vision,
+        summarizer,
+        permissions,
+        run_id,
+        anchor_node_id,
+        verification_artifacts_dir,
+        storage,
+        episodic_ctx,
+        skill_ctx,
+        None,
+        Some(snapshot),
+    )
+    .await
+}
+
 /// Shared test doubles (`ScriptedLlm`, `StaticMcp`, `NullMcp`, `YesVlm`,
 /// `NoVlm`, `llm_reply_tool`, …). Gated on `cfg(test)` for this crate's
 /// own tests and on the `test-stubs` feature for downstream
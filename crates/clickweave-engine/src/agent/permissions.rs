@@ -112,7 +112,7 @@ impl ToolAnnotations {
 /// Glob match for tool-name patterns. Supports `*` (any sequence
 /// including empty) and `?` (any single character). Returns false when
 /// the pattern is malformed or does not match.
-fn glob_match(pattern: &str, text: &str) -> bool {
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
     // Classic recursive glob. Works for short tool names; the pattern
     // space is tiny (rule count × tool count × a handful of metachars).
     let pattern_bytes = pattern.as_bytes();
@@ -1,9 +1,9 @@
 #![allow(dead_code)] // Phase 1: module wired to its own tests only; runtime consumers land in later phases.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Harness-inferred phase of the agent run. Never authored by the LLM (D5).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 #[serde(rename_all = "snake_case")]
 pub enum Phase {
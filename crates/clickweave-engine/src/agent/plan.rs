@@ -0,0 +1,139 @@
+//! Plan-first mode: the agent proposes an ordered subgoal plan through
+//! the `propose_plan` pseudo-tool and waits for the operator to approve
+//! (or edit) it before any state-changing tool is dispatched.
+//!
+//! Approved subgoals are seeded into `TaskState::subgoal_stack` with the
+//! first planned subgoal on top, so `complete_subgoal` walks the plan in
+//! order. Diverging from an approved plan means calling `propose_plan`
+//! again, which goes back through the same approval round-trip.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+pub(crate) const TOOL_NAME: &str = "propose_plan";
+
+/// Upper bound on planned subgoals; a longer plan is rejected at parse
+/// time so the LLM splits the work more coarsely.
+const MAX_PLAN_SUBGOALS: usize = 12;
+
+/// Appended to the goal block of a plan-first run so the first turn
+/// produces a plan instead of an action.
+pub(crate) const PLAN_FIRST_INSTRUCTION: &str = "[PLAN-FIRST MODE] Before acting, call \
+     `propose_plan` with the ordered subgoals you intend to work through, naming the apps \
+     and tools each one needs. You may use read-only observation tools to inform the plan. \
+     State-changing tools are blocked until the operator approves it. Once approved, the \
+     subgoals are on your subgoal stack — finish each with `complete_subgoal`. If you need \
+     to deviate from the approved plan, call `propose_plan` again with the revised plan.";
+
+/// One subgoal as proposed by the LLM (and possibly edited by the
+/// operator).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct PlannedSubgoal {
+    pub text: String,
+    #[serde(default)]
+    pub expected_apps: Vec<String>,
+    #[serde(default)]
+    pub expected_tools: Vec<String>,
+}
+
+/// Operator's answer to a proposed plan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum PlanDecision {
+    /// Approve the plan. `subgoals` is the plan as the operator left it;
+    /// an empty list approves the proposal unchanged.
+    Approve { subgoals: Vec<PlannedSubgoal> },
+    /// Send the plan back; `reason` is shown to the LLM.
+    Reject { reason: String },
+}
+
+/// Extract the ordered subgoals from `propose_plan` arguments.
+pub(crate) fn parse_args(args: &Value) -> Result<Vec<PlannedSubgoal>, String> {
+    let items = args
+        .get("subgoals")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "propose_plan requires a `subgoals` array".to_string())?;
+    let subgoals: Vec<PlannedSubgoal> = items
+        .iter()
+        .filter_map(|item| {
+            let subgoal: PlannedSubgoal = serde_json::from_value(item.clone()).ok()?;
+            let text = subgoal.text.trim();
+            (!text.is_empty()).then(|| PlannedSubgoal {
+                text: text.to_string(),
+                ..subgoal
+            })
+        })
+        .collect();
+    if subgoals.is_empty() {
+        return Err("propose_plan requires at least one subgoal with non-empty `text`".into());
+    }
+    if subgoals.len() > MAX_PLAN_SUBGOALS {
+        return Err(format!(
+            "propose_plan accepts at most {MAX_PLAN_SUBGOALS} subgoals; merge related steps"
+        ));
+    }
+    Ok(subgoals)
+}
+
+/// Tool-result text handed back to the LLM once the operator decided.
+pub(crate) fn tool_result_body(decision: &PlanDecision, edited: bool) -> String {
+    match decision {
+        PlanDecision::Approve { subgoals } => json!({
+            "approved": true,
+            "edited_by_operator": edited,
+            "subgoals": subgoals.iter().map(|s| s.text.as_str()).collect::<Vec<_>>(),
+            "note": "The plan is on your subgoal stack; work through it in order.",
+        }),
+        PlanDecision::Reject { reason } => json!({
+            "approved": false,
+            "reason": reason,
+            "note": "Revise the plan and call propose_plan again.",
+        }),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_args_keeps_order_and_drops_blank_subgoals() {
+        let subgoals = parse_args(&json!({
+            "subgoals": [
+                {"text": " Open the CRM ", "expected_apps": ["Chrome"]},
+                {"text": "  "},
+                {"text": "Export contacts", "expected_tools": ["cdp_click"]},
+            ]
+        }))
+        .unwrap();
+        assert_eq!(subgoals.len(), 2);
+        assert_eq!(subgoals[0].text, "Open the CRM");
+        assert_eq!(subgoals[0].expected_apps, vec!["Chrome"]);
+        assert_eq!(subgoals[1].expected_tools, vec!["cdp_click"]);
+    }
+
+    #[test]
+    fn parse_args_rejects_empty_and_oversized_plans() {
+        assert!(parse_args(&json!({"subgoals": []})).is_err());
+        assert!(parse_args(&json!({})).is_err());
+        let many: Vec<Value> = (0..=MAX_PLAN_SUBGOALS)
+            .map(|i| json!({"text": format!("step {i}")}))
+            .collect();
+        assert!(parse_args(&json!({ "subgoals": many })).is_err());
+    }
+
+    #[test]
+    fn decision_round_trips_through_tagged_json() {
+        let decision: PlanDecision =
+            serde_json::from_value(json!({"decision": "reject", "reason": "too risky"})).unwrap();
+        assert_eq!(
+            decision,
+            PlanDecision::Reject {
+                reason: "too risky".to_string()
+            }
+        );
+    }
+}
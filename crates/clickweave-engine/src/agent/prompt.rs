@@ -329,6 +329,87 @@ pub fn agent_replan_tool() -> Value {
     })
 }
 
+/// Tool definition for the ask_user pseudo-tool. Answered by the operator
+/// (or by the harness's no-human fallback), never by MCP.
+pub fn ask_user_tool() -> Value {
+    json!({
+        "type": "function",
+        "function": {
+            "name": "ask_user",
+            "description": "Ask the human operator a clarifying question when the goal is ambiguous or needs information you cannot observe (which account, which file, confirm an irreversible choice). Use sparingly. The operator may not be available; if no answer comes back, proceed on your best assumption.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "question": {
+                        "type": "string",
+                        "description": "The question, phrased so it can be answered without seeing the screen."
+                    },
+                    "choices": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Optional suggested answers (at most 8)."
+                    }
+                },
+                "required": ["question"]
+            }
+        }
+    })
+}
+
+/// Tool definition for the propose_plan pseudo-tool. Only advertised in
+/// plan-first mode, so it stays out of [`pseudo_tools`] and the shared
+/// tool-list prefix.
+pub fn propose_plan_tool() -> Value {
+    json!({
+        "type": "function",
+        "function": {
+            "name": crate::agent::plan::TOOL_NAME,
+            "description": "Submit your plan for operator approval: the ordered subgoals you will work through. Required before any state-changing tool in plan-first mode; call it again to revise an approved plan before deviating from it.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "subgoals": {
+                        "type": "array",
+                        "description": "Subgoals in execution order (at most 12).",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "text": { "type": "string", "description": "What this subgoal achieves." },
+                                "expected_apps": { "type": "array", "items": { "type": "string" }, "description": "Apps this subgoal will touch." },
+                                "expected_tools": { "type": "array", "items": { "type": "string" }, "description": "Tools you expect to call." }
+                            },
+                            "required": ["text"]
+                        }
+                    }
+                },
+                "required": ["subgoals"]
+            }
+        }
+    })
+}
+
+/// Tool definition for the delegate_subgoal pseudo-tool. Only advertised
+/// while `AgentConfig::max_sub_agent_depth` allows another level, so it
+/// stays out of [`pseudo_tools`] as well.
+pub fn delegate_subgoal_tool() -> Value {
+    json!({
+        "type": "function",
+        "function": {
+            "name": crate::agent::delegate::TOOL_NAME,
+            "description": "Hand a self-contained subgoal to a sub-agent with a fresh transcript, a restricted tool allowlist, and its own step budget. Blocks until the sub-agent stops and returns its summary and last outputs. Use it for large, separable chunks of work (one system, one page) so they do not crowd your own context.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "subgoal": { "type": "string", "description": "What the sub-agent must achieve, with any details it needs; it does not see your transcript." },
+                    "tools": { "type": "array", "items": { "type": "string" }, "description": "Tool names the sub-agent may call. `*` matches any run of characters (e.g. `cdp_*`). Must be tools you can call yourself." },
+                    "max_steps": { "type": "integer", "minimum": 1, "description": "Step budget for the sub-agent (default 15, at most 40)." }
+                },
+                "required": ["subgoal", "tools"]
+            }
+        }
+    })
+}
+
 /// Tool definition for the harness-local date/time oracle.
 ///
 /// This is intentionally a pseudo-tool, not an MCP server tool: the harness
@@ -613,10 +694,10 @@ pub fn skill_patch_promote_to_variable_tool() -> Value {
 /// All harness-local pseudo-tools that the LLM may emit in a turn.
 ///
 /// Order is intentional: the action pseudo-tools (`agent_done`,
-/// `agent_replan`) remain near the end so the LLM-facing tool list still
+/// `agent_replan`, `ask_user`) remain near the end so the LLM-facing tool list still
 /// clusters the "terminate the loop" choices, while the mutations cluster
 /// together at the start of the pseudo-tool block. `invoke_skill` is appended
-/// after `agent_replan` so the tool-list prefix stays stable for prompt-cache
+/// after them so the tool-list prefix stays stable for prompt-cache
 /// compatibility across runs that toggle the skills layer. The three
 /// `skill_patch_*` tools follow `invoke_skill` so the skills-layer prefix
 /// remains cache-stable between runs that don't use patch primitives.
@@ -631,6 +712,7 @@ pub fn pseudo_tools() -> Vec<Value> {
         get_current_datetime_tool(),
         agent_done_tool(),
         agent_replan_tool(),
+        ask_user_tool(),
         invoke_skill_tool(),
         skill_patch_rebind_target_tool(),
         skill_patch_reorder_sections_tool(),
@@ -711,12 +793,14 @@ mod state_spine_prompt_tests {
                 name: "cdp_click".to_string(),
                 description: Some("Click a CDP-backed element".to_string()),
                 input_schema: serde_json::json!({}),
+                output_schema: None,
                 annotations: None,
             },
             Tool {
                 name: "ax_click".to_string(),
                 description: None,
                 input_schema: serde_json::json!({}),
+                output_schema: None,
                 annotations: None,
             },
         ];
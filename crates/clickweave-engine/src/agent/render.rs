@@ -2,7 +2,7 @@
 
 use std::fmt::Write;
 
-use crate::agent::task_state::TaskState;
+use crate::agent::task_state::{PlanItemStatus, TaskState};
 use crate::agent::world_model::{ObservedElement, WorldModel};
 use clickweave_core::cdp::CdpFindElementMatch;
 
@@ -146,13 +146,38 @@ pub fn render_step_input_with_cap(
     let _ = writeln!(out, "phase: {}", phase_str);
     if let Some(top) = ts.subgoal_stack.last() {
         let _ = writeln!(out, "active_subgoal: {}", top.text);
-        if ts.subgoal_stack.len() > 1 {
+        // An approved plan renders as its own progress list below; the
+        // seeded stack entries would only repeat it in reverse.
+        if ts.subgoal_stack.len() > 1 && ts.plan.is_empty() {
             let _ = writeln!(out, "subgoal_stack:");
             for (i, sg) in ts.subgoal_stack.iter().enumerate() {
                 let _ = writeln!(out, "  [{}] {}", i, sg.text);
             }
         }
     }
+    if !ts.plan.is_empty() {
+        let done = ts
+            .plan
+            .iter()
+            .filter(|item| ts.plan_item_status(item) == PlanItemStatus::Done)
+            .count();
+        let _ = writeln!(out, "approved_plan ({} of {} done):", done, ts.plan.len());
+        for (i, item) in ts.plan.iter().enumerate() {
+            let mark = match ts.plan_item_status(item) {
+                PlanItemStatus::Done => "x",
+                PlanItemStatus::Active => ">",
+                PlanItemStatus::Pending => " ",
+            };
+            let _ = write!(out, "  [{}] {}. {}", mark, i + 1, item.text);
+            if !item.expected_apps.is_empty() {
+                let _ = write!(out, " apps={}", item.expected_apps.join(","));
+            }
+            if !item.expected_tools.is_empty() {
+                let _ = write!(out, " tools={}", item.expected_tools.join(","));
+            }
+            let _ = writeln!(out);
+        }
+    }
     if !ts.watch_slots.is_empty() {
         let _ = writeln!(out, "watch_slots:");
         for ws in &ts.watch_slots {
@@ -345,6 +370,35 @@ mod tests {
         assert!(out.contains("sample_labels=[\"Hide Tabs\", \"Chat with Alice\"]"));
     }
 
+    #[test]
+    fn renders_approved_plan_progress_instead_of_seeded_stack() {
+        let mut ts = TaskState::new("g".to_string());
+        let planned = |text: &str, apps: &[&str]| crate::agent::plan::PlannedSubgoal {
+            text: text.to_string(),
+            expected_apps: apps.iter().map(|a| a.to_string()).collect(),
+            expected_tools: vec![],
+        };
+        ts.seed_plan(
+            &[planned("open CRM", &["Chrome"]), planned("export", &[])],
+            0,
+        );
+        ts.apply(
+            &TaskStateMutation::CompleteSubgoal {
+                summary: "opened".to_string(),
+            },
+            2,
+        )
+        .unwrap();
+
+        let out = render_step_input(&WorldModel::default(), &ts, 3);
+
+        assert!(out.contains("approved_plan (1 of 2 done):"));
+        assert!(out.contains("[x] 1. open CRM apps=Chrome"));
+        assert!(out.contains("[>] 2. export"));
+        assert!(out.contains("active_subgoal: export"));
+        assert!(!out.contains("subgoal_stack:"));
+    }
+
     #[test]
     fn renders_cdp_connect_status_when_set() {
         // The status block is the LLM's signal that auto-connect
@@ -0,0 +1,266 @@
+//! MCP resources in tool results.
+//!
+//! A server may answer a tool call with a `resource_link` — a URI to
+//! fetch with `resources/read` — or with the resource embedded, rather
+//! than inline text; an AX tree or a screenshot served as a resource, for
+//! instance. The runner reads linked resources, hands the model their
+//! text, and keeps image resources as run artifacts. Text resources such
+//! as AX trees come back on most steps and already reach the model, so
+//! they are not stored again.
+
+use crate::executor::Mcp;
+use clickweave_mcp::{ResourceContents, ToolCallResult, ToolContent};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// The tool-result body the model sees: text blocks as they are, each
+/// resource as its text or, for binary contents, a one-line stand-in.
+/// Image resources are saved under `artifacts_dir` when it is set. Links
+/// are read before `deadline`, the same one the tool call ran under; a
+/// link that cannot be read in time, or at all, is reported in the body
+/// rather than failing the call.
+pub(crate) async fn tool_result_text<M: Mcp + ?Sized>(
+    mcp: &M,
+    result: &ToolCallResult,
+    artifacts_dir: Option<&Path>,
+    deadline: tokio::time::Instant,
+) -> String {
+    let mut parts = Vec::new();
+    for content in &result.content {
+        match content {
+            ToolContent::Text { text } => parts.push(text.clone()),
+            ToolContent::ResourceLink(link) => {
+                let read = tokio::time::timeout_at(deadline, mcp.read_resource(&link.uri))
+                    .await
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out")));
+                match read {
+                    Ok(read) => parts.extend(
+                        read.contents
+                            .iter()
+                            .map(|contents| describe(contents, artifacts_dir)),
+                    ),
+                    Err(e) => {
+                        warn!(uri = %link.uri, "failed to read linked MCP resource: {e:#}");
+                        parts.push(format!("[resource {}: read failed: {e}]", link.uri));
+                    }
+                }
+            }
+            ToolContent::Resource { resource } => parts.push(describe(resource, artifacts_dir)),
+            ToolContent::Image { .. } | ToolContent::Unknown(_) => {}
+        }
+    }
+    parts.join("\n")
+}
+
+/// Keep `contents` as an artifact if it is an image, then render it for
+/// the model.
+fn describe(contents: &ResourceContents, artifacts_dir: Option<&Path>) -> String {
+    let is_image = contents
+        .mime_type
+        .as_deref()
+        .is_some_and(|mime| mime.starts_with("image/"));
+    let dir = artifacts_dir.filter(|_| is_image);
+    let saved = dir.and_then(|dir| match persist_resource_artifact(dir, contents) {
+        Ok(path) => Some(path),
+        Err(e) => {
+            warn!(
+                uri = %contents.uri,
+                ?dir,
+                "failed to persist MCP resource artifact (non-fatal): {e}"
+            );
+            None
+        }
+    });
+    if let Some(text) = &contents.text {
+        return text.clone();
+    }
+    let mime = contents
+        .mime_type
+        .as_deref()
+        .unwrap_or("application/octet-stream");
+    match saved.as_deref().and_then(Path::file_name) {
+        Some(file) => format!(
+            "[resource {} ({mime}): saved as {}]",
+            contents.uri,
+            file.to_string_lossy()
+        ),
+        None => format!("[resource {} ({mime})]", contents.uri),
+    }
+}
+
+/// Write one resource's contents to `artifacts_dir` as
+/// `resource_<uuid>.<ext>`, next to a `resource_<uuid>.meta.json` naming
+/// its URI and MIME type. Binary contents are base64-decoded first.
+/// Returns the contents file's path.
+pub(crate) fn persist_resource_artifact(
+    artifacts_dir: &Path,
+    contents: &ResourceContents,
+) -> std::io::Result<PathBuf> {
+    use base64::Engine as _;
+
+    let bytes = match (&contents.text, &contents.blob) {
+        (Some(text), _) => text.as_bytes().to_vec(),
+        (None, Some(blob)) => base64::engine::general_purpose::STANDARD
+            .decode(blob)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?,
+        (None, None) => Vec::new(),
+    };
+
+    std::fs::create_dir_all(artifacts_dir)?;
+    let stem = format!("resource_{}", uuid::Uuid::new_v4());
+    let path = artifacts_dir.join(format!("{stem}.{}", extension(contents)));
+    std::fs::write(&path, bytes)?;
+
+    let meta = serde_json::json!({
+        "uri": contents.uri,
+        "mime_type": contents.mime_type,
+    });
+    let meta_bytes = serde_json::to_vec_pretty(&meta).map_err(std::io::Error::other)?;
+    std::fs::write(artifacts_dir.join(format!("{stem}.meta.json")), meta_bytes)?;
+
+    Ok(path)
+}
+
+fn extension(contents: &ResourceContents) -> &'static str {
+    match contents.mime_type.as_deref() {
+        Some("image/png") => "png",
+        Some("image/jpeg") => "jpg",
+        Some("application/json") => "json",
+        Some(mime) if mime.starts_with("text/") => "txt",
+        _ if contents.text.is_some() => "txt",
+        _ => "bin",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::test_stubs::StaticMcp;
+    use clickweave_mcp::{ReadResourceResult, Resource};
+
+    /// Serves `ax://tree` as text and never answers for `ax://stalled`;
+    /// every other URI fails.
+    struct ResourceMcp(StaticMcp);
+
+    impl Mcp for ResourceMcp {
+        async fn call_tool(
+            &self,
+            name: &str,
+            arguments: Option<serde_json::Value>,
+        ) -> anyhow::Result<ToolCallResult> {
+            self.0.call_tool(name, arguments).await
+        }
+
+        fn has_tool(&self, name: &str) -> bool {
+            self.0.has_tool(name)
+        }
+
+        fn tools_as_openai(&self) -> Vec<serde_json::Value> {
+            self.0.tools_as_openai()
+        }
+
+        async fn refresh_server_tool_list(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn read_resource(&self, uri: &str) -> anyhow::Result<ReadResourceResult> {
+            if uri == "ax://stalled" {
+                std::future::pending::<()>().await;
+            }
+            anyhow::ensure!(uri == "ax://tree", "no such resource");
+            Ok(ReadResourceResult {
+                contents: vec![ResourceContents {
+                    uri: uri.to_string(),
+                    mime_type: Some("text/plain".to_string()),
+                    text: Some("uid=a1g3 button \"OK\"".to_string()),
+                    blob: None,
+                }],
+            })
+        }
+    }
+
+    fn link(uri: &str) -> ToolContent {
+        ToolContent::ResourceLink(Resource {
+            uri: uri.to_string(),
+            name: "snapshot".to_string(),
+            description: None,
+            mime_type: None,
+            size: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn linked_and_embedded_resources_reach_the_model_and_only_images_are_saved() {
+        let mcp = ResourceMcp(StaticMcp::with_tools(&[]));
+        let dir = tempfile::tempdir().unwrap();
+        let result = ToolCallResult {
+            content: vec![
+                ToolContent::Text {
+                    text: "snapshot taken".to_string(),
+                },
+                link("ax://tree"),
+                ToolContent::Resource {
+                    resource: ResourceContents {
+                        uri: "screen://main".to_string(),
+                        mime_type: Some("image/png".to_string()),
+                        text: None,
+                        blob: Some("iVBORw0KGgo=".to_string()),
+                    },
+                },
+                link("ax://gone"),
+            ],
+            is_error: None,
+            structured_content: None,
+        };
+
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+        let text = tool_result_text(&mcp, &result, Some(dir.path()), deadline).await;
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "snapshot taken");
+        assert_eq!(lines[1], "uid=a1g3 button \"OK\"");
+        assert!(
+            lines[2].starts_with("[resource screen://main (image/png): saved as resource_"),
+            "{text}"
+        );
+        assert!(
+            lines[3].starts_with("[resource ax://gone: read failed"),
+            "{text}"
+        );
+
+        let files: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        let mut extensions: Vec<&str> =
+            files.iter().map(|f| f.split_once('.').unwrap().1).collect();
+        extensions.sort();
+        // The text resource reaches the model but is not stored.
+        assert_eq!(extensions, ["meta.json", "png"]);
+        let png = files.iter().find(|f| f.ends_with(".png")).unwrap();
+        let bytes = std::fs::read(dir.path().join(png)).unwrap();
+        assert_eq!(&bytes[..4], b"\x89PNG");
+    }
+
+    #[tokio::test]
+    async fn a_stalled_read_gives_up_at_the_deadline() {
+        let mcp = ResourceMcp(StaticMcp::with_tools(&[]));
+        let result = ToolCallResult {
+            content: vec![link("ax://stalled"), link("ax://tree")],
+            is_error: None,
+            structured_content: None,
+        };
+
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(50);
+        let text = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            tool_result_text(&mcp, &result, None, deadline),
+        )
+        .await
+        .expect("reads must not outlive the deadline");
+
+        assert!(
+            text.starts_with("[resource ax://stalled: read failed: timed out]"),
+            "{text}"
+        );
+    }
+}
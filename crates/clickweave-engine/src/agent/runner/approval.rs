@@ -259,6 +259,165 @@ impl StateRunner {
         }
     }
 
+    /// Send a `propose_plan` proposal to the operator and apply their
+    /// decision. An approved plan (as edited) replaces `task_state.plan`
+    /// and is seeded onto the subgoal stack; a rejection leaves the task
+    /// state alone and tells the LLM why.
+    ///
+    /// Mirrors `request_approval`: with no event channel attached there
+    /// is nobody to ask, so the plan is approved as proposed; a channel
+    /// that closes mid-request halts the run.
+    pub(super) async fn review_plan(
+        &mut self,
+        subgoals: &[crate::agent::plan::PlannedSubgoal],
+    ) -> TurnOutcome {
+        use crate::agent::plan::{PlanDecision, tool_result_body};
+
+        if !self.config.plan_first {
+            return TurnOutcome::Replan {
+                reason: "propose_plan is only available in plan-first mode".to_string(),
+            };
+        }
+        let revision = !self.task_state.plan.is_empty();
+        let decision = match &self.event_tx {
+            Some(tx) => {
+                let (reply_tx, reply_rx) = oneshot::channel();
+                let request = RunnerOutput::PlanApproval {
+                    request: PlanApprovalRequest {
+                        step_index: self.step_index,
+                        subgoals: subgoals.to_vec(),
+                        revision,
+                    },
+                    reply: reply_tx,
+                };
+                if tx.send(request).await.is_err() {
+                    warn!("state-spine: plan approval send failed");
+                    return TurnOutcome::ApprovalUnavailable;
+                }
+                match reply_rx.await {
+                    Ok(decision) => decision,
+                    Err(_) => {
+                        warn!("state-spine: plan approval channel closed");
+                        return TurnOutcome::ApprovalUnavailable;
+                    }
+                }
+            }
+            None => {
+                debug!("state-spine: no operator attached — plan approved as proposed");
+                PlanDecision::Approve {
+                    subgoals: Vec::new(),
+                }
+            }
+        };
+
+        let (decision, edited) = match decision {
+            PlanDecision::Approve { subgoals: edited } if !edited.is_empty() => {
+                let changed = edited.as_slice() != subgoals;
+                (PlanDecision::Approve { subgoals: edited }, changed)
+            }
+            PlanDecision::Approve { .. } => (
+                PlanDecision::Approve {
+                    subgoals: subgoals.to_vec(),
+                },
+                false,
+            ),
+            rejected @ PlanDecision::Reject { .. } => (rejected, false),
+        };
+        if let PlanDecision::Approve { subgoals: approved } = &decision {
+            tracing::info!(
+                subgoals = approved.len(),
+                revision,
+                edited,
+                "state-spine: plan approved"
+            );
+            self.seed_approved_plan(approved);
+            self.emit_event(AgentEvent::TaskStateChanged {
+                run_id: self.run_id,
+                task_state: self.task_state.clone(),
+            })
+            .await;
+        }
+        let body = tool_result_body(&decision, edited);
+        self.emit_event(AgentEvent::PlanReviewed {
+            run_id: self.run_id,
+            step_index: self.step_index,
+            proposed: subgoals.to_vec(),
+            decision,
+            revision,
+        })
+        .await;
+        TurnOutcome::PlanReviewed { body }
+    }
+
+    /// Put an `ask_user` question to the operator and return the tool
+    /// result for the LLM. Falls back to a "no human available" body when
+    /// the run is headless (`ask_user_timeout == None` or no event
+    /// channel), when the host drops the question, or after the timeout.
+    /// The wait also ends at the run deadline and on a suspend or stop
+    /// request; the run loop then halts or suspends at the next boundary,
+    /// and a stop returns `Cancelled` straight away. The resolution is
+    /// recorded as `UserQuestionAnswered` either way.
+    pub(super) async fn ask_user(&self, question: &str, choices: &[String]) -> TurnOutcome {
+        use crate::agent::ask_user::{AskUserOutcome, tool_result_body};
+
+        let (outcome, answer) = match (&self.event_tx, self.config.ask_user_timeout) {
+            (Some(tx), Some(timeout)) if !tx.is_closed() => {
+                let (reply_tx, reply_rx) = oneshot::channel();
+                let request = RunnerOutput::UserQuestion {
+                    question: UserQuestion {
+                        step_index: self.step_index,
+                        question: question.to_string(),
+                        choices: choices.to_vec(),
+                    },
+                    reply: reply_tx,
+                };
+                if tx.send(request).await.is_err() {
+                    (AskUserOutcome::NoHumanAvailable, None)
+                } else {
+                    let mut wait_until = tokio::time::Instant::now() + timeout;
+                    if let Some(deadline) = self.run_deadline {
+                        wait_until = wait_until.min(deadline);
+                    }
+                    tokio::select! {
+                        reply = tokio::time::timeout_at(wait_until, reply_rx) => match reply {
+                            Ok(Ok(answer)) => (AskUserOutcome::Answered, Some(answer)),
+                            Ok(Err(_)) => (AskUserOutcome::NoHumanAvailable, None),
+                            Err(_) => {
+                                warn!(?timeout, "state-spine: ask_user timed out");
+                                (AskUserOutcome::TimedOut, None)
+                            }
+                        },
+                        _ = token_signalled(self.suspend_signal.clone()) => {
+                            (AskUserOutcome::Interrupted, None)
+                        }
+                        _ = token_signalled(self.cancel_signal.clone()) => {
+                            (AskUserOutcome::Interrupted, None)
+                        }
+                    }
+                }
+            }
+            _ => (AskUserOutcome::NoHumanAvailable, None),
+        };
+
+        self.emit_event(AgentEvent::UserQuestionAnswered {
+            run_id: self.run_id,
+            step_index: self.step_index,
+            question: question.to_string(),
+            choices: choices.to_vec(),
+            outcome,
+            answer: answer.clone(),
+        })
+        .await;
+        if self.cancel_requested() {
+            return TurnOutcome::Cancelled {
+                tool_name: crate::agent::ask_user::TOOL_NAME.to_string(),
+            };
+        }
+        TurnOutcome::UserAnswer {
+            body: tool_result_body(outcome, answer.as_deref()),
+        }
+    }
+
     /// Verify an agent-reported completion against a fresh screenshot via
     /// the VLM. Port of the legacy `AgentRunner::verify_completion`.
     ///
@@ -280,10 +439,10 @@ impl StateRunner {
         mcp: &M,
     ) -> Option<(String, String)> {
         use crate::agent::completion_check::{
-            VlmVerdict, build_completion_prompt, parse_yes_no, persist_verification_artifacts,
-            pick_completion_screenshot_scope,
+            VlmVerdict, annotate_cited_mark, build_completion_prompt, completion_mark_candidates,
+            parse_yes_no, persist_verification_artifacts, pick_completion_screenshot_scope,
         };
-        use crate::executor::screenshot::capture_screenshot_for_vlm;
+        use crate::executor::screenshot::capture_marked_screenshot_for_vlm;
 
         let vision = self.vision.as_ref()?.clone();
 
@@ -292,7 +451,14 @@ impl StateRunner {
         // `maybe_cdp_connect`, so `connected_app` now flows through to
         // the scope picker (matching legacy behaviour).
         let scope = pick_completion_screenshot_scope(self.cdp_state.connected_app.as_ref());
-        let Some((prepared_b64, mime)) = capture_screenshot_for_vlm(mcp, scope.clone()).await
+        let candidates = self
+            .world_model
+            .elements
+            .as_ref()
+            .map(|elements| completion_mark_candidates(&elements.value))
+            .unwrap_or_default();
+        let Some((prepared_b64, mime, marks)) =
+            capture_marked_screenshot_for_vlm(mcp, scope.clone(), &candidates).await
         else {
             warn!(
                 scope = ?scope,
@@ -302,10 +468,18 @@ impl StateRunner {
         };
 
         let messages = vec![Message::user_with_images(
-            build_completion_prompt(goal, summary),
+            build_completion_prompt(goal, summary, &marks),
             vec![(prepared_b64.clone(), mime)],
         )];
-        let raw_reply = match vision.chat_boxed(&messages, None).await {
+        let options = ChatOptions {
+            escalate: self.config.llm_escalation.completion_check,
+            temperature: self.config.completion_check_temperature,
+            ..ChatOptions::default()
+        };
+        let raw_reply = match vision
+            .chat_with_options_boxed(&messages, None, &options)
+            .await
+        {
             Ok(resp) => resp
                 .choices
                 .first()
@@ -317,7 +491,7 @@ impl StateRunner {
             }
         };
         let reply = match raw_reply {
-            Some(r) if !r.trim().is_empty() => r,
+            Some(r) if !r.trim().is_empty() => annotate_cited_mark(r, &marks),
             _ => {
                 warn!("state-spine: VLM returned empty reply — skipping completion check");
                 return None;
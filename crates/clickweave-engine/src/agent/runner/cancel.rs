@@ -0,0 +1,79 @@
+use super::*;
+
+/// Reason recorded for a tool call abandoned because the run was stopped.
+pub(crate) const RUN_STOPPED_REASON: &str = "run stopped";
+
+impl StateRunner {
+    /// Attach a stop signal. Cancelling the token abandons an in-flight
+    /// tool call (the MCP server is sent `notifications/cancelled` for
+    /// it), records an `AgentEvent::ToolCallCancelled`, and halts with
+    /// `TerminalReason::Cancelled`. Between turns the run halts at the
+    /// next boundary. Unlike the suspend signal, nothing is snapshotted.
+    pub fn with_cancel_signal(mut self, token: tokio_util::sync::CancellationToken) -> Self {
+        self.cancel_signal = Some(token);
+        self
+    }
+
+    pub(super) fn cancel_requested(&self) -> bool {
+        self.cancel_signal
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+    }
+
+    pub(super) fn halt_on_cancel(&mut self) {
+        warn!("state-spine: run stopped by the host — halting");
+        self.state.terminal_reason = Some(TerminalReason::Cancelled {
+            steps_executed: self.state.steps.len(),
+        });
+    }
+
+    /// Run `call` unless the stop signal fires first; `None` means it was
+    /// abandoned. Either way, a call that does not run to completion —
+    /// including one whose whole run future the host drops — is recorded
+    /// as `ToolCallCancelled`.
+    pub(super) async fn call_unless_cancelled<T>(
+        &self,
+        tool_name: &str,
+        call: impl std::future::Future<Output = T>,
+    ) -> Option<T> {
+        let mut guard = CancelledCallGuard {
+            armed: true,
+            event_tx: self.event_tx.clone(),
+            run_id: self.run_id,
+            step_index: self.step_index,
+            tool_name: tool_name.to_string(),
+        };
+        let result = tokio::select! {
+            _ = token_signalled(self.cancel_signal.clone()) => None,
+            result = call => Some(result),
+        };
+        guard.armed = result.is_none();
+        result
+    }
+}
+
+/// Emits `ToolCallCancelled` when dropped. `Drop` cannot await, so the
+/// event is sent with `try_send`; a full channel loses it.
+struct CancelledCallGuard {
+    armed: bool,
+    event_tx: Option<mpsc::Sender<RunnerOutput>>,
+    run_id: uuid::Uuid,
+    step_index: usize,
+    tool_name: String,
+}
+
+impl Drop for CancelledCallGuard {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        warn!(tool = %self.tool_name, "state-spine: tool call cancelled");
+        let Some(tx) = &self.event_tx else { return };
+        let _ = tx.try_send(RunnerOutput::Event(AgentEvent::ToolCallCancelled {
+            run_id: self.run_id,
+            step_index: self.step_index,
+            tool_name: std::mem::take(&mut self.tool_name),
+            reason: RUN_STOPPED_REASON.to_string(),
+        }));
+    }
+}
@@ -0,0 +1,90 @@
+use super::*;
+use crate::agent::context::{ContextFit, estimate_messages_tokens, estimate_tokens};
+
+/// Length rejections one turn may answer by re-compacting before the
+/// error ends the run.
+pub(super) const MAX_OVERFLOW_RETRIES: usize = 3;
+
+impl StateRunner {
+    /// Size the compaction budget for the model behind `llm`: its context
+    /// length less the request's tool schemas and the reply reserve.
+    /// Without a known context length the fixed default budget applies.
+    pub(super) async fn fit_context<B>(
+        &self,
+        llm: &B,
+        system_prompt: &str,
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+    ) -> ContextFit
+    where
+        B: ChatBackend + ?Sized,
+    {
+        let context_length = match llm.fetch_model_info().await {
+            Ok(info) => info.and_then(|info| info.effective_context_length()),
+            Err(e) => {
+                debug!(error = %e, "Model info unavailable; using the default context budget");
+                None
+            }
+        };
+        let Some(context_length) = context_length else {
+            return ContextFit::unknown(CompactBudget::default().max_tokens);
+        };
+
+        // Schema-constrained turns carry the tool list inside
+        // `response_format` instead of `tools`.
+        let schemas = match (tools, &options.response_format) {
+            (Some(tools), _) => serde_json::to_string(tools).unwrap_or_default(),
+            (None, Some(format)) => format.to_string(),
+            (None, None) => String::new(),
+        };
+        let tool_tokens = match llm.count_tokens(&schemas).await {
+            Ok(Some(count)) => count,
+            _ => estimate_tokens(&schemas),
+        };
+        let mut fit = ContextFit::new(
+            context_length as usize,
+            tool_tokens,
+            self.config.response_reserve_tokens,
+        );
+        if let Ok(Some(actual)) = llm.count_tokens(system_prompt).await {
+            fit.calibrate(estimate_tokens(system_prompt), actual);
+        }
+        debug!(
+            context_length,
+            tool_tokens,
+            budget_tokens = fit.budget_tokens(),
+            "Fitted compaction budget to the model's context window"
+        );
+        fit
+    }
+
+    /// The endpoint refused the transcript as too long: tighten `fit`
+    /// below it and compact again. Returns false when compaction frees no
+    /// more room, so retrying would only be refused again.
+    pub(super) async fn recompact_after_overflow(
+        &self,
+        fit: &mut ContextFit,
+        loop_ctx: &mut RunLoopContext,
+    ) -> bool {
+        let before = estimate_messages_tokens(&loop_ctx.messages);
+        fit.shrink_below(before);
+        loop_ctx.budget.max_tokens = fit.budget_tokens();
+        loop_ctx.messages = compact(std::mem::take(&mut loop_ctx.messages), &loop_ctx.budget);
+        let after = estimate_messages_tokens(&loop_ctx.messages);
+        if after >= before {
+            return false;
+        }
+        warn!(
+            before,
+            after, "LLM rejected the transcript as too long; re-compacted"
+        );
+        self.emit_event(AgentEvent::Warning {
+            message: format!(
+                "The model's context window was exceeded; compacted the transcript \
+                 from ~{before} to ~{after} tokens and retrying"
+            ),
+        })
+        .await;
+        true
+    }
+}
@@ -0,0 +1,207 @@
+use super::*;
+
+use crate::agent::delegate::{self, DelegationRequest};
+
+impl StateRunner {
+    /// Run `request.subgoal` in a child runner and return its report as
+    /// the `delegate_subgoal` tool result.
+    ///
+    /// The child starts from a copy of this runner's world model and CDP
+    /// bookkeeping, shares the trace graph (its nodes chain off
+    /// `last_node_id` and carry the child's run id), and gets the same
+    /// permission policy and approval gate. It runs headless — no
+    /// `ask_user` round-trips, no plan-first — and its events are
+    /// recorded to its own `events.jsonl` under this run's execution
+    /// directory rather than forwarded to the host. A suspend request
+    /// stops the child at its next turn boundary; its report then lands
+    /// in this run's snapshot as the tool result.
+    pub(super) async fn delegate_subgoal<B, M>(
+        &mut self,
+        llm: &B,
+        mcp: &M,
+        mcp_tools: &[Value],
+        request: &DelegationRequest,
+        deadline: Option<tokio::time::Instant>,
+    ) -> TurnOutcome
+    where
+        B: ChatBackend + ?Sized,
+        M: Mcp + ?Sized,
+    {
+        if self.config.max_sub_agent_depth == 0 {
+            return TurnOutcome::Replan {
+                reason: format!("{} is not available at this depth", delegate::TOOL_NAME),
+            };
+        }
+        let available: Vec<String> = mcp_tools
+            .iter()
+            .filter_map(|t| t.pointer("/function/name").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect();
+        let scope = match delegate::resolve_tool_scope(&request.tools, &available) {
+            Ok(scope) => scope,
+            Err(reason) => return TurnOutcome::Replan { reason },
+        };
+
+        let child_run_id = uuid::Uuid::new_v4();
+        let step_index = self.step_index;
+        self.emit_event(AgentEvent::SubAgentStarted {
+            run_id: self.run_id,
+            child_run_id,
+            step_index,
+            subgoal: request.subgoal.clone(),
+            tools: scope.clone(),
+        })
+        .await;
+
+        let mut child = self.build_sub_agent(child_run_id, request, scope.clone(), deadline);
+        let child_storage = self
+            .storage
+            .as_ref()
+            .and_then(|storage| {
+                storage
+                    .lock()
+                    .ok()?
+                    .sub_agent_storage(child_run_id)
+                    .map_err(|e| warn!(error = %e, "state-spine: sub-agent storage unavailable"))
+                    .ok()
+                    .flatten()
+            })
+            .map(|storage| Arc::new(std::sync::Mutex::new(storage)));
+        child.storage = child_storage.clone();
+        let (event_tx, mut event_rx) = mpsc::channel::<RunnerOutput>(64);
+        child.event_tx = Some(event_tx);
+
+        let child_tools = delegate::scoped_tools(mcp_tools, &scope);
+        let trace_graph = self.state.trace_graph.clone();
+        let anchor = self.state.last_node_id;
+        let nodes_before = trace_graph.nodes.len();
+        let run = async {
+            // Boxed: `run_inner` recursing into itself needs an indirection.
+            let result = Box::pin(child.run_inner(
+                llm,
+                mcp,
+                request.subgoal.clone(),
+                trace_graph,
+                child_tools,
+                anchor,
+            ))
+            .await;
+            child.event_tx = None;
+            result
+        };
+        let record = async {
+            while let Some(output) = event_rx.recv().await {
+                match output {
+                    RunnerOutput::Event(event) if event.is_transient() => {}
+                    RunnerOutput::Event(event) => {
+                        if let Some(storage) = &child_storage
+                            && let Ok(guard) = storage.lock()
+                        {
+                            let _ = guard.append_agent_event(&event);
+                        }
+                    }
+                    RunnerOutput::DrainBarrier { ack } => {
+                        let _ = ack.send(());
+                    }
+                    RunnerOutput::SkillProposalNeeded { .. }
+                    | RunnerOutput::UserQuestion { .. }
+                    | RunnerOutput::PlanApproval { .. } => {}
+                }
+            }
+        };
+        let (result, ()) = tokio::join!(run, record);
+
+        self.adopt_sub_agent_effects(&mut child, nodes_before).await;
+        let outcome = match result {
+            Ok(()) if delegate::halts_parent(child.state.terminal_reason.as_ref()) => {
+                TurnOutcome::ApprovalUnavailable
+            }
+            Ok(())
+                if matches!(
+                    child.state.terminal_reason,
+                    Some(TerminalReason::Cancelled { .. })
+                ) =>
+            {
+                TurnOutcome::Cancelled {
+                    tool_name: delegate::TOOL_NAME.to_string(),
+                }
+            }
+            Ok(()) => TurnOutcome::SubAgentFinished {
+                body: delegate::tool_result_body(child_run_id, &child.state),
+            },
+            Err(e) => {
+                warn!(error = %e, "state-spine: sub-agent run failed");
+                TurnOutcome::SubAgentFinished {
+                    body: delegate::failure_body(child_run_id, &format!("{e:#}")),
+                }
+            }
+        };
+        self.emit_event(AgentEvent::SubAgentFinished {
+            run_id: self.run_id,
+            child_run_id,
+            step_index,
+            completed: child.state.completed,
+            summary: child.state.summary.clone(),
+            steps_executed: child.state.steps.len(),
+        })
+        .await;
+        outcome
+    }
+
+    fn build_sub_agent(
+        &self,
+        child_run_id: uuid::Uuid,
+        request: &DelegationRequest,
+        scope: Vec<String>,
+        deadline: Option<tokio::time::Instant>,
+    ) -> StateRunner {
+        let mut config = self.config.clone();
+        config.max_steps = request.max_steps;
+        config.max_duration =
+            deadline.map(|d| d.saturating_duration_since(tokio::time::Instant::now()));
+        config.max_sub_agent_depth -= 1;
+        config.plan_first = false;
+        config.ask_user_timeout = None;
+        config.episodic_enabled = false;
+        config.skills_enabled = false;
+        config.stream_llm = false;
+
+        let mut child = StateRunner::new(request.subgoal.clone(), config)
+            .with_run_id(child_run_id)
+            .with_permissions(self.permissions.clone());
+        child.approval_gate = self.approval_gate.clone();
+        child.suspend_signal = self.suspend_signal.clone();
+        child.cancel_signal = self.cancel_signal.clone();
+        child.agent_system_prompt_override = self.agent_system_prompt_override.clone();
+        child.tool_scope = Some(scope);
+        child.world_model = self.world_model.clone();
+        child.cdp_state = self.cdp_state.clone();
+        child.seen_mcp_restarts = self.seen_mcp_restarts;
+        child.known_app_kinds = self.known_app_kinds.clone();
+        child.state.current_url = self.state.current_url.clone();
+        child.state.recent_destructive_tools = self.state.recent_destructive_tools.clone();
+        child
+    }
+
+    /// Take over what the child changed: the world it left behind, the
+    /// nodes it appended to the shared trace graph, and the destructive
+    /// streak (so the cap cannot be reset by delegating).
+    async fn adopt_sub_agent_effects(&mut self, child: &mut StateRunner, nodes_before: usize) {
+        let new_nodes: Vec<uuid::Uuid> = child.state.trace_graph.nodes[nodes_before..]
+            .iter()
+            .map(|n| n.id)
+            .collect();
+        self.state.trace_graph = std::mem::take(&mut child.state.trace_graph);
+        self.state.last_node_id = child.state.last_node_id;
+        for node_id in new_nodes {
+            self.record_produced_node_id(node_id);
+        }
+        self.world_model = std::mem::take(&mut child.world_model);
+        self.cdp_state = std::mem::take(&mut child.cdp_state);
+        self.seen_mcp_restarts = child.seen_mcp_restarts;
+        self.known_app_kinds = std::mem::take(&mut child.known_app_kinds);
+        self.state.current_url = child.state.current_url.clone();
+        self.state.recent_destructive_tools = child.state.recent_destructive_tools.clone();
+        self.emit_world_model_changed_for_recorded_step().await;
+    }
+}
@@ -1,6 +1,48 @@
 use super::*;
+use crate::agent::context::estimate_messages_tokens;
+use clickweave_llm::is_context_overflow;
 
 impl StateRunner {
+    /// Forward the MCP server's progress and log notifications onto the
+    /// run's event stream until the run ends.
+    fn start_mcp_notification_forwarder<M: Mcp + ?Sized>(
+        &self,
+        mcp: &M,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        let event_tx = self.event_tx.clone()?;
+        let notifications = mcp.notifications()?;
+        Some(tokio::spawn(
+            crate::executor::notifications::forward_notifications_as_agent_events(
+                notifications,
+                event_tx,
+            ),
+        ))
+    }
+
+    /// Drop what the primary MCP server held if it was restarted since the
+    /// last observe: the CDP connection and every page, window, and snapshot
+    /// read through it. Snapshot ids from the old process would otherwise
+    /// be sent to a server that never issued them.
+    pub(super) async fn reset_after_mcp_restart<M: Mcp + ?Sized>(&mut self, mcp: &M) {
+        let restarts = mcp.server_restarts();
+        let seen = self.seen_mcp_restarts.replace(restarts);
+        if seen.is_none_or(|seen| restarts <= seen) {
+            return;
+        }
+        warn!(
+            restarts,
+            "state-spine: MCP server restarted — resetting session state"
+        );
+        self.queue_invalidation(InvalidationEvent::McpServerRestarted);
+        self.cdp_state.take_connected();
+        self.emit_event(AgentEvent::Warning {
+            message: "MCP server restarted after a crash; the CDP session and \
+                      page/window snapshots were reset"
+                .to_string(),
+        })
+        .await;
+    }
+
     fn start_skill_watcher_if_enabled(&mut self) {
         if !self.skill_ctx.enabled
             || !self.config.skills_enabled
@@ -56,7 +98,7 @@ impl StateRunner {
         }
     }
 
-    fn initialize_run_loop(
+    pub(super) fn initialize_run_loop(
         &mut self,
         goal: &str,
         trace_graph: crate::agent::trace_graph::AgentTraceGraph,
@@ -67,8 +109,14 @@ impl StateRunner {
         // trace graph. `AgentState::new(trace_graph)` wipes steps/terminal_reason
         // so the same `StateRunner` could in theory be reused across runs,
         // though `self` is consumed by the public run wrapper.
+        // The page URL and destructive streak survive the reset: a
+        // sub-agent is seeded with its parent's before it starts.
+        let current_url = std::mem::take(&mut self.state.current_url);
+        let recent_destructive_tools = std::mem::take(&mut self.state.recent_destructive_tools);
         self.state = AgentState::new(trace_graph);
         self.state.last_node_id = anchor_node_id;
+        self.state.current_url = current_url;
+        self.state.recent_destructive_tools = recent_destructive_tools;
 
         // Build the system prompt from the raw openai-shaped tool list.
         // `build_system_prompt` expects `clickweave_mcp::Tool`; the raw
@@ -91,11 +139,16 @@ impl StateRunner {
             .collect();
 
         let initial_scope = self.compute_tools_in_scope(&advertised_tool_names);
+        let goal_text = if self.config.plan_first && self.task_state.plan.is_empty() {
+            format!("{goal}\n\n{}", crate::agent::plan::PLAN_FIRST_INSTRUCTION)
+        } else {
+            goal.to_string()
+        };
         let initial_user = build_user_turn_message_from_input(UserTurnMessageInput {
             wm: &self.world_model,
             ts: &self.task_state,
             current_step: 0,
-            observation_text: goal,
+            observation_text: &goal_text,
             retrieved: &[],
             applicable_skills: &[],
             tools_in_scope_names: &initial_scope,
@@ -108,6 +161,15 @@ impl StateRunner {
                 .iter()
                 .cloned()
                 .chain(crate::agent::prompt::pseudo_tools())
+                .chain(
+                    self.config
+                        .plan_first
+                        .then(crate::agent::prompt::propose_plan_tool),
+                )
+                .chain(
+                    (self.config.max_sub_agent_depth > 0)
+                        .then(crate::agent::prompt::delegate_subgoal_tool),
+                )
                 .collect(),
             advertised_tool_names,
             annotations_by_tool: build_annotations_index(mcp_tools),
@@ -115,6 +177,7 @@ impl StateRunner {
                 recent_n: self.config.recent_n,
                 ..CompactBudget::default()
             },
+            summary: summary::SummaryState::default(),
         }
     }
 
@@ -128,6 +191,7 @@ impl StateRunner {
     where
         M: Mcp + ?Sized,
     {
+        self.reset_after_mcp_restart(mcp).await;
         // Capture the pre-mirror world-model signatures so the
         // `WorldModelChanged` diff emitted by `run_turn` sees the
         // direct-observation writes below. Only seed the baseline when it is
@@ -365,6 +429,7 @@ impl StateRunner {
             step_index: step_idx,
             tool_name: tool_name.to_string(),
             summary: crate::agent::prompt::truncate_summary(body, 120),
+            model: self.turn_model.clone(),
         })
         .await;
         append_assistant_and_tool_result(
@@ -553,6 +618,7 @@ impl StateRunner {
             step_index: step_idx,
             tool_name: tool_name.to_string(),
             error: err_msg.clone(),
+            model: self.turn_model.clone(),
         })
         .await;
 
@@ -628,6 +694,102 @@ impl StateRunner {
         .await
     }
 
+    /// Plan-first mode: block state-changing tool calls until the
+    /// operator has approved a plan. Observation tools stay available so
+    /// the LLM can look around before proposing.
+    async fn guard_plan_first(
+        &mut self,
+        turn: &AgentTurn,
+        elements: &[CdpFindElementMatch],
+        loop_ctx: &mut RunLoopContext,
+        trackers: &mut RunLoopTrackers,
+    ) -> LoopStepFlow {
+        if !self.config.plan_first || !self.task_state.plan.is_empty() {
+            return LoopStepFlow::Dispatch;
+        }
+        // A sub-agent would act without a plan too, so delegation waits
+        // for approval like any state-changing tool.
+        let (tool_name, arguments, tool_call_id) = match &turn.action {
+            AgentAction::ToolCall {
+                tool_name,
+                arguments,
+                tool_call_id,
+            } => {
+                if is_observation_tool(tool_name, &loop_ctx.annotations_by_tool) {
+                    return LoopStepFlow::Dispatch;
+                }
+                (tool_name.as_str(), arguments.clone(), tool_call_id.as_str())
+            }
+            action @ AgentAction::DelegateSubgoal { .. } => {
+                let Some(call) = harness_round_trip_call(action) else {
+                    return LoopStepFlow::Dispatch;
+                };
+                call
+            }
+            _ => return LoopStepFlow::Dispatch,
+        };
+        warn!(tool = %tool_name, "state-spine: tool blocked until plan is approved");
+        self.record_blocked_tool_error(
+            loop_ctx,
+            trackers,
+            elements,
+            tool_name,
+            &arguments,
+            tool_call_id,
+            format!(
+                "Tool `{}` blocked: plan-first mode requires an approved plan. Call `{}` first.",
+                tool_name,
+                crate::agent::plan::TOOL_NAME
+            ),
+            "blocked: awaiting plan approval",
+            false,
+        )
+        .await
+    }
+
+    /// Sub-agents: block MCP tools outside the scope the parent granted.
+    /// The child only advertises its scoped tools, but an LLM can still
+    /// name any tool, so the allowlist is enforced here as well.
+    async fn guard_tool_scope(
+        &mut self,
+        turn: &AgentTurn,
+        elements: &[CdpFindElementMatch],
+        loop_ctx: &mut RunLoopContext,
+        trackers: &mut RunLoopTrackers,
+    ) -> LoopStepFlow {
+        let Some(scope) = &self.tool_scope else {
+            return LoopStepFlow::Dispatch;
+        };
+        let AgentAction::ToolCall {
+            tool_name,
+            arguments,
+            tool_call_id,
+        } = &turn.action
+        else {
+            return LoopStepFlow::Dispatch;
+        };
+        if tool_name == crate::agent::time_oracle::TOOL_NAME || scope.contains(tool_name) {
+            return LoopStepFlow::Dispatch;
+        }
+        warn!(tool = %tool_name, "state-spine: tool outside sub-agent scope blocked");
+        self.record_blocked_tool_error(
+            loop_ctx,
+            trackers,
+            elements,
+            tool_name,
+            arguments,
+            tool_call_id,
+            format!(
+                "Tool `{}` is outside this sub-agent's scope. Allowed tools: {}",
+                tool_name,
+                scope.join(", ")
+            ),
+            "blocked: outside sub-agent tool scope",
+            false,
+        )
+        .await
+    }
+
     async fn guard_coordinate_primitive<M>(
         &mut self,
         turn: &AgentTurn,
@@ -791,9 +953,11 @@ impl StateRunner {
             TurnOutcome::ToolSuccess {
                 tool_name,
                 tool_body,
+                structured,
             } => {
                 self.handle_tool_success_outcome(
                     mcp, mcp_tools, loop_ctx, trackers, turn, elements, tool_name, tool_body,
+                    structured,
                 )
                 .await
             }
@@ -807,6 +971,21 @@ impl StateRunner {
                 reset_no_progress_tracking(&mut trackers.last_action, &mut trackers.recent_actions);
                 LoopStepFlow::Continue
             }
+            TurnOutcome::UserAnswer { body }
+            | TurnOutcome::PlanReviewed { body }
+            | TurnOutcome::SubAgentFinished { body } => {
+                self.handle_harness_answer_outcome(trackers, turn, elements, body)
+                    .await
+            }
+            TurnOutcome::ApprovalUnavailable => {
+                warn!("state-spine: approval system unavailable — terminating");
+                self.state.terminal_reason = Some(TerminalReason::ApprovalUnavailable);
+                LoopStepFlow::Break
+            }
+            TurnOutcome::Cancelled { .. } => {
+                self.halt_on_cancel();
+                LoopStepFlow::Break
+            }
         };
 
         if matches!(flow, LoopStepFlow::Continue) {
@@ -822,7 +1001,11 @@ impl StateRunner {
         match outcome {
             TurnOutcome::ToolError { tool_name, error } => {
                 self.last_failed_tool_name = Some(tool_name.clone());
-                self.last_failed_error_kind = Some(error.clone());
+                self.last_failed_error_kind = Some(if is_timeout_error(error) {
+                    TIMEOUT_ERROR_KIND.to_string()
+                } else {
+                    error.clone()
+                });
             }
             TurnOutcome::ToolSuccess { .. } => {
                 self.clear_last_failure_tracking();
@@ -841,6 +1024,10 @@ impl StateRunner {
                 TurnOutcome::ToolError { .. } => "error",
                 TurnOutcome::Done { .. } => "done",
                 TurnOutcome::Replan { .. } => "replan",
+                TurnOutcome::UserAnswer { .. } | TurnOutcome::PlanReviewed { .. } => "answered",
+                TurnOutcome::SubAgentFinished { .. } => "delegated",
+                TurnOutcome::ApprovalUnavailable => "error",
+                TurnOutcome::Cancelled { .. } => "cancelled",
             };
             self.recovery_actions_accumulator
                 .push(crate::agent::episodic::types::CompactAction {
@@ -908,6 +1095,7 @@ impl StateRunner {
         elements: &[CdpFindElementMatch],
         tool_name: String,
         tool_body: String,
+        structured: Option<Value>,
     ) -> LoopStepFlow
     where
         M: Mcp + ?Sized,
@@ -934,6 +1122,7 @@ impl StateRunner {
             tool_name: tool_name.clone(),
             arguments: arguments.clone(),
             result_text: tool_body.clone(),
+            result_structured: structured,
             world_model_pre: self.pre_dispatch_snapshot.take().unwrap_or_else(|| {
                 crate::agent::step_record::WorldModelSnapshot::from_world_model(&self.world_model)
             }),
@@ -957,6 +1146,7 @@ impl StateRunner {
             step_index: step_idx,
             tool_name: tool_name.clone(),
             summary: crate::agent::prompt::truncate_summary(&tool_body, 120),
+            model: self.turn_model.clone(),
         })
         .await;
         if unverified_side_effect {
@@ -1052,14 +1242,19 @@ impl StateRunner {
             step_index: step_idx,
             tool_name: tool_name.clone(),
             error: error.clone(),
+            model: self.turn_model.clone(),
         })
         .await;
 
-        let looped = matches!(
-            trackers.last_failure.as_ref(),
-            Some((prev_tool, prev_args, prev_err))
-                if prev_tool == &tool_name && prev_args == arguments && prev_err == &error
-        );
+        // A repeated timeout is not evidence of a deterministic loop — the
+        // same call may well succeed once the app settles — so timeouts
+        // only count against `max_consecutive_errors`.
+        let looped = !is_timeout_error(&error)
+            && matches!(
+                trackers.last_failure.as_ref(),
+                Some((prev_tool, prev_args, prev_err))
+                    if prev_tool == &tool_name && prev_args == arguments && prev_err == &error
+            );
         if looped {
             warn!(
                 tool = %tool_name,
@@ -1089,6 +1284,21 @@ impl StateRunner {
         LoopStepFlow::Continue
     }
 
+    /// Record a `DeadlineExceeded` terminal reason. The caller breaks out
+    /// of the loop; the post-loop terminal write persists it.
+    fn halt_on_deadline(&mut self, started: tokio::time::Instant) {
+        let elapsed_secs = started.elapsed().as_secs();
+        let max_duration_secs = self.config.max_duration.unwrap_or_default().as_secs();
+        warn!(
+            elapsed_secs,
+            max_duration_secs, "state-spine: run deadline exceeded — halting"
+        );
+        self.state.terminal_reason = Some(TerminalReason::DeadlineExceeded {
+            elapsed_secs,
+            max_duration_secs,
+        });
+    }
+
     async fn handle_done_outcome<M>(&mut self, goal: &str, mcp: &M, summary: String) -> LoopStepFlow
     where
         M: Mcp + ?Sized,
@@ -1118,6 +1328,39 @@ impl StateRunner {
         LoopStepFlow::Break
     }
 
+    /// Record a resolved harness round-trip (`ask_user`, `propose_plan`,
+    /// `delegate_subgoal`) as a step so the exchange survives in the
+    /// trace, then hand the answer back to the LLM as the tool result.
+    async fn handle_harness_answer_outcome(
+        &mut self,
+        trackers: &mut RunLoopTrackers,
+        turn: &AgentTurn,
+        elements: &[CdpFindElementMatch],
+        body: String,
+    ) -> LoopStepFlow {
+        let Some((tool_name, arguments, tool_call_id)) = harness_round_trip_call(&turn.action)
+        else {
+            unreachable!("harness answer outcome implies a round-trip pseudo-tool action");
+        };
+        let step_idx = self.push_tool_step(
+            elements,
+            tool_name,
+            &arguments,
+            tool_call_id,
+            StepOutcome::Success(body.clone()),
+        );
+        self.emit_event(AgentEvent::StepCompleted {
+            step_index: step_idx,
+            tool_name: tool_name.to_string(),
+            summary: crate::agent::prompt::truncate_summary(&body, 120),
+            model: self.turn_model.clone(),
+        })
+        .await;
+        trackers.previous_result = Some(body);
+        reset_no_progress_tracking(&mut trackers.last_action, &mut trackers.recent_actions);
+        LoopStepFlow::Continue
+    }
+
     fn append_action_result_to_history(
         &mut self,
         loop_ctx: &mut RunLoopContext,
@@ -1143,6 +1386,20 @@ impl StateRunner {
                     .messages
                     .push(Message::assistant(format!("replan: {}", reason)));
             }
+            AgentAction::AskUser { .. }
+            | AgentAction::ProposePlan { .. }
+            | AgentAction::DelegateSubgoal { .. } => {
+                if let Some((tool_name, arguments, tool_call_id)) = harness_round_trip_call(action)
+                {
+                    append_assistant_and_tool_result(
+                        &mut loop_ctx.messages,
+                        tool_name,
+                        &arguments,
+                        tool_call_id,
+                        trackers.previous_result.as_deref(),
+                    );
+                }
+            }
             AgentAction::AgentDone { .. } | AgentAction::InvokeSkill { .. } => {}
             AgentAction::SkillPatch {
                 patch,
@@ -1204,6 +1461,7 @@ impl StateRunner {
         M: Mcp + ?Sized,
     {
         self.start_skill_watcher_if_enabled();
+        let notification_forwarder = self.start_mcp_notification_forwarder(mcp);
         // Drain queued episodic writes on *every* exit path,
         // including the early `?` returns from chat/parse failures.
         // Without this, a recovery write queued moments before an LLM
@@ -1237,13 +1495,27 @@ impl StateRunner {
         if let Some(handle) = self.skill_watcher_handle.take() {
             handle.abort();
         }
+        if let Some(handle) = notification_forwarder {
+            handle.abort();
+        }
         match result {
             Ok(()) => Ok(self.state),
             Err(e) => Err(e),
         }
     }
 
-    async fn run_inner<B, M>(
+    /// Whether this turn's LLM call should ask for the escalation route,
+    /// per `AgentConfig::llm_escalation`.
+    pub(super) fn escalate_turn(&self) -> bool {
+        let policy = &self.config.llm_escalation;
+        policy
+            .after_consecutive_errors
+            .is_some_and(|n| n > 0 && self.consecutive_errors >= n)
+            || (policy.while_recovering
+                && self.task_state.phase == crate::agent::phase::Phase::Recovering)
+    }
+
+    pub(super) async fn run_inner<B, M>(
         &mut self,
         llm: &B,
         mcp: &M,
@@ -1256,13 +1528,69 @@ impl StateRunner {
         B: ChatBackend + ?Sized,
         M: Mcp + ?Sized,
     {
-        let mut loop_ctx = self.initialize_run_loop(&goal, trace_graph, &mcp_tools, anchor_node_id);
-        let mut trackers = RunLoopTrackers::default();
-
-        for _step_index in 0..self.config.max_steps {
+        // A resumed run restores its transcript and counters from the
+        // snapshot; the caller-provided goal / graph / anchor are ignored.
+        let mut goal = goal;
+        let mut prior_active = std::time::Duration::ZERO;
+        let json_turns = llm.tool_mode() == clickweave_llm::ToolMode::JsonSchema;
+        let (mut loop_ctx, mut trackers) = match self.pending_resume.take() {
+            Some(snapshot) => {
+                goal = snapshot.goal.clone();
+                prior_active = std::time::Duration::from_secs(snapshot.active_secs);
+                self.resume_run_loop(*snapshot, &mcp_tools)
+            }
+            None => (
+                self.initialize_run_loop(&goal, trace_graph, &mcp_tools, anchor_node_id),
+                RunLoopTrackers::default(),
+            ),
+        };
+        // The system prompt is rebuilt on resume, so the reply-format
+        // instruction is added to it on both paths.
+        if json_turns {
+            structured::append_instruction(&mut loop_ctx.messages[0]);
+        }
+        // Schema-constrained endpoints get the tool list as a JSON Schema
+        // instead of `tools`; the list is fixed for the run.
+        let (chat_tools, mut chat_options) = if json_turns {
+            let schema = structured::agent_turn_schema(&loop_ctx.tools);
+            (
+                None,
+                ChatOptions::default().with_json_schema(structured::SCHEMA_NAME, schema),
+            )
+        } else {
+            (Some(loop_ctx.tools.clone()), ChatOptions::default())
+        };
+        let system_prompt = loop_ctx.messages[0].content_text().unwrap_or_default();
+        let mut fit = self
+            .fit_context(llm, system_prompt, chat_tools.as_deref(), &chat_options)
+            .await;
+        // Backdate the start by the time already spent before a
+        // suspension so the deadline covers active time across resumes.
+        let now = tokio::time::Instant::now();
+        let started = now.checked_sub(prior_active).unwrap_or(now);
+        let deadline = self.config.max_duration.map(|d| started + d);
+        self.run_deadline = deadline;
+        let tool_timeouts = self.config.tool_timeouts.clone();
+        let artifacts_dir = self.verification_artifacts_dir.clone();
+        let remaining_steps = self.config.max_steps.saturating_sub(self.state.steps.len());
+
+        for _step_index in 0..remaining_steps {
             if self.state.completed {
                 break;
             }
+            if deadline.is_some_and(|d| tokio::time::Instant::now() >= d) {
+                self.halt_on_deadline(started);
+                break;
+            }
+            if self.suspend_requested() {
+                self.suspend(&goal, &loop_ctx, &trackers, started.elapsed().as_secs())
+                    .await;
+                break;
+            }
+            if self.cancel_requested() {
+                self.halt_on_cancel();
+                break;
+            }
 
             // 1. Observe — refresh the compact CDP page summary, drain
             // invalidations, re-infer phase, and run episodic retrieval if
@@ -1289,13 +1617,104 @@ impl StateRunner {
                 max_elements: self.config.state_block_max_elements,
             });
             loop_ctx.messages.push(Message::user(step_msg));
+            loop_ctx.budget.max_tokens = fit.budget_tokens();
+            self.capture_collapsing_pairs(&mut loop_ctx);
             loop_ctx.messages = compact(loop_ctx.messages, &loop_ctx.budget);
+            self.fold_collapsed_steps(&mut loop_ctx).await;
 
             // 3. LLM call.
-            let response = llm
-                .chat(&loop_ctx.messages, Some(&loop_ctx.tools))
-                .await
-                .context("Agent LLM call failed")?;
+            // The LLM call is bounded by the run deadline too — a stalled
+            // endpoint is the other way a run outlives `max_duration`.
+            // A suspend request abandons the call outright: nothing has
+            // been dispatched yet, so the turn can simply be replayed on
+            // resume.
+            // With `stream_llm`, text/reasoning fragments go out as
+            // `LlmDelta` events while the turn arrives. They are display
+            // only, so a full channel drops them rather than stalling.
+            chat_options.escalate = self.escalate_turn();
+            let delta_tx = self.event_tx.clone().filter(|_| self.config.stream_llm);
+            let (run_id, step_index) = (self.run_id, self.step_index);
+            let on_delta = |delta: clickweave_llm::StreamDelta| {
+                let Some(tx) = &delta_tx else { return };
+                let (kind, text) = LlmDeltaKind::split(delta);
+                let _ = tx.try_send(RunnerOutput::Event(AgentEvent::LlmDelta {
+                    run_id,
+                    step_index,
+                    kind,
+                    text,
+                }));
+            };
+            // A length rejection re-compacts under a tighter budget and
+            // asks again instead of ending the run.
+            let mut overflow_retries = 0;
+            let chat_result = loop {
+                let chat = async {
+                    if delta_tx.is_some() {
+                        llm.chat_stream_with_options(
+                            &loop_ctx.messages,
+                            chat_tools.as_deref(),
+                            &chat_options,
+                            &on_delta,
+                        )
+                        .await
+                    } else {
+                        llm.chat_with_options(
+                            &loop_ctx.messages,
+                            chat_tools.as_deref(),
+                            &chat_options,
+                        )
+                        .await
+                    }
+                };
+                let bounded_chat = async {
+                    match deadline {
+                        Some(deadline) => tokio::time::timeout_at(deadline, chat).await.ok(),
+                        None => Some(chat.await),
+                    }
+                };
+                let result = tokio::select! {
+                    _ = token_signalled(self.suspend_signal.clone()) => None,
+                    result = bounded_chat => Some(result),
+                };
+                if let Some(Some(Err(error))) = &result
+                    && is_context_overflow(error)
+                    && overflow_retries < context_window::MAX_OVERFLOW_RETRIES
+                    && self.recompact_after_overflow(&mut fit, &mut loop_ctx).await
+                {
+                    overflow_retries += 1;
+                    continue;
+                }
+                break result;
+            };
+            let response = match chat_result {
+                Some(Some(response)) => response,
+                Some(None) => {
+                    self.halt_on_deadline(started);
+                    break;
+                }
+                None => {
+                    // Drop the just-composed user turn; the resumed run
+                    // recomposes it from a fresh observation.
+                    loop_ctx.messages.pop();
+                    self.pending_applicable_skills = applicable;
+                    self.suspend(&goal, &loop_ctx, &trackers, started.elapsed().as_secs())
+                        .await;
+                    break;
+                }
+            }
+            .context("Agent LLM call failed")?;
+            if let Some(usage) = &response.usage {
+                fit.observe_prompt(
+                    estimate_messages_tokens(&loop_ctx.messages),
+                    usage.prompt_tokens as usize,
+                );
+            }
+            self.turn_model = Some(
+                response
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| llm.model_name().to_string()),
+            );
             let choice = response
                 .choices
                 .into_iter()
@@ -1305,7 +1724,11 @@ impl StateRunner {
             // 4. Parse the LLM response into an AgentTurn carrying any
             //    `0..N` task-state mutations followed by exactly one
             //    action.
-            let mut turn = parse_agent_turn(&choice.message)?;
+            let mut turn = if json_turns {
+                parse_structured_turn(&choice.message, self.step_index)?
+            } else {
+                parse_agent_turn(&choice.message)?
+            };
             if guard_completion_after_unverified_side_effect(
                 trackers.previous_result.as_deref(),
                 &mut turn,
@@ -1324,6 +1747,16 @@ impl StateRunner {
             )
             .await;
 
+            // Before the launch/focus skips: those already call MCP.
+            match self
+                .guard_tool_scope(&turn, &elements, &mut loop_ctx, &mut trackers)
+                .await
+            {
+                LoopStepFlow::Continue => continue,
+                LoopStepFlow::Break => break,
+                LoopStepFlow::Dispatch => {}
+            }
+
             if self
                 .handle_no_focus_launch_skip(&turn, &elements, &mut loop_ctx, &mut trackers, mcp)
                 .await
@@ -1349,6 +1782,15 @@ impl StateRunner {
                 LoopStepFlow::Dispatch => {}
             }
 
+            match self
+                .guard_plan_first(&turn, &elements, &mut loop_ctx, &mut trackers)
+                .await
+            {
+                LoopStepFlow::Continue => continue,
+                LoopStepFlow::Break => break,
+                LoopStepFlow::Dispatch => {}
+            }
+
             match self
                 .guard_coordinate_primitive(&turn, &elements, &mut loop_ctx, &mut trackers, mcp)
                 .await
@@ -1379,13 +1821,37 @@ impl StateRunner {
             //    `Recovering -> Executing` transition persisted as a
             //    `BoundaryKind::RecoverySucceeded` record.
             let previous_errors = self.consecutive_errors;
-            let executor = McpToolExecutor { mcp };
+            let executor = McpToolExecutor {
+                mcp,
+                timeouts: &tool_timeouts,
+                deadline,
+                artifacts_dir: artifacts_dir.as_deref(),
+            };
             let action_only_turn = AgentTurn {
                 mutations: Vec::new(),
                 action: turn.action.clone(),
             };
-            let (outcome, warnings, _run_turn_milestones) =
-                self.run_turn(&action_only_turn, &executor).await;
+            //    Delegation needs the LLM backend, which `run_turn` does
+            //    not carry, so it is dispatched here instead.
+            let (outcome, warnings, _run_turn_milestones) = match &turn.action {
+                AgentAction::DelegateSubgoal {
+                    subgoal,
+                    tools,
+                    max_steps,
+                    ..
+                } => {
+                    let request = crate::agent::delegate::DelegationRequest {
+                        subgoal: subgoal.clone(),
+                        tools: tools.clone(),
+                        max_steps: *max_steps,
+                    };
+                    let outcome = self
+                        .delegate_subgoal(llm, mcp, &mcp_tools, &request, deadline)
+                        .await;
+                    (outcome, Vec::new(), 0)
+                }
+                _ => self.run_turn(&action_only_turn, &executor).await,
+            };
             for w in warnings {
                 tracing::warn!(warning = %w, "state-spine: mutation warning");
             }
@@ -1422,7 +1888,8 @@ impl StateRunner {
         // plus the post-loop MaxStepsReached fallback right above — so a
         // single write here covers `Completed`, `MaxStepsReached`,
         // `MaxErrorsReached`, `ApprovalUnavailable`, `CompletionDisagreement`,
-        // `ConsecutiveDestructiveCap`, and `LoopDetected` uniformly. A
+        // `ConsecutiveDestructiveCap`, `LoopDetected`, `DeadlineExceeded`,
+        // `Suspended` and `Cancelled` uniformly. A
         // run without any terminal_reason is a bug (no known code path
         // produces it), so the match_ is exhaustive on `Some`.
         if self.state.terminal_reason.is_some() {
@@ -1436,6 +1903,42 @@ impl StateRunner {
     }
 }
 
+/// `(tool_name, arguments, tool_call_id)` of a pseudo-tool the harness
+/// answers (via the operator or a sub-agent), rebuilt from the parsed
+/// action for the step record and the transcript.
+fn harness_round_trip_call(action: &AgentAction) -> Option<(&'static str, Value, &str)> {
+    match action {
+        AgentAction::AskUser {
+            question,
+            choices,
+            tool_call_id,
+        } => Some((
+            crate::agent::ask_user::TOOL_NAME,
+            serde_json::json!({"question": question, "choices": choices}),
+            tool_call_id,
+        )),
+        AgentAction::ProposePlan {
+            subgoals,
+            tool_call_id,
+        } => Some((
+            crate::agent::plan::TOOL_NAME,
+            serde_json::json!({ "subgoals": subgoals }),
+            tool_call_id,
+        )),
+        AgentAction::DelegateSubgoal {
+            subgoal,
+            tools,
+            max_steps,
+            tool_call_id,
+        } => Some((
+            crate::agent::delegate::TOOL_NAME,
+            serde_json::json!({"subgoal": subgoal, "tools": tools, "max_steps": max_steps}),
+            tool_call_id,
+        )),
+        _ => None,
+    }
+}
+
 /// Translate the openai-shaped `Vec<Value>` tool list (produced by
 /// `Mcp::tools_as_openai`) into the `clickweave_mcp::Tool` shape the
 /// prompt-spine builder needs. Keeps the openai format as the source of
@@ -1460,6 +1963,7 @@ fn openai_tools_to_mcp_tool_list(tools: &[Value]) -> Vec<clickweave_mcp::Tool> {
                 name,
                 description,
                 input_schema,
+                output_schema: None,
                 annotations,
             })
         })
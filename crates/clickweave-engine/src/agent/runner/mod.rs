@@ -16,7 +16,7 @@ use std::sync::Arc;
 
 use anyhow::Context as _;
 use clickweave_core::cdp::CdpFindElementMatch;
-use clickweave_llm::{ChatBackend, DynChatBackend, Message};
+use clickweave_llm::{ChatBackend, ChatOptions, DynChatBackend, Message};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::{mpsc, oneshot};
@@ -37,9 +37,10 @@ use crate::agent::skills::{
     SubgoalSignature,
 };
 use crate::agent::task_state::{Milestone, SubgoalId, TaskState, TaskStateMutation};
+use crate::agent::tool_timeouts::{TIMEOUT_ERROR_KIND, ToolTimeouts, is_timeout_error};
 use crate::agent::types::{
-    AgentCommand, AgentConfig, AgentEvent, AgentState, AgentStep, ApprovalRequest, RunnerOutput,
-    StepOutcome, TerminalReason, WorldModelDiff,
+    AgentCommand, AgentConfig, AgentEvent, AgentState, AgentStep, ApprovalRequest, LlmDeltaKind,
+    PlanApprovalRequest, RunnerOutput, StepOutcome, TerminalReason, UserQuestion, WorldModelDiff,
 };
 use crate::agent::world_model::{
     CdpElementInventorySummary, InvalidationEvent, ObservedElement, WorldModel,
@@ -47,11 +48,17 @@ use crate::agent::world_model::{
 use crate::executor::Mcp;
 
 mod approval;
+mod cancel;
 mod cdp_lifecycle;
+mod context_window;
+mod delegation;
 mod focus;
 mod loop_control;
 mod progress;
 mod records;
+mod structured;
+mod summary;
+mod suspend;
 mod tool_classification;
 mod turn;
 mod turn_runtime;
@@ -67,6 +74,8 @@ pub(crate) use tool_classification::{
 pub(crate) use tool_classification::{is_ax_dispatch_tool, is_state_transition_tool};
 
 pub(super) use approval::{ApprovalResult, CapStatus};
+pub use structured::parse_structured_turn;
+pub use suspend::{SUSPENDED_RUN_SCHEMA_VERSION, SuspendedRun};
 pub use turn::{AgentAction, AgentTurn, ToolExecutor, TurnOutcome, parse_agent_turn};
 pub(crate) use turn::{McpToolExecutor, append_assistant_and_tool_result};
 
@@ -85,6 +94,7 @@ use progress::{
     is_stale_cdp_uid_error, is_text_composition_tool, is_unverified_side_effect_action,
     reset_no_progress_tracking, stable_no_progress_context_signature,
 };
+use suspend::token_signalled;
 use tool_classification::{
     APP_LIFECYCLE_TOOLS, CDP_NAVIGATION_TOOLS, FOCUS_CHANGING_TOOLS, OBSERVATION_TOOLS,
     brief_summarize_args, build_annotations_index,
@@ -103,9 +113,10 @@ struct RunLoopContext {
     advertised_tool_names: Vec<String>,
     annotations_by_tool: HashMap<String, ToolAnnotations>,
     budget: CompactBudget,
+    summary: summary::SummaryState,
 }
 
-#[derive(Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct RunLoopTrackers {
     previous_result: Option<String>,
     last_failure: Option<(String, Value, String)>,
@@ -159,12 +170,17 @@ pub struct StateRunner {
     /// can be different concrete types without polluting `StateRunner`'s
     /// generics.
     pub vision: Option<Arc<dyn DynChatBackend>>,
+    /// Optional backend, typically a cheap model, that folds the steps
+    /// compaction collapses into a narrative summary. `None` keeps the
+    /// one-line stand-ins.
+    pub summarizer: Option<Arc<dyn DynChatBackend>>,
     /// Permission policy consulted before every non-observation tool
     /// call. Default policy denies nothing and asks for nothing —
     /// matches the legacy behaviour.
     pub permissions: PermissionPolicy,
-    /// Directory for completion-verification artifacts (PNG + JSON).
-    /// `None` disables artifact persistence.
+    /// Directory for run artifacts: completion-verification PNG + JSON,
+    /// and MCP resources that tools return. `None` disables artifact
+    /// persistence.
     pub verification_artifacts_dir: Option<PathBuf>,
     /// Monotonic counter feeding the `completion_verification_<n>.{png,json}`
     /// filename ordinal so repeated `verify_completion` calls within the
@@ -287,6 +303,38 @@ pub struct StateRunner {
     /// the runner. `None` when skills are disabled or the watcher
     /// failed to spawn.
     pub(crate) skill_watcher_handle: Option<tokio::task::JoinHandle<()>>,
+
+    // --- Suspend / resume ---
+    /// Cancelled by the host to request suspension at the next turn
+    /// boundary. See [`Self::with_suspend_signal`].
+    pub(crate) suspend_signal: Option<tokio_util::sync::CancellationToken>,
+    /// Cancelled by the host to stop the run, abandoning any in-flight
+    /// tool call. See [`Self::with_cancel_signal`].
+    pub(crate) cancel_signal: Option<tokio_util::sync::CancellationToken>,
+    /// When the running loop must stop, from `AgentConfig::max_duration`.
+    /// Set on loop entry so waits outside the tool executor, such as an
+    /// `ask_user` question, are bounded by it too.
+    pub(crate) run_deadline: Option<tokio::time::Instant>,
+    /// Snapshot to rehydrate instead of starting a fresh transcript.
+    /// Taken (and cleared) by `run` on entry.
+    pub(crate) pending_resume: Option<Box<SuspendedRun>>,
+
+    // --- Sub-agents ---
+    /// Tools a sub-agent may dispatch, resolved by its parent from the
+    /// `delegate_subgoal` allowlist. Enforced at dispatch on top of the
+    /// advertised tool list. `None` for a top-level run.
+    pub(crate) tool_scope: Option<Vec<String>>,
+
+    // --- Model routing ---
+    /// Model that answered the current turn's LLM call, stamped on the
+    /// step events it produces.
+    pub(crate) turn_model: Option<String>,
+
+    // --- MCP server supervision ---
+    /// `Mcp::server_restarts` as of the last observe; `None` until the
+    /// first one. A higher count means the primary server was replaced
+    /// and its session state is gone.
+    pub(crate) seen_mcp_restarts: Option<u64>,
 }
 
 impl StateRunner {
@@ -414,6 +462,7 @@ impl StateRunner {
             event_tx: None,
             approval_gate: None,
             vision: None,
+            summarizer: None,
             permissions: PermissionPolicy::default(),
             verification_artifacts_dir: None,
             verification_count: 0,
@@ -444,6 +493,13 @@ impl StateRunner {
             agent_system_prompt_override: None,
             suspended_skill_frame: None,
             skill_watcher_handle: None,
+            suspend_signal: None,
+            cancel_signal: None,
+            run_deadline: None,
+            pending_resume: None,
+            tool_scope: None,
+            turn_model: None,
+            seen_mcp_restarts: None,
         }
     }
 
@@ -498,6 +554,13 @@ impl StateRunner {
         self
     }
 
+    /// Attach a backend that summarizes the steps compaction collapses
+    /// out of the transcript.
+    pub fn with_summarizer(mut self, summarizer: Arc<dyn DynChatBackend>) -> Self {
+        self.summarizer = Some(summarizer);
+        self
+    }
+
     /// Replace the default permission policy.
     pub fn with_permissions(mut self, policy: PermissionPolicy) -> Self {
         self.permissions = policy;
@@ -33,7 +33,7 @@ pub(super) const UNVERIFIED_SIDE_EFFECT_COMPLETION_BLOCKED_REASON: &str = "[UNVE
 
 pub(crate) const STALE_CDP_UID_PREFIX: &str = "[STALE CDP UID]";
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(super) struct LastActionProgress {
     pub(super) tool_name: String,
     pub(super) arguments: Value,
@@ -41,14 +41,14 @@ pub(super) struct LastActionProgress {
     pub(super) count: u32,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(super) struct ActionProgressSignature {
     pub(super) tool_name: String,
     pub(super) arguments: Value,
     pub(super) context_signature: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(super) struct TextSubmitSearchProgress {
     pub(super) context_signature: String,
     pub(super) count: u32,
@@ -192,6 +192,9 @@ impl StateRunner {
                         .apply_complete_subgoal(summary, self.step_index)
                     {
                         Ok(milestone) => {
+                            if self.is_plan_subgoal(milestone.subgoal_id) {
+                                self.start_next_plan_subgoal();
+                            }
                             let pre_state_sig = push_sig.unwrap_or_else(|| {
                                 crate::agent::skills::signature::compute_subgoal_signature(
                                     &milestone.text,
@@ -218,6 +221,58 @@ impl StateRunner {
         warnings
     }
 
+    /// Seed an approved plan onto the subgoal stack, keeping the Spec 3
+    /// extraction frames aligned with it: frames for entries the
+    /// revision drops are discarded, and one frame is pushed per seeded
+    /// subgoal.
+    pub(super) fn seed_approved_plan(&mut self, subgoals: &[crate::agent::plan::PlannedSubgoal]) {
+        self.task_state.seed_plan(subgoals, self.step_index);
+        let kept = self.task_state.subgoal_stack.len() - subgoals.len();
+        self.push_idx_stack.truncate(kept);
+        self.push_signature_stack.truncate(kept);
+        self.produced_node_ids_stack.truncate(kept);
+        for subgoal in self.task_state.subgoal_stack[kept..].iter() {
+            self.push_idx_stack.push(self.recorded_steps.len());
+            self.push_signature_stack.push(
+                crate::agent::skills::signature::compute_subgoal_signature(
+                    &subgoal.text,
+                    &self.world_model,
+                ),
+            );
+            self.produced_node_ids_stack.push(Vec::new());
+        }
+    }
+
+    fn is_plan_subgoal(&self, id: crate::agent::task_state::SubgoalId) -> bool {
+        self.task_state.plan.iter().any(|p| p.subgoal_id == id)
+    }
+
+    /// Seeded plan subgoals below the top have been "pushed" since
+    /// approval but only start once their predecessor completes. Reset
+    /// the new top's extraction frame so it covers just its own steps.
+    fn start_next_plan_subgoal(&mut self) {
+        let Some(top) = self.task_state.subgoal_stack.last() else {
+            return;
+        };
+        if !self.is_plan_subgoal(top.id) {
+            return;
+        }
+        let signature = crate::agent::skills::signature::compute_subgoal_signature(
+            &top.text,
+            &self.world_model,
+        );
+        let recorded = self.recorded_steps.len();
+        if let Some(idx) = self.push_idx_stack.last_mut() {
+            *idx = recorded;
+        }
+        if let Some(sig) = self.push_signature_stack.last_mut() {
+            *sig = signature;
+        }
+        if let Some(produced) = self.produced_node_ids_stack.last_mut() {
+            produced.clear();
+        }
+    }
+
     pub(super) fn record_produced_node_id(&mut self, node_id: uuid::Uuid) {
         for produced_node_ids in &mut self.produced_node_ids_stack {
             produced_node_ids.push(node_id);
@@ -412,14 +467,24 @@ impl StateRunner {
 
     /// After a successful tool call, refresh the world model's identity
     /// fields that the tool just captured. Non-snapshot tools are no-ops.
-    pub fn update_continuity_after_tool_success(&mut self, tool_name: &str, body: &str) {
+    /// The result's `structured` content is read in preference to its
+    /// text `body` when it has the expected shape.
+    pub fn update_continuity_after_tool_success(
+        &mut self,
+        tool_name: &str,
+        body: &str,
+        structured: Option<&serde_json::Value>,
+    ) {
         use crate::agent::world_model::{
             AxSnapshotData, Fresh, FreshnessSource, ObservedElement, ScreenshotRef,
-            parse_ax_snapshot, parse_ocr_matches,
+            parse_ax_snapshot, parse_ax_snapshot_structured, parse_ocr_matches,
+            parse_ocr_matches_structured,
         };
         match tool_name {
             "take_ax_snapshot" => {
-                let parsed = parse_ax_snapshot(body);
+                let parsed = structured
+                    .and_then(parse_ax_snapshot_structured)
+                    .unwrap_or_else(|| parse_ax_snapshot(body));
                 let snapshot_id = parsed
                     .first()
                     .map(|e| e.uid.clone())
@@ -452,12 +517,17 @@ impl StateRunner {
                 }
             }
             "take_screenshot" => {
-                let id = serde_json::from_str::<serde_json::Value>(body)
-                    .ok()
-                    .and_then(|v| {
-                        v.get("screenshot_id")
-                            .and_then(|s| s.as_str())
-                            .map(String::from)
+                let screenshot_id = |v: &serde_json::Value| {
+                    v.get("screenshot_id")
+                        .and_then(|s| s.as_str())
+                        .map(String::from)
+                };
+                let id = structured
+                    .and_then(screenshot_id)
+                    .or_else(|| {
+                        serde_json::from_str::<serde_json::Value>(body)
+                            .ok()
+                            .and_then(|v| screenshot_id(&v))
                     })
                     .unwrap_or_else(|| format!("ss-{}", self.step_index));
                 self.world_model.last_screenshot = Some(Fresh {
@@ -476,7 +546,10 @@ impl StateRunner {
                 // when the response is parseable. Parse failures are
                 // tolerated silently — `find_text` has multiple legacy
                 // body shapes, so a non-OCR-shaped body is normal.
-                if let Ok(matches) = parse_ocr_matches(body)
+                let matches = structured
+                    .and_then(parse_ocr_matches_structured)
+                    .or_else(|| parse_ocr_matches(body).ok());
+                if let Some(matches) = matches
                     && !matches.is_empty()
                 {
                     let observed: Vec<ObservedElement> =
@@ -516,8 +589,13 @@ impl StateRunner {
             .await
         {
             Ok(result) if result.is_error != Some(true) => {
-                let text = crate::cdp_lifecycle::extract_text(&result);
-                match serde_json::from_str::<clickweave_core::cdp::CdpPageSummaryResponse>(&text) {
+                let parsed = match result.structured_as() {
+                    Some(summary) => Ok(summary),
+                    None => serde_json::from_str::<clickweave_core::cdp::CdpPageSummaryResponse>(
+                        &crate::cdp_lifecycle::extract_text(&result),
+                    ),
+                };
+                match parsed {
                     Ok(parsed) => {
                         self.state.current_url = parsed.page_url.clone();
                         let page_fingerprint = crate::agent::transition::page_inventory_fingerprint(
@@ -699,6 +777,7 @@ impl StateRunner {
             TurnOutcome::ToolSuccess {
                 tool_name,
                 tool_body,
+                ..
             } => serde_json::json!({
                 "kind": "tool_success",
                 "tool_name": tool_name,
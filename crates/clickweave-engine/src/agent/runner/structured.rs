@@ -0,0 +1,145 @@
+//! Schema-constrained JSON turns for endpoints in `ToolMode::JsonSchema`.
+//!
+//! Instead of `tool_calls`, the model replies with one JSON object:
+//!
+//! ```json
+//! {"mutations": [{"tool": "push_subgoal", "arguments": {"text": "..."}}],
+//!  "action": {"tool": "cdp_click", "arguments": {"uid": "e12"}}}
+//! ```
+//!
+//! [`agent_turn_schema`] derives the schema from the same tool list native
+//! mode advertises, pairing each `tool` tag with that tool's parameter
+//! schema, so grammar-constrained decoding cannot produce an unknown tool
+//! or ill-typed arguments. [`parse_structured_turn`] rewrites the reply as
+//! ordinary `tool_calls` and hands it to `parse_agent_turn`, so both modes
+//! share one classifier.
+
+use super::*;
+
+use clickweave_llm::{CallType, FunctionCall, ToolCall};
+use serde_json::{Map, json};
+
+use crate::agent::prompt::is_mutation_tool_name;
+
+/// `response_format.json_schema.name` for agent turns.
+pub(crate) const SCHEMA_NAME: &str = "agent_turn";
+
+/// Appended to the system prompt when turns are schema-constrained.
+const INSTRUCTION: &str = "\n\n## Reply format\n\
+Reply with a single JSON object and nothing else: `mutations` is a list of \
+task-state updates (`{\"tool\": <name>, \"arguments\": {...}}`, possibly \
+empty) and `action` is the one tool to run this turn, in the same shape.";
+
+/// JSON Schema for an `AgentTurn` over the OpenAI-shaped `tools`.
+pub(crate) fn agent_turn_schema(tools: &[Value]) -> Value {
+    let (mutations, actions): (Vec<&Value>, Vec<&Value>) = tools
+        .iter()
+        .filter_map(|tool| tool.get("function"))
+        .filter(|function| function.get("name").and_then(Value::as_str).is_some())
+        .partition(|function| is_mutation_tool_name(function["name"].as_str().unwrap_or_default()));
+
+    let mutation_items = if mutations.is_empty() {
+        json!({"not": {}})
+    } else {
+        json!({"anyOf": mutations.into_iter().map(tagged_call).collect::<Vec<_>>()})
+    };
+    let mut schema = json!({
+        "type": "object",
+        "properties": {
+            "mutations": {"type": "array", "items": mutation_items},
+        },
+        "required": ["mutations"],
+        "additionalProperties": false,
+    });
+    // An empty `anyOf` matches nothing, so with no tools to act with the
+    // reply carries mutations only.
+    if !actions.is_empty() {
+        schema["properties"]["action"] =
+            json!({"anyOf": actions.into_iter().map(tagged_call).collect::<Vec<_>>()});
+        schema["required"] = json!(["mutations", "action"]);
+    }
+    schema
+}
+
+/// `{"tool": <const name>, "arguments": <parameters>}` for one tool.
+fn tagged_call(function: &Value) -> Value {
+    let mut variant = Map::new();
+    variant.insert("type".into(), json!("object"));
+    if let Some(description) = function.get("description") {
+        variant.insert("description".into(), description.clone());
+    }
+    variant.insert(
+        "properties".into(),
+        json!({
+            "tool": {"const": function["name"]},
+            "arguments": function
+                .get("parameters")
+                .cloned()
+                .unwrap_or_else(|| json!({"type": "object"})),
+        }),
+    );
+    variant.insert("required".into(), json!(["tool", "arguments"]));
+    variant.insert("additionalProperties".into(), json!(false));
+    Value::Object(variant)
+}
+
+/// Tell the model about the reply format. The tool list itself is already
+/// in the system prompt.
+pub(super) fn append_instruction(system: &mut Message) {
+    let text = system.content_text().unwrap_or_default();
+    *system = Message::system(format!("{text}{INSTRUCTION}"));
+}
+
+#[derive(Deserialize)]
+struct StructuredTurn {
+    #[serde(default)]
+    mutations: Vec<StructuredCall>,
+    action: Option<StructuredCall>,
+}
+
+#[derive(Deserialize)]
+struct StructuredCall {
+    tool: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+/// Parse a schema-constrained reply into an `AgentTurn`.
+///
+/// Synthesized call ids are `json-<step>-<n>`, unique within a run. A
+/// reply that is not a turn object becomes an `AgentReplan` naming the
+/// parse error, like a text-only reply in native mode.
+pub fn parse_structured_turn(message: &Message, step_index: usize) -> anyhow::Result<AgentTurn> {
+    let parsed = serde_json::from_str::<StructuredTurn>(message.json_text());
+    let turn = match parsed {
+        Ok(turn) => turn,
+        Err(e) => {
+            tracing::warn!(error = %e, "state-spine: reply is not a valid agent_turn object");
+            return Ok(AgentTurn {
+                mutations: Vec::new(),
+                action: AgentAction::AgentReplan {
+                    reason: format!("Reply was not a valid {SCHEMA_NAME} JSON object: {e}"),
+                },
+            });
+        }
+    };
+
+    let tool_calls = turn
+        .mutations
+        .into_iter()
+        .chain(turn.action)
+        .enumerate()
+        .map(|(n, call)| ToolCall {
+            id: format!("json-{step_index}-{n}"),
+            call_type: CallType::Function,
+            function: FunctionCall {
+                name: call.tool,
+                arguments: match call.arguments {
+                    Value::Null => json!({}),
+                    arguments => arguments,
+                },
+            },
+        })
+        .collect();
+    parse_agent_turn(&Message::assistant_tool_calls(tool_calls))
+}
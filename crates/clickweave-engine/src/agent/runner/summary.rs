@@ -0,0 +1,173 @@
+use super::*;
+use crate::agent::context::{self, truncate};
+
+/// Collapsed steps that accumulate before they are folded into the
+/// summary. Between folds the summary message does not change, so the
+/// prompt prefix up to it stays cacheable.
+const SUMMARY_BATCH: usize = 4;
+/// Reply budget for one summary, and a hard cap on what is kept of it.
+const SUMMARY_MAX_TOKENS: u32 = 400;
+const SUMMARY_MAX_CHARS: usize = 2_000;
+/// How much of each message of a collapsed step the summarizer sees.
+const SUMMARY_MESSAGE_CHARS: usize = 400;
+
+const SUMMARIZER_PROMPT: &str = "You keep the running summary of a UI automation agent's \
+earlier steps. Merge the previous summary with the new steps into one short narrative \
+(at most 150 words): what was tried, what worked, what failed and why an approach was \
+abandoned, and facts learned that still matter. Reply with the summary text only.";
+
+/// Summaries kept for reuse; past this many the cache starts over.
+const SUMMARY_CACHE_ENTRIES: usize = 16;
+
+/// Full copies of the steps collapsed since the last fold, and the
+/// summaries written so far.
+#[derive(Debug, Default)]
+pub(super) struct SummaryState {
+    /// Oldest first. They are the last one-liners of the collapsed run;
+    /// earlier one-liners (e.g. from before a resume) have no full copy.
+    pending: Vec<Vec<Message>>,
+    /// Collapsed-run length that triggers the next fold. Pushed out after
+    /// a failed fold so a broken summarizer is not asked every turn.
+    next_fold_at: usize,
+    /// Summary per window: the previous summary plus the one-liners folded
+    /// into it. A window folded again gets the same text back instead of
+    /// a fresh reply, so the summary message and the prompt prefix up to
+    /// it stay byte-identical.
+    cache: HashMap<u64, String>,
+}
+
+impl StateRunner {
+    /// Keep full copies of the pairs the upcoming compaction collapses.
+    pub(super) fn capture_collapsing_pairs(&self, loop_ctx: &mut RunLoopContext) {
+        if self.summarizer.is_none() {
+            return;
+        }
+        let pending = &mut loop_ctx.summary.pending;
+        pending.extend(context::pairs_leaving_window(
+            &loop_ctx.messages,
+            &loop_ctx.budget,
+        ));
+        let excess = pending.len().saturating_sub(2 * SUMMARY_BATCH);
+        pending.drain(..excess);
+    }
+
+    /// Once enough one-liners have built up after compaction, fold them
+    /// into the summary of earlier steps, reusing the cached summary when
+    /// this window was folded before and asking the summarizer otherwise.
+    /// When it fails, the one-liners stay and the fold is tried again
+    /// later.
+    pub(super) async fn fold_collapsed_steps(&self, loop_ctx: &mut RunLoopContext) {
+        let Some(summarizer) = &self.summarizer else {
+            return;
+        };
+        let lines = context::collapsed_lines(&loop_ctx.messages);
+        if lines.len() < loop_ctx.summary.next_fold_at.max(SUMMARY_BATCH) {
+            return;
+        }
+        let previous = context::current_summary(&loop_ctx.messages);
+        let window = window_key(previous, &lines);
+        let line_count = lines.len();
+        let summary = match loop_ctx.summary.cache.get(&window) {
+            Some(cached) => cached.clone(),
+            None => {
+                let request = summary_request(previous, &lines, &loop_ctx.summary.pending);
+                match summarize(summarizer.as_ref(), request).await {
+                    Ok(text) => truncate(&text, SUMMARY_MAX_CHARS),
+                    Err(e) => {
+                        loop_ctx.summary.next_fold_at = line_count + SUMMARY_BATCH;
+                        warn!(error = %format!("{e:#}"), "Transcript summarizer failed");
+                        self.emit_event(AgentEvent::Warning {
+                            message: format!(
+                                "Transcript summarizer failed ({e:#}); keeping one-line step summaries"
+                            ),
+                        })
+                        .await;
+                        return;
+                    }
+                }
+            }
+        };
+        loop_ctx.messages =
+            context::fold_into_summary(std::mem::take(&mut loop_ctx.messages), &summary);
+        let state = &mut loop_ctx.summary;
+        state.pending.clear();
+        state.next_fold_at = 0;
+        if state.cache.len() >= SUMMARY_CACHE_ENTRIES {
+            state.cache.clear();
+        }
+        state.cache.insert(window, summary);
+        debug!(
+            steps = line_count,
+            "Folded collapsed steps into the transcript summary"
+        );
+    }
+}
+
+/// Ask `summarizer` for the summary `request` describes.
+async fn summarize(summarizer: &dyn DynChatBackend, request: String) -> anyhow::Result<String> {
+    let messages = [Message::system(SUMMARIZER_PROMPT), Message::user(request)];
+    let options = ChatOptions {
+        max_tokens: Some(SUMMARY_MAX_TOKENS),
+        ..ChatOptions::with_temperature(0.0)
+    };
+    let response = summarizer
+        .chat_with_options_boxed(&messages, None, &options)
+        .await?;
+    response
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|choice| {
+            choice
+                .message
+                .content_text()
+                .map(str::trim)
+                .map(String::from)
+        })
+        .filter(|text| !text.is_empty())
+        .context("summarizer returned no text")
+}
+
+/// Identifies a fold by what it covers: the previous summary and the
+/// one-liners being folded into it.
+fn window_key(previous: Option<&str>, lines: &[&str]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    previous.hash(&mut hasher);
+    lines.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The summarizer's input: the previous summary, then every step to fold
+/// in. Steps with a full copy are shown in full (bodies truncated), the
+/// rest by their one-liner.
+fn summary_request(previous: Option<&str>, lines: &[&str], pending: &[Vec<Message>]) -> String {
+    let mut out = format!(
+        "Previous summary:\n{}\n\nNew steps, oldest first:\n",
+        previous.unwrap_or("(none)")
+    );
+    let without_copy = lines.len().saturating_sub(pending.len());
+    for line in &lines[..without_copy] {
+        out.push_str(&format!("- {line}\n"));
+    }
+    for pair in pending.iter().rev().take(lines.len()).rev() {
+        out.push_str("- step:\n");
+        for m in pair {
+            if let Some(text) = m.content_text().filter(|t| !t.is_empty()) {
+                out.push_str(&format!(
+                    "  {:?}: {}\n",
+                    m.role,
+                    truncate(text, SUMMARY_MESSAGE_CHARS)
+                ));
+            }
+            for call in m.tool_calls.iter().flatten() {
+                out.push_str(&format!(
+                    "  call {} {}\n",
+                    call.function.name,
+                    truncate(&call.function.arguments.to_string(), SUMMARY_MESSAGE_CHARS)
+                ));
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,233 @@
+use super::*;
+
+/// Bumped whenever [`SuspendedRun`]'s shape changes incompatibly. A
+/// snapshot written under a different version is refused on resume
+/// rather than half-rehydrated.
+pub const SUSPENDED_RUN_SCHEMA_VERSION: u32 = 1;
+
+/// Observation text prepended to the first turn after a resume. The
+/// world model is rebuilt from a fresh observation, but the transcript
+/// still describes the pre-suspension screen, so the LLM is told
+/// explicitly not to trust it.
+pub(crate) const RESUMED_RUN_NOTE: &str = "[RUN RESUMED] This run was suspended and has just been resumed. \
+     The app and page may have changed in the meantime — trust the current state block over \
+     earlier observations in the transcript.";
+
+/// Everything a suspended run needs to pick up where it left off.
+///
+/// Captured at a turn boundary, so no tool call is ever half-dispatched.
+/// `WorldModel` is deliberately absent: it describes a screen that may
+/// be gone by the time the run resumes, so the resumed loop re-observes
+/// before its first turn. Likewise `messages[0]` (the system prompt) is
+/// not stored — it is rebuilt from the tool list the MCP server
+/// advertises at resume time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspendedRun {
+    pub schema_version: u32,
+    pub run_id: uuid::Uuid,
+    pub suspended_at: chrono::DateTime<chrono::Utc>,
+    /// Goal block the run started with (already composed by
+    /// `build_goal_block`).
+    pub goal: String,
+    /// Wall-clock time the run had been active before suspension.
+    /// Charged against `AgentConfig::max_duration` on resume; time spent
+    /// suspended is not.
+    pub active_secs: u64,
+    pub task_state: TaskState,
+    /// Compacted transcript from `messages[1]` onward.
+    pub transcript: Vec<Message>,
+    pub steps: Vec<AgentStep>,
+    pub trace_graph: crate::agent::trace_graph::AgentTraceGraph,
+    pub last_node_id: Option<uuid::Uuid>,
+    pub current_url: String,
+    pub step_index: usize,
+    pub consecutive_errors: usize,
+    pub last_replan_step: Option<usize>,
+    pub recent_destructive_tools: Vec<String>,
+    trackers: RunLoopTrackers,
+    skill_frames: SuspendedSkillFrames,
+}
+
+/// Spec 3 extraction bookkeeping that must stay aligned with
+/// `task_state.subgoal_stack` — without it, completing a subgoal opened
+/// before the suspension would extract from the wrong step window.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SuspendedSkillFrames {
+    recorded_steps: Vec<RecordedStep>,
+    push_idx_stack: Vec<usize>,
+    push_signature_stack: Vec<SubgoalSignature>,
+    produced_node_ids_stack: Vec<Vec<uuid::Uuid>>,
+}
+
+impl StateRunner {
+    /// Attach a suspend signal. Cancelling the token makes the runner
+    /// snapshot its state at the next turn boundary (or abandon an
+    /// in-flight LLM call), persist it through the attached storage, and
+    /// halt with `TerminalReason::Suspended`. Tool calls already
+    /// dispatched are allowed to finish first.
+    pub fn with_suspend_signal(mut self, token: tokio_util::sync::CancellationToken) -> Self {
+        self.suspend_signal = Some(token);
+        self
+    }
+
+    /// Continue a previously suspended run instead of starting fresh.
+    /// `run` then restores the snapshot in place of building the initial
+    /// transcript; its `goal` / `trace_graph` / `anchor_node_id`
+    /// arguments are ignored in favour of the snapshot's.
+    pub fn with_resume(mut self, snapshot: SuspendedRun) -> Self {
+        self.run_id = snapshot.run_id;
+        self.pending_resume = Some(Box::new(snapshot));
+        self
+    }
+
+    pub(super) fn suspend_requested(&self) -> bool {
+        self.suspend_signal
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+    }
+
+    pub(super) fn snapshot_for_suspend(
+        &self,
+        goal: &str,
+        loop_ctx: &RunLoopContext,
+        trackers: &RunLoopTrackers,
+        active_secs: u64,
+    ) -> SuspendedRun {
+        SuspendedRun {
+            schema_version: SUSPENDED_RUN_SCHEMA_VERSION,
+            run_id: self.run_id,
+            suspended_at: chrono::Utc::now(),
+            goal: goal.to_string(),
+            active_secs,
+            task_state: self.task_state.clone(),
+            transcript: loop_ctx.messages.iter().skip(1).cloned().collect(),
+            steps: self.state.steps.clone(),
+            trace_graph: self.state.trace_graph.clone(),
+            last_node_id: self.state.last_node_id,
+            current_url: self.state.current_url.clone(),
+            step_index: self.step_index,
+            consecutive_errors: self.consecutive_errors,
+            last_replan_step: self.last_replan_step,
+            recent_destructive_tools: self.state.recent_destructive_tools.clone(),
+            trackers: trackers.clone(),
+            skill_frames: SuspendedSkillFrames {
+                recorded_steps: self.recorded_steps.clone(),
+                push_idx_stack: self.push_idx_stack.clone(),
+                push_signature_stack: self.push_signature_stack.clone(),
+                produced_node_ids_stack: self.produced_node_ids_stack.clone(),
+            },
+        }
+    }
+
+    /// Snapshot the run, persist it, and record the `Suspended` terminal
+    /// reason. The caller breaks out of the loop afterwards.
+    ///
+    /// A failed or skipped write (no storage attached, or persistence
+    /// disabled) still halts the run — the operator asked it to stop —
+    /// but surfaces a warning so the lost snapshot is not silent.
+    ///
+    /// A sub-agent writes nothing: its report is the parent's
+    /// `delegate_subgoal` result, which the parent's snapshot keeps.
+    pub(super) async fn suspend(
+        &mut self,
+        goal: &str,
+        loop_ctx: &RunLoopContext,
+        trackers: &RunLoopTrackers,
+        active_secs: u64,
+    ) {
+        if self.tool_scope.is_some() {
+            self.state.terminal_reason = Some(TerminalReason::Suspended {
+                steps_executed: self.state.steps.len(),
+            });
+            return;
+        }
+        let snapshot = self.snapshot_for_suspend(goal, loop_ctx, trackers, active_secs);
+        let persisted = match &self.storage {
+            Some(storage) => {
+                let guard = storage.lock().unwrap_or_else(|e| e.into_inner());
+                if guard.is_persistent() {
+                    guard
+                        .write_suspended_run(&snapshot)
+                        .map_err(|e| e.to_string())
+                } else {
+                    Err("trace persistence is disabled".to_string())
+                }
+            }
+            None => Err("no run storage attached".to_string()),
+        };
+        if let Err(reason) = persisted {
+            warn!(%reason, "state-spine: suspended run snapshot not persisted");
+            self.emit_event(AgentEvent::Warning {
+                message: format!("suspend: run state could not be saved ({reason})"),
+            })
+            .await;
+        }
+        self.state.terminal_reason = Some(TerminalReason::Suspended {
+            steps_executed: self.state.steps.len(),
+        });
+    }
+
+    /// Rehydrate a suspended run. Builds a fresh loop context for the
+    /// current tool list (so the system prompt matches what the server
+    /// advertises now), then splices the saved transcript back in behind
+    /// it. The world model is left empty so the first iteration's
+    /// observe step rebuilds it from the live screen.
+    pub(super) fn resume_run_loop(
+        &mut self,
+        snapshot: SuspendedRun,
+        mcp_tools: &[Value],
+    ) -> (RunLoopContext, RunLoopTrackers) {
+        let SuspendedRun {
+            goal,
+            task_state,
+            transcript,
+            steps,
+            trace_graph,
+            last_node_id,
+            current_url,
+            step_index,
+            consecutive_errors,
+            last_replan_step,
+            recent_destructive_tools,
+            mut trackers,
+            skill_frames,
+            ..
+        } = snapshot;
+
+        self.task_state = task_state;
+        let mut loop_ctx = self.initialize_run_loop(&goal, trace_graph, mcp_tools, last_node_id);
+        loop_ctx.messages.truncate(1);
+        loop_ctx.messages.extend(transcript);
+
+        self.state.steps = steps;
+        self.state.current_url = current_url;
+        self.state.consecutive_errors = consecutive_errors;
+        self.state.recent_destructive_tools = recent_destructive_tools.clone();
+        self.recent_destructive_tools = recent_destructive_tools;
+        self.step_index = step_index;
+        self.consecutive_errors = consecutive_errors;
+        self.last_replan_step = last_replan_step;
+        self.recorded_steps = skill_frames.recorded_steps;
+        self.push_idx_stack = skill_frames.push_idx_stack;
+        self.push_signature_stack = skill_frames.push_signature_stack;
+        self.produced_node_ids_stack = skill_frames.produced_node_ids_stack;
+        // Not a run start: episodic run-start retrieval already had its
+        // chance before the suspension.
+        self.episodic_run_start_retrieved = true;
+
+        trackers.previous_result = Some(match trackers.previous_result.take() {
+            Some(prev) => format!("{RESUMED_RUN_NOTE}\n\n{prev}"),
+            None => RESUMED_RUN_NOTE.to_string(),
+        });
+        (loop_ctx, trackers)
+    }
+}
+
+/// Resolves when `signal` is cancelled; never resolves for `None`. Used for
+/// both the suspend and the cancel signal.
+pub(super) async fn token_signalled(signal: Option<tokio_util::sync::CancellationToken>) {
+    match signal {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
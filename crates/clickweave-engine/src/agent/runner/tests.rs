@@ -20,6 +20,8 @@ mod agent_turn_parsing_tests;
 
 mod parse_agent_turn_tool_calls_tests;
 
+mod structured_turn_tests;
+
 mod unverified_side_effect_guard_tests;
 
 mod no_progress_guard_tests;
@@ -62,3 +64,12 @@ mod retrieval_gate_tests;
 mod skills_apply_mutations_tests;
 
 mod dispatch_skill_tests;
+
+/// MCP server restarts: a higher `Mcp::server_restarts` count at observe
+/// time drops the CDP connection and the server-held world-model fields,
+/// and warns once per restart.
+mod mcp_restart_tests;
+
+/// Transcript summary cache: folding a window seen before reuses its
+/// summary instead of asking the summarizer again.
+mod summary_cache_tests;
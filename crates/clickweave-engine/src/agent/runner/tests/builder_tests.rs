@@ -20,6 +20,7 @@ impl ChatBackend for YesVlmStub {
     ) -> anyhow::Result<ChatResponse> {
         Ok(ChatResponse {
             id: "t".into(),
+            model: None,
             choices: vec![clickweave_llm::Choice {
                 index: 0,
                 message: Message::assistant("YES"),
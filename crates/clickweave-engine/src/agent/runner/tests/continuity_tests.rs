@@ -5,7 +5,7 @@ fn take_ax_snapshot_success_populates_last_native_ax_snapshot() {
     let mut r = StateRunner::new_for_test("g".to_string());
     r.step_index = 5;
     let body = "uid=a1g3 button \"OK\"\n  uid=a2g3 textbox";
-    r.update_continuity_after_tool_success("take_ax_snapshot", body);
+    r.update_continuity_after_tool_success("take_ax_snapshot", body, None);
     let ax = r.world_model.last_native_ax_snapshot.as_ref().unwrap();
     assert_eq!(ax.value.captured_at_step, 5);
     assert!(ax.value.element_count >= 2);
@@ -17,7 +17,7 @@ fn take_screenshot_success_populates_last_screenshot_ref() {
     let mut r = StateRunner::new_for_test("g".to_string());
     r.step_index = 4;
     let body = r#"{"screenshot_id":"ss-abc","width":1440,"height":900}"#;
-    r.update_continuity_after_tool_success("take_screenshot", body);
+    r.update_continuity_after_tool_success("take_screenshot", body, None);
     let s = r.world_model.last_screenshot.as_ref().unwrap();
     assert_eq!(s.value.screenshot_id, "ss-abc");
     assert_eq!(s.value.captured_at_step, 4);
@@ -26,7 +26,59 @@ fn take_screenshot_success_populates_last_screenshot_ref() {
 #[test]
 fn non_snapshot_tool_does_not_touch_continuity() {
     let mut r = StateRunner::new_for_test("g".to_string());
-    r.update_continuity_after_tool_success("cdp_click", "ok");
+    r.update_continuity_after_tool_success("cdp_click", "ok", None);
     assert!(r.world_model.last_native_ax_snapshot.is_none());
     assert!(r.world_model.last_screenshot.is_none());
 }
+
+#[test]
+fn structured_content_is_preferred_over_the_text_body() {
+    let mut r = StateRunner::new_for_test("g".to_string());
+    let structured = serde_json::json!({"elements": [
+        {"uid": "a1g3", "role": "window", "name": "Mail", "depth": 0},
+        {"uid": "a2g3", "role": "button", "name": "Send", "depth": 1, "focused": true}
+    ]});
+    r.update_continuity_after_tool_success(
+        "take_ax_snapshot",
+        "Snapshot captured (2 elements)",
+        Some(&structured),
+    );
+    let ax = r.world_model.last_native_ax_snapshot.as_ref().unwrap();
+    assert_eq!(ax.value.snapshot_id, "a1g3");
+    assert_eq!(ax.value.element_count, 2);
+    let els = &r.world_model.elements.as_ref().unwrap().value;
+    let ObservedElement::Ax(send) = &els[1] else {
+        panic!("expected AX element, got {:?}", els[1]);
+    };
+    assert_eq!(send.parent_name.as_deref(), Some("Mail"));
+    assert!(send.focused);
+
+    let structured = serde_json::json!({"screenshot_id": "ss-structured"});
+    r.update_continuity_after_tool_success(
+        "take_screenshot",
+        r#"{"screenshot_id":"ss-text"}"#,
+        Some(&structured),
+    );
+    assert_eq!(
+        r.world_model
+            .last_screenshot
+            .as_ref()
+            .unwrap()
+            .value
+            .screenshot_id,
+        "ss-structured"
+    );
+}
+
+#[test]
+fn structured_content_of_the_wrong_shape_falls_back_to_the_text_body() {
+    let mut r = StateRunner::new_for_test("g".to_string());
+    let structured = serde_json::json!({"count": 1});
+    r.update_continuity_after_tool_success(
+        "find_text",
+        r#"[{"text":"OK","x":10,"y":20,"width":30,"height":12}]"#,
+        Some(&structured),
+    );
+    let els = &r.world_model.elements.as_ref().unwrap().value;
+    assert!(matches!(&els[0], ObservedElement::Ocr(m) if m.text == "OK"));
+}
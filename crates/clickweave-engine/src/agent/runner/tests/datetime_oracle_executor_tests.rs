@@ -29,7 +29,12 @@ impl Mcp for PanicMcp {
 
 #[tokio::test]
 async fn get_current_datetime_is_intercepted_before_mcp() {
-    let executor = McpToolExecutor { mcp: &PanicMcp };
+    let executor = McpToolExecutor {
+        mcp: &PanicMcp,
+        timeouts: &ToolTimeouts::default(),
+        deadline: None,
+        artifacts_dir: None,
+    };
 
     let body = executor
         .call_tool(crate::agent::time_oracle::TOOL_NAME, &json!({}))
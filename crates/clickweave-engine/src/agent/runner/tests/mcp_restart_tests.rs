@@ -0,0 +1,85 @@
+use super::*;
+use crate::agent::test_stubs::StaticMcp;
+use crate::agent::world_model::{CdpPageState, Fresh, FreshnessSource};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// `StaticMcp` with a restart count the test bumps by hand.
+struct RestartingMcp {
+    inner: StaticMcp,
+    restarts: AtomicU64,
+}
+
+impl Mcp for RestartingMcp {
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+    ) -> anyhow::Result<clickweave_mcp::ToolCallResult> {
+        self.inner.call_tool(name, arguments).await
+    }
+
+    fn has_tool(&self, name: &str) -> bool {
+        self.inner.has_tool(name)
+    }
+
+    fn tools_as_openai(&self) -> Vec<serde_json::Value> {
+        self.inner.tools_as_openai()
+    }
+
+    async fn refresh_server_tool_list(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn server_restarts(&self) -> u64 {
+        self.restarts.load(Ordering::SeqCst)
+    }
+}
+
+fn warnings(rx: &mut mpsc::Receiver<RunnerOutput>) -> Vec<String> {
+    let mut out = Vec::new();
+    while let Ok(output) = rx.try_recv() {
+        if let RunnerOutput::Event(AgentEvent::Warning { message }) = output {
+            out.push(message);
+        }
+    }
+    out
+}
+
+#[tokio::test]
+async fn a_restart_resets_server_held_state_once() {
+    let mcp = RestartingMcp {
+        inner: StaticMcp::with_tools(&[]),
+        restarts: AtomicU64::new(0),
+    };
+    let (tx, mut rx) = mpsc::channel(16);
+    let mut r = StateRunner::new_for_test("g".to_string()).with_events(tx);
+    r.set_cdp_connected_for_test("Chrome", 4242);
+    r.world_model.cdp_page = Some(Fresh {
+        value: CdpPageState {
+            url: "https://example.com/".to_string(),
+            page_fingerprint: "abc".to_string(),
+            element_inventory: Vec::new(),
+        },
+        written_at: 1,
+        source: FreshnessSource::DirectObservation,
+        ttl_steps: None,
+    });
+
+    // The first observe only records the baseline.
+    r.reset_after_mcp_restart(&mcp).await;
+    assert!(r.pending_events.is_empty());
+    assert!(r.cdp_state().is_connected_to("Chrome", 4242));
+
+    mcp.restarts.store(1, Ordering::SeqCst);
+    r.reset_after_mcp_restart(&mcp).await;
+    assert!(!r.cdp_state().is_connected_to("Chrome", 4242));
+    r.observe();
+    assert!(r.world_model.cdp_page.is_none());
+    let warned = warnings(&mut rx);
+    assert_eq!(warned.len(), 1);
+    assert!(warned[0].contains("MCP server restarted"), "{warned:?}");
+
+    r.reset_after_mcp_restart(&mcp).await;
+    assert!(r.pending_events.is_empty());
+    assert!(warnings(&mut rx).is_empty());
+}
@@ -92,6 +92,7 @@ impl Mcp for MultiBlockListAppsMcp {
                 },
             ],
             is_error: None,
+            structured_content: None,
         })
     }
     fn has_tool(&self, name: &str) -> bool {
@@ -13,7 +13,7 @@ fn runner() -> StateRunner {
 fn take_ax_snapshot_populates_elements_with_ax_variants() {
     let mut r = runner();
     let body = "uid=a1g3 button \"Login\"\n  uid=a2g3 textbox \"Email\"\n";
-    r.update_continuity_after_tool_success("take_ax_snapshot", body);
+    r.update_continuity_after_tool_success("take_ax_snapshot", body, None);
     let els = r.world_model.elements.as_ref().expect("elements populated");
     assert!(!els.value.is_empty(), "expected parsed AX elements");
     assert!(
@@ -45,7 +45,7 @@ fn take_ax_snapshot_with_empty_body_does_not_overwrite_elements() {
         source: crate::agent::world_model::FreshnessSource::DirectObservation,
         ttl_steps: Some(2),
     });
-    r.update_continuity_after_tool_success("take_ax_snapshot", "");
+    r.update_continuity_after_tool_success("take_ax_snapshot", "", None);
     let els = r.world_model.elements.as_ref().unwrap();
     assert!(matches!(els.value.first(), Some(ObservedElement::Cdp(_))));
 }
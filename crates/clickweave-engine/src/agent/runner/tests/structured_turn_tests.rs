@@ -0,0 +1,139 @@
+//! Tests for `ToolMode::JsonSchema` turns: the generated `AgentTurn`
+//! schema and the JSON reply parser.
+
+use super::*;
+use crate::agent::runner::structured::{SCHEMA_NAME, agent_turn_schema};
+use clickweave_llm::Message;
+use serde_json::json;
+
+fn tags(variants: &Value) -> Vec<&str> {
+    variants["anyOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["properties"]["tool"]["const"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn schema_splits_mutations_from_actions_and_keeps_argument_schemas() {
+    let click = json!({
+        "type": "function",
+        "function": {
+            "name": "cdp_click",
+            "description": "Click an element.",
+            "parameters": {
+                "type": "object",
+                "properties": {"uid": {"type": "string"}},
+                "required": ["uid"]
+            }
+        }
+    });
+    let tools: Vec<Value> = std::iter::once(click)
+        .chain(crate::agent::prompt::pseudo_tools())
+        .collect();
+
+    let schema = agent_turn_schema(&tools);
+
+    assert_eq!(schema["required"], json!(["mutations", "action"]));
+    let mutations = tags(&schema["properties"]["mutations"]["items"]);
+    assert!(mutations.contains(&"push_subgoal"));
+    assert!(mutations.contains(&"refute_hypothesis"));
+    let actions = tags(&schema["properties"]["action"]);
+    assert!(actions.contains(&"cdp_click"));
+    assert!(actions.contains(&"agent_done"));
+    assert!(!actions.contains(&"push_subgoal"));
+
+    let click_variant = schema["properties"]["action"]["anyOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|v| v["properties"]["tool"]["const"] == "cdp_click")
+        .unwrap();
+    assert_eq!(click_variant["description"], "Click an element.");
+    assert_eq!(
+        click_variant["properties"]["arguments"]["required"],
+        json!(["uid"])
+    );
+}
+
+#[test]
+fn schema_without_mutation_tools_only_allows_an_empty_list() {
+    let schema = agent_turn_schema(&[]);
+    assert_eq!(
+        schema["properties"]["mutations"]["items"],
+        json!({"not": {}})
+    );
+}
+
+#[test]
+fn schema_without_action_tools_leaves_out_the_action() {
+    let push_subgoal = json!({
+        "type": "function",
+        "function": {"name": "push_subgoal", "parameters": {"type": "object"}},
+    });
+    let schema = agent_turn_schema(&[push_subgoal]);
+    assert!(schema["properties"].get("action").is_none());
+    assert_eq!(schema["required"], json!(["mutations"]));
+}
+
+#[test]
+fn structured_reply_parses_like_native_tool_calls() {
+    let reply = Message::assistant(
+        r#"{"mutations": [{"tool": "push_subgoal", "arguments": {"text": "open menu"}},
+                          {"tool": "record_hypothesis", "arguments": {"text": "menu is hidden"}}],
+            "action": {"tool": "cdp_click", "arguments": {"uid": "e7"}}}"#,
+    );
+
+    let turn = parse_structured_turn(&reply, 4).unwrap();
+
+    assert_eq!(turn.mutations.len(), 2);
+    assert!(matches!(
+        &turn.mutations[0],
+        TaskStateMutation::PushSubgoal { text } if text == "open menu"
+    ));
+    match turn.action {
+        AgentAction::ToolCall {
+            tool_name,
+            arguments,
+            tool_call_id,
+        } => {
+            assert_eq!(tool_name, "cdp_click");
+            assert_eq!(arguments, json!({"uid": "e7"}));
+            assert_eq!(tool_call_id, "json-4-2");
+        }
+        other => panic!("expected tool_call, got {other:?}"),
+    }
+}
+
+#[test]
+fn fenced_reply_and_missing_arguments_are_tolerated() {
+    let reply = Message::assistant("```json\n{\"action\": {\"tool\": \"agent_done\"}}\n```");
+    let turn = parse_structured_turn(&reply, 0).unwrap();
+    assert!(turn.mutations.is_empty());
+    assert!(matches!(
+        turn.action,
+        AgentAction::AgentDone { summary } if summary == "Goal completed"
+    ));
+}
+
+#[test]
+fn malformed_reply_becomes_a_replan_naming_the_error() {
+    let turn = parse_structured_turn(&Message::assistant("I will click the menu."), 0).unwrap();
+    match turn.action {
+        AgentAction::AgentReplan { reason } => {
+            assert!(reason.contains(SCHEMA_NAME), "{reason}");
+        }
+        other => panic!("expected replan, got {other:?}"),
+    }
+}
+
+#[test]
+fn mutations_without_an_action_fall_back_to_replan() {
+    let reply = Message::assistant(
+        r#"{"mutations": [{"tool": "push_subgoal", "arguments": {"text": "x"}}]}"#,
+    );
+    let turn = parse_structured_turn(&reply, 0).unwrap();
+    assert_eq!(turn.mutations.len(), 1);
+    assert!(matches!(turn.action, AgentAction::AgentReplan { .. }));
+}
@@ -0,0 +1,44 @@
+use super::*;
+use crate::agent::context::{collapsed_lines, current_summary};
+use crate::agent::test_stubs::{CapturingLlm, llm_reply_text};
+
+/// System, goal, four collapsed one-liners and one recent step.
+fn collapsed_transcript() -> Vec<Message> {
+    let mut messages = vec![Message::system("sys"), Message::user("goal")];
+    messages.extend((1..=4).map(|n| Message::assistant(format!("[collapsed] clicked row e{n}"))));
+    messages.push(Message::assistant("clicking row e5"));
+    messages
+}
+
+fn loop_ctx(messages: Vec<Message>) -> RunLoopContext {
+    RunLoopContext {
+        messages,
+        tools: Vec::new(),
+        advertised_tool_names: Vec::new(),
+        annotations_by_tool: HashMap::new(),
+        budget: CompactBudget::default(),
+        summary: summary::SummaryState::default(),
+    }
+}
+
+#[tokio::test]
+async fn a_window_folded_again_reuses_its_summary() {
+    let summarizer = Arc::new(CapturingLlm::new(vec![
+        llm_reply_text("Opened rows e1-e4."),
+        llm_reply_text("A different wording of the same steps."),
+    ]));
+    let runner = StateRunner::new_for_test("goal".to_string()).with_summarizer(summarizer.clone());
+    let mut ctx = loop_ctx(collapsed_transcript());
+
+    runner.fold_collapsed_steps(&mut ctx).await;
+    let first = ctx.messages.clone();
+    assert_eq!(current_summary(&first), Some("Opened rows e1-e4."));
+    assert!(collapsed_lines(&first).is_empty());
+
+    ctx.messages = collapsed_transcript();
+    runner.fold_collapsed_steps(&mut ctx).await;
+
+    assert_eq!(current_summary(&ctx.messages), Some("Opened rows e1-e4."));
+    assert_eq!(ctx.messages.len(), first.len());
+    assert_eq!(summarizer.call_count(), 1);
+}
@@ -142,6 +142,11 @@ pub(crate) fn extract_result_text(result: &clickweave_mcp::ToolCallResult) -> St
             clickweave_mcp::ToolContent::Image { mime_type, .. } => {
                 format!("[image: {}]", mime_type)
             }
+            clickweave_mcp::ToolContent::ResourceLink(link) => format!("[resource: {}]", link.uri),
+            clickweave_mcp::ToolContent::Resource { resource } => match &resource.text {
+                Some(text) => text.clone(),
+                None => format!("[resource: {}]", resource.uri),
+            },
             clickweave_mcp::ToolContent::Unknown(_) => "[unknown content]".to_string(),
         })
         .collect::<Vec<_>>()
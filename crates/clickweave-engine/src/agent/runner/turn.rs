@@ -4,8 +4,8 @@ use super::*;
 ///
 /// `ToolCall` usually dispatches to MCP; harness-local observation pseudo-tools
 /// such as `get_current_datetime` are intercepted by `McpToolExecutor`.
-/// `AgentDone` / `AgentReplan` are harness-local pseudo-tools that never reach
-/// MCP.
+/// `AgentDone` / `AgentReplan` / `AskUser` / `ProposePlan` /
+/// `DelegateSubgoal` are harness-local pseudo-tools that never reach MCP.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum AgentAction {
@@ -20,6 +20,28 @@ pub enum AgentAction {
     AgentReplan {
         reason: String,
     },
+    /// Put a clarifying question to the operator. The answer (or the
+    /// no-human fallback) comes back as this call's tool result.
+    AskUser {
+        question: String,
+        choices: Vec<String>,
+        tool_call_id: String,
+    },
+    /// Submit a plan-first plan (or a revision of the approved one) for
+    /// operator approval. The decision comes back as the tool result.
+    ProposePlan {
+        subgoals: Vec<crate::agent::plan::PlannedSubgoal>,
+        tool_call_id: String,
+    },
+    /// Hand a subgoal to a sub-agent restricted to `tools` (globs) with
+    /// its own `max_steps` budget. The child's report comes back as the
+    /// tool result.
+    DelegateSubgoal {
+        subgoal: String,
+        tools: Vec<String>,
+        max_steps: usize,
+        tool_call_id: String,
+    },
     /// Replay a procedural skill listed in the previous turn's
     /// `<applicable_skills>` block. The harness expands the skill's
     /// recorded action sketch through the same dispatch helper as live
@@ -59,10 +81,12 @@ pub struct AgentTurn {
 /// to drive the next iteration.
 #[derive(Debug, Clone)]
 pub enum TurnOutcome {
-    /// Tool call was dispatched; `tool_body` is the successful result text.
+    /// Tool call was dispatched; `tool_body` is the successful result text
+    /// and `structured` the result's `structuredContent`, if any.
     ToolSuccess {
         tool_name: String,
         tool_body: String,
+        structured: Option<serde_json::Value>,
     },
     /// Tool call was dispatched; tool returned an error.
     ToolError { tool_name: String, error: String },
@@ -70,6 +94,18 @@ pub enum TurnOutcome {
     Done { summary: String },
     /// Agent requested replan.
     Replan { reason: String },
+    /// An `ask_user` question was resolved; `body` is the tool result
+    /// handed back to the LLM.
+    UserAnswer { body: String },
+    /// A `propose_plan` call was decided by the operator; `body` is the
+    /// tool result handed back to the LLM.
+    PlanReviewed { body: String },
+    /// A `delegate_subgoal` sub-agent stopped; `body` is its report.
+    SubAgentFinished { body: String },
+    /// The operator could not be reached for a required approval.
+    ApprovalUnavailable,
+    /// The host stopped the run while `tool_name` was in flight.
+    Cancelled { tool_name: String },
 }
 
 /// Executes an MCP tool call and returns either its successful body or an
@@ -82,6 +118,19 @@ pub trait ToolExecutor: Send + Sync {
         tool_name: &str,
         arguments: &serde_json::Value,
     ) -> Result<String, String>;
+
+    /// Like `call_tool`, also handing back the result's validated
+    /// `structuredContent`. Executors without structured results keep
+    /// the default, which has none.
+    async fn call_tool_structured(
+        &self,
+        tool_name: &str,
+        arguments: &serde_json::Value,
+    ) -> Result<(String, Option<serde_json::Value>), String> {
+        self.call_tool(tool_name, arguments)
+            .await
+            .map(|body| (body, None))
+    }
 }
 
 /// Parse a raw LLM response `Message` into an `AgentTurn` carrying
@@ -96,12 +145,13 @@ pub trait ToolExecutor: Send + Sync {
 ///   regardless of position. Malformed args produce a per-call warning
 ///   but never abort the turn — a single bad mutation cannot poison
 ///   the action.
-/// - **Action pseudo-tools** (`agent_done`, `agent_replan`) and any
-///   other tool name become an `AgentAction`. The first action-shaped
-///   call wins; subsequent action calls are dropped, since exactly one
-///   action runs per turn. Mutations after the action are still
-///   preserved — apply order is enforced by `apply_mutations`, not by
-///   tool-call order.
+/// - **Action pseudo-tools** (`agent_done`, `agent_replan`, `ask_user`,
+///   `propose_plan`, `delegate_subgoal`) and any other tool name become an
+///   `AgentAction`. The
+///   first action-shaped call wins; subsequent action calls are dropped,
+///   since exactly one action runs per turn. Mutations after the action
+///   are still preserved — apply order is enforced by `apply_mutations`,
+///   not by tool-call order.
 ///
 /// If only mutations are present (the LLM forgot to choose an action),
 /// the result is an `AgentReplan` with a self-describing reason so the
@@ -162,6 +212,43 @@ pub fn parse_agent_turn(message: &Message) -> anyhow::Result<AgentTurn> {
                         .to_string();
                     AgentAction::AgentReplan { reason }
                 }
+                crate::agent::ask_user::TOOL_NAME => {
+                    match crate::agent::ask_user::parse_args(args) {
+                        Ok((question, choices)) => AgentAction::AskUser {
+                            question,
+                            choices,
+                            tool_call_id: tc.id.clone(),
+                        },
+                        Err(reason) => {
+                            tracing::warn!(error = %reason, "state-spine: malformed ask_user call");
+                            AgentAction::AgentReplan { reason }
+                        }
+                    }
+                }
+                crate::agent::plan::TOOL_NAME => match crate::agent::plan::parse_args(args) {
+                    Ok(subgoals) => AgentAction::ProposePlan {
+                        subgoals,
+                        tool_call_id: tc.id.clone(),
+                    },
+                    Err(reason) => {
+                        tracing::warn!(error = %reason, "state-spine: malformed propose_plan call");
+                        AgentAction::AgentReplan { reason }
+                    }
+                },
+                crate::agent::delegate::TOOL_NAME => {
+                    match crate::agent::delegate::parse_args(args) {
+                        Ok(request) => AgentAction::DelegateSubgoal {
+                            subgoal: request.subgoal,
+                            tools: request.tools,
+                            max_steps: request.max_steps,
+                            tool_call_id: tc.id.clone(),
+                        },
+                        Err(reason) => {
+                            tracing::warn!(error = %reason, "state-spine: malformed delegate_subgoal call");
+                            AgentAction::AgentReplan { reason }
+                        }
+                    }
+                }
                 "invoke_skill" => {
                     let skill_id = args
                         .get("skill_id")
@@ -337,8 +424,18 @@ fn parse_mutation_call(name: &str, args: &Value) -> Result<TaskStateMutation, St
 /// Adapter that turns any `&dyn Mcp` into the `ToolExecutor` trait expected
 /// by `run_turn`. Kept private to `runner.rs` — the plan names this
 /// `McpToolExecutor` so later tasks can grep for the anchor.
+///
+/// Every MCP dispatch is bounded by `timeouts.for_tool(name)`, clamped to
+/// the time left before `deadline` when the run has one. A timed-out call
+/// comes back as an `Err` carrying `tool_timeouts::timeout_error_message`
+/// so the runner can classify it separately from tool-reported failures.
 pub(crate) struct McpToolExecutor<'a, M: Mcp + ?Sized> {
     pub(crate) mcp: &'a M,
+    pub(crate) timeouts: &'a ToolTimeouts,
+    pub(crate) deadline: Option<tokio::time::Instant>,
+    /// Where MCP resources returned by a tool are kept; `None` keeps
+    /// none.
+    pub(crate) artifacts_dir: Option<&'a std::path::Path>,
 }
 
 #[async_trait::async_trait]
@@ -348,25 +445,47 @@ impl<M: Mcp + ?Sized> ToolExecutor for McpToolExecutor<'_, M> {
         tool_name: &str,
         arguments: &serde_json::Value,
     ) -> Result<String, String> {
+        self.call_tool_structured(tool_name, arguments)
+            .await
+            .map(|(body, _)| body)
+    }
+
+    async fn call_tool_structured(
+        &self,
+        tool_name: &str,
+        arguments: &serde_json::Value,
+    ) -> Result<(String, Option<serde_json::Value>), String> {
         if tool_name == crate::agent::time_oracle::TOOL_NAME {
-            return Ok(crate::agent::time_oracle::current_datetime_json());
+            return Ok((crate::agent::time_oracle::current_datetime_json(), None));
         }
 
+        let mut timeout = self.timeouts.for_tool(tool_name);
+        if let Some(deadline) = self.deadline {
+            timeout = timeout.min(deadline.saturating_duration_since(tokio::time::Instant::now()));
+        }
+        // Linked resources are read under the same budget as the call.
+        let call_deadline = tokio::time::Instant::now() + timeout;
         let result = self
             .mcp
-            .call_tool(tool_name, Some(arguments.clone()))
+            .call_tool_with_timeout(tool_name, Some(arguments.clone()), timeout)
             .await
-            .map_err(|e| e.to_string())?;
-        let text = result
-            .content
-            .iter()
-            .filter_map(|c| c.as_text())
-            .collect::<Vec<_>>()
-            .join("\n");
+            .map_err(|e| match e.downcast_ref::<clickweave_mcp::McpError>() {
+                Some(clickweave_mcp::McpError::Timeout { .. }) => {
+                    crate::agent::tool_timeouts::timeout_error_message(tool_name, timeout)
+                }
+                _ => e.to_string(),
+            })?;
+        let text = crate::agent::resources::tool_result_text(
+            self.mcp,
+            &result,
+            self.artifacts_dir,
+            call_deadline,
+        )
+        .await;
         if result.is_error == Some(true) {
             Err(text)
         } else {
-            Ok(text)
+            Ok((text, result.structured_content))
         }
     }
 }
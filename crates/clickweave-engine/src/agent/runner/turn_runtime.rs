@@ -9,6 +9,13 @@ impl StateRunner {
     ///     - `ToolCall`: call the executor, update continuity on success,
     ///       queue `ToolFailed` and bump `consecutive_errors` on error.
     ///     - `AgentDone` / `AgentReplan`: return the terminal outcome.
+    ///     - `AskUser`: wait for the operator (or the fallback) and return
+    ///       the answer as `UserAnswer`, or `Cancelled` if the run is
+    ///       stopped meanwhile.
+    ///     - `ProposePlan`: wait for the operator's plan decision and
+    ///       return it as `PlanReviewed`.
+    ///     - `DelegateSubgoal`: needs the LLM backend, so [`Self::run_inner`]
+    ///       dispatches it itself; reaching it here is a replan.
     /// 4. Advance `step_index`.
     ///
     /// Integration tests drive this with deterministic `AgentTurn`s; Phase 3
@@ -87,17 +94,31 @@ impl StateRunner {
                 tool_name,
                 arguments,
                 ..
-            } => match executor.call_tool(tool_name, arguments).await {
-                Ok(body) => {
-                    self.update_continuity_after_tool_success(tool_name, &body);
+            } => match self
+                .call_unless_cancelled(
+                    tool_name,
+                    executor.call_tool_structured(tool_name, arguments),
+                )
+                .await
+            {
+                None => TurnOutcome::Cancelled {
+                    tool_name: tool_name.clone(),
+                },
+                Some(Ok((body, structured))) => {
+                    self.update_continuity_after_tool_success(
+                        tool_name,
+                        &body,
+                        structured.as_ref(),
+                    );
                     self.queue_invalidations_for_tool_success(tool_name, arguments);
                     self.consecutive_errors = 0;
                     TurnOutcome::ToolSuccess {
                         tool_name: tool_name.clone(),
                         tool_body: body,
+                        structured,
                     }
                 }
-                Err(error) => {
+                Some(Err(error)) => {
                     self.consecutive_errors += 1;
                     let stale_cdp_uid = is_stale_cdp_uid_error(tool_name, &error);
                     if stale_cdp_uid {
@@ -126,6 +147,16 @@ impl StateRunner {
                     reason: reason.clone(),
                 }
             }
+            AgentAction::AskUser {
+                question, choices, ..
+            } => self.ask_user(question, choices).await,
+            AgentAction::ProposePlan { subgoals, .. } => self.review_plan(subgoals).await,
+            AgentAction::DelegateSubgoal { .. } => TurnOutcome::Replan {
+                reason: format!(
+                    "{} is dispatched by the run loop",
+                    crate::agent::delegate::TOOL_NAME
+                ),
+            },
             AgentAction::InvokeSkill {
                 skill_id,
                 version,
@@ -188,6 +219,7 @@ impl StateRunner {
                         TurnOutcome::ToolSuccess {
                             tool_name: tool_name.clone(),
                             tool_body: body,
+                            structured: None,
                         }
                     }
                 }
@@ -455,6 +455,7 @@ mod tests {
             tool_name: tool.into(),
             arguments,
             result_text: body.into(),
+            result_structured: None,
             world_model_pre: WorldModelSnapshot::from_world_model(&wm),
             world_model_post: WorldModelSnapshot::from_world_model(&wm),
         }
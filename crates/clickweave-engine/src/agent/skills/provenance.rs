@@ -70,8 +70,7 @@ pub fn build_action_sketch(action_sequence: &[RecordedStep]) -> Vec<ActionSketch
 
         rewrite_ax_uids_to_captures_pre(&mut rewritten_args, step, idx, &mut captures_by_step);
 
-        let result_value: Value = serde_json::from_str(&step.result_text).unwrap_or(Value::Null);
-        prior_results.push((idx, result_value));
+        prior_results.push((idx, tool_result_value(step)));
         rewritten_args_by_step.push(rewritten_args);
     }
 
@@ -98,6 +97,16 @@ pub fn build_action_sketch(action_sequence: &[RecordedStep]) -> Vec<ActionSketch
     steps
 }
 
+/// The JSON a `CaptureSource::ToolResult` jsonpath addresses: the
+/// step's `structuredContent` when it has one, else its text parsed as
+/// JSON.
+fn tool_result_value(step: &RecordedStep) -> Value {
+    match &step.result_structured {
+        Some(structured) => structured.clone(),
+        None => serde_json::from_str(&step.result_text).unwrap_or(Value::Null),
+    }
+}
+
 fn walk_string_and_number_literals(value: &Value) -> Vec<(String, Value)> {
     let mut out = Vec::new();
     walk_inner(value, String::new(), &mut out);
@@ -357,6 +366,7 @@ mod tests {
             tool_name: tool.into(),
             arguments: args,
             result_text: result.into(),
+            result_structured: None,
             world_model_pre: snap(),
             world_model_post: snap(),
         }
@@ -435,4 +445,31 @@ mod tests {
         assert_eq!(captures.len(), 1);
         assert_eq!(captures[0].name, "step0_first");
     }
+
+    #[test]
+    fn capture_jsonpath_addresses_structured_content_over_text() {
+        let mut producer = step(
+            "find_text",
+            serde_json::json!({}),
+            "Found 1 match: Submit order",
+        );
+        producer.result_structured =
+            Some(serde_json::json!({"matches": [{"text": "Submit order"}]}));
+        let seq = vec![
+            producer,
+            step(
+                "click_text",
+                serde_json::json!({ "text": "Submit order" }),
+                "{}",
+            ),
+        ];
+        let sketch = build_action_sketch(&seq);
+        let ActionSketchStep::ToolCall { captures, .. } = &sketch[0] else {
+            panic!("expected ToolCall, got {:?}", sketch[0]);
+        };
+        assert!(matches!(
+            &captures[0].source,
+            CaptureSource::ToolResult { jsonpath } if jsonpath == "$.matches[0].text"
+        ));
+    }
 }
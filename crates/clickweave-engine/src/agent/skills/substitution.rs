@@ -76,6 +76,12 @@ fn strip_template<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
     }
 }
 
+/// Resolve a capture clause's `$`-rooted jsonpath (`$.matches[0].text`)
+/// against the value it captures from.
+pub fn resolve_capture_jsonpath(root: &Value, jsonpath: &str) -> Option<Value> {
+    resolve_jsonpath(root, jsonpath.strip_prefix('$').unwrap_or(jsonpath))
+}
+
 fn resolve_jsonpath(root: &Value, path: &str) -> Option<Value> {
     let mut current = root;
     let mut segment = String::new();
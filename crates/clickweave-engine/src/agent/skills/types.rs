@@ -374,11 +374,14 @@ impl SkillContext {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordedStep {
     pub tool_name: String,
     pub arguments: serde_json::Value,
     pub result_text: String,
+    /// The result's `structuredContent`, when the tool returned one.
+    #[serde(default)]
+    pub result_structured: Option<serde_json::Value>,
     pub world_model_pre: WorldModelSnapshot,
     pub world_model_post: WorldModelSnapshot,
 }
@@ -28,7 +28,7 @@ impl Default for SubgoalId {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct Subgoal {
     pub id: SubgoalId,
@@ -47,7 +47,7 @@ pub enum WatchSlotName {
     PendingFocusShift,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct WatchSlot {
     pub name: WatchSlotName,
@@ -55,7 +55,7 @@ pub struct WatchSlot {
     pub set_at_step: usize,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct Hypothesis {
     pub text: String,
@@ -63,7 +63,7 @@ pub struct Hypothesis {
     pub refuted: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct Milestone {
     pub subgoal_id: SubgoalId,
@@ -73,7 +73,25 @@ pub struct Milestone {
     pub completed_at_step: usize,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// One subgoal of an approved plan-first plan, linked to the stack
+/// entry it was seeded as so progress can be read off `milestones`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct PlanItem {
+    pub subgoal_id: SubgoalId,
+    pub text: String,
+    pub expected_apps: Vec<String>,
+    pub expected_tools: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanItemStatus {
+    Done,
+    Active,
+    Pending,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct TaskState {
     pub goal: String,
@@ -82,6 +100,10 @@ pub struct TaskState {
     pub hypotheses: Vec<Hypothesis>,
     pub phase: Phase,
     pub milestones: Vec<Milestone>,
+    /// Approved plan-first plan, in order. Empty when the run is not in
+    /// plan-first mode or no plan has been approved yet.
+    #[serde(default)]
+    pub plan: Vec<PlanItem>,
 }
 
 impl TaskState {
@@ -93,6 +115,7 @@ impl TaskState {
             hypotheses: Vec::new(),
             phase: Phase::Exploring,
             milestones: Vec::new(),
+            plan: Vec::new(),
         }
     }
 }
@@ -175,6 +198,64 @@ impl TaskState {
         Ok(milestone)
     }
 
+    /// Replace the approved plan and seed it onto the subgoal stack,
+    /// first subgoal on top. On a revision, the previous plan's
+    /// unfinished entries — and anything the LLM nested above them —
+    /// are dropped first; milestones already earned are kept.
+    pub fn seed_plan(
+        &mut self,
+        subgoals: &[crate::agent::plan::PlannedSubgoal],
+        step_index: usize,
+    ) {
+        if let Some(first_planned) = self
+            .subgoal_stack
+            .iter()
+            .position(|s| self.plan.iter().any(|p| p.subgoal_id == s.id))
+        {
+            self.subgoal_stack.truncate(first_planned);
+        }
+        let parent = self.subgoal_stack.last().map(|s| s.id);
+        self.plan = subgoals
+            .iter()
+            .map(|s| PlanItem {
+                subgoal_id: SubgoalId::new(),
+                text: s.text.clone(),
+                expected_apps: s.expected_apps.clone(),
+                expected_tools: s.expected_tools.clone(),
+            })
+            .collect();
+        for item in self.plan.iter().rev() {
+            self.subgoal_stack.push(Subgoal {
+                id: item.subgoal_id,
+                text: item.text.clone(),
+                pushed_at_step: step_index,
+                parent,
+            });
+        }
+    }
+
+    /// Progress of one plan item: done once its milestone exists, active
+    /// while it is the innermost planned subgoal still on the stack.
+    pub fn plan_item_status(&self, item: &PlanItem) -> PlanItemStatus {
+        if self
+            .milestones
+            .iter()
+            .any(|m| m.subgoal_id == item.subgoal_id)
+        {
+            return PlanItemStatus::Done;
+        }
+        let active = self
+            .subgoal_stack
+            .iter()
+            .rev()
+            .find(|s| self.plan.iter().any(|p| p.subgoal_id == s.id));
+        if active.is_some_and(|s| s.id == item.subgoal_id) {
+            PlanItemStatus::Active
+        } else {
+            PlanItemStatus::Pending
+        }
+    }
+
     pub fn apply(&mut self, m: &TaskStateMutation, step_index: usize) -> Result<(), MutationError> {
         match m {
             TaskStateMutation::PushSubgoal { text } => {
@@ -241,6 +322,7 @@ mod tests {
             hypotheses: Vec::new(),
             phase: crate::agent::phase::Phase::Exploring,
             milestones: Vec::new(),
+            plan: Vec::new(),
         }
     }
 
@@ -443,6 +525,51 @@ mod tests {
             .unwrap();
         assert!(s.hypotheses[0].refuted);
     }
+
+    fn planned(texts: &[&str]) -> Vec<crate::agent::plan::PlannedSubgoal> {
+        texts
+            .iter()
+            .map(|t| crate::agent::plan::PlannedSubgoal {
+                text: t.to_string(),
+                expected_apps: vec![],
+                expected_tools: vec![],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn seed_plan_puts_first_subgoal_on_top_and_tracks_progress() {
+        let mut s = new_state();
+        s.seed_plan(&planned(&["open", "export", "verify"]), 0);
+        assert_eq!(s.subgoal_stack.last().unwrap().text, "open");
+        assert_eq!(s.plan_item_status(&s.plan[0]), PlanItemStatus::Active);
+
+        s.apply(
+            &TaskStateMutation::CompleteSubgoal {
+                summary: "opened".to_string(),
+            },
+            3,
+        )
+        .unwrap();
+        let plan = s.plan.clone();
+        assert_eq!(s.plan_item_status(&plan[0]), PlanItemStatus::Done);
+        assert_eq!(s.plan_item_status(&plan[1]), PlanItemStatus::Active);
+        assert_eq!(s.plan_item_status(&plan[2]), PlanItemStatus::Pending);
+    }
+
+    #[test]
+    fn seed_plan_revision_replaces_unfinished_entries() {
+        let mut s = new_state();
+        s.apply_push_subgoal("outer", 0);
+        s.seed_plan(&planned(&["a", "b"]), 1);
+        s.apply_push_subgoal("nested under a", 2);
+        s.seed_plan(&planned(&["c"]), 3);
+
+        let texts: Vec<&str> = s.subgoal_stack.iter().map(|g| g.text.as_str()).collect();
+        assert_eq!(texts, vec!["outer", "c"]);
+        assert_eq!(s.plan.len(), 1);
+        assert_eq!(s.subgoal_stack[1].parent, Some(s.subgoal_stack[0].id));
+    }
 }
 
 #[cfg(all(test, feature = "specta"))]
@@ -485,6 +612,11 @@ mod specta_derive_tests {
             Hypothesis::inline(&mut TypeCollection::default(), Generics::NONE);
     }
 
+    #[test]
+    fn plan_item_derives_specta_type() {
+        let _: specta::DataType = PlanItem::inline(&mut TypeCollection::default(), Generics::NONE);
+    }
+
     #[test]
     fn milestone_derives_specta_type() {
         let _: specta::DataType = Milestone::inline(&mut TypeCollection::default(), Generics::NONE);
@@ -390,6 +390,7 @@ impl Mcp for StaticMcp {
             return Ok(ToolCallResult {
                 content: vec![ToolContent::Text { text: body.clone() }],
                 is_error: Some(true),
+                structured_content: None,
             });
         }
         // Image replies take precedence — they represent tools like
@@ -401,6 +402,7 @@ impl Mcp for StaticMcp {
                     mime_type: mime_type.clone(),
                 }],
                 is_error: None,
+                structured_content: None,
             });
         }
         let replies = self.replies.lock().unwrap();
@@ -411,6 +413,7 @@ impl Mcp for StaticMcp {
         Ok(ToolCallResult {
             content: vec![ToolContent::Text { text }],
             is_error: None,
+            structured_content: None,
         })
     }
 
@@ -478,6 +481,7 @@ pub fn llm_reply_tool_with_id(
 ) -> ChatResponse {
     ChatResponse {
         id: format!("scripted-{}", tool_name),
+        model: None,
         choices: vec![Choice {
             index: 0,
             message: Message::assistant_tool_calls(vec![ToolCall {
@@ -505,6 +509,7 @@ pub fn llm_reply_text(text: &str) -> ChatResponse {
 fn assistant_stop_response(id: &str, text: &str) -> ChatResponse {
     ChatResponse {
         id: id.to_string(),
+        model: None,
         choices: vec![Choice {
             index: 0,
             message: Message::assistant(text),
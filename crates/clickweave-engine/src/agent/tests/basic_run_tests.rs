@@ -97,6 +97,7 @@ async fn agent_handles_text_only_response() {
         // LLM returns text instead of a tool call
         ChatResponse {
             id: "mock-text".to_string(),
+            model: None,
             choices: vec![Choice {
                 index: 0,
                 message: Message::assistant("I'm thinking about what to do..."),
@@ -300,6 +301,7 @@ async fn agent_state_reports_max_errors_reason() {
                         .to_string(),
                     }],
                     is_error: None,
+                    structured_content: None,
                 }
             } else {
                 // click returns error
@@ -308,6 +310,7 @@ async fn agent_state_reports_max_errors_reason() {
                         text: "Element not found".to_string(),
                     }],
                     is_error: Some(true),
+                    structured_content: None,
                 }
             }
         })
@@ -389,6 +392,7 @@ async fn agent_state_reports_loop_detected_on_identical_repeat_failure() {
                         .to_string(),
                     }],
                     is_error: None,
+                    structured_content: None,
                 }
             } else {
                 ToolCallResult {
@@ -396,6 +400,7 @@ async fn agent_state_reports_loop_detected_on_identical_repeat_failure() {
                         text: "click requires exactly one complete coordinate variant".to_string(),
                     }],
                     is_error: Some(true),
+                    structured_content: None,
                 }
             }
         })
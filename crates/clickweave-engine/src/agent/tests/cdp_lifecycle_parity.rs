@@ -54,6 +54,7 @@ fn text_result(text: &str) -> ToolCallResult {
             text: text.to_string(),
         }],
         is_error: None,
+        structured_content: None,
     }
 }
 
@@ -95,6 +96,7 @@ async fn agent_snapshot_is_silent_on_list_pages_error() {
             text: "boom".to_string(),
         }],
         is_error: Some(true),
+        structured_content: None,
     }]);
 
     runner
@@ -55,6 +55,7 @@ impl Mcp for RoutingMockMcp {
                         text: "ok".to_string(),
                     }],
                     is_error: None,
+                    structured_content: None,
                 });
             }
         };
@@ -65,6 +66,7 @@ impl Mcp for RoutingMockMcp {
                     text: "ok".to_string(),
                 }],
                 is_error: None,
+                structured_content: None,
             })
         } else {
             Ok(q.remove(0))
@@ -96,6 +98,8 @@ impl Mcp for RoutingMockMcp {
 struct RoutingMockAgent {
     agent_responses: Mutex<Vec<ChatResponse>>,
     vision_responses: Mutex<Vec<ChatResponse>>,
+    /// Temperature of every vision-role request, in order.
+    vision_temperatures: Mutex<Vec<Option<f32>>>,
 }
 
 impl RoutingMockAgent {
@@ -103,12 +107,14 @@ impl RoutingMockAgent {
         Self {
             agent_responses: Mutex::new(agent),
             vision_responses: Mutex::new(vision),
+            vision_temperatures: Mutex::new(Vec::new()),
         }
     }
 
     fn text_response(text: &str) -> ChatResponse {
         ChatResponse {
             id: "mock-text".to_string(),
+            model: None,
             choices: vec![Choice {
                 index: 0,
                 message: Message::assistant(text),
@@ -128,11 +134,15 @@ impl ChatBackend for RoutingMockAgent {
         &self,
         _messages: &[Message],
         tools: Option<&[Value]>,
-        _options: &ChatOptions,
+        options: &ChatOptions,
     ) -> Result<ChatResponse> {
         let queue = if tools.is_some() {
             &self.agent_responses
         } else {
+            self.vision_temperatures
+                .lock()
+                .unwrap()
+                .push(options.temperature);
             &self.vision_responses
         };
         let mut q = queue.lock().unwrap();
@@ -160,6 +170,7 @@ fn cdp_empty_page_result() -> ToolCallResult {
             .to_string(),
         }],
         is_error: None,
+        structured_content: None,
     }
 }
 
@@ -170,6 +181,7 @@ fn screenshot_result() -> ToolCallResult {
             mime_type: "image/png".to_string(),
         }],
         is_error: None,
+        structured_content: None,
     }
 }
 
@@ -243,6 +255,48 @@ async fn vlm_yes_verdict_completes_run_normally() {
     );
 }
 
+/// The check samples at the endpoint's temperature unless the config
+/// pins one (the app pins `0.0` when the VLM response cache is on).
+#[tokio::test]
+async fn completion_check_uses_the_configured_temperature() {
+    use clickweave_llm::DynChatBackend;
+
+    for pinned in [None, Some(0.0)] {
+        let agent_backend = Arc::new(RoutingMockAgent::new(
+            vec![MockAgent::done_response("Task finished")],
+            vec![RoutingMockAgent::text_response("YES")],
+        ));
+        let mcp = RoutingMockMcp::new(vec![cdp_empty_page_result()], vec![screenshot_result()]);
+        let config = AgentConfig {
+            max_steps: 5,
+            build_workflow: false,
+            completion_check_temperature: pinned,
+            ..Default::default()
+        };
+        let vlm: Arc<dyn DynChatBackend> = agent_backend.clone();
+        let mcp_tools = mcp.tools_as_openai();
+
+        let state = StateRunner::new("Open settings".to_string(), config)
+            .with_vision(vlm)
+            .run(
+                &*agent_backend,
+                &mcp,
+                "Open settings".to_string(),
+                crate::agent::trace_graph::AgentTraceGraph::new(),
+                mcp_tools,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(state.completed);
+        assert_eq!(
+            *agent_backend.vision_temperatures.lock().unwrap(),
+            vec![pinned]
+        );
+    }
+}
+
 #[tokio::test]
 async fn vlm_no_verdict_halts_run_and_emits_disagreement() {
     use clickweave_llm::DynChatBackend;
@@ -391,6 +445,7 @@ async fn vlm_check_falls_through_when_screenshot_fails() {
             text: "No focused window".to_string(),
         }],
         is_error: Some(true),
+        structured_content: None,
     };
 
     let mcp = RoutingMockMcp::new(vec![cdp_empty_page_result()], vec![failing_screenshot]);
@@ -34,6 +34,7 @@ fn cdp_button_page(url: &str) -> ToolCallResult {
             .to_string(),
         }],
         is_error: None,
+        structured_content: None,
     }
 }
 
@@ -48,6 +49,7 @@ fn cdp_empty_page() -> ToolCallResult {
             .to_string(),
         }],
         is_error: None,
+        structured_content: None,
     }
 }
 
@@ -57,6 +59,7 @@ fn text_result(text: &str) -> ToolCallResult {
             text: text.to_string(),
         }],
         is_error: None,
+        structured_content: None,
     }
 }
 
@@ -399,6 +402,7 @@ async fn focus_window_after_native_launch_is_suppressed_with_ax_toolset() {
             .to_string(),
         }],
         is_error: None,
+        structured_content: None,
     };
     let results = vec![
         cdp_empty_page(),     // step 0 observation
@@ -564,6 +568,7 @@ async fn focus_window_still_runs_when_app_kind_is_unknown() {
             text: "Window focused successfully".to_string(),
         }],
         is_error: None,
+        structured_content: None,
     };
     let results = vec![
         cdp_empty_page(), // step 0 observation
@@ -31,6 +31,7 @@ impl MockAgent {
             .unwrap_or_else(|_| Value::String(arguments.to_string()));
         ChatResponse {
             id: "mock-resp".to_string(),
+            model: None,
             choices: vec![Choice {
                 index: 0,
                 message: Message::assistant_tool_calls(vec![ToolCall {
@@ -135,6 +136,7 @@ impl MockMcp {
                     .to_string(),
                 }],
                 is_error: None,
+                structured_content: None,
             },
             // click result
             ToolCallResult {
@@ -142,6 +144,7 @@ impl MockMcp {
                     text: "Clicked at (100, 200)".to_string(),
                 }],
                 is_error: None,
+                structured_content: None,
             },
             // cdp_find_elements for second observation
             ToolCallResult {
@@ -159,6 +162,7 @@ impl MockMcp {
                     .to_string(),
                 }],
                 is_error: None,
+                structured_content: None,
             },
         ];
 
@@ -179,6 +183,7 @@ impl Mcp for MockMcp {
                     text: "ok".to_string(),
                 }],
                 is_error: None,
+                structured_content: None,
             })
         } else {
             Ok(results.remove(0))
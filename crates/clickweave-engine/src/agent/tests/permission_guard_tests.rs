@@ -59,6 +59,7 @@ fn build_mcp_with_tool(name: &str, annotations: Option<serde_json::Value>) -> Mo
                         .to_string(),
                     }],
                     is_error: None,
+                    structured_content: None,
                 },
                 // Tool result
                 ToolCallResult {
@@ -66,6 +67,7 @@ fn build_mcp_with_tool(name: &str, annotations: Option<serde_json::Value>) -> Mo
                         text: format!("done {}", i),
                     }],
                     is_error: None,
+                    structured_content: None,
                 },
             ]
         })
@@ -295,12 +297,14 @@ fn build_mcp_with_destructive_tools(names: &[&str]) -> MockMcp {
                         .to_string(),
                     }],
                     is_error: None,
+                    structured_content: None,
                 },
                 ToolCallResult {
                     content: vec![ToolContent::Text {
                         text: format!("ok {}", i),
                     }],
                     is_error: None,
+                    structured_content: None,
                 },
             ]
         })
@@ -441,12 +445,14 @@ async fn non_destructive_tool_resets_consecutive_destructive_counter() {
                         .to_string(),
                     }],
                     is_error: None,
+                    structured_content: None,
                 },
                 ToolCallResult {
                     content: vec![ToolContent::Text {
                         text: format!("ok {}", i),
                     }],
                     is_error: None,
+                    structured_content: None,
                 },
             ]
         })
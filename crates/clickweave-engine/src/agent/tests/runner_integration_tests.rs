@@ -12,12 +12,22 @@
 //! below is sufficient to exercise the state-spine invariants the design
 //! doc requires.
 
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 
-use crate::agent::runner::{AgentAction, AgentTurn, StateRunner, ToolExecutor, TurnOutcome};
+use clickweave_llm::ChatBackend;
+use tokio_util::sync::CancellationToken;
+
+use super::super::test_stubs::{StaticMcp, llm_reply_tool};
+use crate::agent::RunStorageHandle;
+use crate::agent::runner::{
+    AgentAction, AgentTurn, StateRunner, SuspendedRun, ToolExecutor, TurnOutcome,
+};
 use crate::agent::task_state::TaskStateMutation;
+use crate::agent::trace_graph::AgentTraceGraph;
+use crate::agent::types::AgentState;
+use crate::executor::Mcp;
 
 /// Deterministic tool executor: pulls the next result off a FIFO queue and
 /// returns it. `Ok(body)` for a successful tool body; `Err(msg)` to
@@ -79,6 +89,81 @@ fn tool_call(tool: &str, args: serde_json::Value, call_id: &str) -> AgentTurn {
     }
 }
 
+/// `agent_done` reply for a scripted LLM.
+fn done(summary: &str) -> clickweave_llm::ChatResponse {
+    llm_reply_tool("agent_done", serde_json::json!({ "summary": summary }))
+}
+
+/// Run `runner` on `goal` against `mcp`, offering every tool it
+/// advertises, and return the final state.
+async fn run_with(
+    llm: &impl ChatBackend,
+    mcp: &impl Mcp,
+    goal: &str,
+    runner: StateRunner,
+) -> AgentState {
+    runner
+        .run(
+            llm,
+            mcp,
+            goal.to_string(),
+            AgentTraceGraph::new(),
+            mcp.tools_as_openai(),
+            None,
+        )
+        .await
+        .expect("run ok")
+}
+
+/// Cancels `token` the first time `trigger_tool` is dispatched, so the
+/// suspend request lands while a tool call is in flight — the runner
+/// must let it finish and suspend at the next turn boundary.
+struct SuspendingMcp {
+    inner: StaticMcp,
+    trigger_tool: &'static str,
+    token: CancellationToken,
+}
+
+impl Mcp for SuspendingMcp {
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+    ) -> anyhow::Result<clickweave_mcp::ToolCallResult> {
+        if name == self.trigger_tool {
+            self.token.cancel();
+        }
+        self.inner.call_tool(name, arguments).await
+    }
+
+    fn has_tool(&self, name: &str) -> bool {
+        self.inner.has_tool(name)
+    }
+
+    fn tools_as_openai(&self) -> Vec<serde_json::Value> {
+        self.inner.tools_as_openai()
+    }
+
+    async fn refresh_server_tool_list(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+fn temp_storage() -> (RunStorageHandle, tempfile::TempDir) {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let mut storage = clickweave_core::storage::RunStorage::new(tmp.path(), "suspend-test");
+    storage.begin_execution().expect("begin_execution");
+    (Arc::new(Mutex::new(storage)), tmp)
+}
+
+fn load_snapshot(storage: &RunStorageHandle) -> Option<SuspendedRun> {
+    storage
+        .lock()
+        .unwrap()
+        .load_suspended_run()
+        .expect("snapshot parses")
+}
+
 #[tokio::test]
 async fn single_step_agent_done_completes_run() {
     let mut r = StateRunner::new_for_test("log in".to_string());
@@ -468,3 +553,56 @@ mod state_spine_event_contract_tests;
 // for `REPEAT_ACTION_THRESHOLD` consecutive turns must emit an
 // `AgentEvent::Warning` carrying `NO_PROGRESS_WARNING_PREFIX`.
 mod repeat_action_loop_detection_tests;
+
+// Suspend / resume: a suspend request snapshots the run into `RunStorage`
+// at the next turn boundary, and `resume_agent_workflow` continues it
+// with the transcript, steps, and step budget intact.
+mod suspend_resume_tests;
+
+// `ask_user`: the question goes out as `RunnerOutput::UserQuestion`, the
+// reply (or the timeout / headless fallback) comes back as the tool
+// result, and the exchange is recorded in the trace.
+mod ask_user_tests;
+
+// Plan-first mode: state-changing tools stay blocked until the operator
+// approves (or edits) the `propose_plan` proposal; approved subgoals are
+// seeded onto the stack, and revisions go back for approval.
+mod plan_first_tests;
+
+// Sub-agents: `delegate_subgoal` runs a child runner with a restricted
+// tool scope and its own budget, inherits the approval gate and
+// permission policy, and records into the parent's graph and run dir.
+mod sub_agent_tests;
+
+// Streaming: with `stream_llm`, the turn is requested through
+// `chat_stream_with_options` and its fragments surface as transient
+// `LlmDelta` events; the assembled response drives the run as before.
+mod streaming_tests;
+
+// Structured output: an endpoint in `ToolMode::JsonSchema` is sent an
+// `agent_turn` JSON Schema instead of `tools`, and its JSON replies are
+// parsed into the same turns as native tool calls.
+mod structured_turn_tests;
+
+// Model routing: `AgentConfig::llm_escalation` marks turns and the
+// completion check for a routing backend's escalation route, and step
+// events record which model chose them.
+mod model_routing_tests;
+
+// Context window: the compaction budget is fitted to the context length
+// the endpoint reports, and a request refused as too long is re-compacted
+// under a tighter budget and asked again.
+mod context_window_tests;
+
+// Transcript summary: with a summarizer attached, steps compaction
+// collapses are folded into one LLM-written summary after the goal, and a
+// failing summarizer leaves the one-line stand-ins in place.
+mod transcript_summary_tests;
+
+// Cancellation: a stop request abandons the tool call in flight, records
+// which one as `ToolCallCancelled`, and halts with `Cancelled`.
+mod cancel_tests;
+
+// MCP replay: a run recorded through an `McpCassette` replays from the
+// cassette alone, so a recorded desktop run becomes a runner fixture.
+mod mcp_replay_tests;
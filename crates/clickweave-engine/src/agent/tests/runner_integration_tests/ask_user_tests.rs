@@ -0,0 +1,257 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use super::super::super::test_stubs::{CapturingLlm, StaticMcp, llm_reply_tool};
+use super::{done, run_with};
+use crate::agent::ask_user::AskUserOutcome;
+use crate::agent::runner::StateRunner;
+use crate::agent::types::{AgentConfig, AgentEvent, RunnerOutput, StepOutcome, TerminalReason};
+use tokio_util::sync::CancellationToken;
+
+fn mcp() -> StaticMcp {
+    StaticMcp::with_tools(&["cdp_click"])
+}
+
+fn ask_then_done() -> CapturingLlm {
+    CapturingLlm::new(vec![
+        llm_reply_tool(
+            "ask_user",
+            serde_json::json!({
+                "question": "Which account should I use?",
+                "choices": ["work", "personal"],
+            }),
+        ),
+        done("done"),
+    ])
+}
+
+/// Text of every message the LLM saw on its second call, joined so the
+/// tool result can be searched regardless of its exact slot.
+fn second_call_transcript(llm: &CapturingLlm) -> String {
+    llm.messages_at(1)
+        .iter()
+        .filter_map(|m| m.content_text().map(str::to_string))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The question reaches the host through `RunnerOutput::UserQuestion`,
+/// the reply comes back as the tool result, and the exchange is
+/// recorded both as a step and as a `UserQuestionAnswered` event.
+#[tokio::test]
+async fn answered_question_is_fed_back_as_tool_result() {
+    let llm = ask_then_done();
+    let (event_tx, mut event_rx) = mpsc::channel::<RunnerOutput>(64);
+    let host = tokio::spawn(async move {
+        let mut answered = None;
+        while let Some(output) = event_rx.recv().await {
+            match output {
+                RunnerOutput::UserQuestion { question, reply } => {
+                    assert_eq!(question.question, "Which account should I use?");
+                    assert_eq!(question.choices, vec!["work", "personal"]);
+                    let _ = reply.send("work".to_string());
+                }
+                RunnerOutput::Event(AgentEvent::UserQuestionAnswered {
+                    outcome, answer, ..
+                }) => answered = Some((outcome, answer)),
+                _ => {}
+            }
+        }
+        answered
+    });
+
+    let state = run_with(
+        &llm,
+        &mcp(),
+        "log in",
+        StateRunner::new("log in".to_string(), AgentConfig::default()).with_events(event_tx),
+    )
+    .await;
+
+    assert!(state.completed);
+    assert_eq!(state.steps.len(), 1);
+    match &state.steps[0].outcome {
+        StepOutcome::Success(body) => assert!(body.contains(r#""answer":"work""#)),
+        other => panic!("expected Success, got {other:?}"),
+    }
+    assert!(second_call_transcript(&llm).contains(r#""answer":"work""#));
+    assert_eq!(
+        host.await.unwrap(),
+        Some((AskUserOutcome::Answered, Some("work".to_string())))
+    );
+}
+
+/// A headless run (no event channel) answers immediately with the
+/// fallback body instead of blocking.
+#[tokio::test]
+async fn headless_run_gets_no_human_fallback() {
+    let llm = ask_then_done();
+    let state = run_with(
+        &llm,
+        &mcp(),
+        "log in",
+        StateRunner::new("log in".to_string(), AgentConfig::default()),
+    )
+    .await;
+
+    assert!(state.completed);
+    assert!(second_call_transcript(&llm).contains("no_human_available"));
+}
+
+/// A listening host that never replies is cut off by
+/// `ask_user_timeout`, and the LLM is told the question timed out.
+#[tokio::test(start_paused = true)]
+async fn unanswered_question_times_out() {
+    let llm = ask_then_done();
+    let (event_tx, mut event_rx) = mpsc::channel::<RunnerOutput>(64);
+    let host = tokio::spawn(async move {
+        let mut held_replies = Vec::new();
+        while let Some(output) = event_rx.recv().await {
+            if let RunnerOutput::UserQuestion { reply, .. } = output {
+                held_replies.push(reply);
+            }
+        }
+        held_replies.len()
+    });
+    let config = AgentConfig {
+        ask_user_timeout: Some(Duration::from_secs(30)),
+        ..AgentConfig::default()
+    };
+
+    let state = run_with(
+        &llm,
+        &mcp(),
+        "log in",
+        StateRunner::new("log in".to_string(), config).with_events(event_tx),
+    )
+    .await;
+
+    assert!(state.completed);
+    assert!(second_call_transcript(&llm).contains("timed_out"));
+    assert_eq!(host.await.unwrap(), 1);
+}
+
+/// A host that holds the question open without replying, cancelling
+/// `token` (when given) as soon as the question arrives. Yields the
+/// recorded outcome.
+fn holding_host(
+    mut event_rx: mpsc::Receiver<RunnerOutput>,
+    token: Option<CancellationToken>,
+) -> tokio::task::JoinHandle<Option<AskUserOutcome>> {
+    tokio::spawn(async move {
+        let mut held_replies = Vec::new();
+        let mut outcome = None;
+        while let Some(output) = event_rx.recv().await {
+            match output {
+                RunnerOutput::UserQuestion { reply, .. } => {
+                    held_replies.push(reply);
+                    if let Some(token) = &token {
+                        token.cancel();
+                    }
+                }
+                RunnerOutput::Event(AgentEvent::UserQuestionAnswered { outcome: o, .. }) => {
+                    outcome = Some(o)
+                }
+                _ => {}
+            }
+        }
+        outcome
+    })
+}
+
+/// The run deadline cuts an open question short even when
+/// `ask_user_timeout` is longer, and the run then halts on the deadline.
+#[tokio::test(start_paused = true)]
+async fn open_question_is_bounded_by_the_run_deadline() {
+    let llm = ask_then_done();
+    let (event_tx, event_rx) = mpsc::channel::<RunnerOutput>(64);
+    let host = holding_host(event_rx, None);
+    let config = AgentConfig {
+        ask_user_timeout: Some(Duration::from_secs(300)),
+        max_duration: Some(Duration::from_secs(10)),
+        ..AgentConfig::default()
+    };
+
+    let started = tokio::time::Instant::now();
+    let state = run_with(
+        &llm,
+        &mcp(),
+        "log in",
+        StateRunner::new("log in".to_string(), config).with_events(event_tx),
+    )
+    .await;
+
+    assert!(
+        matches!(
+            state.terminal_reason,
+            Some(TerminalReason::DeadlineExceeded { .. })
+        ),
+        "{:?}",
+        state.terminal_reason
+    );
+    assert!(started.elapsed() < Duration::from_secs(60));
+    assert_eq!(host.await.unwrap(), Some(AskUserOutcome::TimedOut));
+}
+
+/// A suspend request while a question is open ends the wait and
+/// suspends the run instead of waiting out `ask_user_timeout`.
+#[tokio::test(start_paused = true)]
+async fn suspend_request_ends_an_open_question() {
+    let llm = ask_then_done();
+    let (event_tx, event_rx) = mpsc::channel::<RunnerOutput>(64);
+    let token = CancellationToken::new();
+    let host = holding_host(event_rx, Some(token.clone()));
+
+    let started = tokio::time::Instant::now();
+    let state = run_with(
+        &llm,
+        &mcp(),
+        "log in",
+        StateRunner::new("log in".to_string(), AgentConfig::default())
+            .with_events(event_tx)
+            .with_suspend_signal(token),
+    )
+    .await;
+
+    assert!(
+        matches!(
+            state.terminal_reason,
+            Some(TerminalReason::Suspended { .. })
+        ),
+        "{:?}",
+        state.terminal_reason
+    );
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert_eq!(host.await.unwrap(), Some(AskUserOutcome::Interrupted));
+}
+
+/// A stop request while a question is open halts the run as cancelled.
+#[tokio::test(start_paused = true)]
+async fn stop_request_ends_an_open_question() {
+    let llm = ask_then_done();
+    let (event_tx, event_rx) = mpsc::channel::<RunnerOutput>(64);
+    let token = CancellationToken::new();
+    let host = holding_host(event_rx, Some(token.clone()));
+
+    let state = run_with(
+        &llm,
+        &mcp(),
+        "log in",
+        StateRunner::new("log in".to_string(), AgentConfig::default())
+            .with_events(event_tx)
+            .with_cancel_signal(token),
+    )
+    .await;
+
+    assert!(
+        matches!(
+            state.terminal_reason,
+            Some(TerminalReason::Cancelled { .. })
+        ),
+        "{:?}",
+        state.terminal_reason
+    );
+    assert_eq!(llm.call_count(), 1);
+    assert_eq!(host.await.unwrap(), Some(AskUserOutcome::Interrupted));
+}
@@ -0,0 +1,95 @@
+use super::super::super::test_stubs::{ScriptedLlm, StaticMcp, llm_reply_tool};
+use crate::agent::runner::StateRunner;
+use crate::agent::trace_graph::AgentTraceGraph;
+use crate::agent::types::{AgentConfig, AgentEvent, RunnerOutput, TerminalReason};
+use crate::executor::Mcp;
+use tokio_util::sync::CancellationToken;
+
+/// `cdp_wait_for_page_change` never returns; everything else is
+/// answered by `inner`.
+struct StallingMcp {
+    inner: StaticMcp,
+}
+
+impl Mcp for StallingMcp {
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+    ) -> anyhow::Result<clickweave_mcp::ToolCallResult> {
+        if name == "cdp_wait_for_page_change" {
+            std::future::pending::<()>().await;
+        }
+        self.inner.call_tool(name, arguments).await
+    }
+
+    fn has_tool(&self, name: &str) -> bool {
+        self.inner.has_tool(name)
+    }
+
+    fn tools_as_openai(&self) -> Vec<serde_json::Value> {
+        self.inner.tools_as_openai()
+    }
+
+    async fn refresh_server_tool_list(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Stopping while a tool call hangs abandons it at once instead of
+/// waiting out its timeout, and the trace names the abandoned call.
+#[tokio::test]
+async fn stop_abandons_the_tool_call_in_flight() {
+    let mcp = StallingMcp {
+        inner: StaticMcp::with_tools(&["cdp_find_elements", "cdp_wait_for_page_change"])
+            .with_reply(
+                "cdp_find_elements",
+                r#"{"page_url":"about:blank","source":"cdp","matches":[]}"#,
+            ),
+    };
+    let llm = ScriptedLlm::new(vec![
+        llm_reply_tool("cdp_wait_for_page_change", serde_json::json!({})),
+        llm_reply_tool("agent_done", serde_json::json!({"summary": "unreachable"})),
+    ]);
+    let token = CancellationToken::new();
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(256);
+    let runner = StateRunner::new("wait for the page".to_string(), AgentConfig::default())
+        .with_events(event_tx)
+        .with_cancel_signal(token.clone());
+    let run_id = runner.run_id;
+    let tools = mcp.tools_as_openai();
+
+    let stop = async {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        token.cancel();
+    };
+    let run = runner.run(
+        &llm,
+        &mcp,
+        "wait for the page".to_string(),
+        AgentTraceGraph::new(),
+        tools,
+        None,
+    );
+    let (state, ()) = tokio::join!(run, stop);
+    let state = state.expect("run ok");
+
+    assert!(matches!(
+        state.terminal_reason,
+        Some(TerminalReason::Cancelled { steps_executed: 0 })
+    ));
+    assert_eq!(llm.call_count(), 1, "no LLM call after the stop");
+    let mut cancelled = Vec::new();
+    while let Ok(output) = event_rx.try_recv() {
+        if let RunnerOutput::Event(AgentEvent::ToolCallCancelled {
+            run_id: event_run_id,
+            tool_name,
+            ..
+        }) = output
+        {
+            assert_eq!(event_run_id, run_id);
+            cancelled.push(tool_name);
+        }
+    }
+    assert_eq!(cancelled, vec!["cdp_wait_for_page_change"]);
+}
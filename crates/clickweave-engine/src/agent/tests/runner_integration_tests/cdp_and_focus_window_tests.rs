@@ -269,6 +269,7 @@ async fn no_focus_launch_app_skip_bypasses_foregrounding_mcp_dispatch() {
             Ok(ToolCallResult {
                 content: vec![ToolContent::Text { text }],
                 is_error: None,
+                structured_content: None,
             })
         }
 
@@ -406,6 +407,7 @@ async fn no_focus_launch_app_dispatch_sets_background_true() {
             Ok(ToolCallResult {
                 content: vec![ToolContent::Text { text }],
                 is_error: None,
+                structured_content: None,
             })
         }
 
@@ -758,6 +760,7 @@ async fn auto_connect_cdp_probe_upgrades_focused_app_kind_after_unstructured_lau
                     text: body.to_string(),
                 }],
                 is_error: None,
+                structured_content: None,
             })
         }
         fn has_tool(&self, name: &str) -> bool {
@@ -841,6 +844,7 @@ async fn cdp_attachable_focus_skip_triggers_auto_connect_cdp() {
                     text: body.to_string(),
                 }],
                 is_error: None,
+                structured_content: None,
             })
         }
         fn has_tool(&self, name: &str) -> bool {
@@ -965,6 +969,7 @@ async fn policy_disabled_focus_skip_still_triggers_auto_connect_cdp() {
                     text: body.to_string(),
                 }],
                 is_error: None,
+                structured_content: None,
             })
         }
         fn has_tool(&self, name: &str) -> bool {
@@ -1080,6 +1085,7 @@ async fn raw_cdp_connect_tool_call_is_blocked_before_mcp_dispatch() {
                     text: "should not be called".to_string(),
                 }],
                 is_error: None,
+                structured_content: None,
             })
         }
         fn has_tool(&self, name: &str) -> bool {
@@ -0,0 +1,177 @@
+use std::sync::Mutex;
+
+use anyhow::Result;
+use clickweave_llm::{ChatBackend, ChatOptions, ChatResponse, ContextOverflow, Message, ModelInfo};
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use super::super::super::test_stubs::{ScriptedLlm, StaticMcp, llm_reply_tool};
+use crate::agent::context::estimate_messages_tokens;
+use crate::agent::runner::StateRunner;
+use crate::agent::trace_graph::AgentTraceGraph;
+use crate::agent::types::{AgentConfig, AgentEvent, AgentState, RunnerOutput};
+use crate::executor::Mcp;
+
+/// A scripted endpoint with a context window: reports `context_length`
+/// (when set), refuses the calls listed in `reject_calls` as too long,
+/// and records the estimated size of every transcript it was sent.
+struct WindowedLlm {
+    context_length: Option<u64>,
+    reject_calls: Vec<usize>,
+    script: ScriptedLlm,
+    sent: Mutex<Vec<Vec<Message>>>,
+}
+
+impl WindowedLlm {
+    fn new(responses: Vec<ChatResponse>) -> Self {
+        Self {
+            context_length: None,
+            reject_calls: Vec::new(),
+            script: ScriptedLlm::new(responses),
+            sent: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn sent_tokens(&self) -> Vec<usize> {
+        self.sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|messages| estimate_messages_tokens(messages))
+            .collect()
+    }
+
+    fn last_sent(&self) -> Vec<Message> {
+        self.sent
+            .lock()
+            .unwrap()
+            .last()
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl ChatBackend for WindowedLlm {
+    fn model_name(&self) -> &str {
+        "windowed"
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+    ) -> Result<ChatResponse> {
+        let call = {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(messages.to_vec());
+            sent.len() - 1
+        };
+        if self.reject_calls.contains(&call) {
+            return Err(anyhow::Error::new(ContextOverflow(
+                "This model's maximum context length was exceeded".to_string(),
+            )));
+        }
+        self.script
+            .chat_with_options(messages, tools, options)
+            .await
+    }
+
+    async fn fetch_model_info(&self) -> Result<Option<ModelInfo>> {
+        Ok(self.context_length.map(|length| {
+            serde_json::from_value(serde_json::json!({
+                "id": "windowed",
+                "loaded_context_length": length,
+            }))
+            .unwrap()
+        }))
+    }
+}
+
+/// Three page reads with long bodies, then `agent_done`.
+fn reading_script() -> Vec<ChatResponse> {
+    let mut script: Vec<ChatResponse> = (1..=3)
+        .map(|n| llm_reply_tool("cdp_click", serde_json::json!({"uid": format!("e{n}")})))
+        .collect();
+    script.push(llm_reply_tool(
+        "agent_done",
+        serde_json::json!({"summary": "read"}),
+    ));
+    script
+}
+
+fn wordy_mcp() -> StaticMcp {
+    let body = "The quick brown fox jumps over the lazy dog. ".repeat(200);
+    StaticMcp::with_tools(&["cdp_click"]).with_reply("cdp_click", &body)
+}
+
+async fn run(llm: &WindowedLlm, mcp: &StaticMcp) -> (AgentState, Vec<AgentEvent>) {
+    let (tx, mut rx) = mpsc::channel::<RunnerOutput>(256);
+    let state = StateRunner::new("g".into(), AgentConfig::default())
+        .with_events(tx)
+        .run(
+            llm,
+            mcp,
+            "read the page".to_string(),
+            AgentTraceGraph::new(),
+            mcp.tools_as_openai(),
+            None,
+        )
+        .await
+        .expect("run ok");
+    let mut events = Vec::new();
+    while let Ok(output) = rx.try_recv() {
+        events.extend(output.into_event());
+    }
+    (state, events)
+}
+
+fn collapsed_bodies(messages: &[Message]) -> usize {
+    messages
+        .iter()
+        .filter(|m| {
+            m.content_text()
+                .is_some_and(|text| text.starts_with("[collapsed to fit budget]"))
+        })
+        .count()
+}
+
+#[tokio::test]
+async fn length_rejection_recompacts_and_retries_the_turn() {
+    let mut llm = WindowedLlm::new(reading_script());
+    llm.reject_calls = vec![3];
+    let mcp = wordy_mcp();
+
+    let (state, events) = run(&llm, &mcp).await;
+
+    assert!(state.completed, "the rejection must not end the run");
+    let sent = llm.sent_tokens();
+    assert_eq!(sent.len(), 5, "the rejected turn is asked again once");
+    assert!(
+        sent[4] <= sent[3] * 3 / 4,
+        "retry must fit under the tightened budget: {sent:?}"
+    );
+    assert!(events.iter().any(|event| matches!(
+        event,
+        AgentEvent::Warning { message } if message.contains("context window was exceeded")
+    )));
+}
+
+#[tokio::test]
+async fn budget_follows_the_reported_context_length() {
+    let unknown = WindowedLlm::new(reading_script());
+    run(&unknown, &wordy_mcp()).await;
+    assert_eq!(
+        collapsed_bodies(&unknown.last_sent()),
+        0,
+        "the default budget holds three page reads"
+    );
+
+    let mut small = WindowedLlm::new(reading_script());
+    small.context_length = Some(8_192);
+    run(&small, &wordy_mcp()).await;
+    assert!(
+        collapsed_bodies(&small.last_sent()) > 0,
+        "a small window forces compaction"
+    );
+}
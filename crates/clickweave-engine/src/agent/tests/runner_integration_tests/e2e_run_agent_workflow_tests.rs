@@ -45,6 +45,7 @@ async fn run_agent_workflow_happy_path_preserves_legacy_agent_state_contract() {
         None,
         None,
         None,
+        None,
         uuid::Uuid::new_v4(),
         None,
         None,
@@ -132,6 +133,8 @@ async fn run_agent_workflow_approval_rejected_records_replan_and_stays_incomplet
     let channels = AgentChannels {
         event_tx,
         approval_tx,
+        suspend_signal: None,
+        cancel_signal: None,
     };
 
     let (state, _writer_tx) = run_agent_workflow(
@@ -144,6 +147,7 @@ async fn run_agent_workflow_approval_rejected_records_replan_and_stays_incomplet
         &mcp,
         Some(channels),
         None,
+        None,
         Some(policy),
         uuid::Uuid::new_v4(),
         None,
@@ -232,6 +236,7 @@ async fn run_agent_workflow_with_storage_writes_terminal_boundary_record() {
         None,
         None,
         None,
+        None,
         uuid::Uuid::new_v4(),
         None,
         None,
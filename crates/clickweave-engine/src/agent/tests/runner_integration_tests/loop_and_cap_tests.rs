@@ -333,3 +333,127 @@ async fn max_errors_reached_sets_correct_terminal_reason() {
         other => panic!("expected MaxErrorsReached, got {:?}", other),
     }
 }
+
+/// `Mcp` stub whose named tool never answers within any sane budget.
+/// Every other tool delegates to the wrapped `StaticMcp`.
+struct HangingMcp {
+    inner: StaticMcp,
+    hanging_tool: &'static str,
+}
+
+impl Mcp for HangingMcp {
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+    ) -> anyhow::Result<clickweave_mcp::ToolCallResult> {
+        if name == self.hanging_tool {
+            tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+        }
+        self.inner.call_tool(name, arguments).await
+    }
+
+    fn has_tool(&self, name: &str) -> bool {
+        self.inner.has_tool(name)
+    }
+
+    fn tools_as_openai(&self) -> Vec<serde_json::Value> {
+        self.inner.tools_as_openai()
+    }
+
+    async fn refresh_server_tool_list(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Identical calls that time out are reported with the timeout prefix
+/// and do not trip loop detection — they only count toward
+/// `max_consecutive_errors`.
+#[tokio::test(start_paused = true)]
+async fn repeated_tool_timeouts_count_as_errors_not_loops() {
+    let mut cfg = cfg_with_steps(5);
+    cfg.max_consecutive_errors = 2;
+    cfg.tool_timeouts
+        .overrides
+        .insert("cdp_click".to_string(), std::time::Duration::from_secs(2));
+    let llm = ScriptedLlm::new(vec![
+        llm_reply_tool("cdp_click", serde_json::json!({"uid": "d1"})),
+        llm_reply_tool("cdp_click", serde_json::json!({"uid": "d1"})),
+        llm_reply_tool("agent_done", serde_json::json!({"summary": "x"})),
+    ]);
+    let mcp = HangingMcp {
+        inner: StaticMcp::with_tools(&["cdp_click"]),
+        hanging_tool: "cdp_click",
+    };
+    let tools = mcp.tools_as_openai();
+    let runner = StateRunner::new("goal".to_string(), cfg);
+
+    let state = runner
+        .run(
+            &llm,
+            &mcp,
+            "goal".to_string(),
+            AgentTraceGraph::new(),
+            tools,
+            None,
+        )
+        .await
+        .expect("run ok");
+
+    match state.terminal_reason {
+        Some(TerminalReason::MaxErrorsReached { consecutive_errors }) => {
+            assert_eq!(consecutive_errors, 2);
+        }
+        other => panic!("expected MaxErrorsReached, got {:?}", other),
+    }
+    let Some(crate::agent::types::StepOutcome::Error(error)) =
+        state.steps.last().map(|s| &s.outcome)
+    else {
+        panic!("expected the last step to be an error");
+    };
+    assert!(
+        crate::agent::tool_timeouts::is_timeout_error(error),
+        "timeout must be reported with the stable prefix, got {error:?}"
+    );
+}
+
+/// A tool call that outlives the run deadline is clamped to it, and the
+/// next turn halts with `DeadlineExceeded` instead of waiting out the
+/// tool's own (longer) timeout.
+#[tokio::test(start_paused = true)]
+async fn run_halts_with_deadline_exceeded() {
+    let mut cfg = cfg_with_steps(5);
+    cfg.max_duration = Some(std::time::Duration::from_secs(10));
+    let llm = ScriptedLlm::new(vec![
+        llm_reply_tool("cdp_wait_for_page_change", serde_json::json!({})),
+        llm_reply_tool("agent_done", serde_json::json!({"summary": "x"})),
+    ]);
+    let mcp = HangingMcp {
+        inner: StaticMcp::with_tools(&["cdp_wait_for_page_change"]),
+        hanging_tool: "cdp_wait_for_page_change",
+    };
+    let tools = mcp.tools_as_openai();
+    let runner = StateRunner::new("goal".to_string(), cfg);
+
+    let started = tokio::time::Instant::now();
+    let state = runner
+        .run(
+            &llm,
+            &mcp,
+            "goal".to_string(),
+            AgentTraceGraph::new(),
+            tools,
+            None,
+        )
+        .await
+        .expect("run ok");
+
+    match state.terminal_reason {
+        Some(TerminalReason::DeadlineExceeded {
+            max_duration_secs, ..
+        }) => assert_eq!(max_duration_secs, 10),
+        other => panic!("expected DeadlineExceeded, got {:?}", other),
+    }
+    assert!(started.elapsed() < std::time::Duration::from_secs(45));
+    assert_eq!(state.steps.len(), 1);
+}
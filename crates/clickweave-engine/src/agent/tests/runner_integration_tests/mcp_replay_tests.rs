@@ -0,0 +1,67 @@
+use super::super::super::test_stubs::{CapturingLlm, StaticMcp, llm_reply_tool};
+use super::{done, run_with};
+use crate::agent::runner::StateRunner;
+use crate::agent::types::{AgentConfig, StepOutcome, TerminalReason};
+use crate::executor::{McpCassette, McpReplayMatching};
+
+fn find_then_done() -> CapturingLlm {
+    CapturingLlm::new(vec![
+        llm_reply_tool("find_text", serde_json::json!({"text": "Inbox"})),
+        done("found it"),
+    ])
+}
+
+/// A run recorded against the desktop replays from its MCP cassette
+/// alone: the wrapped server has no tools and no replies, yet the runner
+/// sees the recorded tool list and the LLM is fed the recorded result.
+#[tokio::test]
+async fn a_recorded_agent_run_replays_as_a_runner_fixture() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let path = tmp.path().join("cassettes").join("mcp.jsonl");
+
+    let desktop =
+        StaticMcp::with_tools(&["find_text"]).with_reply("find_text", "Inbox at (40, 12)");
+    let recording = McpCassette::record(desktop, &path).expect("record");
+    let recorded = run_with(
+        &find_then_done(),
+        &recording,
+        "open the inbox",
+        StateRunner::new("open the inbox".into(), AgentConfig::default()),
+    )
+    .await;
+    drop(recording);
+
+    let replay = McpCassette::replay(
+        StaticMcp::with_tools(&[]),
+        &path,
+        McpReplayMatching::Sequence,
+    )
+    .expect("replay");
+    let llm = find_then_done();
+    let replayed = run_with(
+        &llm,
+        &replay,
+        "open the inbox",
+        StateRunner::new("open the inbox".into(), AgentConfig::default()),
+    )
+    .await;
+
+    for state in [&recorded, &replayed] {
+        assert!(matches!(
+            &state.terminal_reason,
+            Some(TerminalReason::Completed { summary }) if summary == "found it"
+        ));
+    }
+    assert_eq!(replayed.steps.len(), recorded.steps.len());
+    assert!(matches!(
+        &replayed.steps[0].outcome,
+        StepOutcome::Success(text) if text.contains("Inbox at (40, 12)")
+    ));
+    let transcript = llm
+        .messages_at(1)
+        .iter()
+        .filter_map(|m| m.content_text().map(str::to_string))
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(transcript.contains("Inbox at (40, 12)"));
+}
@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clickweave_llm::{ChatBackend, ChatOptions, ChatResponse, Message, RoutingBackend};
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use super::super::super::test_stubs::{ScriptedLlm, StaticMcp, llm_reply_text, llm_reply_tool};
+use super::done;
+use crate::agent::runner::StateRunner;
+use crate::agent::trace_graph::AgentTraceGraph;
+use crate::agent::types::{AgentConfig, AgentEvent, AgentState, LlmEscalation, RunnerOutput};
+use crate::executor::Mcp;
+
+const TINY_PNG_BASE64: &str =
+    "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=";
+
+/// A scripted route with its own model name.
+struct Route {
+    name: &'static str,
+    script: ScriptedLlm,
+}
+
+impl Route {
+    fn new(name: &'static str, responses: Vec<ChatResponse>) -> Self {
+        Self {
+            name,
+            script: ScriptedLlm::new(responses),
+        }
+    }
+}
+
+impl ChatBackend for Route {
+    fn model_name(&self) -> &str {
+        self.name
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+    ) -> Result<ChatResponse> {
+        self.script
+            .chat_with_options(messages, tools, options)
+            .await
+    }
+}
+
+fn click(uid: &str) -> ChatResponse {
+    llm_reply_tool("cdp_click", serde_json::json!({"uid": uid}))
+}
+
+async fn run(
+    llm: &RoutingBackend<Route>,
+    mcp: &StaticMcp,
+    runner: StateRunner,
+) -> (AgentState, Vec<AgentEvent>) {
+    let (tx, mut rx) = mpsc::channel::<RunnerOutput>(256);
+    let state = runner
+        .with_events(tx)
+        .run(
+            llm,
+            mcp,
+            "submit the form".to_string(),
+            AgentTraceGraph::new(),
+            mcp.tools_as_openai(),
+            None,
+        )
+        .await
+        .expect("run ok");
+    let mut events = Vec::new();
+    while let Ok(output) = rx.try_recv() {
+        events.extend(output.into_event());
+    }
+    (state, events)
+}
+
+#[tokio::test]
+async fn consecutive_errors_escalate_and_each_step_records_its_model() {
+    let fill = llm_reply_tool("cdp_fill", serde_json::json!({"uid": "e3", "value": "x"}));
+    let llm = RoutingBackend::new(Route::new(
+        "small",
+        vec![click("e1"), click("e2"), done("submitted")],
+    ))
+    .with_fallback(Route::new("large", vec![fill]));
+    let mcp = StaticMcp::with_tools(&["cdp_click", "cdp_fill"])
+        .with_error("cdp_click", "element detached");
+    let config = AgentConfig {
+        llm_escalation: LlmEscalation {
+            after_consecutive_errors: Some(2),
+            ..LlmEscalation::default()
+        },
+        ..AgentConfig::default()
+    };
+
+    let (state, events) = run(&llm, &mcp, StateRunner::new("g".into(), config)).await;
+
+    assert!(state.completed, "a success ends the escalation");
+    let step_models: Vec<(&str, Option<&str>)> = events
+        .iter()
+        .filter_map(|event| match event {
+            AgentEvent::StepFailed {
+                tool_name, model, ..
+            }
+            | AgentEvent::StepCompleted {
+                tool_name, model, ..
+            } => Some((tool_name.as_str(), model.as_deref())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        step_models,
+        [
+            ("cdp_click", Some("small")),
+            ("cdp_click", Some("small")),
+            ("cdp_fill", Some("large")),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn recovering_turns_escalate_only_when_the_policy_says_so() {
+    for (while_recovering, expected) in [(false, "small"), (true, "large")] {
+        let llm = RoutingBackend::new(Route::new("small", vec![click("e1"), click("e2")]))
+            .with_fallback(Route::new("large", vec![click("e3")]));
+        let mcp = StaticMcp::with_tools(&["cdp_click"]).with_error("cdp_click", "not found");
+        let config = AgentConfig {
+            max_steps: 2,
+            llm_escalation: LlmEscalation {
+                while_recovering,
+                ..LlmEscalation::default()
+            },
+            ..AgentConfig::default()
+        };
+
+        let (_, events) = run(&llm, &mcp, StateRunner::new("g".into(), config)).await;
+
+        let second_step_model = events.iter().find_map(|event| match event {
+            AgentEvent::StepFailed {
+                step_index: 1,
+                model,
+                ..
+            } => model.clone(),
+            _ => None,
+        });
+        assert_eq!(second_step_model.as_deref(), Some(expected));
+    }
+}
+
+#[tokio::test]
+async fn completion_check_escalates_the_vision_route() {
+    for (completion_check, completed) in [(false, false), (true, true)] {
+        let llm = RoutingBackend::new(Route::new("small", vec![done("submitted")]));
+        let vision = RoutingBackend::new(Route::new(
+            "small-vlm",
+            vec![llm_reply_text("NO: the form is still open")],
+        ))
+        .with_fallback(Route::new("large-vlm", vec![llm_reply_text("YES")]));
+        let mcp = StaticMcp::with_tools(&["take_screenshot"]).with_image_reply(
+            "take_screenshot",
+            TINY_PNG_BASE64,
+            "image/png",
+        );
+        let config = AgentConfig {
+            llm_escalation: LlmEscalation {
+                completion_check,
+                ..LlmEscalation::default()
+            },
+            ..AgentConfig::default()
+        };
+        let runner = StateRunner::new("g".into(), config).with_vision(Arc::new(vision));
+
+        let (state, _) = run(&llm, &mcp, runner).await;
+
+        assert_eq!(state.completed, completed);
+    }
+}
@@ -0,0 +1,195 @@
+use tokio::sync::mpsc;
+
+use super::super::super::test_stubs::{CapturingLlm, StaticMcp, llm_reply_tool};
+use super::{done, run_with};
+use crate::agent::plan::{PlanDecision, PlannedSubgoal};
+use crate::agent::runner::StateRunner;
+use crate::agent::types::{
+    AgentConfig, AgentEvent, PlanApprovalRequest, RunnerOutput, StepOutcome, TerminalReason,
+};
+
+fn plan_first_config() -> AgentConfig {
+    AgentConfig {
+        plan_first: true,
+        max_steps: 8,
+        ..AgentConfig::default()
+    }
+}
+
+fn propose(texts: &[&str]) -> clickweave_llm::ChatResponse {
+    let subgoals: Vec<_> = texts
+        .iter()
+        .map(|t| serde_json::json!({"text": t}))
+        .collect();
+    llm_reply_tool("propose_plan", serde_json::json!({ "subgoals": subgoals }))
+}
+
+fn subgoal(text: &str) -> PlannedSubgoal {
+    PlannedSubgoal {
+        text: text.to_string(),
+        expected_apps: vec![],
+        expected_tools: vec![],
+    }
+}
+
+type PlanHost = tokio::task::JoinHandle<(Vec<PlanApprovalRequest>, Vec<AgentEvent>)>;
+
+/// Host stub: answers each `PlanApproval` with the next scripted
+/// decision and returns every request it saw plus the recorded
+/// `PlanReviewed` events.
+fn spawn_plan_host(mut decisions: Vec<PlanDecision>) -> (mpsc::Sender<RunnerOutput>, PlanHost) {
+    let (event_tx, mut event_rx) = mpsc::channel::<RunnerOutput>(64);
+    let host = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        let mut reviewed = Vec::new();
+        while let Some(output) = event_rx.recv().await {
+            match output {
+                RunnerOutput::PlanApproval { request, reply } => {
+                    requests.push(request);
+                    let _ = reply.send(decisions.remove(0));
+                }
+                RunnerOutput::Event(event @ AgentEvent::PlanReviewed { .. }) => {
+                    reviewed.push(event)
+                }
+                _ => {}
+            }
+        }
+        (requests, reviewed)
+    });
+    (event_tx, host)
+}
+
+fn mcp() -> StaticMcp {
+    StaticMcp::with_tools(&["cdp_find_elements", "cdp_click"])
+        .with_reply(
+            "cdp_find_elements",
+            r#"{"page_url":"about:blank","source":"cdp","matches":[]}"#,
+        )
+        .with_reply("cdp_click", "clicked")
+}
+
+fn transcript_text(llm: &CapturingLlm, call: usize) -> String {
+    llm.messages_at(call)
+        .iter()
+        .filter_map(|m| m.content_text().map(str::to_string))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// State-changing tools are blocked until a plan is approved; the
+/// operator's edited plan is what gets seeded and rendered, and the
+/// blocked tool dispatches once the plan is in place.
+#[tokio::test]
+async fn edited_plan_is_seeded_before_any_action_runs() {
+    let llm = CapturingLlm::new(vec![
+        llm_reply_tool("cdp_click", serde_json::json!({"uid": "1_0"})),
+        propose(&["open the CRM", "export"]),
+        llm_reply_tool("cdp_click", serde_json::json!({"uid": "1_0"})),
+        done("done"),
+    ]);
+    let (event_tx, host) = spawn_plan_host(vec![PlanDecision::Approve {
+        subgoals: vec![subgoal("open the CRM"), subgoal("export as CSV")],
+    }]);
+
+    let state = run_with(
+        &llm,
+        &mcp(),
+        "export contacts",
+        StateRunner::new("export contacts".to_string(), plan_first_config()).with_events(event_tx),
+    )
+    .await;
+
+    assert!(state.completed);
+    assert!(
+        transcript_text(&llm, 0).contains("[PLAN-FIRST MODE]"),
+        "first turn must ask for a plan"
+    );
+    assert!(matches!(&state.steps[0].outcome, StepOutcome::Error(e) if e.contains("propose_plan")));
+    assert!(
+        matches!(&state.steps[1].outcome, StepOutcome::Success(b) if b.contains(r#""edited_by_operator":true"#))
+    );
+    assert!(matches!(&state.steps[2].outcome, StepOutcome::Success(b) if b == "clicked"));
+
+    let after_approval = transcript_text(&llm, 2);
+    assert!(after_approval.contains("approved_plan (0 of 2 done):"));
+    assert!(after_approval.contains("[>] 1. open the CRM"));
+    assert!(after_approval.contains("[ ] 2. export as CSV"));
+
+    let (requests, reviewed) = host.await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(!requests[0].revision);
+    assert!(matches!(
+        &reviewed[..],
+        [AgentEvent::PlanReviewed { decision: PlanDecision::Approve { subgoals }, .. }]
+            if subgoals[1].text == "export as CSV"
+    ));
+}
+
+/// A rejection goes back to the LLM with the operator's reason, and
+/// proposing a new plan after one was approved is flagged as a revision
+/// that needs approval again.
+#[tokio::test]
+async fn rejection_and_revision_both_round_trip_through_the_operator() {
+    let llm = CapturingLlm::new(vec![
+        propose(&["do it"]),
+        propose(&["open the CRM", "export"]),
+        propose(&["export from the backup system"]),
+        done("done"),
+    ]);
+    let (event_tx, host) = spawn_plan_host(vec![
+        PlanDecision::Reject {
+            reason: "too vague".to_string(),
+        },
+        PlanDecision::Approve { subgoals: vec![] },
+        PlanDecision::Approve { subgoals: vec![] },
+    ]);
+
+    let state = run_with(
+        &llm,
+        &mcp(),
+        "export contacts",
+        StateRunner::new("export contacts".to_string(), plan_first_config()).with_events(event_tx),
+    )
+    .await;
+
+    assert!(state.completed);
+    assert!(transcript_text(&llm, 1).contains("too vague"));
+    let revised = transcript_text(&llm, 3);
+    assert!(revised.contains("approved_plan (0 of 1 done):"));
+    assert!(revised.contains("export from the backup system"));
+    assert!(!revised.contains("[ ] 2. export"));
+
+    let (requests, _) = host.await.unwrap();
+    let revisions: Vec<bool> = requests.iter().map(|r| r.revision).collect();
+    assert_eq!(revisions, vec![false, false, true]);
+}
+
+/// A host that drops the decision halts the run the same way a closed
+/// approval channel does.
+#[tokio::test]
+async fn dropped_plan_decision_halts_with_approval_unavailable() {
+    let llm = CapturingLlm::new(vec![propose(&["open the CRM"])]);
+    let (event_tx, mut event_rx) = mpsc::channel::<RunnerOutput>(64);
+    let host = tokio::spawn(async move {
+        while let Some(output) = event_rx.recv().await {
+            if let RunnerOutput::PlanApproval { reply, .. } = output {
+                drop(reply);
+            }
+        }
+    });
+
+    let state = run_with(
+        &llm,
+        &mcp(),
+        "export contacts",
+        StateRunner::new("export contacts".to_string(), plan_first_config()).with_events(event_tx),
+    )
+    .await;
+    host.await.unwrap();
+
+    assert!(matches!(
+        state.terminal_reason,
+        Some(TerminalReason::ApprovalUnavailable)
+    ));
+    assert!(state.steps.is_empty());
+}
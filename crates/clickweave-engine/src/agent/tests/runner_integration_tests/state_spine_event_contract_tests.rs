@@ -32,6 +32,7 @@ fn tc(id: &str, name: &str, arguments: Value) -> ToolCall {
 fn llm_reply_tools(id: &str, calls: Vec<ToolCall>) -> ChatResponse {
     ChatResponse {
         id: id.to_string(),
+        model: None,
         choices: vec![Choice {
             index: 0,
             message: Message::assistant_tool_calls(calls),
@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::Result;
+use clickweave_llm::{ChatBackend, ChatOptions, ChatResponse, DeltaSink, Message, StreamDelta};
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use super::super::super::test_stubs::{ScriptedLlm, StaticMcp, llm_reply_tool};
+use crate::agent::runner::StateRunner;
+use crate::agent::trace_graph::AgentTraceGraph;
+use crate::agent::types::{AgentConfig, AgentEvent, LlmDeltaKind, RunnerOutput};
+use crate::executor::Mcp;
+
+/// Scripted backend that can stream: each streamed call reports a
+/// reasoning and a text fragment before returning the scripted turn.
+struct StreamingLlm {
+    inner: ScriptedLlm,
+    streamed_calls: AtomicUsize,
+}
+
+impl ChatBackend for StreamingLlm {
+    fn model_name(&self) -> &str {
+        "streaming-llm"
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+    ) -> Result<ChatResponse> {
+        self.inner.chat_with_options(messages, tools, options).await
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+        on_delta: DeltaSink<'_>,
+    ) -> Result<ChatResponse> {
+        self.streamed_calls.fetch_add(1, Ordering::SeqCst);
+        on_delta(StreamDelta::Reasoning("Page is loaded; ".into()));
+        on_delta(StreamDelta::Text("done".into()));
+        self.inner.chat_with_options(messages, tools, options).await
+    }
+}
+
+async fn run(stream_llm: bool) -> (usize, Vec<AgentEvent>) {
+    let llm = StreamingLlm {
+        inner: ScriptedLlm::new(vec![llm_reply_tool(
+            "agent_done",
+            serde_json::json!({"summary": "done"}),
+        )]),
+        streamed_calls: AtomicUsize::new(0),
+    };
+    let mcp = StaticMcp::with_tools(&["cdp_click"]);
+    let (tx, mut rx) = mpsc::channel::<RunnerOutput>(256);
+    let config = AgentConfig {
+        stream_llm,
+        ..AgentConfig::default()
+    };
+    let state = StateRunner::new("check the page".to_string(), config)
+        .with_events(tx)
+        .run(
+            &llm,
+            &mcp,
+            "check the page".to_string(),
+            AgentTraceGraph::new(),
+            mcp.tools_as_openai(),
+            None,
+        )
+        .await
+        .expect("run ok");
+    assert!(state.completed, "the assembled turn is acted on");
+
+    let mut deltas = Vec::new();
+    while let Ok(output) = rx.try_recv() {
+        if let Some(event @ AgentEvent::LlmDelta { .. }) = output.into_event() {
+            deltas.push(event);
+        }
+    }
+    (llm.streamed_calls.load(Ordering::SeqCst), deltas)
+}
+
+#[tokio::test]
+async fn stream_llm_forwards_fragments_as_transient_delta_events() {
+    let (streamed_calls, deltas) = run(true).await;
+
+    assert_eq!(streamed_calls, 1);
+    let fragments: Vec<(LlmDeltaKind, &str, usize)> = deltas
+        .iter()
+        .map(|event| match event {
+            AgentEvent::LlmDelta {
+                kind,
+                text,
+                step_index,
+                ..
+            } => (*kind, text.as_str(), *step_index),
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(
+        fragments,
+        vec![
+            (LlmDeltaKind::Reasoning, "Page is loaded; ", 0),
+            (LlmDeltaKind::Text, "done", 0),
+        ]
+    );
+    assert!(deltas.iter().all(AgentEvent::is_transient));
+}
+
+#[tokio::test]
+async fn without_stream_llm_the_plain_chat_path_is_used() {
+    let (streamed_calls, deltas) = run(false).await;
+    assert_eq!(streamed_calls, 0);
+    assert!(deltas.is_empty());
+}
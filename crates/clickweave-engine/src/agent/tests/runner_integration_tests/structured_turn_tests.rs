@@ -0,0 +1,179 @@
+use std::sync::Mutex;
+
+use anyhow::Result;
+use clickweave_llm::{ChatBackend, ChatOptions, ChatResponse, Message, ToolMode};
+use serde_json::{Value, json};
+
+use super::super::super::test_stubs::{ScriptedLlm, StaticMcp, llm_reply_text};
+use crate::agent::resume_agent_workflow;
+use crate::agent::runner::StateRunner;
+use crate::agent::trace_graph::AgentTraceGraph;
+use crate::agent::types::{AgentCommand, AgentConfig, TerminalReason};
+use crate::executor::Mcp;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// Scripted backend in `ToolMode::JsonSchema` that records what each
+/// call was given.
+struct JsonSchemaLlm {
+    inner: ScriptedLlm,
+    /// `(tools were sent, response_format, system prompt)` per call.
+    calls: Mutex<Vec<(bool, Option<Value>, String)>>,
+}
+
+impl ChatBackend for JsonSchemaLlm {
+    fn model_name(&self) -> &str {
+        "json-schema-llm"
+    }
+
+    fn tool_mode(&self) -> ToolMode {
+        ToolMode::JsonSchema
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+    ) -> Result<ChatResponse> {
+        self.calls.lock().unwrap().push((
+            tools.is_some(),
+            options.response_format.clone(),
+            messages[0].content_text().unwrap_or_default().to_string(),
+        ));
+        self.inner.chat_with_options(messages, tools, options).await
+    }
+}
+
+#[tokio::test]
+async fn json_schema_endpoint_gets_a_turn_schema_and_its_replies_drive_the_run() {
+    let llm = JsonSchemaLlm {
+        inner: ScriptedLlm::new(vec![
+            llm_reply_text(
+                r#"{"mutations": [{"tool": "push_subgoal", "arguments": {"text": "find the total"}}],
+                    "action": {"tool": "find_text", "arguments": {"text": "Total"}}}"#,
+            ),
+            llm_reply_text(
+                "```json\n{\"mutations\": [], \"action\": {\"tool\": \"agent_done\", \"arguments\": {\"summary\": \"found it\"}}}\n```",
+            ),
+        ]),
+        calls: Mutex::new(Vec::new()),
+    };
+    let mcp = StaticMcp::with_tools(&["find_text"]);
+
+    let state = StateRunner::new("find the total".to_string(), AgentConfig::default())
+        .run(
+            &llm,
+            &mcp,
+            "find the total".to_string(),
+            AgentTraceGraph::new(),
+            mcp.tools_as_openai(),
+            None,
+        )
+        .await
+        .expect("run ok");
+
+    assert!(state.completed);
+    match &state.steps[0].command {
+        AgentCommand::ToolCall {
+            tool_name,
+            arguments,
+            tool_call_id,
+        } => {
+            assert_eq!(tool_name, "find_text");
+            assert_eq!(arguments, &json!({"text": "Total"}));
+            assert_eq!(tool_call_id, "json-0-1");
+        }
+        other => panic!("expected find_text, got {other:?}"),
+    }
+
+    let calls = llm.calls.lock().unwrap();
+    assert_eq!(calls.len(), 2);
+    let (sent_tools, response_format, system) = &calls[0];
+    assert!(!sent_tools, "tools are carried by the schema instead");
+    assert!(system.contains("## Reply format"));
+    let format = response_format.as_ref().expect("response_format set");
+    assert_eq!(format["json_schema"]["name"], "agent_turn");
+    let actions: Vec<&str> = format["json_schema"]["schema"]["properties"]["action"]["anyOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|variant| variant["properties"]["tool"]["const"].as_str().unwrap())
+        .collect();
+    assert!(actions.contains(&"find_text"));
+    assert!(actions.contains(&"agent_done"));
+    assert!(
+        !actions.contains(&"push_subgoal"),
+        "mutations are not actions"
+    );
+}
+
+/// The system prompt is rebuilt when a suspended run resumes; the reply
+/// format must be part of the rebuilt one too.
+#[tokio::test]
+async fn resumed_json_schema_run_keeps_the_reply_format_instruction() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let mut storage = clickweave_core::storage::RunStorage::new(tmp.path(), "structured-resume");
+    storage.begin_execution().expect("begin_execution");
+    let storage = Arc::new(Mutex::new(storage));
+    let mcp = StaticMcp::with_tools(&["find_text"]);
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let llm = JsonSchemaLlm {
+        inner: ScriptedLlm::new(vec![]),
+        calls: Mutex::new(Vec::new()),
+    };
+    let state = StateRunner::new("find the total".to_string(), AgentConfig::default())
+        .with_storage(storage.clone())
+        .with_suspend_signal(token)
+        .run(
+            &llm,
+            &mcp,
+            "find the total".to_string(),
+            AgentTraceGraph::new(),
+            mcp.tools_as_openai(),
+            None,
+        )
+        .await
+        .expect("run ok");
+    assert!(matches!(
+        state.terminal_reason,
+        Some(TerminalReason::Suspended { .. })
+    ));
+    let snapshot = storage
+        .lock()
+        .unwrap()
+        .load_suspended_run()
+        .expect("snapshot parses")
+        .expect("snapshot persisted");
+
+    let llm = JsonSchemaLlm {
+        inner: ScriptedLlm::new(vec![llm_reply_text(
+            r#"{"mutations": [], "action": {"tool": "agent_done", "arguments": {"summary": "done"}}}"#,
+        )]),
+        calls: Mutex::new(Vec::new()),
+    };
+    let (state, _writer_tx) = resume_agent_workflow(
+        &llm,
+        AgentConfig::default(),
+        snapshot,
+        &mcp,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(storage),
+        None,
+        None,
+    )
+    .await
+    .expect("resume ok");
+
+    assert!(state.completed);
+    let calls = llm.calls.lock().unwrap();
+    let (_, response_format, system) = &calls[0];
+    assert!(response_format.is_some());
+    assert!(system.contains("## Reply format"), "{system}");
+}
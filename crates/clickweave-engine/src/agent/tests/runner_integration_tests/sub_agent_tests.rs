@@ -0,0 +1,302 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{mpsc, oneshot};
+
+use super::super::super::test_stubs::{CapturingLlm, StaticMcp, llm_reply_tool};
+use super::{SuspendingMcp, done, load_snapshot, run_with, temp_storage};
+use crate::agent::permissions::{PermissionAction, PermissionPolicy, PermissionRule};
+use crate::agent::runner::StateRunner;
+use crate::agent::trace_graph::AgentTraceGraph;
+use crate::agent::types::{
+    AgentConfig, AgentEvent, ApprovalRequest, RunnerOutput, StepOutcome, TerminalReason,
+};
+use crate::executor::Mcp;
+use tokio_util::sync::CancellationToken;
+
+fn delegating_config() -> AgentConfig {
+    AgentConfig {
+        max_sub_agent_depth: 1,
+        max_steps: 6,
+        ..AgentConfig::default()
+    }
+}
+
+fn delegate(tools: &[&str]) -> clickweave_llm::ChatResponse {
+    llm_reply_tool(
+        "delegate_subgoal",
+        serde_json::json!({"subgoal": "export the CRM contacts", "tools": tools, "max_steps": 4}),
+    )
+}
+
+fn mcp() -> StaticMcp {
+    StaticMcp::with_tools(&["cdp_click", "launch_app"]).with_reply("cdp_click", "clicked")
+}
+
+fn system_prompt(llm: &CapturingLlm, call: usize) -> String {
+    llm.messages_at(call)[0]
+        .content_text()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The child sees only its scoped tools, an out-of-scope call is
+/// blocked, its approval requests go through the parent's gate, and its
+/// report, trace nodes, and lifecycle events all surface in the parent.
+#[tokio::test]
+async fn child_runs_scoped_and_reports_back_to_the_parent() {
+    let llm = CapturingLlm::new(vec![
+        delegate(&["cdp_*"]),
+        llm_reply_tool("launch_app", serde_json::json!({"app_name": "Chrome"})),
+        llm_reply_tool("cdp_click", serde_json::json!({"uid": "1_0"})),
+        done("contacts exported"),
+        done("reconciled"),
+    ]);
+    let (approval_tx, mut approval_rx) =
+        mpsc::channel::<(ApprovalRequest, oneshot::Sender<bool>)>(8);
+    let approvals = tokio::spawn(async move {
+        let mut tools = Vec::new();
+        while let Some((request, reply)) = approval_rx.recv().await {
+            tools.push(request.tool_name);
+            let _ = reply.send(true);
+        }
+        tools
+    });
+    let (event_tx, mut event_rx) = mpsc::channel::<RunnerOutput>(256);
+    let events = tokio::spawn(async move {
+        let mut seen = Vec::new();
+        while let Some(output) = event_rx.recv().await {
+            if let Some(
+                event @ (AgentEvent::SubAgentStarted { .. } | AgentEvent::SubAgentFinished { .. }),
+            ) = output.into_event()
+            {
+                seen.push(event);
+            }
+        }
+        seen
+    });
+
+    let runner = StateRunner::new("reconcile contacts".to_string(), delegating_config())
+        .with_approval(approval_tx)
+        .with_events(event_tx);
+    let parent_run_id = runner.run_id;
+    let state = run_with(&llm, &mcp(), "reconcile contacts", runner).await;
+
+    assert!(state.completed);
+    assert_eq!(state.steps.len(), 1, "the child's steps stay in the child");
+    let StepOutcome::Success(report) = &state.steps[0].outcome else {
+        panic!(
+            "delegation step should succeed: {:?}",
+            state.steps[0].outcome
+        );
+    };
+    let report: serde_json::Value = serde_json::from_str(report).unwrap();
+    assert_eq!(report["completed"], true);
+    assert_eq!(report["summary"], "contacts exported");
+    assert_eq!(report["outputs"][0]["tool"], "cdp_click");
+
+    let child_prompt = system_prompt(&llm, 1);
+    assert!(child_prompt.contains("\n- cdp_click"));
+    assert!(!child_prompt.contains("\n- launch_app"));
+    let child_after_block = llm
+        .messages_at(2)
+        .iter()
+        .filter_map(|m| m.content_text().map(str::to_string))
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(child_after_block.contains("outside this sub-agent's scope"));
+
+    assert_eq!(state.trace_graph.nodes.len(), 1);
+    let child_run_id = state.trace_graph.nodes[0].source_run_id.unwrap();
+    assert_ne!(child_run_id, parent_run_id);
+
+    assert_eq!(approvals.await.unwrap(), vec!["cdp_click".to_string()]);
+    let seen = events.await.unwrap();
+    assert!(matches!(
+        &seen[..],
+        [
+            AgentEvent::SubAgentStarted { child_run_id: started, tools, .. },
+            AgentEvent::SubAgentFinished { child_run_id: finished, completed: true, .. },
+        ] if *started == child_run_id && *finished == child_run_id && tools == &["cdp_click"]
+    ));
+}
+
+/// An out-of-scope `launch_app` is blocked before the no-focus launch
+/// skip can query MCP for the running app and answer on its behalf.
+#[tokio::test]
+async fn out_of_scope_launch_is_blocked_before_the_launch_skip() {
+    let llm = CapturingLlm::new(vec![
+        delegate(&["cdp_*"]),
+        llm_reply_tool("launch_app", serde_json::json!({"app_name": "Chrome"})),
+        done("could not launch"),
+        done("gave up"),
+    ]);
+    let mcp = StaticMcp::with_tools(&["cdp_click", "launch_app", "list_apps"])
+        .with_reply("list_apps", r#"[{"name": "Chrome", "pid": 42}]"#);
+    let tools = mcp.tools_as_openai();
+    let state = StateRunner::new("reconcile contacts".to_string(), delegating_config())
+        .run(
+            &llm,
+            &mcp,
+            "reconcile contacts".to_string(),
+            AgentTraceGraph::new(),
+            tools,
+            None,
+        )
+        .await
+        .expect("run ok");
+
+    let child_after_launch = llm
+        .messages_at(2)
+        .iter()
+        .filter_map(|m| m.content_text().map(str::to_string))
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(child_after_launch.contains("outside this sub-agent's scope"));
+    assert!(!child_after_launch.contains("already running"));
+    assert!(state.trace_graph.nodes.is_empty());
+}
+
+/// A deny rule on the parent applies inside the child, and an allowlist
+/// naming a tool the parent cannot call is refused outright.
+#[tokio::test]
+async fn child_cannot_widen_the_parents_policy_or_tools() {
+    let llm = CapturingLlm::new(vec![
+        delegate(&["cdp_click", "quit_app"]),
+        delegate(&["cdp_click"]),
+        llm_reply_tool("cdp_click", serde_json::json!({"uid": "1_0"})),
+        done("could not click"),
+        done("gave up"),
+    ]);
+    let policy = PermissionPolicy {
+        rules: vec![PermissionRule {
+            tool_pattern: "cdp_click".to_string(),
+            args_pattern: None,
+            action: PermissionAction::Deny,
+        }],
+        ..PermissionPolicy::default()
+    };
+    let runner = StateRunner::new("reconcile contacts".to_string(), delegating_config())
+        .with_permissions(policy);
+    let state = run_with(&llm, &mcp(), "reconcile contacts", runner).await;
+
+    let parent_after_refusal = llm
+        .messages_at(1)
+        .iter()
+        .filter_map(|m| m.content_text().map(str::to_string))
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(parent_after_refusal.contains("`quit_app` matches no tool"));
+
+    let child_after_deny = llm
+        .messages_at(3)
+        .iter()
+        .filter_map(|m| m.content_text().map(str::to_string))
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(child_after_deny.contains("denied by permission policy"));
+    assert!(state.trace_graph.nodes.is_empty());
+}
+
+/// The child's own events land under the parent's execution directory.
+#[tokio::test]
+async fn child_events_are_recorded_under_the_parent_execution() {
+    let llm = CapturingLlm::new(vec![
+        delegate(&["cdp_click"]),
+        llm_reply_tool("cdp_click", serde_json::json!({"uid": "1_0"})),
+        done("contacts exported"),
+        done("reconciled"),
+    ]);
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let mut storage = clickweave_core::storage::RunStorage::new(tmp.path(), "sub-agents");
+    let exec_dir = storage.begin_execution().expect("begin_execution");
+    let runner = StateRunner::new("reconcile contacts".to_string(), delegating_config())
+        .with_storage(Arc::new(Mutex::new(storage)));
+    let state = run_with(&llm, &mcp(), "reconcile contacts", runner).await;
+    assert!(state.completed);
+
+    let child_run_id = state.trace_graph.nodes[0].source_run_id.unwrap();
+    let child_events = tmp
+        .path()
+        .join(".clickweave/runs/sub-agents")
+        .join(exec_dir)
+        .join("sub_agents")
+        .join(child_run_id.to_string())
+        .join("events.jsonl");
+    let text = std::fs::read_to_string(child_events).expect("child events.jsonl");
+    assert!(text.contains(r#""type":"goal_complete""#));
+    assert!(text.contains(r#""boundary_kind":"terminal""#));
+}
+
+/// Suspending the parent while a child runs stops the child at its next
+/// turn boundary. The child writes no snapshot of its own; the parent's
+/// snapshot keeps the child's report as the `delegate_subgoal` result.
+#[tokio::test]
+async fn suspend_stops_the_child_and_lands_in_the_parents_snapshot() {
+    let (storage, tmp) = temp_storage();
+    let token = CancellationToken::new();
+    let mcp = SuspendingMcp {
+        inner: mcp(),
+        trigger_tool: "cdp_click",
+        token: token.clone(),
+    };
+    let llm = CapturingLlm::new(vec![
+        delegate(&["cdp_click"]),
+        llm_reply_tool("cdp_click", serde_json::json!({"uid": "1_0"})),
+        done("contacts exported"),
+        done("reconciled"),
+    ]);
+    let runner = StateRunner::new("reconcile contacts".to_string(), delegating_config())
+        .with_storage(storage.clone())
+        .with_suspend_signal(token);
+    let tools = mcp.tools_as_openai();
+    let state = runner
+        .run(
+            &llm,
+            &mcp,
+            "reconcile contacts".to_string(),
+            AgentTraceGraph::new(),
+            tools,
+            None,
+        )
+        .await
+        .expect("run ok");
+
+    assert!(matches!(
+        state.terminal_reason,
+        Some(TerminalReason::Suspended { steps_executed: 1 })
+    ));
+    assert_eq!(llm.call_count(), 2, "neither run asks the LLM again");
+
+    let snapshot = load_snapshot(&storage).expect("parent snapshot persisted");
+    let StepOutcome::Success(report) = &snapshot.steps[0].outcome else {
+        panic!("delegation step: {:?}", snapshot.steps[0].outcome);
+    };
+    let report: serde_json::Value = serde_json::from_str(report).unwrap();
+    assert_eq!(report["completed"], false);
+    assert_eq!(report["steps_executed"], 1);
+    assert_eq!(report["terminal_reason"]["reason"], "suspended");
+    let transcript = serde_json::to_string(&snapshot.transcript).unwrap();
+    assert!(transcript.contains("sub_agent_run_id"));
+
+    assert_eq!(
+        count_files_named(tmp.path(), "suspended_run.json"),
+        1,
+        "only the parent writes a snapshot"
+    );
+}
+
+fn count_files_named(dir: &std::path::Path, name: &str) -> usize {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                count_files_named(&path, name)
+            } else {
+                usize::from(path.file_name().is_some_and(|n| n == name))
+            }
+        })
+        .sum()
+}
@@ -0,0 +1,168 @@
+use super::super::super::test_stubs::{CapturingLlm, ScriptedLlm, StaticMcp, llm_reply_tool};
+use super::{SuspendingMcp, load_snapshot, temp_storage};
+use crate::agent::resume_agent_workflow;
+use crate::agent::runner::{SUSPENDED_RUN_SCHEMA_VERSION, StateRunner};
+use crate::agent::trace_graph::AgentTraceGraph;
+use crate::agent::types::{AgentConfig, TerminalReason};
+use crate::executor::Mcp;
+use tokio_util::sync::CancellationToken;
+
+fn click_mcp() -> StaticMcp {
+    StaticMcp::with_tools(&["cdp_find_elements", "cdp_click"])
+        .with_reply(
+            "cdp_find_elements",
+            r#"{"page_url":"about:blank","source":"cdp","matches":[]}"#,
+        )
+        .with_reply("cdp_click", "clicked")
+}
+
+/// Suspend after one dispatched tool, then resume from the persisted
+/// snapshot: the resumed run keeps the prior steps and transcript, is
+/// told the world may have moved on, and finishes within the original
+/// step budget.
+#[tokio::test]
+async fn suspended_run_resumes_from_persisted_snapshot() {
+    let (storage, _tmp) = temp_storage();
+    let token = CancellationToken::new();
+    let mcp = SuspendingMcp {
+        inner: click_mcp(),
+        trigger_tool: "cdp_click",
+        token: token.clone(),
+    };
+    let llm = ScriptedLlm::new(vec![
+        llm_reply_tool("cdp_click", serde_json::json!({"uid": "1_0"})),
+        llm_reply_tool("agent_done", serde_json::json!({"summary": "too early"})),
+    ]);
+    let config = AgentConfig {
+        max_steps: 3,
+        ..AgentConfig::default()
+    };
+    let runner = StateRunner::new("click the button".to_string(), config.clone())
+        .with_storage(storage.clone())
+        .with_suspend_signal(token);
+    let run_id = runner.run_id;
+    let tools = mcp.tools_as_openai();
+
+    let state = runner
+        .run(
+            &llm,
+            &mcp,
+            "click the button".to_string(),
+            AgentTraceGraph::new(),
+            tools,
+            None,
+        )
+        .await
+        .expect("run ok");
+
+    match state.terminal_reason {
+        Some(TerminalReason::Suspended { steps_executed }) => assert_eq!(steps_executed, 1),
+        other => panic!("expected Suspended, got {:?}", other),
+    }
+    assert_eq!(llm.call_count(), 1, "no LLM call after the suspend request");
+
+    let snapshot = load_snapshot(&storage).expect("snapshot persisted");
+    assert_eq!(snapshot.schema_version, SUSPENDED_RUN_SCHEMA_VERSION);
+    assert_eq!(snapshot.run_id, run_id);
+    assert_eq!(snapshot.goal, "click the button");
+    assert_eq!(snapshot.steps.len(), 1);
+    let transcript_len = snapshot.transcript.len();
+    assert!(transcript_len >= 2, "goal + click exchange must be kept");
+
+    let llm = CapturingLlm::new(vec![llm_reply_tool(
+        "agent_done",
+        serde_json::json!({"summary": "clicked"}),
+    )]);
+    let (state, _writer_tx) = resume_agent_workflow(
+        &llm,
+        config,
+        snapshot,
+        &click_mcp(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(storage.clone()),
+        None,
+        None,
+    )
+    .await
+    .expect("resume ok");
+
+    assert!(state.completed);
+    assert!(matches!(
+        state.terminal_reason,
+        Some(TerminalReason::Completed { .. })
+    ));
+    assert_eq!(state.steps.len(), 1, "pre-suspension step carried over");
+
+    let messages = llm.messages_at(0);
+    assert_eq!(
+        messages.len(),
+        1 + transcript_len + 1,
+        "system prompt + restored transcript + fresh user turn"
+    );
+    assert!(
+        messages[1]
+            .content_text()
+            .unwrap_or("")
+            .contains("click the button")
+    );
+    let resumed_turn = messages.last().unwrap().content_text().unwrap_or("");
+    assert!(
+        resumed_turn.contains("[RUN RESUMED]"),
+        "first resumed turn must flag the suspension, got {resumed_turn:?}"
+    );
+}
+
+/// A snapshot from a different schema version is refused before any
+/// LLM call is made.
+#[tokio::test]
+async fn resume_rejects_mismatched_schema_version() {
+    let (storage, _tmp) = temp_storage();
+    let token = CancellationToken::new();
+    token.cancel();
+    let llm = ScriptedLlm::new(vec![]);
+    let mcp = click_mcp();
+    let tools = mcp.tools_as_openai();
+    let state = StateRunner::new("goal".to_string(), AgentConfig::default())
+        .with_storage(storage.clone())
+        .with_suspend_signal(token)
+        .run(
+            &llm,
+            &mcp,
+            "goal".to_string(),
+            AgentTraceGraph::new(),
+            tools,
+            None,
+        )
+        .await
+        .expect("run ok");
+    assert!(matches!(
+        state.terminal_reason,
+        Some(TerminalReason::Suspended { steps_executed: 0 })
+    ));
+
+    let mut snapshot = load_snapshot(&storage).expect("snapshot persisted");
+    snapshot.schema_version = SUSPENDED_RUN_SCHEMA_VERSION + 1;
+    let llm = ScriptedLlm::new(vec![]);
+    let err = resume_agent_workflow(
+        &llm,
+        AgentConfig::default(),
+        snapshot,
+        &mcp,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect_err("schema mismatch must fail");
+    assert!(err.to_string().contains("schema version"));
+    assert_eq!(llm.call_count(), 0);
+}
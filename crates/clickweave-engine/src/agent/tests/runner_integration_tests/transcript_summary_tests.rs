@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use clickweave_llm::{ChatBackend, ChatOptions, ChatResponse, DynChatBackend, Message};
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use super::super::super::test_stubs::{CapturingLlm, StaticMcp, llm_reply_text, llm_reply_tool};
+use crate::agent::context::{collapsed_lines, current_summary};
+use crate::agent::runner::StateRunner;
+use crate::agent::trace_graph::AgentTraceGraph;
+use crate::agent::types::{AgentConfig, AgentEvent, RunnerOutput};
+use crate::executor::Mcp;
+
+struct BrokenSummarizer;
+
+impl ChatBackend for BrokenSummarizer {
+    fn model_name(&self) -> &str {
+        "broken-summarizer"
+    }
+
+    async fn chat_with_options(
+        &self,
+        _messages: &[Message],
+        _tools: Option<&[Value]>,
+        _options: &ChatOptions,
+    ) -> Result<ChatResponse> {
+        bail!("summarizer offline")
+    }
+}
+
+/// Twelve clicks on distinct rows, then `agent_done`.
+fn clicking_agent() -> CapturingLlm {
+    let mut script: Vec<ChatResponse> = (1..=12)
+        .map(|n| llm_reply_tool("cdp_click", serde_json::json!({"uid": format!("e{n}")})))
+        .collect();
+    script.push(llm_reply_tool(
+        "agent_done",
+        serde_json::json!({"summary": "clicked"}),
+    ));
+    CapturingLlm::new(script)
+}
+
+async fn run(llm: &CapturingLlm, summarizer: Arc<dyn DynChatBackend>) -> Vec<AgentEvent> {
+    let mcp = StaticMcp::with_tools(&["cdp_click"])
+        .with_reply("cdp_click", "row opened; the Save button stayed disabled");
+    let config = AgentConfig {
+        recent_n: 2,
+        ..AgentConfig::default()
+    };
+    let (tx, mut rx) = mpsc::channel::<RunnerOutput>(256);
+    let state = StateRunner::new("g".into(), config)
+        .with_events(tx)
+        .with_summarizer(summarizer)
+        .run(
+            llm,
+            &mcp,
+            "click every row".to_string(),
+            AgentTraceGraph::new(),
+            mcp.tools_as_openai(),
+            None,
+        )
+        .await
+        .expect("run ok");
+    assert!(state.completed);
+    let mut events = Vec::new();
+    while let Ok(output) = rx.try_recv() {
+        events.extend(output.into_event());
+    }
+    events
+}
+
+#[tokio::test]
+async fn collapsed_steps_are_folded_into_one_summary_after_the_goal() {
+    let agent = clicking_agent();
+    let summarizer = Arc::new(CapturingLlm::new(vec![
+        llm_reply_text("Opened rows e1-e3; Save stayed disabled."),
+        llm_reply_text("Opened rows e1-e8; Save never enabled."),
+    ]));
+
+    run(&agent, summarizer.clone()).await;
+
+    let last = agent.messages_at(agent.call_count() - 1);
+    assert_eq!(
+        current_summary(&last),
+        Some("Opened rows e1-e8; Save never enabled.")
+    );
+    assert!(collapsed_lines(&last).len() < 4);
+    // Between folds the summary is sent unchanged, keeping the prefix
+    // stable for prompt caching.
+    let summaries: Vec<Option<String>> = (0..agent.call_count())
+        .map(|i| current_summary(&agent.messages_at(i)).map(String::from))
+        .collect();
+    let changes = summaries.windows(2).filter(|w| w[0] != w[1]).count();
+    assert_eq!(changes, summarizer.call_count());
+
+    // The summarizer saw the full tool bodies the one-liners cut short,
+    // and the second fold built on the first summary.
+    let first_request = summarizer.messages_at(0)[1]
+        .content_text()
+        .unwrap()
+        .to_string();
+    assert!(first_request.contains("the Save button stayed disabled"));
+    let second_request = summarizer.messages_at(1)[1]
+        .content_text()
+        .unwrap()
+        .to_string();
+    assert!(second_request.contains("Opened rows e1-e3"));
+}
+
+#[tokio::test]
+async fn failing_summarizer_falls_back_to_one_liners() {
+    let agent = clicking_agent();
+
+    let events = run(&agent, Arc::new(BrokenSummarizer)).await;
+
+    let last = agent.messages_at(agent.call_count() - 1);
+    assert_eq!(current_summary(&last), None);
+    assert!(collapsed_lines(&last).len() >= 4);
+    let warnings = events
+        .iter()
+        .filter(|event| {
+            matches!(
+                event,
+                AgentEvent::Warning { message } if message.contains("summarizer offline")
+            )
+        })
+        .count();
+    assert_eq!(
+        warnings, 2,
+        "a failed fold is retried after another batch, not every turn"
+    );
+}
@@ -40,6 +40,7 @@ async fn variant_context_lands_in_messages_1_not_messages_0() {
         None,
         None,
         None,
+        None,
         uuid::Uuid::new_v4(),
         None,
         None,
@@ -109,6 +110,7 @@ async fn variant_context_absent_produces_clean_goal_block() {
         None,
         None,
         None,
+        None,
         uuid::Uuid::new_v4(),
         None,
         None,
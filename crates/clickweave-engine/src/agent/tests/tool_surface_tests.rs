@@ -109,6 +109,7 @@ impl Mcp for ShiftingToolsMcp {
                     text: "ok".to_string(),
                 }],
                 is_error: None,
+                structured_content: None,
             })
         } else {
             Ok(results.remove(0))
@@ -179,12 +180,14 @@ async fn tool_list_is_stable_across_cdp_connect_boundary() {
             .to_string(),
         }],
         is_error: None,
+        structured_content: None,
     };
     let text = |s: &str| ToolCallResult {
         content: vec![ToolContent::Text {
             text: s.to_string(),
         }],
         is_error: None,
+        structured_content: None,
     };
     let results = vec![
         text("Launched"),         // launch_app
@@ -75,6 +75,7 @@ async fn append_assistant_message_strips_reasoning_content_from_transcript() {
         msg.reasoning_content = Some("I need to click the button.".to_string());
         ChatResponse {
             id: "mock-r0".to_string(),
+            model: None,
             choices: vec![Choice {
                 index: 0,
                 message: msg,
@@ -149,6 +150,7 @@ async fn multi_tool_response_only_executes_first() {
     let agent_llm = MockAgent::new(vec![
         ChatResponse {
             id: "mock-multi".to_string(),
+            model: None,
             choices: vec![Choice {
                 index: 0,
                 message: Message::assistant_tool_calls(vec![
@@ -239,6 +241,7 @@ async fn malformed_tool_call_json_returns_error() {
         // LLM returns a tool call with unparseable arguments
         ChatResponse {
             id: "mock-malformed".to_string(),
+            model: None,
             choices: vec![Choice {
                 index: 0,
                 message: Message::assistant_tool_calls(vec![ToolCall {
@@ -359,6 +362,7 @@ async fn retained_history_stays_bounded_across_snapshot_heavy_steps() {
                 .to_string(),
             }],
             is_error: None,
+            structured_content: None,
         });
         if i < 6 {
             // cdp_wait_for tool call result with the big snapshot payload
@@ -367,6 +371,7 @@ async fn retained_history_stays_bounded_across_snapshot_heavy_steps() {
                     text: big_snapshot.clone(),
                 }],
                 is_error: None,
+                structured_content: None,
             });
         }
     }
@@ -0,0 +1,145 @@
+//! Per-tool MCP call timeouts.
+//!
+//! `McpClient::call_tool` falls back to a single fixed timeout, which is
+//! far too generous for a screenshot and not obviously right for
+//! `cdp_wait_for_page_change`. The runner instead resolves a timeout per
+//! dispatch: an exact per-tool override (from project settings) wins,
+//! then the tool's family default, then the catch-all default.
+//!
+//! Timed-out calls are reported back to the LLM with a stable
+//! [`TIMEOUT_ERROR_PREFIX`] so the runner can classify them as
+//! [`TIMEOUT_ERROR_KIND`] rather than as an ordinary tool failure.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Stable `FailureSignature::error_kind` recorded for timed-out calls.
+pub const TIMEOUT_ERROR_KIND: &str = "timeout";
+
+/// Prefix of the tool-error text produced for a timed-out call. The
+/// runner keys timeout classification off this prefix, the same way
+/// stale-uid detection keys off the MCP error text.
+pub const TIMEOUT_ERROR_PREFIX: &str = "tool_timeout:";
+
+/// Catch-all timeout for tools without a family default or override.
+const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(30);
+/// Explicit wait primitives (`cdp_wait_for`, `cdp_wait_for_page_change`).
+/// These legitimately block, so they get the longest budget.
+const WAIT_TOOL_TIMEOUT: Duration = Duration::from_secs(45);
+/// App / browser lifecycle: cold launches and CDP attach can take a while.
+const LIFECYCLE_TOOL_TIMEOUT: Duration = Duration::from_secs(40);
+/// Snapshot and recognition tools: should return in seconds, and a hang
+/// here is the most common symptom of a wedged MCP server.
+const SNAPSHOT_TOOL_TIMEOUT: Duration = Duration::from_secs(20);
+
+const LIFECYCLE_TOOLS: &[&str] = &[
+    "launch_app",
+    "quit_app",
+    "cdp_connect",
+    "cdp_disconnect",
+    "cdp_new_page",
+    "cdp_navigate",
+];
+
+const SNAPSHOT_TOOLS: &[&str] = &[
+    "take_screenshot",
+    "take_ax_snapshot",
+    "find_text",
+    "find_image",
+    "list_apps",
+    "list_windows",
+    "cdp_take_snapshot",
+    "cdp_take_dom_snapshot",
+    "cdp_summarize_page",
+    "cdp_find_elements",
+];
+
+/// Timeout policy for MCP tool dispatch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolTimeouts {
+    /// Timeout for tools that match neither an override nor a family.
+    pub default: Duration,
+    /// Exact tool-name overrides. Take precedence over family defaults.
+    pub overrides: HashMap<String, Duration>,
+}
+
+impl Default for ToolTimeouts {
+    fn default() -> Self {
+        Self {
+            default: DEFAULT_TOOL_TIMEOUT,
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+impl ToolTimeouts {
+    /// Resolve the timeout for one dispatch of `tool_name`.
+    pub fn for_tool(&self, tool_name: &str) -> Duration {
+        if let Some(timeout) = self.overrides.get(tool_name) {
+            return *timeout;
+        }
+        family_default(tool_name).unwrap_or(self.default)
+    }
+}
+
+fn family_default(tool_name: &str) -> Option<Duration> {
+    if tool_name.starts_with("cdp_wait_for") {
+        Some(WAIT_TOOL_TIMEOUT)
+    } else if LIFECYCLE_TOOLS.contains(&tool_name) {
+        Some(LIFECYCLE_TOOL_TIMEOUT)
+    } else if SNAPSHOT_TOOLS.contains(&tool_name) {
+        Some(SNAPSHOT_TOOL_TIMEOUT)
+    } else {
+        None
+    }
+}
+
+/// Tool-error text surfaced to the LLM when `tool_name` exceeds `timeout`.
+/// The wording tells the model the outcome is unknown rather than failed,
+/// so it re-observes instead of assuming nothing happened.
+pub fn timeout_error_message(tool_name: &str, timeout: Duration) -> String {
+    format!(
+        "{TIMEOUT_ERROR_PREFIX} `{tool_name}` did not respond within {}s and was abandoned. \
+         Its effect is unknown — re-observe before retrying, or try a different approach.",
+        timeout.as_secs_f32()
+    )
+}
+
+/// True when `error` was produced by [`timeout_error_message`].
+pub fn is_timeout_error(error: &str) -> bool {
+    error.starts_with(TIMEOUT_ERROR_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn family_defaults_apply_without_overrides() {
+        let t = ToolTimeouts::default();
+        assert_eq!(t.for_tool("cdp_wait_for_page_change"), WAIT_TOOL_TIMEOUT);
+        assert_eq!(t.for_tool("launch_app"), LIFECYCLE_TOOL_TIMEOUT);
+        assert_eq!(t.for_tool("take_screenshot"), SNAPSHOT_TOOL_TIMEOUT);
+        assert_eq!(t.for_tool("cdp_click"), DEFAULT_TOOL_TIMEOUT);
+    }
+
+    #[test]
+    fn override_beats_family_default() {
+        let mut t = ToolTimeouts::default();
+        t.overrides
+            .insert("cdp_wait_for_page_change".into(), Duration::from_secs(5));
+        assert_eq!(
+            t.for_tool("cdp_wait_for_page_change"),
+            Duration::from_secs(5)
+        );
+        assert_eq!(t.for_tool("cdp_wait_for"), WAIT_TOOL_TIMEOUT);
+    }
+
+    #[test]
+    fn timeout_message_is_classified() {
+        let msg = timeout_error_message("cdp_click", Duration::from_secs(3));
+        assert!(is_timeout_error(&msg));
+        assert!(msg.contains("`cdp_click`"));
+        assert!(!is_timeout_error("element not found"));
+    }
+}
@@ -28,13 +28,33 @@ pub enum RunnerOutput {
         version: u32,
         run_id: String,
     },
+
+    /// Non-persisted `ask_user` request. The host shows `question` to the
+    /// operator and sends their answer back on `reply` exactly once.
+    /// Dropping `reply` tells the runner no human is available.
+    UserQuestion {
+        question: UserQuestion,
+        reply: tokio::sync::oneshot::Sender<String>,
+    },
+
+    /// Non-persisted plan-first approval request. The host shows the
+    /// proposed plan, lets the operator approve, edit, or reject it, and
+    /// sends the decision back on `reply`. Dropping `reply` is treated
+    /// like a closed approval channel and halts the run.
+    PlanApproval {
+        request: PlanApprovalRequest,
+        reply: tokio::sync::oneshot::Sender<crate::agent::plan::PlanDecision>,
+    },
 }
 
 impl RunnerOutput {
     pub fn into_event(self) -> Option<AgentEvent> {
         match self {
             RunnerOutput::Event(event) => Some(event),
-            RunnerOutput::DrainBarrier { .. } | RunnerOutput::SkillProposalNeeded { .. } => None,
+            RunnerOutput::DrainBarrier { .. }
+            | RunnerOutput::SkillProposalNeeded { .. }
+            | RunnerOutput::UserQuestion { .. }
+            | RunnerOutput::PlanApproval { .. } => None,
         }
     }
 }
@@ -57,6 +77,10 @@ const DEFAULT_MAX_CONSECUTIVE_ERRORS: usize = 3;
 /// Default consecutive-destructive-tool cap. Three irreversible actions in
 /// a row is the circuit-breaker point where the operator should review.
 const DEFAULT_CONSECUTIVE_DESTRUCTIVE_CAP: usize = 3;
+/// Default wall-clock budget for a whole run. Generous next to
+/// `DEFAULT_MAX_STEPS` turns of normal latency; its job is to bound runs
+/// that stall on slow tools or LLM calls, not to pace healthy ones.
+const DEFAULT_MAX_DURATION: std::time::Duration = std::time::Duration::from_secs(15 * 60);
 
 /// Events emitted by the agent loop during execution.
 #[derive(Debug, Clone, Serialize)]
@@ -66,6 +90,9 @@ pub enum AgentEvent {
         step_index: usize,
         tool_name: String,
         summary: String,
+        /// Model that chose this step, when the backend reports one.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
     },
     GoalComplete {
         summary: String,
@@ -85,6 +112,9 @@ pub enum AgentEvent {
         step_index: usize,
         tool_name: String,
         error: String,
+        /// Model that chose this step, when the backend reports one.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
     },
     /// An automatic sub-action performed by the agent (e.g. CDP auto-connect
     /// probing, quitting, relaunching). Not a user-approved step.
@@ -244,6 +274,121 @@ pub enum AgentEvent {
         skill_id: String,
         version: u32,
     },
+    /// An `ask_user` question was resolved. Recorded so the durable
+    /// trace shows what the agent asked and what (if anything) the
+    /// operator told it. `answer` is `None` unless `outcome` is
+    /// `answered`.
+    UserQuestionAnswered {
+        run_id: Uuid,
+        step_index: usize,
+        question: String,
+        choices: Vec<String>,
+        outcome: crate::agent::ask_user::AskUserOutcome,
+        answer: Option<String>,
+    },
+    /// A plan-first proposal was decided. `proposed` is the LLM's plan;
+    /// an approving `decision` carries the plan as actually seeded,
+    /// including any operator edits. `revision` marks a request to
+    /// deviate from an already-approved plan.
+    PlanReviewed {
+        run_id: Uuid,
+        step_index: usize,
+        proposed: Vec<crate::agent::plan::PlannedSubgoal>,
+        decision: crate::agent::plan::PlanDecision,
+        revision: bool,
+    },
+    /// A `delegate_subgoal` call spawned a sub-agent. `tools` is the
+    /// resolved tool scope the child runs with.
+    SubAgentStarted {
+        run_id: Uuid,
+        child_run_id: Uuid,
+        step_index: usize,
+        subgoal: String,
+        tools: Vec<String>,
+    },
+    /// A sub-agent stopped. Its own events are recorded under
+    /// `sub_agents/<child_run_id>/` in the parent's execution directory.
+    SubAgentFinished {
+        run_id: Uuid,
+        child_run_id: Uuid,
+        step_index: usize,
+        completed: bool,
+        summary: Option<String>,
+        steps_executed: usize,
+    },
+    /// A fragment of the LLM turn in progress, emitted only when
+    /// `AgentConfig::stream_llm` is set. The assembled turn is what the
+    /// runner acts on; these exist purely for live display.
+    LlmDelta {
+        run_id: Uuid,
+        step_index: usize,
+        kind: LlmDeltaKind,
+        text: String,
+    },
+    /// `notifications/progress` from the MCP server for a tool call in
+    /// flight. Live display only, like `LlmDelta`.
+    McpProgress {
+        /// The tool the progress belongs to, when the server echoed a
+        /// token the client issued.
+        tool_name: Option<String>,
+        progress: f64,
+        total: Option<f64>,
+        message: Option<String>,
+    },
+    /// `notifications/message`: a log line from an MCP server.
+    McpLog {
+        level: String,
+        logger: Option<String>,
+        message: String,
+    },
+    /// A tool call was abandoned before it returned because the run was
+    /// stopped; the MCP server was told to stop working on it.
+    ToolCallCancelled {
+        run_id: Uuid,
+        step_index: usize,
+        tool_name: String,
+        reason: String,
+    },
+    /// VLM response-cache counters for the run. Like
+    /// `CompletionDisagreementResolved`, appended to `events.jsonl` by the
+    /// Tauri layer once the run ends; the engine never sends it.
+    VlmCacheStats {
+        hits: u64,
+        misses: u64,
+        /// Sampled calls that went to the endpoint without the cache.
+        bypassed: u64,
+        evicted: u64,
+    },
+}
+
+impl AgentEvent {
+    /// Live-display events that are forwarded to the host but not
+    /// written to `events.jsonl` — every fragment of every turn would
+    /// swamp the durable trace.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            AgentEvent::LlmDelta { .. } | AgentEvent::McpProgress { .. }
+        )
+    }
+}
+
+/// Which part of the LLM turn an [`AgentEvent::LlmDelta`] extends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmDeltaKind {
+    Text,
+    Reasoning,
+}
+
+impl LlmDeltaKind {
+    /// Split a backend stream fragment into its kind and text.
+    pub fn split(delta: clickweave_llm::StreamDelta) -> (Self, String) {
+        match delta {
+            clickweave_llm::StreamDelta::Text(text) => (LlmDeltaKind::Text, text),
+            clickweave_llm::StreamDelta::Reasoning(text) => (LlmDeltaKind::Reasoning, text),
+        }
+    }
 }
 
 /// Scope partitioning carried by [`AgentEvent::EpisodesRetrieved`].
@@ -293,6 +438,26 @@ pub struct ApprovalRequest {
     pub description: String,
 }
 
+/// Clarifying question sent to the operator by the `ask_user` pseudo-tool.
+#[derive(Debug, Clone, Serialize)]
+pub struct UserQuestion {
+    pub step_index: usize,
+    pub question: String,
+    /// Suggested answers. Empty means free-form; the operator may still
+    /// answer free-form when choices are offered.
+    pub choices: Vec<String>,
+}
+
+/// Plan proposal sent to the operator in plan-first mode.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanApprovalRequest {
+    pub step_index: usize,
+    pub subgoals: Vec<crate::agent::plan::PlannedSubgoal>,
+    /// `true` when the run already has an approved plan and the LLM is
+    /// asking to deviate from it.
+    pub revision: bool,
+}
+
 /// Configuration for an agent run.
 #[derive(Debug, Clone)]
 pub struct AgentConfig {
@@ -317,12 +482,46 @@ pub struct AgentConfig {
     /// per-call whether the focus is redundant and should be suppressed
     /// anyway.
     pub allow_focus_window: bool,
+    /// Wall-clock deadline for the whole run. Checked before every turn
+    /// and used to clamp in-flight LLM and tool calls, so a run cannot
+    /// outlive it by more than one observation. `None` disables the
+    /// deadline.
+    pub max_duration: Option<std::time::Duration>,
+    /// Per-tool MCP call timeouts (family defaults plus project-settings
+    /// overrides).
+    pub tool_timeouts: crate::agent::tool_timeouts::ToolTimeouts,
+    /// How long an `ask_user` question waits for the operator before the
+    /// LLM is told nobody answered. `None` marks the run as headless: the
+    /// fallback is returned immediately without sending the question.
+    pub ask_user_timeout: Option<std::time::Duration>,
+    /// Plan-first mode: the first turn must propose a subgoal plan via
+    /// `propose_plan`, and state-changing tools stay blocked until the
+    /// operator approves it.
+    pub plan_first: bool,
+    /// How many levels of `delegate_subgoal` sub-agents the run may
+    /// spawn. Each child gets one less; `0` (the default) keeps the tool
+    /// off the advertised list.
+    pub max_sub_agent_depth: usize,
+    /// Request streamed completions and emit `AgentEvent::LlmDelta` for
+    /// each text/reasoning fragment. Off by default; backends that cannot
+    /// stream behave as if it were off.
+    pub stream_llm: bool,
+    /// When agent turns and the completion check ask for the escalation
+    /// route of a routing backend. Off by default.
+    pub llm_escalation: LlmEscalation,
+    /// Sampling temperature for the VLM completion check. `None` keeps
+    /// the endpoint's own; the app sets `0.0` when the VLM response cache
+    /// is on, so repeated checks can be answered from it.
+    pub completion_check_temperature: Option<f32>,
     /// Maximum elements to render in the state block (D19). The runner may
     /// fetch a larger CDP set for fingerprints/inventory, but the prompt
     /// renders a bounded slice so one page cannot dominate the context window.
     pub state_block_max_elements: usize,
     /// Recent-N window for compaction (D12).
     pub recent_n: usize,
+    /// Tokens of the model's context window kept free for its reply when
+    /// the compaction budget is fitted to a known context length.
+    pub response_reserve_tokens: usize,
     /// Uncertainty threshold above which the state block marks fields
     /// as "?" rather than rendering their nominal value (D14).
     pub uncertainty_threshold: f32,
@@ -391,6 +590,23 @@ impl From<EpisodicScoreWeights> for crate::agent::episodic::retrieval::ScoreWeig
     }
 }
 
+/// Escalation policy for [`clickweave_llm::RoutingBackend`]: which LLM
+/// calls set `ChatOptions::escalate`. Backends without routes ignore it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct LlmEscalation {
+    /// Escalate once this many steps in a row have failed. `None` or `0`
+    /// never escalates on errors alone.
+    #[serde(default)]
+    pub after_consecutive_errors: Option<usize>,
+    /// Escalate every turn taken in `Phase::Recovering`.
+    #[serde(default)]
+    pub while_recovering: bool,
+    /// Escalate the VLM check that confirms `agent_done`.
+    #[serde(default)]
+    pub completion_check: bool,
+}
+
 impl Default for AgentConfig {
     fn default() -> Self {
         Self {
@@ -399,8 +615,17 @@ impl Default for AgentConfig {
             build_workflow: true,
             consecutive_destructive_cap: DEFAULT_CONSECUTIVE_DESTRUCTIVE_CAP,
             allow_focus_window: false,
+            max_duration: Some(DEFAULT_MAX_DURATION),
+            tool_timeouts: crate::agent::tool_timeouts::ToolTimeouts::default(),
+            ask_user_timeout: Some(crate::agent::ask_user::DEFAULT_ASK_USER_TIMEOUT),
+            plan_first: false,
+            max_sub_agent_depth: 0,
+            stream_llm: false,
+            llm_escalation: LlmEscalation::default(),
+            completion_check_temperature: None,
             state_block_max_elements: crate::agent::render::DEFAULT_MAX_ELEMENTS,
             recent_n: 6,
+            response_reserve_tokens: 4_096,
             uncertainty_threshold: 0.75,
             episodic_enabled: true,
             retrieved_episodes_k: 2,
@@ -418,7 +643,7 @@ impl Default for AgentConfig {
 }
 
 /// A single step in the agent's observe-act history.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentStep {
     /// Step index (0-based).
     pub index: usize,
@@ -433,7 +658,7 @@ pub struct AgentStep {
 }
 
 /// The action the LLM decided to take.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AgentCommand {
     /// Execute an MCP tool call.
     ToolCall {
@@ -546,6 +771,17 @@ pub enum TerminalReason {
     /// loop and halted immediately rather than burning through the
     /// `max_consecutive_errors` budget on the same failing call.
     LoopDetected { tool_name: String, error: String },
+    /// The run exceeded `AgentConfig::max_duration`.
+    DeadlineExceeded {
+        elapsed_secs: u64,
+        max_duration_secs: u64,
+    },
+    /// The host asked the run to suspend. Its state was snapshotted to
+    /// run storage so a later `resume_agent_workflow` can continue it.
+    Suspended { steps_executed: usize },
+    /// The host stopped the run while a tool call was in flight, or
+    /// between turns.
+    Cancelled { steps_executed: usize },
 }
 
 impl TerminalReason {
@@ -589,12 +825,25 @@ impl TerminalReason {
                 "Loop detected: `{}` kept returning the same error — {}",
                 tool_name, error
             ),
+            Self::DeadlineExceeded {
+                elapsed_secs,
+                max_duration_secs,
+            } => format!(
+                "Stopped after {}s (run deadline of {}s exceeded)",
+                elapsed_secs, max_duration_secs
+            ),
+            Self::Suspended { steps_executed } => {
+                format!("Suspended after {} steps", steps_executed)
+            }
+            Self::Cancelled { steps_executed } => {
+                format!("Stopped by the host after {} steps", steps_executed)
+            }
         }
     }
 }
 
 /// The result of executing a single step.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StepOutcome {
     /// Tool executed successfully with the given result text.
     Success(String),
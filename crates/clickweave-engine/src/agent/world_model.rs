@@ -190,6 +190,9 @@ pub enum InvalidationEvent {
         kind: SnapshotKind,
         age_steps: u32,
     },
+    /// The MCP server was replaced after a crash. Page, window, and
+    /// snapshot state lived in the old process and is gone with it.
+    McpServerRestarted,
 }
 
 /// Signals passed into `WorldModel::recompute_uncertainty`. Collected by
@@ -359,6 +362,17 @@ impl WorldModel {
                     self.modal_present = None;
                     self.dialog_present = None;
                 }
+                InvalidationEvent::McpServerRestarted => {
+                    // The focused app is an OS fact the restart does not
+                    // change; everything read through the server is stale.
+                    self.window_list = None;
+                    self.cdp_page = None;
+                    self.elements = None;
+                    self.modal_present = None;
+                    self.dialog_present = None;
+                    self.last_screenshot = None;
+                    self.last_native_ax_snapshot = None;
+                }
                 InvalidationEvent::ToolFailed { tool } => {
                     self.bump_uncertainty(0.15, format!("tool_failed: {}", tool));
                 }
@@ -570,12 +584,48 @@ fn parse_window_list(body: &str) -> Result<Vec<WindowRef>, String> {
 /// shallower that carried a non-empty name. Mirrors the derivation in
 /// `crate::executor::deterministic::ax::parse_ax_snapshot`.
 pub fn parse_ax_snapshot(text: &str) -> Vec<AxElement> {
+    link_ax_parents(text.lines().filter_map(parse_ax_line))
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct AxElementRaw {
+    uid: String,
+    role: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    value: Option<String>,
+    depth: u32,
+    #[serde(default)]
+    focused: bool,
+    #[serde(default)]
+    disabled: bool,
+}
+
+/// Parse `take_ax_snapshot`'s `structuredContent`: `{"elements": [...]}`
+/// with one entry per AX node in tree order. `None` when it does not
+/// have that shape, so the caller can fall back to
+/// [`parse_ax_snapshot`] on the text body.
+pub fn parse_ax_snapshot_structured(structured: &serde_json::Value) -> Option<Vec<AxElement>> {
+    let raw: Vec<AxElementRaw> =
+        serde_json::from_value(structured.get("elements")?.clone()).ok()?;
+    Some(link_ax_parents(raw.into_iter().map(|r| AxElement {
+        uid: r.uid,
+        role: r.role,
+        name: r.name,
+        value: r.value,
+        depth: r.depth,
+        focused: r.focused,
+        disabled: r.disabled,
+        parent_name: None,
+    })))
+}
+
+/// Fill in each element's `parent_name` from the elements before it.
+fn link_ax_parents(elements: impl IntoIterator<Item = AxElement>) -> Vec<AxElement> {
     let mut out = Vec::new();
     let mut ancestor_stack: Vec<(u32, String)> = Vec::new();
-    for line in text.lines() {
-        let Some(mut el) = parse_ax_line(line) else {
-            continue;
-        };
+    for mut el in elements {
         // Drop ancestors at the same depth or deeper.
         while let Some((d, _)) = ancestor_stack.last() {
             if *d >= el.depth {
@@ -670,19 +720,30 @@ struct OcrMatchRaw {
     confidence: f32,
 }
 
-pub fn parse_ocr_matches(text: &str) -> Result<Vec<OcrMatch>, serde_json::Error> {
-    let raw: Vec<OcrMatchRaw> = serde_json::from_str(text)?;
-    Ok(raw
-        .into_iter()
-        .map(|r| OcrMatch {
+impl From<OcrMatchRaw> for OcrMatch {
+    fn from(r: OcrMatchRaw) -> Self {
+        Self {
             text: r.text,
             x: r.x,
             y: r.y,
             width: r.width,
             height: r.height,
             confidence: r.confidence,
-        })
-        .collect())
+        }
+    }
+}
+
+pub fn parse_ocr_matches(text: &str) -> Result<Vec<OcrMatch>, serde_json::Error> {
+    let raw: Vec<OcrMatchRaw> = serde_json::from_str(text)?;
+    Ok(raw.into_iter().map(OcrMatch::from).collect())
+}
+
+/// Parse `find_text`'s `structuredContent`: `{"matches": [...]}`, each
+/// match shaped as in the text body. `None` when it does not have that
+/// shape.
+pub fn parse_ocr_matches_structured(structured: &serde_json::Value) -> Option<Vec<OcrMatch>> {
+    let raw: Vec<OcrMatchRaw> = serde_json::from_value(structured.get("matches")?.clone()).ok()?;
+    Some(raw.into_iter().map(OcrMatch::from).collect())
 }
 
 #[cfg(test)]
@@ -778,6 +839,37 @@ mod tests {
         assert!(wm.last_native_ax_snapshot.is_none());
     }
 
+    #[test]
+    fn apply_events_mcp_server_restarted_keeps_only_focused_app() {
+        let mut wm = WorldModel::default();
+        wm.focused_app = Some(fresh_focused_app(1));
+        wm.cdp_page = Some(Fresh {
+            value: CdpPageState {
+                url: "https://example.com/".to_string(),
+                page_fingerprint: "abc".to_string(),
+                element_inventory: Vec::new(),
+            },
+            written_at: 1,
+            source: FreshnessSource::DirectObservation,
+            ttl_steps: None,
+        });
+        wm.last_native_ax_snapshot = Some(Fresh {
+            value: AxSnapshotData {
+                snapshot_id: "a1g3".to_string(),
+                element_count: 5,
+                captured_at_step: 1,
+                ax_tree_text: "uid=a1g3 button \"OK\"".to_string(),
+            },
+            written_at: 1,
+            source: FreshnessSource::DirectObservation,
+            ttl_steps: None,
+        });
+        wm.apply_events(vec![InvalidationEvent::McpServerRestarted]);
+        assert!(wm.focused_app.is_some());
+        assert!(wm.cdp_page.is_none());
+        assert!(wm.last_native_ax_snapshot.is_none());
+    }
+
     #[test]
     fn apply_events_tool_failed_bumps_uncertainty_but_does_not_drop_fields() {
         let mut wm = WorldModel::default();
@@ -1143,6 +1235,7 @@ mod observation_union_tests {
                 },
             ],
             is_error: None,
+            structured_content: None,
         };
         assert_eq!(
             extract_result_text(&result),
@@ -1158,6 +1251,7 @@ mod observation_union_tests {
                 mime_type: "image/png".to_string(),
             }],
             is_error: None,
+            structured_content: None,
         };
         let text = extract_result_text(&result);
         assert!(text.contains("image"), "got {text:?}");
@@ -1169,6 +1263,7 @@ mod observation_union_tests {
         let result = clickweave_mcp::ToolCallResult {
             content: vec![],
             is_error: None,
+            structured_content: None,
         };
         assert_eq!(extract_result_text(&result), "");
     }
@@ -473,6 +473,7 @@ mod tests {
                     text: text.to_string(),
                 }],
                 is_error: None,
+                structured_content: None,
             }));
         }
 
@@ -482,6 +483,7 @@ mod tests {
                     text: text.to_string(),
                 }],
                 is_error: Some(true),
+                structured_content: None,
             }));
         }
 
@@ -736,6 +738,7 @@ mod tests {
         ToolCallResult {
             content,
             is_error: None,
+            structured_content: None,
         }
     }
 
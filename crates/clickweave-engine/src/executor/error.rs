@@ -103,6 +103,15 @@ pub enum ExecutorError {
     #[error("Cancelled")]
     Cancelled,
 
+    /// The run was stopped while this tool call was in flight. The call
+    /// was abandoned and the MCP server told to stop working on it.
+    #[error("Tool call cancelled: {tool} (step {step_id})")]
+    ToolCallCancelled { step_id: String, tool: String },
+
+    /// A gated step was put to the run's approver and refused.
+    #[error("Approval denied: {tool} (step {step_id})")]
+    ApprovalDenied { step_id: String, tool: String },
+
     #[error("IO error: {0}")]
     Io(String),
 
@@ -0,0 +1,742 @@
+//! Record/replay of MCP tool calls.
+//!
+//! [`McpCassette`] wraps any [`Mcp`]. In record mode every `call_tool` is
+//! appended to a JSONL cassette as it completes, together with the tool
+//! list whenever it changed since the previous call. Image content is
+//! written to files in a directory named after the cassette
+//! (`cassettes/mcp.jsonl` → `cassettes/mcp/`) and referenced by relative
+//! path, so a run directory can be copied elsewhere and still replay.
+//!
+//! In replay mode the cassette answers tool calls and reports the recorded
+//! tool list instead of the wrapped server, so a desktop run can be
+//! reproduced without the desktop — paired with a recorded LLM cassette,
+//! or with the `test_stubs` LLMs as a regression fixture for the agent
+//! runner or the skill runner.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use base64::Engine as _;
+use clickweave_mcp::{ToolCallResult, ToolContent};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tracing::{debug, warn};
+
+use super::Mcp;
+
+/// How replay pairs incoming tool calls with recorded ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpReplayMatching {
+    /// Serve recorded calls in recording order. The next recorded call
+    /// must be for the same tool, or replay fails with an
+    /// [`McpCassetteDivergence`]; its arguments may differ.
+    #[default]
+    Sequence,
+    /// Serve the first unused call with the same tool and arguments, in
+    /// whatever order they arrive.
+    ToolAndArguments,
+}
+
+/// One line of the cassette.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum CassetteLine {
+    /// The tool list, in `tools_as_openai` form, as of the calls that
+    /// follow it.
+    Tools {
+        tools: Vec<Value>,
+    },
+    Call(RecordedCall),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedCall {
+    seq: usize,
+    tool: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    arguments: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<RecordedResult>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// A `ToolCallResult` in wire form, except that image blocks carry an
+/// `artifact` path relative to the cassette's directory instead of `data`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordedResult {
+    content: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    is_error: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    structured_content: Option<Value>,
+}
+
+/// A recorded call awaiting replay, with the tool list that was current
+/// when it was made.
+struct PendingCall {
+    call: RecordedCall,
+    tools: usize,
+}
+
+/// Replay received a tool call the cassette did not record.
+#[derive(Debug)]
+pub struct McpCassetteDivergence {
+    /// 0-based index of the call that diverged.
+    pub seq: usize,
+    pub detail: String,
+}
+
+impl fmt::Display for McpCassetteDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MCP cassette diverged at call #{}: {}",
+            self.seq, self.detail
+        )
+    }
+}
+
+impl std::error::Error for McpCassetteDivergence {}
+
+enum Mode {
+    Passthrough,
+    Record {
+        path: PathBuf,
+        next_seq: usize,
+        /// The tool list last written to the cassette.
+        tools: Vec<Value>,
+    },
+    Replay {
+        matching: McpReplayMatching,
+        /// Directory image artifacts are resolved against.
+        base_dir: PathBuf,
+        /// Every recorded tool list, in recording order.
+        tool_lists: Vec<Vec<Value>>,
+        /// Unused calls in recording order.
+        remaining: VecDeque<PendingCall>,
+        next_seq: usize,
+    },
+}
+
+/// [`Mcp`] wrapper that records tool calls to or replays them from a
+/// cassette. Notifications, restarts and resources always come from the
+/// wrapped server.
+pub struct McpCassette<M> {
+    inner: M,
+    mode: Mutex<Mode>,
+}
+
+impl<M: Mcp> McpCassette<M> {
+    /// Forward every call to `inner` untouched.
+    pub fn passthrough(inner: M) -> Self {
+        Self {
+            inner,
+            mode: Mutex::new(Mode::Passthrough),
+        }
+    }
+
+    /// Forward every call to `inner` and append it to the cassette at
+    /// `path`, starting with `inner`'s current tool list. The file is
+    /// created up front, so a run that made no calls still leaves a
+    /// cassette to replay.
+    pub fn record(inner: M, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let tools = inner.tools_as_openai();
+        clickweave_core::storage::append_jsonl(
+            &path,
+            &CassetteLine::Tools {
+                tools: tools.clone(),
+            },
+        )
+        .with_context(|| format!("Failed to create MCP cassette {}", path.display()))?;
+        Ok(Self {
+            inner,
+            mode: Mutex::new(Mode::Record {
+                path,
+                next_seq: 0,
+                tools,
+            }),
+        })
+    }
+
+    /// Answer tool calls and tool-list queries from the cassette at
+    /// `path`. `inner` is never asked to call a tool.
+    pub fn replay(inner: M, path: &Path, matching: McpReplayMatching) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read MCP cassette {}", path.display()))?;
+        let mut tool_lists: Vec<Vec<Value>> = Vec::new();
+        let mut remaining = VecDeque::new();
+        for (n, line) in text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+        {
+            let line: CassetteLine = serde_json::from_str(line).with_context(|| {
+                format!(
+                    "Malformed MCP cassette line {} in {}",
+                    n + 1,
+                    path.display()
+                )
+            })?;
+            match line {
+                CassetteLine::Tools { tools } => tool_lists.push(tools),
+                CassetteLine::Call(call) => remaining.push_back(PendingCall {
+                    call,
+                    tools: tool_lists.len().saturating_sub(1),
+                }),
+            }
+        }
+        Ok(Self {
+            inner,
+            mode: Mutex::new(Mode::Replay {
+                matching,
+                base_dir: artifact_base(path),
+                tool_lists,
+                remaining,
+                next_seq: 0,
+            }),
+        })
+    }
+
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Serve `name(arguments)` from the cassette, or `None` when not
+    /// replaying.
+    fn replay_call(&self, name: &str, arguments: &Option<Value>) -> Option<Result<ToolCallResult>> {
+        let mut mode = self.mode.lock().unwrap();
+        let Mode::Replay {
+            matching,
+            base_dir,
+            remaining,
+            next_seq,
+            ..
+        } = &mut *mode
+        else {
+            return None;
+        };
+        let seq = *next_seq;
+        *next_seq += 1;
+
+        let position = match matching {
+            McpReplayMatching::Sequence => remaining
+                .front()
+                .is_some_and(|pending| pending.call.tool == name)
+                .then_some(0),
+            McpReplayMatching::ToolAndArguments => remaining.iter().position(|pending| {
+                pending.call.tool == name && pending.call.arguments == *arguments
+            }),
+        };
+        let Some(pending) = position.and_then(|p| remaining.remove(p)) else {
+            let detail = match remaining.front() {
+                None => "the cassette has no more recorded calls".to_string(),
+                Some(_) if *matching == McpReplayMatching::ToolAndArguments => {
+                    format!("no unused recorded call to `{name}` with these arguments")
+                }
+                Some(expected) => format!(
+                    "expected `{}` (recorded call #{}), got `{name}`",
+                    expected.call.tool, expected.call.seq
+                ),
+            };
+            return Some(Err(McpCassetteDivergence { seq, detail }.into()));
+        };
+        if pending.call.arguments != *arguments {
+            debug!(
+                seq,
+                tool = name,
+                "MCP cassette: serving a call recorded with other arguments"
+            );
+        }
+        Some(match (pending.call.result, pending.call.error) {
+            (Some(result), _) => restore_result(result, base_dir),
+            (None, error) => Err(anyhow!(
+                "{}",
+                error.unwrap_or_else(|| "recorded MCP call has no result".to_string())
+            )),
+        })
+    }
+
+    /// Append the outcome of a live call when recording. `tools` is the
+    /// tool list the call was made against.
+    fn record_call(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+        tools: Vec<Value>,
+        result: &Result<ToolCallResult>,
+    ) {
+        let mut mode = self.mode.lock().unwrap();
+        let Mode::Record {
+            path,
+            next_seq,
+            tools: recorded_tools,
+        } = &mut *mode
+        else {
+            return;
+        };
+        let seq = *next_seq;
+        *next_seq += 1;
+
+        if tools != *recorded_tools {
+            if let Err(e) = clickweave_core::storage::append_jsonl(
+                path,
+                &CassetteLine::Tools {
+                    tools: tools.clone(),
+                },
+            ) {
+                warn!(path = %path.display(), error = %e, "Failed to append to MCP cassette");
+            }
+            *recorded_tools = tools;
+        }
+        let call = RecordedCall {
+            seq,
+            tool: name.to_string(),
+            arguments,
+            result: result
+                .as_ref()
+                .ok()
+                .map(|result| store_result(result, path, seq)),
+            error: result.as_ref().err().map(|e| format!("{e:#}")),
+        };
+        if let Err(e) = clickweave_core::storage::append_jsonl(path, &CassetteLine::Call(call)) {
+            warn!(path = %path.display(), error = %e, "Failed to append to MCP cassette");
+        }
+    }
+
+    fn is_recording(&self) -> bool {
+        matches!(*self.mode.lock().unwrap(), Mode::Record { .. })
+    }
+
+    /// The recorded tool list current for the next call, or `None` when
+    /// not replaying.
+    fn replayed_tools(&self) -> Option<Vec<Value>> {
+        let mode = self.mode.lock().unwrap();
+        let Mode::Replay {
+            tool_lists,
+            remaining,
+            ..
+        } = &*mode
+        else {
+            return None;
+        };
+        let index = remaining
+            .front()
+            .map_or(tool_lists.len().saturating_sub(1), |pending| pending.tools);
+        Some(tool_lists.get(index).cloned().unwrap_or_default())
+    }
+
+    async fn call(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+        timeout: Option<Duration>,
+    ) -> Result<ToolCallResult> {
+        if let Some(replayed) = self.replay_call(name, &arguments) {
+            return replayed;
+        }
+        let recording = self
+            .is_recording()
+            .then(|| (arguments.clone(), self.inner.tools_as_openai()));
+        let result = match timeout {
+            Some(timeout) => {
+                self.inner
+                    .call_tool_with_timeout(name, arguments, timeout)
+                    .await
+            }
+            None => self.inner.call_tool(name, arguments).await,
+        };
+        if let Some((arguments, tools)) = recording {
+            self.record_call(name, arguments, tools, &result);
+        }
+        result
+    }
+}
+
+impl<M: Mcp> Mcp for McpCassette<M> {
+    fn call_tool(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+    ) -> impl Future<Output = Result<ToolCallResult>> + Send {
+        self.call(name, arguments, None)
+    }
+
+    /// Replayed calls answer at once, whatever `timeout` is.
+    fn call_tool_with_timeout(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<ToolCallResult>> + Send {
+        self.call(name, arguments, Some(timeout))
+    }
+
+    fn has_tool(&self, name: &str) -> bool {
+        match self.replayed_tools() {
+            Some(tools) => tools
+                .iter()
+                .any(|tool| tool["function"]["name"].as_str() == Some(name)),
+            None => self.inner.has_tool(name),
+        }
+    }
+
+    fn tools_as_openai(&self) -> Vec<Value> {
+        self.replayed_tools()
+            .unwrap_or_else(|| self.inner.tools_as_openai())
+    }
+
+    /// A replay's tool list follows the recording, so there is nothing
+    /// to refresh.
+    fn refresh_server_tool_list(&self) -> impl Future<Output = Result<()>> + Send {
+        let replaying = self.replayed_tools().is_some();
+        async move {
+            if replaying {
+                Ok(())
+            } else {
+                self.inner.refresh_server_tool_list().await
+            }
+        }
+    }
+
+    fn notifications(
+        &self,
+    ) -> Option<tokio::sync::broadcast::Receiver<clickweave_mcp::McpNotification>> {
+        self.inner.notifications()
+    }
+
+    fn server_restarts(&self) -> u64 {
+        self.inner.server_restarts()
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> impl Future<Output = Result<clickweave_mcp::ReadResourceResult>> + Send {
+        self.inner.read_resource(uri)
+    }
+}
+
+/// Directory the cassette at `path` keeps its artifacts relative to.
+fn artifact_base(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// `result` in cassette form, its images written next to the cassette as
+/// `<cassette stem>/<seq>-<block>.<ext>`. An image that cannot be written
+/// keeps its data inline.
+fn store_result(result: &ToolCallResult, path: &Path, seq: usize) -> RecordedResult {
+    let base = artifact_base(path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "mcp".to_string());
+    let content = result
+        .content
+        .iter()
+        .enumerate()
+        .map(|(block, content)| match content {
+            ToolContent::Image { data, mime_type } => {
+                let relative = format!("{stem}/{seq:06}-{block}.{}", extension(mime_type));
+                match write_artifact(&base.join(&relative), data) {
+                    Ok(()) => json!({"type": "image", "mimeType": mime_type, "artifact": relative}),
+                    Err(e) => {
+                        warn!(artifact = %relative, "Failed to write MCP cassette image (kept inline): {e:#}");
+                        json!({"type": "image", "mimeType": mime_type, "data": data})
+                    }
+                }
+            }
+            ToolContent::Unknown(raw) => raw.clone(),
+            other => serde_json::to_value(other).unwrap_or(Value::Null),
+        })
+        .collect();
+    RecordedResult {
+        content,
+        is_error: result.is_error,
+        structured_content: result.structured_content.clone(),
+    }
+}
+
+fn write_artifact(path: &Path, data: &str) -> Result<()> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(data)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Rebuild a `ToolCallResult`, reading image artifacts back from `base_dir`.
+fn restore_result(mut recorded: RecordedResult, base_dir: &Path) -> Result<ToolCallResult> {
+    for block in &mut recorded.content {
+        let Some(relative) = block.get("artifact").and_then(Value::as_str) else {
+            continue;
+        };
+        let path = base_dir.join(relative);
+        let bytes = std::fs::read(&path)
+            .with_context(|| format!("Failed to read MCP cassette image {}", path.display()))?;
+        block["data"] = Value::String(base64::engine::general_purpose::STANDARD.encode(bytes));
+    }
+    serde_json::from_value(serde_json::to_value(recorded)?)
+        .context("Malformed recorded MCP tool result")
+}
+
+fn extension(mime_type: &str) -> &'static str {
+    match mime_type {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        _ => "bin",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::skills::types::{ActionSketchStep, ExpectedWorldModelDelta};
+    use crate::agent::test_stubs::{NullMcp, StaticMcp};
+    use crate::executor::{SkillRunContext, run_skill_steps};
+    use std::collections::HashMap;
+
+    /// 1x1 PNG header bytes, base64-encoded.
+    const PNG: &str = "iVBORw0KGgo=";
+
+    fn live() -> StaticMcp {
+        StaticMcp::with_tools(&["find_text", "take_screenshot", "click"])
+            .with_reply(
+                "find_text",
+                r#"[{"text":"OK","x":1,"y":2,"width":3,"height":4}]"#,
+            )
+            .with_image_reply("take_screenshot", PNG, "image/png")
+            .with_error("click", "element is disabled")
+    }
+
+    async fn record(path: &Path, calls: &[(&str, Value)]) {
+        let recorder = McpCassette::record(live(), path).unwrap();
+        for (name, arguments) in calls {
+            let _ = recorder.call_tool(name, Some(arguments.clone())).await;
+        }
+    }
+
+    fn text(result: &ToolCallResult) -> &str {
+        result.content[0].as_text().unwrap()
+    }
+
+    #[tokio::test]
+    async fn sequence_replay_serves_text_images_and_errors_without_the_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassettes").join("mcp.jsonl");
+        record(
+            &path,
+            &[
+                ("find_text", json!({"text": "OK"})),
+                ("take_screenshot", json!({})),
+                ("click", json!({"x": 1})),
+            ],
+        )
+        .await;
+        assert!(
+            dir.path().join("cassettes/mcp/000001-0.png").exists(),
+            "screenshot kept as an artifact"
+        );
+        assert!(
+            !std::fs::read_to_string(&path).unwrap().contains(PNG),
+            "image data is not inlined"
+        );
+
+        let replay = McpCassette::replay(NullMcp, &path, McpReplayMatching::Sequence).unwrap();
+        assert!(replay.has_tool("take_screenshot"));
+        assert_eq!(replay.tools_as_openai().len(), 3);
+
+        let found = replay
+            .call_tool("find_text", Some(json!({"text": "Cancel"})))
+            .await
+            .unwrap();
+        assert!(text(&found).contains("\"OK\""));
+        let shot = replay
+            .call_tool_with_timeout("take_screenshot", None, Duration::from_millis(1))
+            .await
+            .unwrap();
+        assert!(matches!(
+            &shot.content[0],
+            ToolContent::Image { data, mime_type } if data == PNG && mime_type == "image/png"
+        ));
+        let click = replay
+            .call_tool("click", Some(json!({"x": 1})))
+            .await
+            .unwrap();
+        assert_eq!(click.is_error, Some(true));
+        assert_eq!(text(&click), "element is disabled");
+
+        let err = replay.call_tool("click", None).await.unwrap_err();
+        assert!(err.to_string().contains("no more recorded calls"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn sequence_replay_reports_a_different_tool() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mcp.jsonl");
+        record(&path, &[("find_text", json!({"text": "OK"}))]).await;
+
+        let replay = McpCassette::replay(NullMcp, &path, McpReplayMatching::Sequence).unwrap();
+        let err = replay.call_tool("click", None).await.unwrap_err();
+        let divergence = err
+            .downcast_ref::<McpCassetteDivergence>()
+            .expect("typed divergence");
+        assert_eq!(divergence.seq, 0);
+        assert!(
+            divergence.detail.contains("expected `find_text`"),
+            "{divergence}"
+        );
+    }
+
+    #[tokio::test]
+    async fn tool_and_arguments_replay_matches_out_of_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mcp.jsonl");
+        let recorder = McpCassette::record(
+            StaticMcp::with_tools(&["find_text"]).with_reply("find_text", "first"),
+            &path,
+        )
+        .unwrap();
+        recorder
+            .call_tool("find_text", Some(json!({"text": "A"})))
+            .await
+            .unwrap();
+        drop(recorder);
+        let recorder = McpCassette::record(
+            StaticMcp::with_tools(&["find_text"]).with_reply("find_text", "second"),
+            &path,
+        )
+        .unwrap();
+        recorder
+            .call_tool("find_text", Some(json!({"text": "B"})))
+            .await
+            .unwrap();
+
+        let replay =
+            McpCassette::replay(NullMcp, &path, McpReplayMatching::ToolAndArguments).unwrap();
+        let b = replay
+            .call_tool("find_text", Some(json!({"text": "B"})))
+            .await
+            .unwrap();
+        assert_eq!(text(&b), "second");
+        let a = replay
+            .call_tool("find_text", Some(json!({"text": "A"})))
+            .await
+            .unwrap();
+        assert_eq!(text(&a), "first");
+        assert!(
+            replay
+                .call_tool("find_text", Some(json!({"text": "A"})))
+                .await
+                .is_err()
+        );
+    }
+
+    /// Advertises `cdp_find_elements` only once `cdp_connect` was called.
+    struct ConnectingMcp {
+        connected: std::sync::atomic::AtomicBool,
+    }
+
+    impl Mcp for ConnectingMcp {
+        async fn call_tool(&self, name: &str, _: Option<Value>) -> Result<ToolCallResult> {
+            if name == "cdp_connect" {
+                self.connected
+                    .store(true, std::sync::atomic::Ordering::SeqCst);
+            }
+            Ok(ToolCallResult {
+                content: vec![ToolContent::Text {
+                    text: "ok".to_string(),
+                }],
+                is_error: None,
+                structured_content: None,
+            })
+        }
+
+        fn has_tool(&self, name: &str) -> bool {
+            self.tools_as_openai()
+                .iter()
+                .any(|tool| tool["function"]["name"] == name)
+        }
+
+        fn tools_as_openai(&self) -> Vec<Value> {
+            let mut names = vec!["cdp_connect"];
+            if self.connected.load(std::sync::atomic::Ordering::SeqCst) {
+                names.push("cdp_find_elements");
+            }
+            names
+                .into_iter()
+                .map(|name| json!({"type": "function", "function": {"name": name}}))
+                .collect()
+        }
+
+        async fn refresh_server_tool_list(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn replayed_tool_list_follows_the_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mcp.jsonl");
+        let recorder = McpCassette::record(
+            ConnectingMcp {
+                connected: false.into(),
+            },
+            &path,
+        )
+        .unwrap();
+        recorder.call_tool("cdp_connect", None).await.unwrap();
+        recorder.call_tool("cdp_find_elements", None).await.unwrap();
+
+        let replay = McpCassette::replay(NullMcp, &path, McpReplayMatching::Sequence).unwrap();
+        assert!(!replay.has_tool("cdp_find_elements"));
+        replay.call_tool("cdp_connect", None).await.unwrap();
+        assert!(replay.has_tool("cdp_find_elements"));
+        replay.call_tool("cdp_find_elements", None).await.unwrap();
+        assert!(replay.has_tool("cdp_find_elements"));
+    }
+
+    #[tokio::test]
+    async fn a_recorded_skill_run_replays_through_the_skill_runner() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mcp.jsonl");
+        let steps: Vec<ActionSketchStep> = ["find_text", "click"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, tool)| ActionSketchStep::ToolCall {
+                step_id: format!("s_{i}"),
+                tool: tool.to_string(),
+                args: json!({}),
+                captures_pre: Vec::new(),
+                captures: Vec::new(),
+                expected_world_model_delta: ExpectedWorldModelDelta::default(),
+                requires_approval: None,
+            })
+            .collect();
+
+        let recorder = McpCassette::record(live(), &path).unwrap();
+        let mut ctx = SkillRunContext::new(&recorder, HashMap::new());
+        let live_err = run_skill_steps(&mut ctx, &steps).await.unwrap_err();
+
+        let replay = McpCassette::replay(NullMcp, &path, McpReplayMatching::Sequence).unwrap();
+        let mut ctx = SkillRunContext::new(&replay, HashMap::new());
+        let replay_err = run_skill_steps(&mut ctx, &steps).await.unwrap_err();
+        assert_eq!(ctx.completed_steps, ["s_0"]);
+        assert_eq!(replay_err.to_string(), live_err.to_string());
+    }
+}
@@ -11,7 +11,12 @@
 //!   `CandidateView`, `Rect`).
 //! - `skill_runner` — the index-walking runner that consumes
 //!   `&Skill::action_sketch` directly.
+//! - `skill_server` — confirmed skills published as MCP tools, run
+//!   through `skill_runner`.
 //! - `screenshot` — VLM-input capture helper used by the agent runner.
+//! - `mcp_cassette` — record/replay `Mcp` wrapper for reproducing runs
+//!   offline.
+//! - `notifications` — MCP server notifications rendered as run logs.
 //! - `cdp_helpers` and `best_effort` — pure-process helpers carried
 //!   forward from the deleted deterministic module so the agent's
 //!   CDP lifecycle still compiles.
@@ -19,16 +24,23 @@
 pub(crate) mod best_effort;
 pub(crate) mod cdp_helpers;
 pub mod error;
+pub(crate) mod mcp_cassette;
+pub(crate) mod notifications;
 pub(crate) mod screenshot;
 pub mod skill_runner;
+pub mod skill_server;
 
 pub use error::*;
-pub use skill_runner::{SkillRunContext, run_skill_steps};
+pub use mcp_cassette::{McpCassette, McpCassetteDivergence, McpReplayMatching};
+pub use notifications::forward_notifications_as_logs;
+pub use skill_runner::{SkillRunContext, StepApproval, StepApprover, run_skill_steps};
+pub use skill_server::SkillServer;
 
 use clickweave_core::SkillRun;
 use clickweave_llm::ChatBackend;
 use serde::{Deserialize, Serialize};
 use std::future::Future;
+use std::time::Duration;
 use uuid::Uuid;
 
 /// Trait abstracting MCP tool operations, used to enable test stubs.
@@ -44,6 +56,31 @@ pub trait Mcp: Send + Sync {
         arguments: Option<serde_json::Value>,
     ) -> impl Future<Output = anyhow::Result<clickweave_mcp::ToolCallResult>> + Send;
 
+    /// `call_tool` bounded by `timeout`. On expiry the error is a
+    /// [`clickweave_mcp::McpError::Timeout`] so callers can tell a hung
+    /// call from a tool-reported failure via `downcast_ref`.
+    ///
+    /// The default wraps `call_tool` in `tokio::time::timeout`, which is
+    /// all a stub needs. `McpClient` overrides it so the deadline lands
+    /// on the JSON-RPC request itself.
+    fn call_tool_with_timeout(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+        timeout: Duration,
+    ) -> impl Future<Output = anyhow::Result<clickweave_mcp::ToolCallResult>> + Send {
+        async move {
+            match tokio::time::timeout(timeout, self.call_tool(name, arguments)).await {
+                Ok(result) => result,
+                Err(_) => Err(clickweave_mcp::McpError::Timeout {
+                    method: format!("tools/call {name}"),
+                    timeout,
+                }
+                .into()),
+            }
+        }
+    }
+
     /// Check whether a tool with the given name is available.
     fn has_tool(&self, name: &str) -> bool;
 
@@ -58,6 +95,36 @@ pub trait Mcp: Send + Sync {
     /// latter is seeded once per run in `agent/mod.rs` and kept stable
     /// for prompt-cache stability.
     fn refresh_server_tool_list(&self) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Subscribe to the server's notifications (progress, log lines,
+    /// tool-list changes). `None` when there is no server to listen to,
+    /// as with test stubs.
+    fn notifications(
+        &self,
+    ) -> Option<tokio::sync::broadcast::Receiver<clickweave_mcp::McpNotification>> {
+        None
+    }
+
+    /// How many times the primary server was restarted after going away.
+    /// Each restart loses whatever the server held (its CDP session,
+    /// snapshot ids), so callers keeping state derived from it compare
+    /// this between calls. Auxiliary servers hold none of that state, so
+    /// their restarts are not counted. Always `0` for an unsupervised
+    /// connection.
+    fn server_restarts(&self) -> u64 {
+        0
+    }
+
+    /// Fetch a resource's contents (`resources/read`), such as the target
+    /// of a `resource_link` a tool returned. The default refuses: stubs
+    /// serve no resources.
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> impl Future<Output = anyhow::Result<clickweave_mcp::ReadResourceResult>> + Send {
+        let uri = uri.to_string();
+        async move { anyhow::bail!("MCP server does not serve resource `{uri}`") }
+    }
 }
 
 impl Mcp for clickweave_mcp::McpClient {
@@ -69,6 +136,15 @@ impl Mcp for clickweave_mcp::McpClient {
         clickweave_mcp::McpClient::call_tool(self, name, arguments)
     }
 
+    fn call_tool_with_timeout(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+        timeout: Duration,
+    ) -> impl Future<Output = anyhow::Result<clickweave_mcp::ToolCallResult>> + Send {
+        clickweave_mcp::McpClient::call_tool_with_timeout(self, name, arguments, timeout)
+    }
+
     fn has_tool(&self, name: &str) -> bool {
         clickweave_mcp::McpClient::has_tool(self, name)
     }
@@ -80,6 +156,115 @@ impl Mcp for clickweave_mcp::McpClient {
     fn refresh_server_tool_list(&self) -> impl Future<Output = anyhow::Result<()>> + Send {
         clickweave_mcp::McpClient::refresh_tools(self)
     }
+
+    fn notifications(
+        &self,
+    ) -> Option<tokio::sync::broadcast::Receiver<clickweave_mcp::McpNotification>> {
+        Some(clickweave_mcp::McpClient::notifications(self))
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> impl Future<Output = anyhow::Result<clickweave_mcp::ReadResourceResult>> + Send {
+        clickweave_mcp::McpClient::read_resource(self, uri)
+    }
+}
+
+impl Mcp for clickweave_mcp::McpMultiplexer {
+    fn call_tool(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+    ) -> impl Future<Output = anyhow::Result<clickweave_mcp::ToolCallResult>> + Send {
+        clickweave_mcp::McpMultiplexer::call_tool(self, name, arguments)
+    }
+
+    fn call_tool_with_timeout(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+        timeout: Duration,
+    ) -> impl Future<Output = anyhow::Result<clickweave_mcp::ToolCallResult>> + Send {
+        clickweave_mcp::McpMultiplexer::call_tool_with_timeout(self, name, arguments, timeout)
+    }
+
+    fn has_tool(&self, name: &str) -> bool {
+        clickweave_mcp::McpMultiplexer::has_tool(self, name)
+    }
+
+    fn tools_as_openai(&self) -> Vec<serde_json::Value> {
+        clickweave_mcp::McpMultiplexer::tools_as_openai(self)
+    }
+
+    fn refresh_server_tool_list(&self) -> impl Future<Output = anyhow::Result<()>> + Send {
+        clickweave_mcp::McpMultiplexer::refresh_tools(self)
+    }
+
+    fn notifications(
+        &self,
+    ) -> Option<tokio::sync::broadcast::Receiver<clickweave_mcp::McpNotification>> {
+        Some(clickweave_mcp::McpMultiplexer::notifications(self))
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> impl Future<Output = anyhow::Result<clickweave_mcp::ReadResourceResult>> + Send {
+        clickweave_mcp::McpMultiplexer::read_resource(self, uri)
+    }
+
+    fn server_restarts(&self) -> u64 {
+        clickweave_mcp::McpMultiplexer::primary_restarts(self)
+    }
+}
+
+impl Mcp for clickweave_mcp::McpSupervisor {
+    fn call_tool(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+    ) -> impl Future<Output = anyhow::Result<clickweave_mcp::ToolCallResult>> + Send {
+        clickweave_mcp::McpSupervisor::call_tool(self, name, arguments)
+    }
+
+    fn call_tool_with_timeout(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+        timeout: Duration,
+    ) -> impl Future<Output = anyhow::Result<clickweave_mcp::ToolCallResult>> + Send {
+        clickweave_mcp::McpSupervisor::call_tool_with_timeout(self, name, arguments, timeout)
+    }
+
+    fn has_tool(&self, name: &str) -> bool {
+        clickweave_mcp::McpSupervisor::has_tool(self, name)
+    }
+
+    fn tools_as_openai(&self) -> Vec<serde_json::Value> {
+        clickweave_mcp::McpSupervisor::tools_as_openai(self)
+    }
+
+    fn refresh_server_tool_list(&self) -> impl Future<Output = anyhow::Result<()>> + Send {
+        clickweave_mcp::McpSupervisor::refresh_tools(self)
+    }
+
+    fn notifications(
+        &self,
+    ) -> Option<tokio::sync::broadcast::Receiver<clickweave_mcp::McpNotification>> {
+        Some(clickweave_mcp::McpSupervisor::notifications(self))
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> impl Future<Output = anyhow::Result<clickweave_mcp::ReadResourceResult>> + Send {
+        clickweave_mcp::McpSupervisor::read_resource(self, uri)
+    }
+
+    fn server_restarts(&self) -> u64 {
+        clickweave_mcp::McpSupervisor::restarts(self)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -0,0 +1,150 @@
+//! MCP server notifications as run-visible messages.
+//!
+//! The MCP client broadcasts what the server says outside of responses.
+//! The agent runner turns progress and log lines into `AgentEvent`s; the
+//! skill executor, whose UI only has a log pane, gets them as
+//! `ExecutorEvent::Log` lines via [`forward_notifications_as_logs`].
+
+use super::ExecutorEvent;
+use crate::agent::{AgentEvent, RunnerOutput};
+use clickweave_mcp::McpNotification;
+use tokio::sync::{broadcast, mpsc};
+
+/// The tool a progress notification belongs to, from the client's
+/// `tools/call <name>` request label.
+pub(crate) fn progress_tool_name(request: Option<&str>) -> Option<String> {
+    request?.strip_prefix("tools/call ").map(str::to_string)
+}
+
+/// One log line for a notification; `None` for ones not worth showing.
+pub(crate) fn log_line(notification: &McpNotification) -> Option<String> {
+    match notification {
+        McpNotification::Progress { request, progress } => {
+            let mut line = String::from("MCP progress");
+            if let Some(tool) = progress_tool_name(request.as_deref()) {
+                line.push_str(&format!(" ({tool})"));
+            }
+            match progress.total {
+                Some(total) => line.push_str(&format!(": {}/{}", progress.progress, total)),
+                None => line.push_str(&format!(": {}", progress.progress)),
+            }
+            if let Some(message) = &progress.message {
+                line.push_str(&format!(" {message}"));
+            }
+            Some(line)
+        }
+        McpNotification::Message(message) => Some(match &message.logger {
+            Some(logger) => format!("MCP [{}] {logger}: {}", message.level, message.text()),
+            None => format!("MCP [{}] {}", message.level, message.text()),
+        }),
+        McpNotification::ToolsListChanged
+        | McpNotification::ResourcesListChanged
+        | McpNotification::ResourceUpdated { .. }
+        | McpNotification::PromptsListChanged
+        | McpNotification::Other { .. } => None,
+    }
+}
+
+/// The agent event for a notification; `None` for ones not worth showing.
+pub(crate) fn agent_event(notification: McpNotification) -> Option<AgentEvent> {
+    match notification {
+        McpNotification::Progress { request, progress } => Some(AgentEvent::McpProgress {
+            tool_name: progress_tool_name(request.as_deref()),
+            progress: progress.progress,
+            total: progress.total,
+            message: progress.message,
+        }),
+        McpNotification::Message(message) => Some(AgentEvent::McpLog {
+            message: message.text(),
+            level: message.level,
+            logger: message.logger,
+        }),
+        McpNotification::ToolsListChanged
+        | McpNotification::ResourcesListChanged
+        | McpNotification::ResourceUpdated { .. }
+        | McpNotification::PromptsListChanged
+        | McpNotification::Other { .. } => None,
+    }
+}
+
+/// Forward progress and log notifications to an agent run's event
+/// stream until either side closes.
+pub(crate) async fn forward_notifications_as_agent_events(
+    mut notifications: broadcast::Receiver<McpNotification>,
+    event_tx: mpsc::Sender<RunnerOutput>,
+) {
+    loop {
+        let notification = match notifications.recv().await {
+            Ok(notification) => notification,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        if let Some(event) = agent_event(notification)
+            && event_tx.send(RunnerOutput::Event(event)).await.is_err()
+        {
+            return;
+        }
+    }
+}
+
+/// Forward progress and log notifications to `event_tx` as
+/// `ExecutorEvent::Log` until either side closes.
+pub async fn forward_notifications_as_logs(
+    mut notifications: broadcast::Receiver<McpNotification>,
+    event_tx: mpsc::Sender<ExecutorEvent>,
+) {
+    loop {
+        let notification = match notifications.recv().await {
+            Ok(notification) => notification,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        if let Some(line) = log_line(&notification)
+            && event_tx.send(ExecutorEvent::Log(line)).await.is_err()
+        {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn progress_and_messages_render_as_log_lines() {
+        let progress = McpNotification::Progress {
+            request: Some("tools/call cdp_wait_for_page_change".into()),
+            progress: serde_json::from_value(
+                json!({"progressToken": 3, "progress": 1, "total": 4, "message": "waiting"}),
+            )
+            .unwrap(),
+        };
+        assert_eq!(
+            log_line(&progress).unwrap(),
+            "MCP progress (cdp_wait_for_page_change): 1/4 waiting"
+        );
+
+        let message = McpNotification::parse(
+            "notifications/message",
+            Some(json!({"level": "error", "logger": "fs", "data": "disk full"})),
+        );
+        assert_eq!(log_line(&message).unwrap(), "MCP [error] fs: disk full");
+        assert_eq!(log_line(&McpNotification::ToolsListChanged), None);
+    }
+
+    #[test]
+    fn progress_carries_the_tool_name_into_the_agent_event() {
+        let progress = McpNotification::Progress {
+            request: Some("tools/call take_screenshot".into()),
+            progress: serde_json::from_value(json!({"progressToken": 1, "progress": 0.5})).unwrap(),
+        };
+        assert!(matches!(
+            agent_event(progress),
+            Some(AgentEvent::McpProgress { tool_name: Some(name), total: None, .. })
+                if name == "take_screenshot"
+        ));
+        assert!(agent_event(McpNotification::ToolsListChanged).is_none());
+    }
+}
@@ -10,6 +10,10 @@
 //! handle the `None` branch by falling back to text-only verification.
 
 use super::Mcp;
+use base64::Engine;
+use clickweave_core::walkthrough::ScreenshotMeta;
+use clickweave_core::walkthrough::enrichment::parse_screenshot_metadata_json;
+use clickweave_llm::{MarkCandidate, SetOfMarks};
 use clickweave_mcp::ToolContent;
 use serde_json::Value;
 use tracing::warn;
@@ -63,6 +67,16 @@ pub(crate) async fn capture_raw_image_with_args(
     mcp: &(impl Mcp + ?Sized),
     args: Value,
 ) -> Option<String> {
+    capture_image_block(mcp, args).await.map(|(image, _)| image)
+}
+
+/// Shared body of the capture helpers: the first image block as raw
+/// base64, plus the screenshot's origin and scale when the server
+/// reported them.
+async fn capture_image_block(
+    mcp: &(impl Mcp + ?Sized),
+    args: Value,
+) -> Option<(String, Option<ScreenshotMeta>)> {
     let result = match mcp.call_tool("take_screenshot", Some(args.clone())).await {
         Ok(r) => r,
         Err(e) => {
@@ -86,7 +100,11 @@ pub(crate) async fn capture_raw_image_with_args(
     if image.is_none() {
         warn!(tool_args = %args, "take_screenshot returned no image block");
     }
-    image
+    let meta = result.content.iter().find_map(|content| {
+        let json = serde_json::from_str::<Value>(content.as_text()?).ok()?;
+        parse_screenshot_metadata_json(&json)
+    });
+    image.map(|image| (image, meta))
 }
 
 /// Call `take_screenshot`, extract the first image block, and prepare it
@@ -104,6 +122,36 @@ pub(crate) async fn capture_screenshot_for_vlm(
     clickweave_llm::prepare_base64_image_for_vlm(&raw_b64, clickweave_llm::DEFAULT_MAX_DIMENSION)
 }
 
+/// [`capture_screenshot_for_vlm`] with numbered boxes drawn over
+/// `candidates` (in screen points), returning the mark mapping alongside
+/// the image. When the server reports no screenshot origin and scale the
+/// boxes cannot be placed, so the image is sent unmarked with no marks.
+pub(crate) async fn capture_marked_screenshot_for_vlm(
+    mcp: &(impl Mcp + ?Sized),
+    scope: ScreenshotScope,
+    candidates: &[MarkCandidate],
+) -> Option<(String, String, SetOfMarks)> {
+    if candidates.is_empty() {
+        let (b64, mime) = capture_screenshot_for_vlm(mcp, scope).await?;
+        return Some((b64, mime, SetOfMarks::default()));
+    }
+    let (raw_b64, meta) = capture_image_block(mcp, scope.to_arguments()).await?;
+    let max_dimension = clickweave_llm::DEFAULT_MAX_DIMENSION;
+    match meta {
+        Some(meta) => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(&raw_b64)
+                .ok()?;
+            clickweave_llm::prepare_marked_image_for_vlm(&bytes, candidates, meta, max_dimension)
+        }
+        None => {
+            let (b64, mime) =
+                clickweave_llm::prepare_base64_image_for_vlm(&raw_b64, max_dimension)?;
+            Some((b64, mime, SetOfMarks::default()))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,6 +234,7 @@ mod tests {
                 mime_type: "image/png".to_string(),
             }],
             is_error: None,
+            structured_content: None,
         }]);
         let out = capture_screenshot_for_vlm(&mcp, ScreenshotScope::Screen).await;
         assert!(out.is_some(), "happy path must succeed");
@@ -207,6 +256,7 @@ mod tests {
                 text: "permission denied".to_string(),
             }],
             is_error: Some(true),
+            structured_content: None,
         }]);
         let out =
             capture_screenshot_for_vlm(&mcp, ScreenshotScope::Window("Chrome".to_string())).await;
@@ -222,8 +272,30 @@ mod tests {
                 text: "ok".to_string(),
             }],
             is_error: None,
+            structured_content: None,
         }]);
         let out = capture_screenshot_for_vlm(&mcp, ScreenshotScope::Screen).await;
         assert!(out.is_none(), "missing image block must surface as None");
     }
+
+    #[tokio::test]
+    async fn marked_capture_without_metadata_sends_the_image_unmarked() {
+        let mcp = ScriptedMcp::new(vec![ToolCallResult {
+            content: vec![ToolContent::Image {
+                data: TINY_PNG_BASE64.to_string(),
+                mime_type: "image/png".to_string(),
+            }],
+            is_error: None,
+            structured_content: None,
+        }]);
+        let candidates = [MarkCandidate::point(0.0, 0.0, 8.0)];
+
+        let (b64, _, marks) =
+            capture_marked_screenshot_for_vlm(&mcp, ScreenshotScope::Screen, &candidates)
+                .await
+                .expect("image still captured");
+
+        assert!(!b64.is_empty());
+        assert!(marks.is_empty(), "no origin or scale to place boxes with");
+    }
 }
@@ -6,18 +6,45 @@
 //! executed in order, `iteration_delay_ms` separates iterations, and
 //! `max_iterations` caps runaway loops.
 //!
-//! Phase 1.D scope is intentionally minimal: per-step tool dispatch
-//! through the [`Mcp`] trait, plus the `Loop` primitive, plus event
-//! emission. Repair / supervision / approval flows live above this
-//! runner and will be wired through the
+//! Each tool call resolves its `{{params.X}}` / `{{captured.X}}`
+//! placeholders first, and its `captures` clauses bind values from the
+//! result for later steps; [`SkillRunContext::outputs`] reads a skill's
+//! declared outputs off those bindings once the run is done. Steps
+//! [`should_gate_step`] gates are put to the context's
+//! [`StepApprover`], when it has one. Repair / supervision flows live
+//! above this runner and will be wired through the
 //! [`crate::executor::ExecutorEvent`] channel in later phases.
 
 use crate::agent::permissions::ToolAnnotations;
-use crate::agent::skills::types::{ActionSketchStep, LoopPredicate};
+use crate::agent::skills::substitution::{resolve_capture_jsonpath, substitute_value};
+use crate::agent::skills::types::{
+    ActionSketchStep, BindingRef, CaptureClause, CaptureSource, LoopPredicate, OutputDeclaration,
+};
 use crate::executor::Mcp;
 use crate::executor::error::{ExecutorError, ExecutorResult};
+use clickweave_mcp::{ToolCallResult, ToolContent};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// A gated step awaiting a decision, as shown to a [`StepApprover`].
+#[derive(Debug)]
+pub struct StepApproval<'a> {
+    pub step_id: &'a str,
+    pub tool: &'a str,
+    /// The arguments the call will be made with, placeholders resolved.
+    pub arguments: &'a Value,
+    pub annotations: ToolAnnotations,
+}
+
+/// Decides whether a gated step may run.
+#[async_trait::async_trait]
+pub trait StepApprover: Send + Sync {
+    /// `true` runs the step; `false` stops the run with
+    /// [`ExecutorError::ApprovalDenied`].
+    async fn approve(&self, step: &StepApproval<'_>) -> bool;
+}
 
 /// Mutable state carried through a skill run. Holds the active world
 /// model, captured tool results, and runtime variable bindings.
@@ -29,11 +56,22 @@ pub struct SkillRunContext<'mcp, M: Mcp + ?Sized> {
     /// MCP transport used for every `tool_call` dispatch.
     pub mcp: &'mcp M,
     /// Runtime variable bindings (e.g. `recipient -> "alice@example.com"`)
-    /// supplied by the `RunWithValuesForm`. Shared with `evaluate_until`
-    /// for loop predicates.
+    /// supplied by the `RunWithValuesForm`; `{{params.X}}` placeholders
+    /// resolve against them. Shared with `evaluate_until` for loop
+    /// predicates.
     pub variables: HashMap<String, Value>,
+    /// Values bound by `captures` clauses so far, read by
+    /// `{{captured.X}}` placeholders in later steps.
+    pub captured: HashMap<String, Value>,
     /// Steps executed so far this run. Indexed by `step_id`.
     pub completed_steps: Vec<String>,
+    /// Stops the run when cancelled: an in-flight tool call is abandoned
+    /// with [`ExecutorError::ToolCallCancelled`], otherwise the next step
+    /// fails with [`ExecutorError::Cancelled`].
+    pub cancel_signal: Option<CancellationToken>,
+    /// Asked before every step [`should_gate_step`] gates. Without one,
+    /// gated steps run unasked.
+    pub approver: Option<Arc<dyn StepApprover>>,
 }
 
 impl<'mcp, M: Mcp + ?Sized> SkillRunContext<'mcp, M> {
@@ -41,8 +79,51 @@ impl<'mcp, M: Mcp + ?Sized> SkillRunContext<'mcp, M> {
         Self {
             mcp,
             variables,
+            captured: HashMap::new(),
             completed_steps: Vec::new(),
+            cancel_signal: None,
+            approver: None,
+        }
+    }
+
+    pub fn with_cancel_signal(mut self, token: CancellationToken) -> Self {
+        self.cancel_signal = Some(token);
+        self
+    }
+
+    pub fn with_approver(mut self, approver: Arc<dyn StepApprover>) -> Self {
+        self.approver = Some(approver);
+        self
+    }
+
+    /// The skill's declared outputs, by name, from the run's parameters
+    /// and captures. Fails on an output whose source was never bound.
+    pub fn outputs(&self, declarations: &[OutputDeclaration]) -> ExecutorResult<Value> {
+        let mut outputs = serde_json::Map::new();
+        for output in declarations {
+            let (bindings, name, kind) = match &output.from {
+                BindingRef::Params { name } => (&self.variables, name, "parameter"),
+                BindingRef::Captured { name } => (&self.captured, name, "capture"),
+            };
+            let value = bindings.get(name).ok_or_else(|| {
+                ExecutorError::Validation(format!(
+                    "output `{}` reads {kind} `{name}`, which the run never bound",
+                    output.name
+                ))
+            })?;
+            outputs.insert(output.name.clone(), value.clone());
         }
+        Ok(Value::Object(outputs))
+    }
+
+    fn params(&self) -> Value {
+        Value::Object(self.variables.clone().into_iter().collect())
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel_signal
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
     }
 }
 
@@ -107,14 +188,31 @@ async fn run_step<M: Mcp + ?Sized>(
     ctx: &mut SkillRunContext<'_, M>,
     step: &ActionSketchStep,
 ) -> ExecutorResult<()> {
+    if ctx.cancelled() {
+        return Err(ExecutorError::Cancelled);
+    }
     match step {
         ActionSketchStep::ToolCall {
             step_id,
             tool,
             args,
+            captures_pre,
+            captures,
             requires_approval,
             ..
-        } => run_tool_call(ctx, step_id, tool, args, *requires_approval).await,
+        } => {
+            for clause in captures_pre {
+                bind_capture(ctx, step_id, clause, None)?;
+            }
+            let arguments = substitute_value(args, &ctx.params(), &ctx.captured)
+                .map_err(|e| ExecutorError::Validation(format!("step {step_id}: {e}")))?;
+            let result = run_tool_call(ctx, step_id, tool, arguments, *requires_approval).await?;
+            for clause in captures {
+                bind_capture(ctx, step_id, clause, Some(&result))?;
+            }
+            ctx.completed_steps.push(step_id.to_string());
+            Ok(())
+        }
         ActionSketchStep::Loop {
             step_id,
             until,
@@ -139,22 +237,50 @@ async fn run_tool_call<M: Mcp + ?Sized>(
     ctx: &mut SkillRunContext<'_, M>,
     step_id: &str,
     tool: &str,
-    args: &Value,
-    _requires_approval: Option<bool>,
-) -> ExecutorResult<()> {
-    let result = ctx
-        .mcp
-        .call_tool(tool, Some(args.clone()))
-        .await
-        .map_err(|e| ExecutorError::ToolCall {
-            tool: tool.to_string(),
-            message: e.to_string(),
-        })?;
+    arguments: Value,
+    requires_approval: Option<bool>,
+) -> ExecutorResult<ToolCallResult> {
+    if let Some(approver) = &ctx.approver {
+        let annotations = tool_annotations(ctx.mcp, tool);
+        if should_gate_step(tool, requires_approval, &annotations) {
+            let step = StepApproval {
+                step_id,
+                tool,
+                arguments: &arguments,
+                annotations,
+            };
+            if !approver.approve(&step).await {
+                return Err(ExecutorError::ApprovalDenied {
+                    step_id: step_id.to_string(),
+                    tool: tool.to_string(),
+                });
+            }
+        }
+    }
+
+    let call = ctx.mcp.call_tool(tool, Some(arguments));
+    // Dropping the call future is what tells the server to stop.
+    let result = match &ctx.cancel_signal {
+        Some(token) => tokio::select! {
+            _ = token.cancelled() => {
+                return Err(ExecutorError::ToolCallCancelled {
+                    step_id: step_id.to_string(),
+                    tool: tool.to_string(),
+                });
+            }
+            result = call => result,
+        },
+        None => call.await,
+    };
+    let result = result.map_err(|e| ExecutorError::ToolCall {
+        tool: tool.to_string(),
+        message: e.to_string(),
+    })?;
     if result.is_error == Some(true) {
         let msg = result
             .content
             .iter()
-            .find_map(clickweave_mcp::ToolContent::as_text)
+            .find_map(ToolContent::as_text)
             .unwrap_or("<no error text>")
             .to_string();
         return Err(ExecutorError::ToolCall {
@@ -162,10 +288,73 @@ async fn run_tool_call<M: Mcp + ?Sized>(
             message: msg,
         });
     }
-    ctx.completed_steps.push(step_id.to_string());
+    Ok(result)
+}
+
+fn tool_annotations<M: Mcp + ?Sized>(mcp: &M, tool: &str) -> ToolAnnotations {
+    mcp.tools_as_openai()
+        .iter()
+        .find(|t| {
+            t.get("function")
+                .and_then(|f| f.get("name"))
+                .or_else(|| t.get("name"))
+                .and_then(Value::as_str)
+                == Some(tool)
+        })
+        .map(ToolAnnotations::from_tool_json)
+        .unwrap_or_default()
+}
+
+/// Bind one capture clause. `result` is the step's tool result for
+/// post-call `captures`, `None` for `captures_pre`.
+fn bind_capture<M: Mcp + ?Sized>(
+    ctx: &mut SkillRunContext<'_, M>,
+    step_id: &str,
+    clause: &CaptureClause,
+    result: Option<&ToolCallResult>,
+) -> ExecutorResult<()> {
+    let value = match (&clause.source, result) {
+        (CaptureSource::Literal { value }, _) => value.clone(),
+        (CaptureSource::ToolResult { jsonpath }, Some(result)) => {
+            resolve_capture_jsonpath(&capture_root(result), jsonpath).ok_or_else(|| {
+                ExecutorError::Validation(format!(
+                    "step {step_id}: capture `{}` found nothing at {jsonpath}",
+                    clause.name
+                ))
+            })?
+        }
+        (CaptureSource::ToolResult { .. }, None) => {
+            return Err(ExecutorError::Validation(format!(
+                "step {step_id}: capture `{}` reads a tool result before the call",
+                clause.name
+            )));
+        }
+        (CaptureSource::AxDescriptor { .. }, _) => {
+            return Err(ExecutorError::Validation(format!(
+                "step {step_id}: capture `{}` needs AX descriptor resolution, \
+                 which the skill runner does not do yet",
+                clause.name
+            )));
+        }
+    };
+    ctx.captured.insert(clause.name.clone(), value);
     Ok(())
 }
 
+/// The JSON a `ToolResult` capture's jsonpath addresses: the result's
+/// `structuredContent` when it has one, else its text parsed as JSON.
+fn capture_root(result: &ToolCallResult) -> Value {
+    if let Some(structured) = &result.structured_content {
+        return structured.clone();
+    }
+    let text: Vec<&str> = result
+        .content
+        .iter()
+        .filter_map(ToolContent::as_text)
+        .collect();
+    serde_json::from_str(&text.join("\n")).unwrap_or(Value::Null)
+}
+
 async fn run_loop<M: Mcp + ?Sized>(
     ctx: &mut SkillRunContext<'_, M>,
     step_id: &str,
@@ -182,7 +371,14 @@ async fn run_loop<M: Mcp + ?Sized>(
         run_skill_steps(ctx, body).await?;
         iter += 1;
         if iter < max_iterations && iteration_delay_ms > 0 {
-            tokio::time::sleep(std::time::Duration::from_millis(iteration_delay_ms)).await;
+            let delay = tokio::time::sleep(std::time::Duration::from_millis(iteration_delay_ms));
+            match &ctx.cancel_signal {
+                Some(token) => tokio::select! {
+                    _ = token.cancelled() => return Err(ExecutorError::Cancelled),
+                    _ = delay => {}
+                },
+                None => delay.await,
+            }
         }
     }
     if evaluate_until(ctx, until, iter)? {
@@ -219,8 +415,7 @@ fn evaluate_until<M: Mcp + ?Sized>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::agent::skills::types::{ActionSketchStep, ExpectedWorldModelDelta, LoopPredicate};
-    use clickweave_mcp::ToolCallResult;
+    use crate::agent::skills::types::ExpectedWorldModelDelta;
     use serde_json::json;
     use std::sync::Arc;
     use std::sync::Mutex;
@@ -259,6 +454,7 @@ mod tests {
             Ok(ToolCallResult {
                 content: vec![],
                 is_error: Some(false),
+                structured_content: None,
             })
         }
 
@@ -429,6 +625,250 @@ mod tests {
         assert_eq!(inner_count, 2);
     }
 
+    /// `Mcp` stub whose `wait` never returns, standing in for a tool call
+    /// stuck on the server.
+    struct StalledMcp;
+
+    impl Mcp for StalledMcp {
+        async fn call_tool(&self, name: &str, _: Option<Value>) -> anyhow::Result<ToolCallResult> {
+            if name == "wait" {
+                std::future::pending::<()>().await;
+            }
+            Ok(ToolCallResult {
+                content: vec![],
+                is_error: Some(false),
+                structured_content: None,
+            })
+        }
+
+        fn has_tool(&self, _: &str) -> bool {
+            true
+        }
+
+        fn tools_as_openai(&self) -> Vec<Value> {
+            Vec::new()
+        }
+
+        async fn refresh_server_tool_list(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn cancel_signal_abandons_the_call_in_flight() {
+        let token = CancellationToken::new();
+        let mut ctx =
+            SkillRunContext::new(&StalledMcp, HashMap::new()).with_cancel_signal(token.clone());
+        let steps = vec![
+            tool_call("s_001", "click"),
+            tool_call("s_002", "wait"),
+            tool_call("s_003", "type_text"),
+        ];
+
+        let stop = async {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            token.cancel();
+        };
+        let (result, ()) = tokio::join!(run_skill_steps(&mut ctx, &steps), stop);
+
+        match result {
+            Err(ExecutorError::ToolCallCancelled { step_id, tool }) => {
+                assert_eq!((step_id.as_str(), tool.as_str()), ("s_002", "wait"));
+            }
+            other => panic!("expected ToolCallCancelled, got {other:?}"),
+        }
+        assert_eq!(ctx.completed_steps, vec!["s_001"]);
+        // Once stopped, nothing further is dispatched.
+        let err = run_skill_steps(&mut ctx, &steps[..1]).await.unwrap_err();
+        assert!(matches!(err, ExecutorError::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn cancel_signal_interrupts_the_iteration_delay() {
+        let token = CancellationToken::new();
+        let mcp = ReplayingMcp::new();
+        let mut ctx = SkillRunContext::new(&mcp, HashMap::new()).with_cancel_signal(token.clone());
+        let steps = vec![ActionSketchStep::Loop {
+            step_id: "loop_slow".to_string(),
+            until: LoopPredicate::StepCountReached { count: 5 },
+            body: vec![tool_call("b_001", "click")],
+            max_iterations: 5,
+            iteration_delay_ms: 60_000,
+        }];
+
+        let stop = async {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            token.cancel();
+        };
+        let (result, ()) = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            tokio::join!(run_skill_steps(&mut ctx, &steps), stop)
+        })
+        .await
+        .expect("the delay must not outlive the cancel");
+
+        assert!(
+            matches!(result, Err(ExecutorError::Cancelled)),
+            "{result:?}"
+        );
+        assert_eq!(ctx.completed_steps, vec!["b_001"]);
+    }
+
+    /// `Mcp` stub answering `find_text` with structured matches, `click`
+    /// with its arguments echoed as text, and listing `click` as
+    /// destructive.
+    struct FindAndClickMcp {
+        log: Arc<Mutex<Vec<(String, Value)>>>,
+    }
+
+    impl Mcp for FindAndClickMcp {
+        async fn call_tool(
+            &self,
+            name: &str,
+            arguments: Option<Value>,
+        ) -> anyhow::Result<ToolCallResult> {
+            let arguments = arguments.unwrap_or(Value::Null);
+            self.log
+                .lock()
+                .unwrap()
+                .push((name.to_string(), arguments.clone()));
+            let structured = (name == "find_text")
+                .then(|| json!({"matches": [{"text": "Send", "uid": "a7g2"}]}));
+            Ok(ToolCallResult {
+                content: vec![ToolContent::Text {
+                    text: arguments.to_string(),
+                }],
+                is_error: None,
+                structured_content: structured,
+            })
+        }
+
+        fn has_tool(&self, _: &str) -> bool {
+            true
+        }
+
+        fn tools_as_openai(&self) -> Vec<Value> {
+            vec![json!({
+                "type": "function",
+                "function": {"name": "click", "annotations": {"destructiveHint": true}}
+            })]
+        }
+
+        async fn refresh_server_tool_list(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn find_then_click() -> Vec<ActionSketchStep> {
+        vec![
+            ActionSketchStep::ToolCall {
+                step_id: "s_001".to_string(),
+                tool: "find_text".to_string(),
+                args: json!({"text": "{{params.label}}"}),
+                captures_pre: Vec::new(),
+                captures: vec![CaptureClause {
+                    name: "button".to_string(),
+                    source: CaptureSource::ToolResult {
+                        jsonpath: "$.matches[0].uid".to_string(),
+                    },
+                }],
+                expected_world_model_delta: ExpectedWorldModelDelta::default(),
+                requires_approval: None,
+            },
+            ActionSketchStep::ToolCall {
+                step_id: "s_002".to_string(),
+                tool: "click".to_string(),
+                args: json!({"uid": "{{captured.button}}"}),
+                captures_pre: Vec::new(),
+                captures: Vec::new(),
+                expected_world_model_delta: ExpectedWorldModelDelta::default(),
+                requires_approval: None,
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn placeholders_resolve_from_params_and_captures_and_feed_outputs() {
+        let mcp = FindAndClickMcp {
+            log: Arc::new(Mutex::new(Vec::new())),
+        };
+        let params = HashMap::from([("label".to_string(), json!("Send"))]);
+        let mut ctx = SkillRunContext::new(&mcp, params);
+
+        run_skill_steps(&mut ctx, &find_then_click())
+            .await
+            .expect("ok");
+
+        let calls = mcp.log.lock().unwrap().clone();
+        assert_eq!(calls[0], ("find_text".into(), json!({"text": "Send"})));
+        assert_eq!(calls[1], ("click".into(), json!({"uid": "a7g2"})));
+
+        let declare = |name: &str, from: BindingRef| OutputDeclaration {
+            name: name.to_string(),
+            type_tag: "string".to_string(),
+            from,
+        };
+        let outputs = ctx
+            .outputs(&[
+                declare(
+                    "clicked",
+                    BindingRef::Captured {
+                        name: "button".into(),
+                    },
+                ),
+                declare(
+                    "label",
+                    BindingRef::Params {
+                        name: "label".into(),
+                    },
+                ),
+            ])
+            .unwrap();
+        assert_eq!(outputs, json!({"clicked": "a7g2", "label": "Send"}));
+        let err = ctx
+            .outputs(&[declare(
+                "gone",
+                BindingRef::Captured {
+                    name: "nope".into(),
+                },
+            )])
+            .unwrap_err();
+        assert!(err.to_string().contains("`gone`"), "{err}");
+    }
+
+    struct Refuse(Mutex<Vec<String>>);
+
+    #[async_trait::async_trait]
+    impl StepApprover for Refuse {
+        async fn approve(&self, step: &StepApproval<'_>) -> bool {
+            assert_eq!(step.arguments, &json!({"uid": "a7g2"}));
+            self.0.lock().unwrap().push(step.tool.to_string());
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn gated_steps_are_put_to_the_approver() {
+        let mcp = FindAndClickMcp {
+            log: Arc::new(Mutex::new(Vec::new())),
+        };
+        let approver = Arc::new(Refuse(Mutex::new(Vec::new())));
+        let params = HashMap::from([("label".to_string(), json!("Send"))]);
+        let mut ctx = SkillRunContext::new(&mcp, params).with_approver(approver.clone());
+
+        let err = run_skill_steps(&mut ctx, &find_then_click())
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(&err, ExecutorError::ApprovalDenied { step_id, tool } if step_id == "s_002" && tool == "click"),
+            "{err:?}"
+        );
+        // Only the destructive `click` was asked about, and it never ran.
+        assert_eq!(*approver.0.lock().unwrap(), vec!["click"]);
+        assert_eq!(mcp.log.lock().unwrap().len(), 1);
+        assert_eq!(ctx.completed_steps, vec!["s_001"]);
+    }
+
     // ── should_gate_step tests ─────────────────────────────────────────────
 
     /// (a) explicit Some(true) always gates, regardless of annotations.
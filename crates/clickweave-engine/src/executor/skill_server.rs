@@ -0,0 +1,622 @@
+//! Confirmed skills served as MCP tools.
+//!
+//! [`SkillServer`] is a [`ToolServer`] publishing the skills of a
+//! [`SkillIndex`] that replay may run — the newest `Confirmed` or
+//! `Promoted` version of every skill id — each as a tool named after the
+//! id. The tool's `inputSchema` comes from the skill's `parameter_schema`
+//! and its `outputSchema` from its `outputs`. `tools/call` runs the
+//! action sketch through the skill runner against the downstream [`Mcp`]
+//! server and answers with the declared outputs as structured content.
+//! Skills drive the one desktop, so calls run one at a time.
+//!
+//! Steps the runner gates are settled by the server's
+//! [`PermissionPolicy`]: `Allow` runs the step, `Deny` fails the call,
+//! and `Ask` puts the step to the user through MCP elicitation, which
+//! counts as a refusal when the client cannot elicit.
+
+use crate::agent::permissions::{self, PermissionAction, PermissionPolicy};
+use crate::agent::skills::SkillIndex;
+use crate::agent::skills::replay::validate_parameters;
+use crate::agent::skills::types::{Skill, SkillState};
+use crate::executor::Mcp;
+use crate::executor::skill_runner::{SkillRunContext, StepApproval, StepApprover, run_skill_steps};
+use clickweave_mcp::{
+    ClientPeer, ElicitAction, ElicitRequestParams, JsonRpcError, ServerInfo, Tool, ToolCallResult,
+    ToolContent, ToolServer,
+};
+use parking_lot::RwLock;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Publishes a [`SkillIndex`] over MCP and runs called skills against
+/// `mcp`.
+pub struct SkillServer<M> {
+    index: Arc<RwLock<SkillIndex>>,
+    mcp: Arc<M>,
+    policy: PermissionPolicy,
+    /// Held for the length of a skill run.
+    run_lock: tokio::sync::Mutex<()>,
+}
+
+impl<M: Mcp + 'static> SkillServer<M> {
+    /// Serve `index` with the default policy: gated steps are asked
+    /// about unless their tool is read-only.
+    pub fn new(index: Arc<RwLock<SkillIndex>>, mcp: Arc<M>) -> Self {
+        Self {
+            index,
+            mcp,
+            policy: PermissionPolicy::default(),
+            run_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: PermissionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The skills on offer, newest eligible version per id, by id.
+    fn published(&self) -> Vec<Arc<Skill>> {
+        let index = self.index.read();
+        let mut newest: BTreeMap<String, Arc<Skill>> = BTreeMap::new();
+        for skill in [SkillState::Confirmed, SkillState::Promoted]
+            .into_iter()
+            .flat_map(|state| index.skills_in_state(state))
+        {
+            match newest.get(&skill.id) {
+                Some(kept) if kept.version >= skill.version => {}
+                _ => {
+                    newest.insert(skill.id.clone(), skill);
+                }
+            }
+        }
+        newest.into_values().collect()
+    }
+
+    async fn run(&self, skill: &Skill, params: Value, client: ClientPeer) -> ToolCallResult {
+        let _running = self.run_lock.lock().await;
+        info!(skill = %skill.id, version = skill.version, "running skill for MCP client");
+        self.index
+            .write()
+            .mark_invoked(&skill.id, skill.version, chrono::Utc::now());
+
+        let variables = match params {
+            Value::Object(map) => map.into_iter().collect(),
+            _ => Default::default(),
+        };
+        let approver = Arc::new(PolicyApprover {
+            policy: self.policy.clone(),
+            client,
+            skill: skill.name.clone(),
+        });
+        let mut ctx = SkillRunContext::new(&*self.mcp, variables).with_approver(approver);
+        let outcome = match run_skill_steps(&mut ctx, &skill.action_sketch).await {
+            Ok(()) => ctx.outputs(&skill.outputs),
+            Err(e) => Err(e),
+        };
+
+        match outcome {
+            Ok(outputs) => {
+                let text = if skill.outputs.is_empty() {
+                    format!(
+                        "Skill \"{}\" completed ({} steps).",
+                        skill.name,
+                        ctx.completed_steps.len()
+                    )
+                } else {
+                    outputs.to_string()
+                };
+                ToolCallResult {
+                    content: vec![ToolContent::Text { text }],
+                    is_error: None,
+                    structured_content: (!skill.outputs.is_empty()).then_some(outputs),
+                }
+            }
+            Err(e) => {
+                warn!(skill = %skill.id, "skill run for MCP client failed: {e}");
+                ToolCallResult {
+                    content: vec![ToolContent::Text {
+                        text: format!("Skill \"{}\" failed: {e}", skill.name),
+                    }],
+                    is_error: Some(true),
+                    structured_content: None,
+                }
+            }
+        }
+    }
+}
+
+impl<M: Mcp + 'static> ToolServer for SkillServer<M> {
+    fn info(&self) -> ServerInfo {
+        ServerInfo {
+            name: "clickweave-skills".to_string(),
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        }
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.published()
+            .iter()
+            .map(|skill| skill_tool(skill))
+            .collect()
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+        client: ClientPeer,
+    ) -> Result<ToolCallResult, JsonRpcError> {
+        let skill = self
+            .published()
+            .into_iter()
+            .find(|skill| skill.id == name)
+            .ok_or_else(|| JsonRpcError::invalid_params(format!("unknown tool: {name}")))?;
+        let params =
+            validate_parameters(&arguments.unwrap_or(Value::Null), &skill.parameter_schema)
+                .map_err(|e| JsonRpcError::invalid_params(e.to_string()))?;
+        Ok(self.run(&skill, params, client).await)
+    }
+}
+
+/// The MCP tool a skill is published as.
+pub fn skill_tool(skill: &Skill) -> Tool {
+    let mut properties = serde_json::Map::new();
+    let mut required = Vec::new();
+    for slot in &skill.parameter_schema {
+        let mut schema = type_schema(&slot.type_tag);
+        if let Some(description) = &slot.description {
+            schema.insert("description".into(), json!(description));
+        }
+        if let Some(values) = &slot.enum_values {
+            schema.insert("enum".into(), json!(values));
+        }
+        match &slot.default {
+            Some(default) => {
+                schema.insert("default".into(), default.clone());
+            }
+            None => required.push(slot.name.clone()),
+        }
+        properties.insert(slot.name.clone(), Value::Object(schema));
+    }
+    let input_schema = json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    });
+
+    let output_schema = (!skill.outputs.is_empty()).then(|| {
+        let properties: serde_json::Map<String, Value> = skill
+            .outputs
+            .iter()
+            .map(|output| {
+                (
+                    output.name.clone(),
+                    Value::Object(type_schema(&output.type_tag)),
+                )
+            })
+            .collect();
+        let required: Vec<&str> = skill.outputs.iter().map(|o| o.name.as_str()).collect();
+        json!({"type": "object", "properties": properties, "required": required})
+    });
+
+    Tool {
+        name: skill.id.clone(),
+        description: Some(skill.description.clone()),
+        input_schema,
+        output_schema,
+        annotations: Some(json!({"title": skill.name})),
+    }
+}
+
+/// `{"type": tag}` for the JSON Schema types; `{}` (anything) for type
+/// tags outside that vocabulary, which the parameter validator lets
+/// through too.
+fn type_schema(type_tag: &str) -> serde_json::Map<String, Value> {
+    let mut schema = serde_json::Map::new();
+    if matches!(
+        type_tag,
+        "string" | "number" | "integer" | "boolean" | "object" | "array"
+    ) {
+        schema.insert("type".into(), json!(type_tag));
+    }
+    schema
+}
+
+/// Settles gated steps by policy, eliciting the `Ask`s.
+struct PolicyApprover {
+    policy: PermissionPolicy,
+    client: ClientPeer,
+    /// Display name of the skill being run.
+    skill: String,
+}
+
+#[async_trait::async_trait]
+impl StepApprover for PolicyApprover {
+    async fn approve(&self, step: &StepApproval<'_>) -> bool {
+        match permissions::evaluate(&self.policy, step.tool, step.arguments, &step.annotations) {
+            PermissionAction::Allow => true,
+            PermissionAction::Deny => false,
+            PermissionAction::Ask => self.ask(step).await,
+        }
+    }
+}
+
+impl PolicyApprover {
+    async fn ask(&self, step: &StepApproval<'_>) -> bool {
+        if !self.client.supports_elicitation() {
+            warn!(
+                tool = step.tool,
+                "step needs approval but the MCP client cannot elicit; refusing"
+            );
+            return false;
+        }
+        let question = ElicitRequestParams {
+            message: format!(
+                "Skill \"{}\" wants to run `{}` with {}. Allow it?",
+                self.skill, step.tool, step.arguments
+            ),
+            requested_schema: json!({"type": "object", "properties": {}}),
+        };
+        match self.client.elicit(question).await {
+            Ok(answer) => answer.action == ElicitAction::Accept,
+            Err(e) => {
+                warn!(tool = step.tool, "approval elicitation failed: {e:#}");
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::episodic::HashedShingleEmbedder;
+    use crate::agent::permissions::{PermissionAction, PermissionRule};
+    use crate::agent::skills::types::{
+        ActionSketchStep, ApplicabilityHints, ApplicabilitySignature, BindingRef, CaptureClause,
+        CaptureSource, ExpectedWorldModelDelta, OutcomePredicate, OutputDeclaration, ParameterSlot,
+        SkillScope, SkillStats, SubgoalSignature,
+    };
+    use std::sync::Mutex;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, Lines};
+
+    /// Downstream server: `find_text` answers with a match as JSON text,
+    /// `click` (destructive) with `clicked`. Every call is logged.
+    #[derive(Default)]
+    struct Desktop(Mutex<Vec<(String, Value)>>);
+
+    impl Mcp for Desktop {
+        async fn call_tool(
+            &self,
+            name: &str,
+            arguments: Option<Value>,
+        ) -> anyhow::Result<ToolCallResult> {
+            self.0
+                .lock()
+                .unwrap()
+                .push((name.to_string(), arguments.unwrap_or(Value::Null)));
+            let text = match name {
+                "find_text" => json!({"matches": [{"uid": "a7g2"}]}).to_string(),
+                _ => "clicked".to_string(),
+            };
+            Ok(ToolCallResult {
+                content: vec![ToolContent::Text { text }],
+                is_error: None,
+                structured_content: None,
+            })
+        }
+
+        fn has_tool(&self, _: &str) -> bool {
+            true
+        }
+
+        fn tools_as_openai(&self) -> Vec<Value> {
+            vec![json!({
+                "type": "function",
+                "function": {"name": "click", "annotations": {"destructiveHint": true}}
+            })]
+        }
+
+        async fn refresh_server_tool_list(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn step(
+        step_id: &str,
+        tool: &str,
+        args: Value,
+        captures: Vec<CaptureClause>,
+    ) -> ActionSketchStep {
+        ActionSketchStep::ToolCall {
+            step_id: step_id.to_string(),
+            tool: tool.to_string(),
+            args,
+            captures_pre: Vec::new(),
+            captures,
+            expected_world_model_delta: ExpectedWorldModelDelta::default(),
+            requires_approval: None,
+        }
+    }
+
+    /// `click_label`: finds `label` (required) and clicks it, returning
+    /// the clicked uid as `uid`.
+    fn click_label(version: u32, state: SkillState) -> Skill {
+        let now = chrono::Utc::now();
+        Skill {
+            id: "skl_click_label".to_string(),
+            version,
+            state,
+            scope: SkillScope::ProjectLocal,
+            name: "Click label".to_string(),
+            description: format!("Click a labelled button (v{version})"),
+            tags: vec![],
+            subgoal_text: "click the button".to_string(),
+            subgoal_signature: SubgoalSignature("sg".to_string()),
+            applicability: ApplicabilityHints {
+                apps: vec![],
+                hosts: vec![],
+                signature: ApplicabilitySignature("app".to_string()),
+            },
+            parameter_schema: vec![
+                ParameterSlot {
+                    name: "label".to_string(),
+                    type_tag: "string".to_string(),
+                    description: Some("Button text".to_string()),
+                    default: None,
+                    enum_values: None,
+                },
+                ParameterSlot {
+                    name: "button".to_string(),
+                    type_tag: "string".to_string(),
+                    description: None,
+                    default: Some(json!("left")),
+                    enum_values: Some(vec!["left".to_string(), "right".to_string()]),
+                },
+            ],
+            action_sketch: vec![
+                step(
+                    "s_001",
+                    "find_text",
+                    json!({"text": "{{params.label}}"}),
+                    vec![CaptureClause {
+                        name: "target".to_string(),
+                        source: CaptureSource::ToolResult {
+                            jsonpath: "$.matches[0].uid".to_string(),
+                        },
+                    }],
+                ),
+                step(
+                    "s_002",
+                    "click",
+                    json!({"uid": "{{captured.target}}", "button": "{{params.button}}"}),
+                    vec![],
+                ),
+            ],
+            outputs: vec![OutputDeclaration {
+                name: "uid".to_string(),
+                type_tag: "string".to_string(),
+                from: BindingRef::Captured {
+                    name: "target".to_string(),
+                },
+            }],
+            outcome_predicate: OutcomePredicate::SubgoalCompleted {
+                post_state_world_model_signature: None,
+            },
+            provenance: vec![],
+            stats: SkillStats {
+                occurrence_count: 1,
+                success_rate: 1.0,
+                last_seen_at: Some(now),
+                last_invoked_at: None,
+            },
+            edited_by_user: false,
+            created_at: now,
+            updated_at: now,
+            produced_node_ids: vec![],
+            body: String::new(),
+            schema_version: crate::agent::skills::SKILL_SCHEMA_VERSION,
+            variables: vec![],
+            sections: vec![],
+            replay: None,
+        }
+    }
+
+    fn server(
+        skills: Vec<Skill>,
+        policy: PermissionPolicy,
+    ) -> (SkillServer<Desktop>, Arc<Desktop>) {
+        let mut index = SkillIndex::empty(Arc::new(HashedShingleEmbedder::default()));
+        for skill in skills {
+            index.upsert(skill);
+        }
+        let desktop = Arc::new(Desktop::default());
+        let server = SkillServer::new(Arc::new(RwLock::new(index)), Arc::clone(&desktop))
+            .with_policy(policy);
+        (server, desktop)
+    }
+
+    /// An MCP client talking to `server` over an in-memory stdio pipe.
+    struct Client {
+        input: DuplexStream,
+        output: Lines<BufReader<DuplexStream>>,
+        next_id: u64,
+    }
+
+    impl Client {
+        async fn connect(server: SkillServer<Desktop>, capabilities: Value) -> Self {
+            let (input, server_in) = tokio::io::duplex(64 * 1024);
+            let (server_out, output) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(clickweave_mcp::serve_stdio(
+                Arc::new(server),
+                server_in,
+                server_out,
+            ));
+            let mut client = Self {
+                input,
+                output: BufReader::new(output).lines(),
+                next_id: 0,
+            };
+            client
+                .request("initialize", json!({"capabilities": capabilities}))
+                .await;
+            client.recv().await;
+            client
+        }
+
+        async fn send(&mut self, message: Value) {
+            let line = format!("{message}\n");
+            self.input.write_all(line.as_bytes()).await.unwrap();
+        }
+
+        async fn request(&mut self, method: &str, params: Value) {
+            self.next_id += 1;
+            let id = self.next_id;
+            self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))
+                .await;
+        }
+
+        async fn recv(&mut self) -> Value {
+            let line = self.output.next_line().await.unwrap().unwrap();
+            serde_json::from_str(&line).unwrap()
+        }
+    }
+
+    fn allow_all() -> PermissionPolicy {
+        PermissionPolicy {
+            rules: vec![PermissionRule {
+                tool_pattern: "*".to_string(),
+                args_pattern: None,
+                action: PermissionAction::Allow,
+            }],
+            ..PermissionPolicy::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_the_newest_confirmed_version_with_its_schemas() {
+        let (server, _) = server(
+            vec![
+                click_label(1, SkillState::Confirmed),
+                click_label(2, SkillState::Promoted),
+                click_label(3, SkillState::Draft),
+            ],
+            PermissionPolicy::default(),
+        );
+        let tools = server.list_tools();
+        assert_eq!(tools.len(), 1);
+        let tool = &tools[0];
+        assert_eq!(tool.name, "skl_click_label");
+        assert_eq!(
+            tool.description.as_deref(),
+            Some("Click a labelled button (v2)")
+        );
+        assert_eq!(
+            tool.input_schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "label": {"type": "string", "description": "Button text"},
+                    "button": {"type": "string", "enum": ["left", "right"], "default": "left"}
+                },
+                "required": ["label"],
+                "additionalProperties": false
+            })
+        );
+        assert_eq!(
+            tool.output_schema,
+            Some(json!({
+                "type": "object",
+                "properties": {"uid": {"type": "string"}},
+                "required": ["uid"]
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn a_call_runs_the_skill_and_returns_its_outputs() {
+        let (server, desktop) = server(vec![click_label(1, SkillState::Confirmed)], allow_all());
+        let mut client = Client::connect(server, json!({})).await;
+
+        client
+            .request(
+                "tools/call",
+                json!({"name": "skl_click_label", "arguments": {"label": "Send"}}),
+            )
+            .await;
+        let done = client.recv().await;
+        assert_eq!(done["result"]["structuredContent"], json!({"uid": "a7g2"}));
+        assert_eq!(done["result"].get("isError"), None);
+        assert_eq!(
+            *desktop.0.lock().unwrap(),
+            vec![
+                ("find_text".to_string(), json!({"text": "Send"})),
+                (
+                    "click".to_string(),
+                    json!({"uid": "a7g2", "button": "left"})
+                ),
+            ]
+        );
+
+        client
+            .request(
+                "tools/call",
+                json!({"name": "skl_click_label", "arguments": {"colour": "red"}}),
+            )
+            .await;
+        let invalid = client.recv().await;
+        assert_eq!(invalid["error"]["code"], JsonRpcError::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn gated_steps_are_elicited_and_a_decline_fails_the_call() {
+        let (server, desktop) = server(
+            vec![click_label(1, SkillState::Confirmed)],
+            PermissionPolicy::default(),
+        );
+        let mut client = Client::connect(server, json!({"elicitation": {}})).await;
+
+        client
+            .request(
+                "tools/call",
+                json!({"name": "skl_click_label", "arguments": {"label": "Send"}}),
+            )
+            .await;
+        let ask = client.recv().await;
+        assert_eq!(ask["method"], "elicitation/create");
+        let message = ask["params"]["message"].as_str().unwrap();
+        assert!(message.contains("`click`"), "{message}");
+        client
+            .send(json!({"jsonrpc": "2.0", "id": ask["id"], "result": {"action": "decline"}}))
+            .await;
+
+        let done = client.recv().await;
+        assert_eq!(done["result"]["isError"], true);
+        let text = done["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("Approval denied"), "{text}");
+        assert_eq!(desktop.0.lock().unwrap().len(), 1, "click never ran");
+    }
+
+    #[tokio::test]
+    async fn without_elicitation_an_ask_is_a_refusal() {
+        let (server, desktop) = server(
+            vec![click_label(1, SkillState::Confirmed)],
+            PermissionPolicy::default(),
+        );
+        let mut client = Client::connect(server, json!({})).await;
+
+        client
+            .request(
+                "tools/call",
+                json!({"name": "skl_click_label", "arguments": {"label": "Send"}}),
+            )
+            .await;
+        let done = client.recv().await;
+        assert_eq!(done["result"]["isError"], true);
+        assert_eq!(desktop.0.lock().unwrap().len(), 1);
+    }
+}
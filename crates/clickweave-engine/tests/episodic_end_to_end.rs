@@ -36,6 +36,7 @@ fn empty_task_state(goal: &str) -> TaskState {
         hypotheses: vec![],
         phase: Phase::Recovering,
         milestones: vec![],
+        plan: vec![],
     }
 }
 
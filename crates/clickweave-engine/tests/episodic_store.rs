@@ -25,6 +25,7 @@ fn empty_task_state(goal: &str, phase: Phase) -> TaskState {
         hypotheses: vec![],
         phase,
         milestones: vec![],
+        plan: vec![],
     }
 }
 
@@ -41,6 +41,7 @@ impl Mcp for CountingMcp {
         Ok(ToolCallResult {
             content: vec![],
             is_error: Some(false),
+            structured_content: None,
         })
     }
 
@@ -42,6 +42,7 @@ impl Mcp for LoggingMcp {
         Ok(ToolCallResult {
             content: vec![],
             is_error: Some(false),
+            structured_content: None,
         })
     }
 
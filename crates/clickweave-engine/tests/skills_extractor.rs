@@ -33,6 +33,7 @@ fn step(tool: &str, args: serde_json::Value, result: &str) -> RecordedStep {
         tool_name: tool.into(),
         arguments: args,
         result_text: result.into(),
+        result_structured: None,
         world_model_pre: WorldModelSnapshot::from_world_model(&wm),
         world_model_post: WorldModelSnapshot::from_world_model(&wm),
     }
@@ -30,6 +30,7 @@ fn make_recorded_step(tool: &str, args: serde_json::Value) -> RecordedStep {
         tool_name: tool.into(),
         arguments: args,
         result_text: r#"{"ok":true}"#.into(),
+        result_structured: None,
         world_model_pre: WorldModelSnapshot::from_world_model(&wm),
         world_model_post: WorldModelSnapshot::from_world_model(&wm),
     }
@@ -38,6 +38,7 @@ pub(crate) async fn run_semantic_judge<J: ChatBackend>(
             &ChatOptions {
                 temperature: Some(0.0),
                 max_tokens: Some(2048),
+                ..ChatOptions::default()
             },
         )
         .await?;
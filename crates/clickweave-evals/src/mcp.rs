@@ -160,6 +160,7 @@ fn text_result(text: String, is_error: bool) -> ToolCallResult {
     ToolCallResult {
         content: vec![ToolContent::Text { text }],
         is_error: is_error.then_some(true),
+        structured_content: None,
     }
 }
 
@@ -121,4 +121,8 @@ impl<B: ChatBackend> ChatBackend for RecordingBackend<B> {
     async fn fetch_model_info(&self) -> Result<Option<clickweave_llm::ModelInfo>> {
         self.inner.fetch_model_info().await
     }
+
+    async fn count_tokens(&self, text: &str) -> Result<Option<usize>> {
+        self.inner.count_tokens(text).await
+    }
 }
@@ -35,6 +35,9 @@ where
         ..AgentConfig::default()
     };
     config.allow_focus_window = false;
+    // Nobody reads `event_rx` until the run ends, so `ask_user` must take
+    // the no-human fallback instead of waiting out its timeout.
+    config.ask_user_timeout = None;
 
     let run = run_agent_workflow_with_prompt_override(
         &recording_agent,
@@ -44,8 +47,11 @@ where
         Some(AgentChannels {
             event_tx,
             approval_tx,
+            suspend_signal: None,
+            cancel_signal: None,
         }),
         None,
+        None,
         Some(PermissionPolicy {
             allow_all: true,
             ..PermissionPolicy::default()
@@ -0,0 +1,664 @@
+//! Record/replay of LLM traffic.
+//!
+//! [`CassetteBackend`] wraps any [`ChatBackend`]. In record mode every
+//! call is appended to a JSONL cassette as it completes. In replay mode the
+//! cassette answers instead of the wrapped backend, so a misbehaving run can
+//! be reproduced without resampling the model.
+//!
+//! Requests are keyed by a BLAKE3 digest of their normalized form: the
+//! messages without `reasoning_content` (it is never sent), image data URLs
+//! reduced to digests, tools sorted by name, and the sampling options. The
+//! model name is not part of the key, so a cassette replays against any
+//! endpoint configuration.
+//!
+//! Model info and token counts are recorded too, as `kind`-tagged lines
+//! outside the call sequence, so a replay fits its context window to the
+//! same budget as the recorded run.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tracing::warn;
+
+use crate::client::{ChatBackend, ChatOptions, DeltaSink, ToolMode};
+use crate::types::{ChatResponse, Content, ContentPart, Message, ModelInfo};
+
+/// How replay pairs incoming requests with recorded ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayMatching {
+    /// The next recorded call must have the same key, or replay fails
+    /// with a [`CassetteDivergence`].
+    #[default]
+    Strict,
+    /// Serve the first unused call with the same key, else the next
+    /// unused call in recording order. Fails only when the cassette is
+    /// exhausted. Tolerates timestamps and other incidental drift.
+    Lenient,
+}
+
+/// One recorded call, one line of the cassette. Recording writes
+/// `CassetteEntry<&ChatResponse>`; replay reads it back owned.
+#[derive(Debug, Serialize, Deserialize)]
+struct CassetteEntry<R = ChatResponse> {
+    seq: usize,
+    key: String,
+    /// Key of each normalized message, so a divergence can name the
+    /// first message that differs.
+    message_keys: Vec<String>,
+    request: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response: Option<R>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// A context-window lookup, one `kind`-tagged line of the cassette.
+/// Failed lookups are recorded as `None`, which is how the runner treats
+/// them.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Lookup {
+    ModelInfo {
+        model_info: Option<ModelInfo>,
+    },
+    TokenCount {
+        /// Digest of the counted text.
+        key: String,
+        tokens: Option<usize>,
+    },
+}
+
+/// Replay received a request the cassette did not record.
+#[derive(Debug)]
+pub struct CassetteDivergence {
+    /// 0-based index of the call that diverged.
+    pub seq: usize,
+    pub detail: String,
+}
+
+impl fmt::Display for CassetteDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "LLM cassette diverged at call #{}: {}",
+            self.seq, self.detail
+        )
+    }
+}
+
+impl std::error::Error for CassetteDivergence {}
+
+enum Mode {
+    Passthrough,
+    Record {
+        path: PathBuf,
+        next_seq: usize,
+    },
+    Replay {
+        matching: ReplayMatching,
+        /// Unused entries in recording order.
+        remaining: VecDeque<CassetteEntry>,
+        next_seq: usize,
+        model_info: Option<Box<ModelInfo>>,
+        /// Recorded counts by text digest.
+        token_counts: HashMap<String, Option<usize>>,
+    },
+}
+
+/// [`ChatBackend`] wrapper that records to or replays from a cassette.
+pub struct CassetteBackend<B> {
+    inner: B,
+    mode: Mutex<Mode>,
+}
+
+impl<B> CassetteBackend<B> {
+    /// Forward every call to `inner` untouched.
+    pub fn passthrough(inner: B) -> Self {
+        Self {
+            inner,
+            mode: Mutex::new(Mode::Passthrough),
+        }
+    }
+
+    /// Forward every call to `inner` and append it to the cassette at
+    /// `path`. The file is created up front, so a backend that was never
+    /// called still leaves an (empty) cassette to replay.
+    pub fn record(inner: B, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to create LLM cassette {}", path.display()))?;
+        Ok(Self {
+            inner,
+            mode: Mutex::new(Mode::Record { path, next_seq: 0 }),
+        })
+    }
+
+    /// Answer calls from the cassette at `path`. `inner` is never called
+    /// for chat; it only supplies the model name and tool mode.
+    pub fn replay(inner: B, path: &Path, matching: ReplayMatching) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read LLM cassette {}", path.display()))?;
+        let mut remaining = VecDeque::new();
+        let mut model_info = None;
+        let mut token_counts = HashMap::new();
+        for (n, line) in text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+        {
+            let malformed = || {
+                format!(
+                    "Malformed LLM cassette line {} in {}",
+                    n + 1,
+                    path.display()
+                )
+            };
+            let value: Value = serde_json::from_str(line).with_context(malformed)?;
+            if value.get("kind").is_none() {
+                remaining.push_back(serde_json::from_value(value).with_context(malformed)?);
+                continue;
+            }
+            match serde_json::from_value(value).with_context(malformed)? {
+                Lookup::ModelInfo { model_info: info } => model_info = info.map(Box::new),
+                Lookup::TokenCount { key, tokens } => {
+                    token_counts.insert(key, tokens);
+                }
+            }
+        }
+        Ok(Self {
+            inner,
+            mode: Mutex::new(Mode::Replay {
+                matching,
+                remaining,
+                next_seq: 0,
+                model_info,
+                token_counts,
+            }),
+        })
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Serve `request` from the cassette, or `None` when not replaying.
+    fn replay_call(&self, request: &NormalizedRequest) -> Option<Result<ChatResponse>> {
+        let mut mode = self.mode.lock().unwrap();
+        let Mode::Replay {
+            matching,
+            remaining,
+            next_seq,
+            ..
+        } = &mut *mode
+        else {
+            return None;
+        };
+        let seq = *next_seq;
+        *next_seq += 1;
+
+        let position = match matching {
+            ReplayMatching::Strict => remaining
+                .front()
+                .is_some_and(|entry| entry.key == request.key)
+                .then_some(0),
+            ReplayMatching::Lenient => remaining
+                .iter()
+                .position(|entry| entry.key == request.key)
+                .or_else(|| {
+                    (!remaining.is_empty()).then(|| {
+                        warn!(
+                            seq,
+                            "LLM cassette: no recorded call matches; serving the next one"
+                        );
+                        0
+                    })
+                }),
+        };
+        let Some(entry) = position.and_then(|p| remaining.remove(p)) else {
+            let detail = match remaining.front() {
+                None => "the cassette has no more recorded calls".to_string(),
+                Some(expected) => describe_divergence(expected, request),
+            };
+            return Some(Err(CassetteDivergence { seq, detail }.into()));
+        };
+        Some(match (entry.response, entry.error) {
+            (Some(response), _) => Ok(response),
+            (None, error) => Err(anyhow!(
+                "{}",
+                error.unwrap_or_else(|| "recorded LLM call has no response".to_string())
+            )),
+        })
+    }
+
+    /// Append the outcome of a live call when recording.
+    fn record_call(&self, request: NormalizedRequest, result: &Result<ChatResponse>) {
+        let mut mode = self.mode.lock().unwrap();
+        let Mode::Record { path, next_seq } = &mut *mode else {
+            return;
+        };
+        let entry = CassetteEntry {
+            seq: *next_seq,
+            key: request.key,
+            message_keys: request.message_keys,
+            request: request.body,
+            response: result.as_ref().ok(),
+            error: result.as_ref().err().map(|e| format!("{e:#}")),
+        };
+        *next_seq += 1;
+        if let Err(e) = clickweave_core::storage::append_jsonl(path, &entry) {
+            warn!(path = %path.display(), error = %e, "Failed to append to LLM cassette");
+        }
+    }
+
+    /// Append a context-window lookup when recording.
+    fn record_lookup(&self, lookup: &Lookup) {
+        let mode = self.mode.lock().unwrap();
+        let Mode::Record { path, .. } = &*mode else {
+            return;
+        };
+        if let Err(e) = clickweave_core::storage::append_jsonl(path, lookup) {
+            warn!(path = %path.display(), error = %e, "Failed to append to LLM cassette");
+        }
+    }
+
+    /// The recorded model info, or `None` when not replaying.
+    fn replay_model_info(&self) -> Option<Option<ModelInfo>> {
+        match &*self.mode.lock().unwrap() {
+            Mode::Replay { model_info, .. } => Some(model_info.as_deref().cloned()),
+            _ => None,
+        }
+    }
+
+    /// The recorded count for the text with digest `key`, or `None` when
+    /// not replaying. Text the recording never counted has no count.
+    fn replay_token_count(&self, key: &str) -> Option<Option<usize>> {
+        match &*self.mode.lock().unwrap() {
+            Mode::Replay { token_counts, .. } => Some(token_counts.get(key).copied().flatten()),
+            _ => None,
+        }
+    }
+}
+
+impl<B: ChatBackend> ChatBackend for CassetteBackend<B> {
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    fn tool_mode(&self) -> ToolMode {
+        self.inner.tool_mode()
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+    ) -> Result<ChatResponse> {
+        let request = NormalizedRequest::new(messages, tools, options);
+        if let Some(replayed) = self.replay_call(&request) {
+            return replayed;
+        }
+        let result = self.inner.chat_with_options(messages, tools, options).await;
+        self.record_call(request, &result);
+        result
+    }
+
+    /// Replayed responses are returned whole, without deltas.
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+        on_delta: DeltaSink<'_>,
+    ) -> Result<ChatResponse> {
+        let request = NormalizedRequest::new(messages, tools, options);
+        if let Some(replayed) = self.replay_call(&request) {
+            return replayed;
+        }
+        let result = self
+            .inner
+            .chat_stream_with_options(messages, tools, options, on_delta)
+            .await;
+        self.record_call(request, &result);
+        result
+    }
+
+    /// A replay serves the recorded model info instead of contacting an
+    /// endpoint that may not exist any more.
+    fn fetch_model_info(&self) -> impl Future<Output = Result<Option<ModelInfo>>> + Send {
+        let replayed = self.replay_model_info();
+        async move {
+            if let Some(info) = replayed {
+                return Ok(info);
+            }
+            let result = self.inner.fetch_model_info().await;
+            self.record_lookup(&Lookup::ModelInfo {
+                model_info: result.as_ref().ok().cloned().flatten(),
+            });
+            result
+        }
+    }
+
+    fn count_tokens(&self, text: &str) -> impl Future<Output = Result<Option<usize>>> + Send {
+        let key = digest(&Value::String(text.to_string()));
+        let replayed = self.replay_token_count(&key);
+        async move {
+            if let Some(tokens) = replayed {
+                return Ok(tokens);
+            }
+            let result = self.inner.count_tokens(text).await;
+            self.record_lookup(&Lookup::TokenCount {
+                key,
+                tokens: result.as_ref().ok().copied().flatten(),
+            });
+            result
+        }
+    }
+}
+
+/// A request reduced to what determines the reply; shared with the
+/// [`ResponseCache`](crate::ResponseCache) key.
+pub(crate) struct NormalizedRequest {
+    pub(crate) key: String,
+    message_keys: Vec<String>,
+    pub(crate) body: Value,
+}
+
+impl NormalizedRequest {
+    pub(crate) fn new(
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+    ) -> Self {
+        let messages: Vec<Value> = messages.iter().map(normalize_message).collect();
+        let message_keys = messages.iter().map(digest).collect();
+        let mut tools = tools.map(<[Value]>::to_vec).unwrap_or_default();
+        tools.sort_by_cached_key(|tool| {
+            tool.pointer("/function/name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        });
+        let body = json!({
+            "messages": messages,
+            "tools": tools,
+            "temperature": options.temperature,
+            "max_tokens": options.max_tokens,
+            "response_format": options.response_format,
+        });
+        Self {
+            key: digest(&body),
+            message_keys,
+            body,
+        }
+    }
+}
+
+fn normalize_message(message: &Message) -> Value {
+    let mut message = Message {
+        reasoning_content: None,
+        ..message.clone()
+    };
+    if let Some(Content::Parts(parts)) = &mut message.content {
+        for part in parts {
+            if let ContentPart::ImageUrl { image_url } = part
+                && image_url.url.starts_with("data:")
+            {
+                image_url.url = format!("blake3:{}", blake3::hash(image_url.url.as_bytes()));
+            }
+        }
+    }
+    serde_json::to_value(message).unwrap_or(Value::Null)
+}
+
+fn digest(value: &Value) -> String {
+    blake3::hash(value.to_string().as_bytes()).to_hex()[..16].to_string()
+}
+
+fn describe_divergence(expected: &CassetteEntry, actual: &NormalizedRequest) -> String {
+    let first_diff = expected
+        .message_keys
+        .iter()
+        .zip(&actual.message_keys)
+        .position(|(a, b)| a != b);
+    let messages = match first_diff {
+        Some(index) => format!(
+            "message #{index} ({}) differs",
+            actual.body["messages"][index]["role"]
+                .as_str()
+                .unwrap_or("unknown")
+        ),
+        None if expected.message_keys.len() != actual.message_keys.len() => format!(
+            "recorded {} messages, got {}",
+            expected.message_keys.len(),
+            actual.message_keys.len()
+        ),
+        None => "messages match; tools or options differ".to_string(),
+    };
+    format!(
+        "expected request {} (recorded call #{}), got {}; {messages}",
+        expected.key, expected.seq, actual.key
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Choice;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Echoes the last message's text and counts calls. A message
+    /// containing "fail" produces an error instead.
+    #[derive(Default)]
+    struct EchoBackend {
+        calls: AtomicUsize,
+    }
+
+    impl ChatBackend for EchoBackend {
+        fn model_name(&self) -> &str {
+            "echo"
+        }
+
+        async fn chat_with_options(
+            &self,
+            messages: &[Message],
+            _tools: Option<&[Value]>,
+            _options: &ChatOptions,
+        ) -> Result<ChatResponse> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            let text = messages
+                .last()
+                .and_then(Message::content_text)
+                .unwrap_or_default();
+            if text.contains("fail") {
+                anyhow::bail!("endpoint returned 500");
+            }
+            Ok(ChatResponse {
+                id: format!("live-{n}"),
+                model: None,
+                choices: vec![Choice {
+                    index: 0,
+                    message: Message::assistant(format!("echo: {text}")),
+                    finish_reason: Some("stop".to_string()),
+                }],
+                usage: None,
+            })
+        }
+
+        async fn fetch_model_info(&self) -> Result<Option<ModelInfo>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(serde_json::from_value(json!({
+                "id": "echo",
+                "loaded_context_length": 8192
+            }))?))
+        }
+
+        /// One token per word.
+        async fn count_tokens(&self, text: &str) -> Result<Option<usize>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(text.split_whitespace().count()))
+        }
+    }
+
+    fn turn(text: &str) -> Vec<Message> {
+        vec![Message::system("You are a test."), Message::user(text)]
+    }
+
+    async fn ask(backend: &impl ChatBackend, text: &str) -> Result<String> {
+        let response = backend.chat(&turn(text), None).await?;
+        Ok(response.choices[0]
+            .message
+            .content_text()
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    async fn record(path: &Path, prompts: &[&str]) {
+        let recorder = CassetteBackend::record(EchoBackend::default(), path).unwrap();
+        for prompt in prompts {
+            let _ = ask(&recorder, prompt).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn strict_replay_serves_recorded_responses_without_calling_the_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassettes").join("agent.jsonl");
+        record(&path, &["open the menu", "please fail", "click save"]).await;
+
+        let replay =
+            CassetteBackend::replay(EchoBackend::default(), &path, ReplayMatching::Strict).unwrap();
+        assert_eq!(
+            ask(&replay, "open the menu").await.unwrap(),
+            "echo: open the menu"
+        );
+        let err = ask(&replay, "please fail").await.unwrap_err();
+        assert!(err.to_string().contains("endpoint returned 500"));
+        assert_eq!(
+            ask(&replay, "click save").await.unwrap(),
+            "echo: click save"
+        );
+        assert_eq!(replay.inner().calls.load(Ordering::SeqCst), 0);
+
+        let err = ask(&replay, "one more").await.unwrap_err();
+        assert!(err.to_string().contains("no more recorded calls"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn strict_replay_reports_the_first_differing_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent.jsonl");
+        record(&path, &["open the menu"]).await;
+
+        let replay =
+            CassetteBackend::replay(EchoBackend::default(), &path, ReplayMatching::Strict).unwrap();
+        let err = ask(&replay, "open the door").await.unwrap_err();
+        let divergence = err
+            .downcast_ref::<CassetteDivergence>()
+            .expect("typed divergence");
+        assert_eq!(divergence.seq, 0);
+        assert!(
+            divergence.detail.contains("message #1 (user) differs"),
+            "{divergence}"
+        );
+    }
+
+    #[tokio::test]
+    async fn lenient_replay_matches_out_of_order_and_tolerates_drift() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent.jsonl");
+        record(&path, &["first", "second", "third"]).await;
+
+        let replay =
+            CassetteBackend::replay(EchoBackend::default(), &path, ReplayMatching::Lenient)
+                .unwrap();
+        assert_eq!(ask(&replay, "second").await.unwrap(), "echo: second");
+        // No exact match: the next unused call in recording order.
+        assert_eq!(
+            ask(&replay, "first, at 10:02").await.unwrap(),
+            "echo: first"
+        );
+        assert_eq!(ask(&replay, "third").await.unwrap(), "echo: third");
+        assert!(ask(&replay, "fourth").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn replay_sizes_the_context_window_from_the_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent.jsonl");
+        let recorder = CassetteBackend::record(EchoBackend::default(), &path).unwrap();
+        recorder.fetch_model_info().await.unwrap();
+        recorder
+            .count_tokens("click the save button")
+            .await
+            .unwrap();
+        ask(&recorder, "open the menu").await.unwrap();
+
+        let replay =
+            CassetteBackend::replay(EchoBackend::default(), &path, ReplayMatching::Strict).unwrap();
+        let info = replay.fetch_model_info().await.unwrap().unwrap();
+        assert_eq!(info.effective_context_length(), Some(8192));
+        assert_eq!(
+            replay.count_tokens("click the save button").await.unwrap(),
+            Some(4)
+        );
+        assert_eq!(replay.count_tokens("never counted").await.unwrap(), None);
+        // Lookups sit outside the call sequence.
+        assert_eq!(
+            ask(&replay, "open the menu").await.unwrap(),
+            "echo: open the menu"
+        );
+        assert_eq!(replay.inner().calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn key_ignores_reasoning_and_digests_image_data() {
+        let options = ChatOptions::default();
+        let plain = Message::assistant("done");
+        let thinking = Message {
+            reasoning_content: Some("let me think".into()),
+            ..plain.clone()
+        };
+        assert_eq!(
+            NormalizedRequest::new(&[plain], None, &options).key,
+            NormalizedRequest::new(&[thinking], None, &options).key
+        );
+
+        let shot = |data: &str| {
+            Message::user_with_images("screen", vec![(data.to_string(), "image/png".into())])
+        };
+        let a = NormalizedRequest::new(&[shot("AAAA")], None, &options);
+        let b = NormalizedRequest::new(&[shot("BBBB")], None, &options);
+        assert_ne!(a.key, b.key);
+        assert!(
+            !a.body.to_string().contains("AAAA"),
+            "image data is not stored"
+        );
+    }
+
+    #[test]
+    fn key_is_independent_of_tool_order() {
+        let tool = |name: &str| json!({"type": "function", "function": {"name": name}});
+        let options = ChatOptions::default();
+        let messages = turn("hi");
+        assert_eq!(
+            NormalizedRequest::new(&messages, Some(&[tool("a"), tool("b")]), &options).key,
+            NormalizedRequest::new(&messages, Some(&[tool("b"), tool("a")]), &options).key
+        );
+    }
+}
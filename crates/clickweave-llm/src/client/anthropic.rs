@@ -0,0 +1,282 @@
+//! Anthropic Messages API (`POST /v1/messages`) wire format.
+//!
+//! The rest of the crate speaks OpenAI chat completions, so this module
+//! only translates at the edge: [`build_request`] turns the OpenAI-shaped
+//! transcript and tool list into a Messages request, and
+//! [`parse_response`] turns the reply back into a [`ChatResponse`].
+//!
+//! - System messages are lifted into the top-level `system` blocks. The
+//!   last block carries `cache_control`, so the tool list and the stable
+//!   system prompt form a cached prefix.
+//! - Assistant `tool_calls` become `tool_use` blocks. Tool results become
+//!   `tool_result` blocks in a user turn.
+//! - `ContentPart::ImageUrl` data URLs become base64 `image` blocks.
+//! - Consecutive same-role turns are merged, because the API requires
+//!   user and assistant turns to alternate.
+
+use super::*;
+
+use serde::Deserialize;
+use serde_json::{Map, json};
+
+/// Sent as `anthropic-version` on every request.
+pub(super) const API_VERSION: &str = "2023-06-01";
+
+/// `max_tokens` is mandatory on the Messages API; used when neither the
+/// call nor the config sets one.
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// `extra_body` keys that only mean something to OpenAI-compatible local
+/// servers and would be rejected here.
+const OPENAI_ONLY_EXTRA_KEYS: &[&str] = &["chat_template_kwargs"];
+
+/// Build the Messages API request body.
+pub(super) fn build_request(
+    model: &str,
+    messages: &[Message],
+    tools: Option<&[Value]>,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    extra_body: &Map<String, Value>,
+) -> Value {
+    let mut system: Vec<Value> = messages
+        .iter()
+        .filter(|m| m.role == Role::System)
+        .filter_map(|m| m.content_text())
+        .filter(|text| !text.is_empty())
+        .map(|text| json!({"type": "text", "text": text}))
+        .collect();
+    if let Some(last) = system.last_mut() {
+        last["cache_control"] = json!({"type": "ephemeral"});
+    }
+
+    let mut turns: Vec<(&'static str, Vec<Value>)> = Vec::new();
+    for message in messages.iter().filter(|m| m.role != Role::System) {
+        let (role, blocks) = match message.role {
+            Role::Assistant => ("assistant", assistant_blocks(message)),
+            Role::Tool => ("user", vec![tool_result_block(message)]),
+            _ => ("user", content_blocks(message.content.as_ref())),
+        };
+        if blocks.is_empty() {
+            continue;
+        }
+        match turns.last_mut() {
+            Some((last_role, last_blocks)) if *last_role == role => last_blocks.extend(blocks),
+            _ => turns.push((role, blocks)),
+        }
+    }
+
+    let mut body = Map::new();
+    body.insert("model".into(), json!(model));
+    body.insert(
+        "max_tokens".into(),
+        json!(max_tokens.unwrap_or(DEFAULT_MAX_TOKENS)),
+    );
+    if !system.is_empty() {
+        body.insert("system".into(), Value::Array(system));
+    }
+    body.insert(
+        "messages".into(),
+        Value::Array(
+            turns
+                .into_iter()
+                .map(|(role, content)| json!({"role": role, "content": content}))
+                .collect(),
+        ),
+    );
+    if let Some(tools) = tools.filter(|t| !t.is_empty()) {
+        body.insert(
+            "tools".into(),
+            Value::Array(tools.iter().filter_map(tool_definition).collect()),
+        );
+    }
+    if let Some(temperature) = temperature {
+        body.insert("temperature".into(), json!(temperature));
+    }
+    for (key, value) in extra_body {
+        if !OPENAI_ONLY_EXTRA_KEYS.contains(&key.as_str()) {
+            body.insert(key.clone(), value.clone());
+        }
+    }
+    Value::Object(body)
+}
+
+fn content_blocks(content: Option<&Content>) -> Vec<Value> {
+    match content {
+        Some(Content::Text(text)) if !text.is_empty() => {
+            vec![json!({"type": "text", "text": text})]
+        }
+        Some(Content::Parts(parts)) => parts
+            .iter()
+            .map(|part| match part {
+                ContentPart::Text { text } => json!({"type": "text", "text": text}),
+                ContentPart::ImageUrl { image_url } => image_block(&image_url.url),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `data:<mime>;base64,<data>` becomes an inline base64 source; any
+/// other URL is passed by reference.
+fn image_block(url: &str) -> Value {
+    let inline = url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"));
+    match inline {
+        Some((media_type, data)) => json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": media_type, "data": data},
+        }),
+        None => json!({"type": "image", "source": {"type": "url", "url": url}}),
+    }
+}
+
+fn assistant_blocks(message: &Message) -> Vec<Value> {
+    let mut blocks = content_blocks(message.content.as_ref());
+    for call in message.tool_calls.iter().flatten() {
+        let input = match &call.function.arguments {
+            Value::Object(_) => call.function.arguments.clone(),
+            Value::String(raw) => serde_json::from_str(raw).unwrap_or_else(|_| json!({})),
+            _ => json!({}),
+        };
+        blocks.push(json!({
+            "type": "tool_use",
+            "id": call.id,
+            "name": call.function.name,
+            "input": input,
+        }));
+    }
+    blocks
+}
+
+fn tool_result_block(message: &Message) -> Value {
+    json!({
+        "type": "tool_result",
+        "tool_use_id": message.tool_call_id.as_deref().unwrap_or_default(),
+        "content": content_blocks(message.content.as_ref()),
+    })
+}
+
+/// OpenAI `{"type": "function", "function": {...}}` → Messages tool.
+fn tool_definition(tool: &Value) -> Option<Value> {
+    let function = tool.get("function")?;
+    let mut out = Map::new();
+    out.insert("name".into(), function.get("name")?.clone());
+    if let Some(description) = function.get("description") {
+        out.insert("description".into(), description.clone());
+    }
+    out.insert(
+        "input_schema".into(),
+        function
+            .get("parameters")
+            .cloned()
+            .unwrap_or_else(|| json!({"type": "object", "properties": {}})),
+    );
+    Some(Value::Object(out))
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    id: String,
+    #[serde(default)]
+    model: Option<String>,
+    content: Vec<ResponseBlock>,
+    stop_reason: Option<String>,
+    usage: Option<MessagesUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ResponseBlock {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    Thinking {
+        thinking: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesUsage {
+    input_tokens: u32,
+    output_tokens: u32,
+    #[serde(default)]
+    cache_creation_input_tokens: u32,
+    #[serde(default)]
+    cache_read_input_tokens: u32,
+}
+
+/// Translate a Messages API response body into a [`ChatResponse`].
+pub(super) fn parse_response(body: &str) -> Result<ChatResponse> {
+    let response: MessagesResponse = serde_json::from_str(body).map_err(|e| {
+        error!(
+            error = %e,
+            body = %&body[..body.len().min(500)],
+            "Failed to parse Anthropic response"
+        );
+        anyhow::Error::new(e).context("Failed to parse Anthropic response")
+    })?;
+
+    let mut text = String::new();
+    let mut reasoning = String::new();
+    let mut tool_calls = Vec::new();
+    for block in response.content {
+        match block {
+            ResponseBlock::Text { text: t } => text.push_str(&t),
+            ResponseBlock::Thinking { thinking } => reasoning.push_str(&thinking),
+            ResponseBlock::ToolUse { id, name, input } => tool_calls.push(ToolCall {
+                id,
+                call_type: CallType::Function,
+                function: FunctionCall {
+                    name,
+                    arguments: input,
+                },
+            }),
+            ResponseBlock::Other => {}
+        }
+    }
+    let finish_reason = response.stop_reason.map(|reason| {
+        match reason.as_str() {
+            "end_turn" | "stop_sequence" => "stop",
+            "tool_use" => "tool_calls",
+            "max_tokens" => "length",
+            other => other,
+        }
+        .to_string()
+    });
+    // Cached prompt tokens still occupy the context window, so they count
+    // towards `prompt_tokens` like they do on OpenAI-style endpoints.
+    let usage = response.usage.map(|u| {
+        let prompt_tokens =
+            u.input_tokens + u.cache_creation_input_tokens + u.cache_read_input_tokens;
+        Usage {
+            prompt_tokens,
+            completion_tokens: u.output_tokens,
+            total_tokens: prompt_tokens + u.output_tokens,
+        }
+    });
+
+    Ok(ChatResponse {
+        id: response.id,
+        model: response.model,
+        choices: vec![Choice {
+            index: 0,
+            message: Message {
+                content: (!text.is_empty()).then_some(Content::Text(text)),
+                reasoning_content: (!reasoning.is_empty()).then_some(reasoning),
+                tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+                ..Message::assistant("")
+            },
+            finish_reason,
+        }],
+        usage,
+    })
+}
@@ -6,15 +6,34 @@ use super::*;
 pub struct ChatOptions {
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
+    /// OpenAI `response_format` object, e.g. from
+    /// [`with_json_schema`](Self::with_json_schema).
+    pub response_format: Option<Value>,
+    /// Ask a [`RoutingBackend`](crate::RoutingBackend) to start at its
+    /// escalation route instead of the primary. Other backends ignore it.
+    pub escalate: bool,
 }
 
 impl ChatOptions {
     pub fn with_temperature(temperature: f32) -> Self {
         Self {
             temperature: Some(temperature),
-            max_tokens: None,
+            ..Self::default()
         }
     }
+
+    /// Constrain the reply to JSON matching `schema` (chainable).
+    ///
+    /// `strict` is left unset: servers that compile the schema into a
+    /// grammar (vLLM, llama.cpp, LM Studio) enforce it regardless, and
+    /// OpenAI's strict mode rejects most MCP tool parameter schemas.
+    pub fn with_json_schema(mut self, name: &str, schema: Value) -> Self {
+        self.response_format = Some(serde_json::json!({
+            "type": "json_schema",
+            "json_schema": { "name": name, "schema": schema },
+        }));
+        self
+    }
 }
 
 /// Seam for LLM interaction, allowing mock backends in tests.
@@ -40,11 +59,40 @@ pub trait ChatBackend: Send + Sync {
         }
     }
 
+    /// Like [`chat_with_options`](Self::chat_with_options), but report
+    /// text and reasoning fragments to `on_delta` as they arrive. The
+    /// returned response is the same fully-assembled `ChatResponse`.
+    /// Backends that cannot stream keep this default, which makes one
+    /// ordinary call and reports no deltas.
+    fn chat_stream_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+        on_delta: DeltaSink<'_>,
+    ) -> impl Future<Output = Result<ChatResponse>> + Send {
+        let _ = on_delta;
+        self.chat_with_options(messages, tools, options)
+    }
+
     fn model_name(&self) -> &str;
 
+    /// How this endpoint should be asked for structured actions. Mocks
+    /// and plain backends use native tool calling.
+    fn tool_mode(&self) -> ToolMode {
+        ToolMode::NativeTools
+    }
+
     /// Query the provider for model metadata (context length, etc.).
     /// Returns None by default (e.g. for mock backends).
     fn fetch_model_info(&self) -> impl Future<Output = Result<Option<ModelInfo>>> + Send {
         async { Ok(None) }
     }
+
+    /// Count `text` with the model's own tokenizer, when the provider
+    /// exposes one. Returns None by default, leaving callers to estimate.
+    fn count_tokens(&self, text: &str) -> impl Future<Output = Result<Option<usize>>> + Send {
+        let _ = text;
+        async { Ok(None) }
+    }
 }
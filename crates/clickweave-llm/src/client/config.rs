@@ -1,5 +1,34 @@
+/// Which wire protocol an endpoint speaks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum LlmProvider {
+    /// `/chat/completions` — LM Studio, vLLM, llama.cpp, OpenRouter, OpenAI.
+    #[default]
+    OpenAiCompatible,
+    /// Anthropic Messages API (`/messages`). `base_url` is the API root,
+    /// e.g. `https://api.anthropic.com/v1`.
+    Anthropic,
+}
+
+/// How an agent asks the endpoint for its next action.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum ToolMode {
+    /// OpenAI `tools` / `tool_calls`.
+    #[default]
+    NativeTools,
+    /// No `tools`; the reply is a JSON document constrained by a
+    /// `response_format: {type: "json_schema"}` the caller supplies.
+    /// For servers whose tool-call parsing is unreliable for the model.
+    JsonSchema,
+}
+
 #[derive(Debug, Clone)]
 pub struct LlmConfig {
+    /// Wire protocol of `base_url`.
+    pub provider: LlmProvider,
     pub base_url: String,
     pub api_key: Option<String>,
     pub model: String,
@@ -12,6 +41,10 @@ pub struct LlmConfig {
     /// reasoning mode ON, which would silently add ~15× latency for any caller
     /// that constructs `LlmConfig::default()` without an explicit `.with_thinking()` chain.
     pub extra_body: serde_json::Map<String, serde_json::Value>,
+    /// Retry and circuit-breaker behaviour for chat completions.
+    pub retry: super::RetryPolicy,
+    /// Native tool calling or schema-constrained JSON for agent turns.
+    pub tool_mode: ToolMode,
 }
 
 impl LlmConfig {
@@ -21,6 +54,24 @@ impl LlmConfig {
         self
     }
 
+    /// Select the endpoint's wire protocol (chainable).
+    pub fn with_provider(mut self, provider: LlmProvider) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    /// Choose native tool calling or schema-constrained JSON (chainable).
+    pub fn with_tool_mode(mut self, tool_mode: ToolMode) -> Self {
+        self.tool_mode = tool_mode;
+        self
+    }
+
+    /// Replace the retry policy (chainable).
+    pub fn with_retry(mut self, retry: super::RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Enable or disable model thinking/reasoning via `chat_template_kwargs` (chainable).
     /// Always sends an explicit `{"chat_template_kwargs": {"enable_thinking": <bool>}}`
     /// so the server/template default cannot silently override the caller's intent.
@@ -36,6 +87,7 @@ impl LlmConfig {
 impl Default for LlmConfig {
     fn default() -> Self {
         let base = Self {
+            provider: LlmProvider::OpenAiCompatible,
             // LM Studio default
             base_url: "http://localhost:1234/v1".to_string(),
             api_key: None,
@@ -43,6 +95,8 @@ impl Default for LlmConfig {
             temperature: Some(0.7),
             max_tokens: Some(4096),
             extra_body: serde_json::Map::new(),
+            retry: super::RetryPolicy::default(),
+            tool_mode: ToolMode::NativeTools,
         };
         // Explicit `enable_thinking: false` so the server template default (which is ON
         // for Gemma 4 / Qwen 3) cannot silently add latency to callers that forget to
@@ -0,0 +1,113 @@
+use super::*;
+use serde::Deserialize;
+
+/// Error for a request the endpoint refused because the prompt does not
+/// fit the model's context window. Callers can shrink the transcript and
+/// try again; see [`is_context_overflow`].
+#[derive(Debug)]
+pub struct ContextOverflow(pub String);
+
+impl std::fmt::Display for ContextOverflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ContextOverflow {}
+
+/// Whether `error` (or anything it wraps) is a [`ContextOverflow`].
+pub fn is_context_overflow(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| cause.is::<ContextOverflow>())
+}
+
+/// Servers word length rejections differently: OpenAI and vLLM mention
+/// the "maximum context length", llama.cpp the "context size", LM Studio
+/// "context length", Anthropic "prompt is too long". A 500 is otherwise a
+/// retryable server fault, so it only counts with a message that says the
+/// prompt exceeded the limit, not one that merely mentions the context.
+pub(super) fn looks_like_overflow(status: reqwest::StatusCode, body: &str) -> bool {
+    const MARKERS: [&str; 5] = [
+        "context length",
+        "context_length",
+        "context size",
+        "context window",
+        "maximum context",
+    ];
+    const EXCEEDED_MARKERS: [&str; 4] = [
+        "maximum context length",
+        "prompt is too long",
+        "too many tokens",
+        "exceeds the available context",
+    ];
+    let body = body.to_ascii_lowercase();
+    let exceeded = EXCEEDED_MARKERS.iter().any(|marker| body.contains(marker));
+    match status.as_u16() {
+        400 | 413 | 422 => exceeded || MARKERS.iter().any(|marker| body.contains(marker)),
+        500 => exceeded,
+        _ => false,
+    }
+}
+
+/// Reply of a `/tokenize` endpoint: vLLM reports `count`, llama.cpp only
+/// the `tokens` themselves.
+#[derive(Deserialize)]
+struct TokenizeResponse {
+    count: Option<usize>,
+    tokens: Option<Vec<Value>>,
+}
+
+impl LlmClient {
+    /// Count `text` with the server's own tokenizer via `POST /tokenize`
+    /// on the server origin. A server that answers without the route
+    /// (404, 405, 501) or with a reply that is not a count is not asked
+    /// again, so servers without one cost a single request. Other
+    /// failures may be transient and only skip this count.
+    pub(super) async fn tokenize(&self, text: &str) -> Result<Option<usize>> {
+        if self.config.provider == LlmProvider::Anthropic
+            || self.tokenize_unsupported.load(Ordering::Relaxed)
+        {
+            return Ok(None);
+        }
+        let base = self.config.base_url.trim_end_matches('/');
+        let url = format!("{}/tokenize", base.strip_suffix("/v1").unwrap_or(base));
+        let body = serde_json::json!({
+            "model": self.config.model,
+            "prompt": text,
+            "content": text,
+        });
+        let mut req = self.http.post(&url).json(&body);
+        if let Some(api_key) = &self.config.api_key {
+            req = req.bearer_auth(api_key);
+        }
+
+        let (count, unsupported) = match req.send().await {
+            Ok(response) if response.status().is_success() => {
+                let count = response
+                    .json::<TokenizeResponse>()
+                    .await
+                    .ok()
+                    .and_then(|r| r.count.or(r.tokens.map(|tokens| tokens.len())));
+                (count, count.is_none())
+            }
+            Ok(response) => {
+                let status = response.status();
+                debug!(url = %url, status = %status, "Tokenize endpoint returned error");
+                let unsupported = matches!(
+                    status,
+                    reqwest::StatusCode::NOT_FOUND
+                        | reqwest::StatusCode::METHOD_NOT_ALLOWED
+                        | reqwest::StatusCode::NOT_IMPLEMENTED
+                );
+                (None, unsupported)
+            }
+            Err(e) => {
+                debug!(url = %url, error = %e, "Tokenize endpoint failed");
+                (None, false)
+            }
+        };
+        if unsupported {
+            self.tokenize_unsupported.store(true, Ordering::Relaxed);
+        }
+        Ok(count)
+    }
+}
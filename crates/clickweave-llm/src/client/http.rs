@@ -1,4 +1,9 @@
+use super::anthropic;
+use super::context;
+use super::retry::{self, AttemptError};
+use super::stream;
 use super::*;
+use serde::Serialize;
 
 impl LlmClient {
     pub fn new(config: LlmConfig) -> Self {
@@ -11,9 +16,17 @@ impl LlmClient {
             config,
             http,
             context_length: AtomicU64::new(0),
+            tokenize_unsupported: AtomicBool::new(false),
+            retry_observer: None,
         }
     }
 
+    /// Report retries and circuit trips to `observer` (chainable).
+    pub fn with_retry_observer(mut self, observer: RetryObserver) -> Self {
+        self.retry_observer = Some(observer);
+        self
+    }
+
     pub fn config(&self) -> &LlmConfig {
         &self.config
     }
@@ -23,10 +36,11 @@ impl LlmClient {
     }
 
     fn chat_url(&self) -> String {
-        format!(
-            "{}/chat/completions",
-            self.config.base_url.trim_end_matches('/')
-        )
+        let path = match self.config.provider {
+            LlmProvider::OpenAiCompatible => "chat/completions",
+            LlmProvider::Anthropic => "messages",
+        };
+        format!("{}/{}", self.config.base_url.trim_end_matches('/'), path)
     }
 
     fn sanitize_request_messages(messages: &[Message]) -> Vec<Message> {
@@ -54,7 +68,7 @@ impl LlmClient {
         &'a self,
         messages: &'a [Message],
         tools: Option<&'a [Value]>,
-        options: &ChatOptions,
+        options: &'a ChatOptions,
     ) -> ChatRequest<'a> {
         ChatRequest {
             model: &self.config.model,
@@ -62,17 +76,169 @@ impl LlmClient {
             tools,
             temperature: options.temperature.or(self.config.temperature),
             max_tokens: options.max_tokens.or(self.config.max_tokens),
+            stream: false,
+            stream_options: None,
+            response_format: options.response_format.as_ref(),
             extra_body: &self.config.extra_body,
         }
     }
 
-    async fn send_chat_request(&self, url: &str, request: &ChatRequest<'_>) -> Result<String> {
-        debug!(
-            url = %url,
-            message_count = request.messages.len(),
-            model = %request.model,
-            "LLM request"
-        );
+    /// POST `request` to `url` and return the raw response body.
+    async fn send_chat_request(&self, url: &str, request: &impl Serialize) -> Result<String> {
+        self.with_retry(url, || async {
+            let response = self.post_chat(url, request).await?;
+            match response.text().await {
+                Ok(t) => Ok(t),
+                Err(e) => {
+                    error!(url = %url, error = %e, "Failed to read LLM response body");
+                    Err(AttemptError {
+                        retryable: retry::is_retryable_transport(&e),
+                        error: anyhow::Error::new(e).context("Failed to read LLM response body"),
+                        retry_after: None,
+                    })
+                }
+            }
+        })
+        .await
+    }
+
+    /// POST a `stream: true` request to `url` and assemble the SSE body,
+    /// reporting deltas as they arrive. A failure after the first delta
+    /// was reported is not retried, so the caller never sees a fragment
+    /// twice.
+    async fn stream_chat_request(
+        &self,
+        url: &str,
+        request: &ChatRequest<'_>,
+        on_delta: DeltaSink<'_>,
+    ) -> Result<ChatResponse> {
+        self.with_retry(url, || async {
+            let mut response = self.post_chat(url, request).await?;
+            let emitted = std::sync::atomic::AtomicBool::new(false);
+            let tracking = |delta: StreamDelta| {
+                emitted.store(true, Ordering::Relaxed);
+                on_delta(delta);
+            };
+            let mut assembler = stream::StreamAssembler::default();
+            let result = async {
+                loop {
+                    match response.chunk().await {
+                        Ok(Some(bytes)) => {
+                            if assembler
+                                .feed(&bytes, &tracking)
+                                .map_err(AttemptError::fatal)?
+                            {
+                                break;
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            error!(url = %url, error = %e, "LLM stream interrupted");
+                            return Err(AttemptError {
+                                retryable: retry::is_retryable_transport(&e),
+                                error: anyhow::Error::new(e).context("LLM stream interrupted"),
+                                retry_after: None,
+                            });
+                        }
+                    }
+                }
+                assembler.finish(&tracking).map_err(AttemptError::fatal)
+            }
+            .await;
+            result.map_err(|mut failure| {
+                failure.retryable &= !emitted.load(Ordering::Relaxed);
+                failure
+            })
+        })
+        .await
+    }
+
+    /// Run `attempt` until it succeeds, fails permanently, or the retry
+    /// budget in `config.retry` is spent, honouring the endpoint's
+    /// circuit breaker.
+    async fn with_retry<T, F, Fut>(&self, url: &str, mut attempt_fn: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = std::result::Result<T, AttemptError>>,
+    {
+        let policy = &self.config.retry;
+        let mut attempt = 0u32;
+        loop {
+            if let Some(remaining) = retry::circuit_open_for(url) {
+                warn!(url = %url, retry_in_ms = remaining.as_millis() as u64, "LLM circuit open; failing fast");
+                anyhow::bail!(
+                    "LLM endpoint {} is unavailable (circuit open, retry in {}s)",
+                    url,
+                    remaining.as_secs().max(1)
+                );
+            }
+
+            let failure = match attempt_fn().await {
+                Ok(value) => {
+                    retry::record_success(url);
+                    return Ok(value);
+                }
+                Err(failure) => failure,
+            };
+            if !failure.retryable {
+                return Err(failure.error);
+            }
+            if retry::record_failure(url, policy) {
+                self.notify_retry(&RetryNotice::CircuitOpened {
+                    endpoint: url.to_string(),
+                    cooldown: policy.breaker_cooldown,
+                });
+                return Err(failure.error);
+            }
+            if attempt >= policy.max_retries {
+                return Err(failure.error);
+            }
+            if let Some(wait) = failure.retry_after
+                && wait > policy.max_retry_after
+            {
+                warn!(
+                    url = %url,
+                    retry_after_ms = wait.as_millis() as u64,
+                    "LLM Retry-After exceeds the configured maximum; not retrying"
+                );
+                return Err(failure.error);
+            }
+
+            attempt += 1;
+            let delay =
+                retry::backoff_delay(policy, attempt).max(failure.retry_after.unwrap_or_default());
+            let reason = format!("{:#}", failure.error);
+            warn!(
+                url = %url,
+                attempt,
+                max_retries = policy.max_retries,
+                delay_ms = delay.as_millis() as u64,
+                reason = %reason,
+                "LLM request failed; retrying"
+            );
+            self.notify_retry(&RetryNotice::Retrying {
+                endpoint: url.to_string(),
+                attempt,
+                max_retries: policy.max_retries,
+                delay,
+                reason,
+            });
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn notify_retry(&self, notice: &RetryNotice) {
+        if let Some(observer) = &self.retry_observer {
+            observer(notice);
+        }
+    }
+
+    /// Send one request and classify a failed send or non-2xx status.
+    async fn post_chat(
+        &self,
+        url: &str,
+        request: &impl Serialize,
+    ) -> std::result::Result<reqwest::Response, AttemptError> {
         trace!(
             request_body = %serde_json::to_string(request)
                 .unwrap_or_else(|e| format!("<serialization failed: {e}>")),
@@ -81,33 +247,56 @@ impl LlmClient {
 
         let mut req_builder = self.http.post(url).json(request);
 
-        if let Some(api_key) = &self.config.api_key {
-            req_builder = req_builder.bearer_auth(api_key);
+        match (self.config.provider, &self.config.api_key) {
+            (LlmProvider::Anthropic, api_key) => {
+                req_builder = req_builder.header("anthropic-version", anthropic::API_VERSION);
+                if let Some(api_key) = api_key {
+                    req_builder = req_builder.header("x-api-key", api_key);
+                }
+            }
+            (LlmProvider::OpenAiCompatible, Some(api_key)) => {
+                req_builder = req_builder.bearer_auth(api_key);
+            }
+            (LlmProvider::OpenAiCompatible, None) => {}
         }
 
         let response = match req_builder.send().await {
             Ok(r) => r,
             Err(e) => {
                 error!(url = %url, error = %e, "LLM request failed to send");
-                return Err(e).context("Failed to send request to LLM");
+                return Err(AttemptError {
+                    retryable: retry::is_retryable_transport(&e),
+                    error: anyhow::Error::new(e).context("Failed to send request to LLM"),
+                    retry_after: None,
+                });
             }
         };
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(retry::parse_retry_after);
             let error_text = response.text().await.unwrap_or_default();
             error!(url = %url, status = %status, body = %error_text, "LLM returned error");
             let user_msg = llm_error_message(&error_text, status);
-            anyhow::bail!("{}", user_msg);
-        }
-
-        match response.text().await {
-            Ok(t) => Ok(t),
-            Err(e) => {
-                error!(url = %url, error = %e, "Failed to read LLM response body");
-                Err(e).context("Failed to read LLM response body")
-            }
+            // An overflow fails the same way every time; the caller
+            // shrinks the prompt instead.
+            let overflow = context::looks_like_overflow(status, &error_text);
+            let error = if overflow {
+                anyhow::Error::new(context::ContextOverflow(user_msg))
+            } else {
+                anyhow!("{}", user_msg)
+            };
+            return Err(AttemptError {
+                error,
+                retryable: !overflow && retry::is_retryable_status(status),
+                retry_after,
+            });
         }
+        Ok(response)
     }
 
     fn parse_chat_response(response_text: &str) -> Result<ChatResponse> {
@@ -192,6 +381,15 @@ impl ChatBackend for LlmClient {
         &self.config.model
     }
 
+    fn tool_mode(&self) -> ToolMode {
+        // The Messages API has no `response_format`; its tool use is
+        // reliable enough that there is no reason to avoid it.
+        match self.config.provider {
+            LlmProvider::Anthropic => ToolMode::NativeTools,
+            LlmProvider::OpenAiCompatible => self.config.tool_mode,
+        }
+    }
+
     async fn chat_with_options(
         &self,
         messages: &[Message],
@@ -201,8 +399,67 @@ impl ChatBackend for LlmClient {
         let url = self.chat_url();
         let sanitized = Self::sanitize_request_messages(messages);
         let request = self.build_chat_request(&sanitized, tools, options);
-        let response_text = self.send_chat_request(&url, &request).await?;
-        let chat_response = Self::parse_chat_response(&response_text)?;
+        debug!(
+            url = %url,
+            message_count = request.messages.len(),
+            model = %request.model,
+            provider = ?self.config.provider,
+            "LLM request"
+        );
+        let chat_response = match self.config.provider {
+            LlmProvider::OpenAiCompatible => {
+                let response_text = self.send_chat_request(&url, &request).await?;
+                Self::parse_chat_response(&response_text)?
+            }
+            LlmProvider::Anthropic => {
+                let body = anthropic::build_request(
+                    request.model,
+                    request.messages,
+                    request.tools,
+                    request.temperature,
+                    request.max_tokens,
+                    request.extra_body,
+                );
+                let response_text = self.send_chat_request(&url, &body).await?;
+                trace!(response_body = %response_text, "LLM response body");
+                anthropic::parse_response(&response_text)?
+            }
+        };
+
+        self.log_usage(&chat_response);
+        Self::log_chat_response_summary(&chat_response);
+        Ok(chat_response)
+    }
+
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+        on_delta: DeltaSink<'_>,
+    ) -> Result<ChatResponse> {
+        // Messages API streaming uses a different event schema; until it is
+        // supported, Anthropic endpoints answer in one piece.
+        if self.config.provider == LlmProvider::Anthropic {
+            return self.chat_with_options(messages, tools, options).await;
+        }
+        let url = self.chat_url();
+        let sanitized = Self::sanitize_request_messages(messages);
+        let request = ChatRequest {
+            stream: true,
+            stream_options: Some(StreamOptions {
+                include_usage: true,
+            }),
+            ..self.build_chat_request(&sanitized, tools, options)
+        };
+        debug!(
+            url = %url,
+            message_count = request.messages.len(),
+            model = %request.model,
+            stream = true,
+            "LLM request"
+        );
+        let chat_response = self.stream_chat_request(&url, &request, on_delta).await?;
 
         self.log_usage(&chat_response);
         Self::log_chat_response_summary(&chat_response);
@@ -210,6 +467,11 @@ impl ChatBackend for LlmClient {
     }
 
     async fn fetch_model_info(&self) -> Result<Option<ModelInfo>> {
+        // The Messages API has no model-metadata endpoint that reports a
+        // context length.
+        if self.config.provider == LlmProvider::Anthropic {
+            return Ok(None);
+        }
         let base = self.config.base_url.trim_end_matches('/');
         let model_id = &self.config.model;
 
@@ -255,6 +517,10 @@ impl ChatBackend for LlmClient {
 
         Ok(fallback)
     }
+
+    async fn count_tokens(&self, text: &str) -> Result<Option<usize>> {
+        self.tokenize(text).await
+    }
 }
 
 fn llm_error_message(error_text: &str, status: reqwest::StatusCode) -> String {
@@ -2,7 +2,7 @@ use crate::types::*;
 use anyhow::{Context, Result, anyhow};
 use serde_json::Value;
 use std::future::Future;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 use tracing::{debug, error, info, trace, warn};
 
@@ -11,18 +11,25 @@ use tracing::{debug, error, info, trace, warn};
 /// while still leaving room for slow local models on CPU.
 const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
 
+mod anthropic;
 mod backend;
 mod config;
+mod context;
 mod endpoint;
 mod http;
 mod model_info;
 mod prompts;
+mod retry;
+mod stream;
 mod vision;
 
 pub use backend::{ChatBackend, ChatOptions};
-pub use config::LlmConfig;
+pub use config::{LlmConfig, LlmProvider, ToolMode};
+pub use context::{ContextOverflow, is_context_overflow};
 pub use endpoint::{check_endpoint, list_models};
 pub use prompts::{build_step_prompt, build_vlm_prompt, vlm_system_prompt, workflow_system_prompt};
+pub use retry::{RetryNotice, RetryObserver, RetryPolicy};
+pub use stream::{DeltaSink, StreamDelta};
 pub use vision::analyze_images;
 
 pub struct LlmClient {
@@ -30,6 +37,11 @@ pub struct LlmClient {
     http: reqwest::Client,
     /// Cached context length from provider, 0 means unknown.
     context_length: AtomicU64,
+    /// Set once the server has shown it has no usable `/tokenize`.
+    tokenize_unsupported: AtomicBool,
+    /// Told about every retry and circuit trip, e.g. to surface them as
+    /// agent warnings.
+    retry_observer: Option<RetryObserver>,
 }
 
 #[cfg(test)]
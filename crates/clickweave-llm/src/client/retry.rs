@@ -0,0 +1,225 @@
+//! Retry, backoff and circuit breaking for chat completions.
+//!
+//! A transient failure (408/429/5xx, a refused or reset connection) is
+//! retried with jittered exponential backoff, waiting at least as long as
+//! the server's `Retry-After`. Consecutive transient failures against the
+//! same chat URL open a process-wide circuit for that endpoint, so every
+//! client pointed at a dead server fails fast until the cooldown expires.
+
+use super::*;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+/// How `LlmClient` retries a chat completion that failed transiently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt. `0` disables retrying.
+    pub max_retries: u32,
+    /// Backoff before the first retry; doubles on each further retry.
+    pub initial_backoff: Duration,
+    /// Upper bound on the computed (pre-jitter) backoff.
+    pub max_backoff: Duration,
+    /// Longest `Retry-After` worth waiting for. A server asking for more
+    /// fails the request straight away instead of stalling the run.
+    pub max_retry_after: Duration,
+    /// Consecutive transient failures that open an endpoint's circuit.
+    /// `0` disables the breaker.
+    pub breaker_threshold: u32,
+    /// How long an open circuit rejects requests before letting one
+    /// through again.
+    pub breaker_cooldown: Duration,
+}
+
+impl RetryPolicy {
+    /// Never retry and never trip the breaker.
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            breaker_threshold: 0,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            max_retry_after: Duration::from_secs(60),
+            breaker_threshold: 5,
+            breaker_cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Something the retry layer did that the operator may want to see.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RetryNotice {
+    /// A failed attempt is about to be retried after `delay`.
+    Retrying {
+        endpoint: String,
+        /// 1-based number of the retry about to run.
+        attempt: u32,
+        max_retries: u32,
+        delay: Duration,
+        reason: String,
+    },
+    /// The endpoint's circuit opened; requests fail fast for `cooldown`.
+    CircuitOpened {
+        endpoint: String,
+        cooldown: Duration,
+    },
+    /// A routed call gave up on model `from` and is retrying on `to`.
+    FellBack {
+        from: String,
+        to: String,
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for RetryNotice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Retrying {
+                attempt,
+                max_retries,
+                delay,
+                reason,
+                ..
+            } => write!(
+                f,
+                "LLM request failed ({reason}); retry {attempt}/{max_retries} in {:.1}s",
+                delay.as_secs_f64()
+            ),
+            Self::CircuitOpened { endpoint, cooldown } => write!(
+                f,
+                "LLM endpoint {endpoint} keeps failing; pausing requests for {}s",
+                cooldown.as_secs()
+            ),
+            Self::FellBack { from, to, reason } => {
+                write!(f, "LLM {from} failed ({reason}); falling back to {to}")
+            }
+        }
+    }
+}
+
+/// Callback invoked synchronously for every [`RetryNotice`].
+pub type RetryObserver = Arc<dyn Fn(&RetryNotice) + Send + Sync>;
+
+/// Why one attempt failed, and whether another attempt may help.
+pub(super) struct AttemptError {
+    pub error: anyhow::Error,
+    pub retryable: bool,
+    pub retry_after: Option<Duration>,
+}
+
+impl AttemptError {
+    pub fn fatal(error: anyhow::Error) -> Self {
+        Self {
+            error,
+            retryable: false,
+            retry_after: None,
+        }
+    }
+}
+
+pub(super) fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 408 | 429) || status.is_server_error()
+}
+
+/// Refused connections and connections dropped mid-exchange are worth
+/// retrying; timeouts are not (the request already waited the full
+/// client timeout) and neither are builder or decode errors.
+pub(super) fn is_retryable_transport(error: &reqwest::Error) -> bool {
+    if error.is_timeout() {
+        return false;
+    }
+    if error.is_connect() {
+        return true;
+    }
+    let mut source = std::error::Error::source(error);
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            return matches!(
+                io.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::UnexpectedEof
+            );
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Parse a `Retry-After` value: either delta-seconds or an HTTP date.
+pub(super) fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delta = at.signed_duration_since(chrono::Utc::now());
+    Some(delta.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Exponential backoff for the `attempt`-th retry (1-based), capped at
+/// `max_backoff`, with "equal jitter": a uniform pick from the upper half
+/// of the window so concurrent clients spread out without ever retrying
+/// immediately.
+pub(super) fn backoff_delay(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let exp = policy
+        .initial_backoff
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(policy.max_backoff);
+    let half = exp / 2;
+    half + half.mul_f64(rand::random::<f64>())
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+fn breakers() -> &'static Mutex<HashMap<String, BreakerState>> {
+    static BREAKERS: OnceLock<Mutex<HashMap<String, BreakerState>>> = OnceLock::new();
+    BREAKERS.get_or_init(Default::default)
+}
+
+/// Remaining cooldown when `endpoint`'s circuit is open.
+pub(super) fn circuit_open_for(endpoint: &str) -> Option<Duration> {
+    let map = breakers().lock().unwrap_or_else(|e| e.into_inner());
+    let until = map.get(endpoint)?.open_until?;
+    until.checked_duration_since(Instant::now())
+}
+
+pub(super) fn record_success(endpoint: &str) {
+    breakers()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(endpoint);
+}
+
+/// Count a transient failure; returns `true` when it (re)opens the
+/// circuit. Once the threshold is reached, the first request after the
+/// cooldown acts as the probe: another failure reopens straight away.
+pub(super) fn record_failure(endpoint: &str, policy: &RetryPolicy) -> bool {
+    if policy.breaker_threshold == 0 {
+        return false;
+    }
+    let mut map = breakers().lock().unwrap_or_else(|e| e.into_inner());
+    let state = map.entry(endpoint.to_string()).or_default();
+    state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+    if state.consecutive_failures >= policy.breaker_threshold {
+        state.open_until = Some(Instant::now() + policy.breaker_cooldown);
+        true
+    } else {
+        false
+    }
+}
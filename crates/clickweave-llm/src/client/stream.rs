@@ -0,0 +1,184 @@
+//! OpenAI `stream: true` chat completions.
+//!
+//! The server sends Server-Sent Events whose `data:` payloads are
+//! `chat.completion.chunk` objects, terminated by `data: [DONE]`.
+//! [`StreamAssembler`] folds those chunks — text, reasoning and
+//! incremental `tool_calls` argument fragments, plus the trailing usage
+//! chunk — back into the same [`ChatResponse`] a non-streaming call
+//! returns, reporting text and reasoning fragments as they arrive.
+
+use super::*;
+
+use std::collections::BTreeMap;
+
+/// One incremental fragment of an assistant turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamDelta {
+    /// Visible response text.
+    Text(String),
+    /// Thinking-model scratchpad (`reasoning_content` / `reasoning`).
+    Reasoning(String),
+}
+
+/// Callback receiving [`StreamDelta`]s while a streamed response arrives.
+pub type DeltaSink<'a> = &'a (dyn Fn(StreamDelta) + Send + Sync);
+
+#[derive(Debug, Default)]
+struct PartialToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+/// Folds SSE lines into a [`ChatResponse`].
+#[derive(Debug, Default)]
+pub(super) struct StreamAssembler {
+    /// Bytes received after the last complete line.
+    pending: Vec<u8>,
+    id: String,
+    model: Option<String>,
+    text: String,
+    reasoning: String,
+    /// Keyed by the chunk's `index`, so fragments for parallel calls
+    /// interleave safely.
+    tool_calls: BTreeMap<u64, PartialToolCall>,
+    finish_reason: Option<String>,
+    usage: Option<Usage>,
+    done: bool,
+}
+
+impl StreamAssembler {
+    /// Feed raw body bytes. Returns `true` once `data: [DONE]` was seen.
+    pub fn feed(&mut self, bytes: &[u8], on_delta: DeltaSink<'_>) -> Result<bool> {
+        self.pending.extend_from_slice(bytes);
+        while let Some(newline) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            self.feed_line(line.trim_end_matches(['\r', '\n']), on_delta)?;
+            if self.done {
+                break;
+            }
+        }
+        Ok(self.done)
+    }
+
+    fn feed_line(&mut self, line: &str, on_delta: DeltaSink<'_>) -> Result<()> {
+        // Blank lines separate events; `:` lines are comments/keep-alives;
+        // `event:`/`id:`/`retry:` fields carry nothing we use.
+        let Some(data) = line.strip_prefix("data:") else {
+            return Ok(());
+        };
+        let data = data.trim_start();
+        if data == "[DONE]" {
+            self.done = true;
+            return Ok(());
+        }
+        let chunk: Value = serde_json::from_str(data)
+            .with_context(|| format!("Malformed LLM stream chunk: {data}"))?;
+        if let Some(message) = chunk.pointer("/error/message").and_then(Value::as_str) {
+            anyhow::bail!("{message}");
+        }
+        if self.id.is_empty()
+            && let Some(id) = chunk.get("id").and_then(Value::as_str)
+        {
+            self.id = id.to_string();
+        }
+        if self.model.is_none()
+            && let Some(model) = chunk.get("model").and_then(Value::as_str)
+        {
+            self.model = Some(model.to_string());
+        }
+        if let Some(usage) = chunk.get("usage").filter(|u| !u.is_null()) {
+            self.usage = serde_json::from_value(usage.clone()).ok();
+        }
+        let Some(choice) = chunk.pointer("/choices/0") else {
+            return Ok(());
+        };
+        if let Some(reason) = choice.get("finish_reason").and_then(Value::as_str) {
+            self.finish_reason = Some(reason.to_string());
+        }
+        let Some(delta) = choice.get("delta") else {
+            return Ok(());
+        };
+        if let Some(text) = delta.get("content").and_then(Value::as_str)
+            && !text.is_empty()
+        {
+            self.text.push_str(text);
+            on_delta(StreamDelta::Text(text.to_string()));
+        }
+        let reasoning = delta
+            .get("reasoning_content")
+            .or_else(|| delta.get("reasoning"))
+            .and_then(Value::as_str);
+        if let Some(reasoning) = reasoning
+            && !reasoning.is_empty()
+        {
+            self.reasoning.push_str(reasoning);
+            on_delta(StreamDelta::Reasoning(reasoning.to_string()));
+        }
+        for call in delta
+            .get("tool_calls")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let index = call.get("index").and_then(Value::as_u64).unwrap_or(0);
+            let partial = self.tool_calls.entry(index).or_default();
+            if let Some(id) = call.get("id").and_then(Value::as_str) {
+                partial.id = id.to_string();
+            }
+            if let Some(name) = call.pointer("/function/name").and_then(Value::as_str) {
+                partial.name.push_str(name);
+            }
+            match call.pointer("/function/arguments") {
+                Some(Value::String(fragment)) => partial.arguments.push_str(fragment),
+                // Some servers send the whole object in one delta.
+                Some(args @ Value::Object(_)) => partial.arguments = args.to_string(),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the response once the stream ended.
+    pub fn finish(mut self, on_delta: DeltaSink<'_>) -> Result<ChatResponse> {
+        // A body that ends without a trailing newline still holds a line.
+        if !self.done && !self.pending.is_empty() {
+            let line = String::from_utf8_lossy(&std::mem::take(&mut self.pending)).into_owned();
+            self.feed_line(line.trim_end(), on_delta)?;
+        }
+        if !self.done && self.finish_reason.is_none() {
+            anyhow::bail!("LLM stream ended before the response was complete");
+        }
+        let tool_calls = self
+            .tool_calls
+            .into_values()
+            .map(|call| {
+                // Round-trip through serde so streamed arguments get the
+                // same string-or-object handling as non-streamed ones.
+                serde_json::from_value::<ToolCall>(serde_json::json!({
+                    "id": call.id,
+                    "type": "function",
+                    "function": { "name": call.name, "arguments": call.arguments },
+                }))
+                .context("Malformed streamed tool call")
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let message = Message {
+            content: (!self.text.is_empty()).then_some(Content::Text(self.text)),
+            reasoning_content: (!self.reasoning.is_empty()).then_some(self.reasoning),
+            tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+            ..Message::assistant("")
+        };
+        Ok(ChatResponse {
+            id: self.id,
+            model: self.model,
+            choices: vec![Choice {
+                index: 0,
+                message,
+                finish_reason: self.finish_reason,
+            }],
+            usage: self.usage,
+        })
+    }
+}
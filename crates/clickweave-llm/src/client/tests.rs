@@ -1,5 +1,5 @@
 use super::*;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 /// Mock backend that records calls and returns a canned response.
 struct MockBackend {
@@ -43,6 +43,7 @@ impl ChatBackend for MockBackend {
         self.calls.lock().unwrap().push(messages.to_vec());
         Ok(ChatResponse {
             id: "mock".to_string(),
+            model: None,
             choices: vec![Choice {
                 index: 0,
                 message: Message::assistant(&self.response_text),
@@ -261,6 +262,9 @@ fn assistant_reasoning_content_stripped_from_request() {
         tools: None,
         temperature: None,
         max_tokens: None,
+        stream: false,
+        stream_options: None,
+        response_format: None,
         extra_body: &extra,
     };
     let serialized = serde_json::to_string(&request).unwrap();
@@ -362,3 +366,667 @@ fn list_models_returns_err_for_non_json_body() {
         "error should indicate invalid JSON, got: {err}"
     );
 }
+
+// ---- retry / circuit breaker (local mock HTTP server) ----
+
+/// Scripted reply for one connection to [`mock_chat_server`].
+enum MockReply {
+    Status(u16, Vec<(&'static str, String)>),
+    /// Read the request, then drop the socket with an RST.
+    Reset,
+    /// `200` with a `text/event-stream` body.
+    Sse(String),
+    /// `200` with this `application/json` body.
+    Json(&'static str),
+    /// This status with this `application/json` body.
+    Rejected(u16, &'static str),
+}
+
+const OK_COMPLETION: &str = r#"{"id":"ok","choices":[{"index":0,"message":{"role":"assistant","content":"hi"},"finish_reason":"stop"}]}"#;
+
+/// Serve one scripted reply per connection (the last one repeats) and
+/// count the requests received. Returns a `base_url` for `LlmConfig`.
+async fn mock_chat_server(
+    replies: Vec<MockReply>,
+) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+    let (base_url, hits, _) = recording_mock_server(replies).await;
+    (base_url, hits)
+}
+
+/// [`mock_chat_server`] that also keeps each raw request (head and body).
+async fn recording_mock_server(
+    replies: Vec<MockReply>,
+) -> (
+    String,
+    Arc<std::sync::atomic::AtomicUsize>,
+    Arc<Mutex<Vec<String>>>,
+) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let hits = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let counter = Arc::clone(&hits);
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&requests);
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 4096];
+            loop {
+                let n = socket.read(&mut chunk).await.unwrap_or(0);
+                if n == 0 {
+                    break;
+                }
+                buf.extend_from_slice(&chunk[..n]);
+                let text = String::from_utf8_lossy(&buf);
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                        })
+                        .unwrap_or(0);
+                    if buf.len() >= header_end + 4 + content_length {
+                        break;
+                    }
+                }
+            }
+            log.lock()
+                .unwrap()
+                .push(String::from_utf8_lossy(&buf).into_owned());
+            let index = counter.fetch_add(1, Ordering::SeqCst);
+            match &replies[index.min(replies.len() - 1)] {
+                MockReply::Reset => {
+                    // A zero linger sends RST on drop without blocking,
+                    // which is what the deprecation warns about.
+                    #[allow(deprecated)]
+                    let _ = socket.set_linger(Some(Duration::ZERO));
+                    drop(socket);
+                }
+                reply @ (MockReply::Status(..)
+                | MockReply::Sse(_)
+                | MockReply::Json(_)
+                | MockReply::Rejected(..)) => {
+                    let (status, headers, content_type, body) = match reply {
+                        MockReply::Status(200, headers) => (
+                            200,
+                            headers.as_slice(),
+                            "application/json",
+                            OK_COMPLETION.to_string(),
+                        ),
+                        MockReply::Status(status, headers) => (
+                            *status,
+                            headers.as_slice(),
+                            "application/json",
+                            format!(r#"{{"error":{{"message":"mock status {status}"}}}}"#),
+                        ),
+                        MockReply::Sse(body) => (200, &[][..], "text/event-stream", body.clone()),
+                        MockReply::Json(body) => {
+                            (200, &[][..], "application/json", body.to_string())
+                        }
+                        MockReply::Rejected(status, body) => {
+                            (*status, &[][..], "application/json", body.to_string())
+                        }
+                        MockReply::Reset => unreachable!(),
+                    };
+                    let mut response = format!(
+                        "HTTP/1.1 {status} Mock\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\nconnection: close\r\n",
+                        body.len()
+                    );
+                    for (name, value) in headers {
+                        response.push_str(&format!("{name}: {value}\r\n"));
+                    }
+                    response.push_str("\r\n");
+                    response.push_str(&body);
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                }
+            }
+        }
+    });
+    (base_url, hits, requests)
+}
+
+fn status(code: u16) -> MockReply {
+    MockReply::Status(code, Vec::new())
+}
+
+fn fast_retry() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 2,
+        initial_backoff: Duration::from_millis(5),
+        max_backoff: Duration::from_millis(20),
+        ..RetryPolicy::default()
+    }
+}
+
+fn retrying_client(
+    base_url: String,
+    retry: RetryPolicy,
+) -> (LlmClient, Arc<Mutex<Vec<RetryNotice>>>) {
+    let notices = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&notices);
+    let config = LlmConfig {
+        base_url,
+        ..LlmConfig::default()
+    }
+    .with_retry(retry);
+    let client = LlmClient::new(config).with_retry_observer(Arc::new(move |notice| {
+        sink.lock().unwrap().push(notice.clone());
+    }));
+    (client, notices)
+}
+
+#[tokio::test]
+async fn transient_statuses_and_resets_are_retried_until_success() {
+    let (base_url, hits) = mock_chat_server(vec![status(503), MockReply::Reset, status(200)]).await;
+    let (client, notices) = retrying_client(base_url, fast_retry());
+
+    let response = client.chat(&[Message::user("hi")], None).await.unwrap();
+
+    assert_eq!(response.id, "ok");
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+    let notices = notices.lock().unwrap();
+    assert_eq!(notices.len(), 2);
+    assert!(matches!(
+        &notices[0],
+        RetryNotice::Retrying { attempt: 1, max_retries: 2, reason, .. } if reason.contains("mock status 503")
+    ));
+    assert!(matches!(
+        &notices[1],
+        RetryNotice::Retrying { attempt: 2, .. }
+    ));
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried_and_retries_are_bounded() {
+    let (base_url, hits) = mock_chat_server(vec![status(400)]).await;
+    let (client, notices) = retrying_client(base_url, fast_retry());
+    let err = client.chat(&[Message::user("hi")], None).await.unwrap_err();
+    assert!(err.to_string().contains("mock status 400"));
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+    assert!(notices.lock().unwrap().is_empty());
+
+    let (base_url, hits) = mock_chat_server(vec![status(500)]).await;
+    let (client, _) = retrying_client(base_url, fast_retry());
+    let err = client.chat(&[Message::user("hi")], None).await.unwrap_err();
+    assert!(err.to_string().contains("mock status 500"));
+    assert_eq!(hits.load(Ordering::SeqCst), 3, "first attempt + 2 retries");
+}
+
+#[tokio::test]
+async fn retry_after_is_honoured_and_capped() {
+    let (base_url, hits) = mock_chat_server(vec![
+        MockReply::Status(429, vec![("retry-after", "1".to_string())]),
+        status(200),
+    ])
+    .await;
+    let (client, notices) = retrying_client(base_url, fast_retry());
+    let started = std::time::Instant::now();
+    client.chat(&[Message::user("hi")], None).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    assert!(matches!(
+        notices.lock().unwrap()[0],
+        RetryNotice::Retrying { delay, .. } if delay >= Duration::from_secs(1)
+    ));
+
+    let (base_url, hits) = mock_chat_server(vec![MockReply::Status(
+        429,
+        vec![("retry-after", "3600".to_string())],
+    )])
+    .await;
+    let (client, _) = retrying_client(base_url, fast_retry());
+    client.chat(&[Message::user("hi")], None).await.unwrap_err();
+    assert_eq!(
+        hits.load(Ordering::SeqCst),
+        1,
+        "an hour is past max_retry_after"
+    );
+}
+
+#[tokio::test]
+async fn circuit_opens_after_consecutive_failures_and_fails_fast() {
+    let (base_url, hits) = mock_chat_server(vec![status(502)]).await;
+    let policy = RetryPolicy {
+        max_retries: 1,
+        breaker_threshold: 2,
+        breaker_cooldown: Duration::from_secs(60),
+        ..fast_retry()
+    };
+    let (client, notices) = retrying_client(base_url, policy);
+
+    client.chat(&[Message::user("hi")], None).await.unwrap_err();
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    assert!(matches!(
+        notices.lock().unwrap().last(),
+        Some(RetryNotice::CircuitOpened { cooldown, .. }) if *cooldown == Duration::from_secs(60)
+    ));
+
+    let err = client.chat(&[Message::user("hi")], None).await.unwrap_err();
+    assert!(err.to_string().contains("circuit open"), "got: {err}");
+    assert_eq!(
+        hits.load(Ordering::SeqCst),
+        2,
+        "open circuit must not hit the server"
+    );
+}
+
+#[test]
+fn retry_after_parses_seconds_and_http_dates() {
+    assert_eq!(
+        retry::parse_retry_after(" 7 "),
+        Some(Duration::from_secs(7))
+    );
+    assert_eq!(
+        retry::parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+        Some(Duration::ZERO),
+        "dates in the past mean retry now"
+    );
+    assert_eq!(retry::parse_retry_after("soon"), None);
+}
+
+#[test]
+fn backoff_grows_exponentially_within_jitter_and_cap() {
+    let policy = RetryPolicy {
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(300),
+        ..RetryPolicy::default()
+    };
+    for _ in 0..20 {
+        let first = retry::backoff_delay(&policy, 1);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+        let second = retry::backoff_delay(&policy, 2);
+        assert!(second >= Duration::from_millis(100) && second <= Duration::from_millis(200));
+        let capped = retry::backoff_delay(&policy, 10);
+        assert!(capped >= Duration::from_millis(150) && capped <= Duration::from_millis(300));
+    }
+}
+
+// ---- streaming (SSE) ----
+
+/// An OpenAI-shaped stream: reasoning, text, one tool call whose name and
+/// arguments arrive in fragments, a finish chunk, then a usage chunk.
+const SSE_TURN: &str = concat!(
+    ": keep-alive\n\n",
+    "data: {\"id\":\"chatcmpl-1\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"reasoning_content\":\"Need to \"}}]}\n\n",
+    "data: {\"id\":\"chatcmpl-1\",\"choices\":[{\"index\":0,\"delta\":{\"reasoning_content\":\"click.\"}}]}\n\n",
+    "data: {\"id\":\"chatcmpl-1\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Clicking \"}}]}\n\n",
+    "data: {\"id\":\"chatcmpl-1\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"now\"}}]}\n\n",
+    "data: {\"id\":\"chatcmpl-1\",\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"type\":\"function\",\"function\":{\"name\":\"cdp_click\",\"arguments\":\"\"}}]}}]}\n\n",
+    "data: {\"id\":\"chatcmpl-1\",\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"{\\\"uid\\\":\"}}]}}]}\n\n",
+    "data: {\"id\":\"chatcmpl-1\",\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"\\\"1_0\\\"}\"}}]}}]}\n\n",
+    "data: {\"id\":\"chatcmpl-1\",\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"tool_calls\"}]}\n\n",
+    "data: {\"id\":\"chatcmpl-1\",\"choices\":[],\"usage\":{\"prompt_tokens\":10,\"completion_tokens\":5,\"total_tokens\":15}}\n\n",
+    "data: [DONE]\n\n",
+);
+
+fn assert_assembled_turn(response: &ChatResponse) {
+    assert_eq!(response.id, "chatcmpl-1");
+    let choice = &response.choices[0];
+    assert_eq!(choice.finish_reason.as_deref(), Some("tool_calls"));
+    assert_eq!(choice.message.role, Role::Assistant);
+    assert_eq!(choice.message.content_text(), Some("Clicking now"));
+    assert_eq!(
+        choice.message.reasoning_content.as_deref(),
+        Some("Need to click.")
+    );
+    let calls = choice.message.tool_calls.as_ref().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].id, "call_1");
+    assert_eq!(calls[0].function.name, "cdp_click");
+    assert_eq!(
+        calls[0].function.arguments,
+        serde_json::json!({"uid": "1_0"})
+    );
+    assert_eq!(response.usage.as_ref().unwrap().total_tokens, 15);
+}
+
+#[test]
+fn stream_assembler_rebuilds_the_response_across_arbitrary_chunk_boundaries() {
+    for chunk_size in [1, 7, 64, SSE_TURN.len()] {
+        let deltas = Mutex::new(Vec::new());
+        let sink = |d| deltas.lock().unwrap().push(d);
+        let mut assembler = stream::StreamAssembler::default();
+        let mut done = false;
+        for chunk in SSE_TURN.as_bytes().chunks(chunk_size) {
+            done = assembler.feed(chunk, &sink).unwrap();
+            if done {
+                break;
+            }
+        }
+        assert!(done);
+        assert_assembled_turn(&assembler.finish(&sink).unwrap());
+        assert_eq!(
+            deltas.into_inner().unwrap(),
+            vec![
+                StreamDelta::Reasoning("Need to ".into()),
+                StreamDelta::Reasoning("click.".into()),
+                StreamDelta::Text("Clicking ".into()),
+                StreamDelta::Text("now".into()),
+            ]
+        );
+    }
+}
+
+#[test]
+fn stream_assembler_rejects_truncated_streams_and_error_chunks() {
+    let sink = |_| {};
+    let mut truncated = stream::StreamAssembler::default();
+    truncated
+        .feed(
+            b"data: {\"id\":\"x\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"hal\"}}]}\n\n",
+            &sink,
+        )
+        .unwrap();
+    assert!(truncated.finish(&sink).is_err());
+
+    let mut errored = stream::StreamAssembler::default();
+    let err = errored
+        .feed(
+            b"data: {\"error\":{\"message\":\"model unloaded\"}}\n\n",
+            &sink,
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("model unloaded"));
+}
+
+#[tokio::test]
+async fn llm_client_streams_sse_and_reports_deltas() {
+    let (base_url, hits) =
+        mock_chat_server(vec![status(503), MockReply::Sse(SSE_TURN.into())]).await;
+    let (client, _) = retrying_client(base_url, fast_retry());
+    let deltas = Mutex::new(Vec::new());
+    let sink = |d| deltas.lock().unwrap().push(d);
+
+    let response = client
+        .chat_stream_with_options(&[Message::user("hi")], None, &ChatOptions::default(), &sink)
+        .await
+        .unwrap();
+
+    assert_assembled_turn(&response);
+    assert_eq!(
+        hits.load(Ordering::SeqCst),
+        2,
+        "the 503 before streaming is retried"
+    );
+    assert_eq!(deltas.into_inner().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn json_schema_option_is_sent_as_response_format() {
+    let (base_url, _, requests) = recording_mock_server(vec![status(200)]).await;
+    let client = LlmClient::new(LlmConfig {
+        base_url,
+        ..LlmConfig::default()
+    });
+    let schema = serde_json::json!({"type": "object", "properties": {}});
+    let options = ChatOptions::default().with_json_schema("agent_turn", schema.clone());
+
+    client
+        .chat_with_options(&[Message::user("hi")], None, &options)
+        .await
+        .unwrap();
+
+    let request = requests.lock().unwrap()[0].clone();
+    let body: Value = serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+    assert_eq!(body["response_format"]["type"], "json_schema");
+    assert_eq!(body["response_format"]["json_schema"]["name"], "agent_turn");
+    assert_eq!(body["response_format"]["json_schema"]["schema"], schema);
+    assert!(body.get("tools").is_none());
+}
+
+#[test]
+fn anthropic_endpoints_always_use_native_tools() {
+    let config = LlmConfig::default().with_tool_mode(ToolMode::JsonSchema);
+    assert_eq!(
+        LlmClient::new(config.clone()).tool_mode(),
+        ToolMode::JsonSchema
+    );
+    assert_eq!(
+        LlmClient::new(config.with_provider(LlmProvider::Anthropic)).tool_mode(),
+        ToolMode::NativeTools
+    );
+}
+
+// ---- Anthropic Messages API ----
+
+const ANTHROPIC_REQUEST: &str = include_str!("fixtures/anthropic_request.json");
+const ANTHROPIC_TOOL_USE_RESPONSE: &str = include_str!("fixtures/anthropic_tool_use_response.json");
+const ANTHROPIC_TEXT_RESPONSE: &str = include_str!("fixtures/anthropic_text_response.json");
+
+fn anthropic_transcript() -> Vec<Message> {
+    vec![
+        Message::system("You drive the desktop."),
+        Message::system("Goal: save the document."),
+        Message::user_with_images(
+            "Current screen:",
+            vec![("iVBORw0KGgo=".to_string(), "image/png".to_string())],
+        ),
+        Message {
+            content: Some(Content::Text("Clicking Save.".into())),
+            tool_calls: Some(vec![ToolCall {
+                id: "toolu_1".into(),
+                call_type: CallType::Function,
+                function: FunctionCall {
+                    name: "click".into(),
+                    arguments: serde_json::json!({"x": 412, "y": 88}),
+                },
+            }]),
+            ..Message::assistant("")
+        },
+        Message::tool_result("toolu_1", "clicked"),
+        Message::user("Continue."),
+    ]
+}
+
+fn click_tool() -> Value {
+    serde_json::json!({
+        "type": "function",
+        "function": {
+            "name": "click",
+            "description": "Click at screen coordinates.",
+            "parameters": {
+                "type": "object",
+                "properties": {"x": {"type": "integer"}, "y": {"type": "integer"}},
+                "required": ["x", "y"]
+            }
+        }
+    })
+}
+
+#[test]
+fn anthropic_request_matches_recorded_fixture() {
+    let tools = [click_tool()];
+    let body = anthropic::build_request(
+        "claude-sonnet-4-5",
+        &anthropic_transcript(),
+        Some(&tools),
+        Some(0.5),
+        Some(1024),
+        // The default config's `chat_template_kwargs` must not leak through.
+        &LlmConfig::default().extra_body,
+    );
+    let expected: Value = serde_json::from_str(ANTHROPIC_REQUEST).unwrap();
+    assert_eq!(body, expected);
+}
+
+#[test]
+fn anthropic_tool_use_response_maps_to_tool_calls() {
+    let response = anthropic::parse_response(ANTHROPIC_TOOL_USE_RESPONSE).unwrap();
+    let choice = &response.choices[0];
+
+    assert_eq!(choice.finish_reason.as_deref(), Some("tool_calls"));
+    assert_eq!(choice.message.content_text(), Some("Clicking Save."));
+    assert_eq!(
+        choice.message.reasoning_content.as_deref(),
+        Some("The Save button is visible in the toolbar.")
+    );
+    let calls = choice.message.tool_calls.as_ref().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].id, "toolu_01A09q90qw90lq917835lq9");
+    assert_eq!(calls[0].function.name, "click");
+    assert_eq!(
+        calls[0].function.arguments,
+        serde_json::json!({"x": 412, "y": 88})
+    );
+
+    let usage = response.usage.unwrap();
+    assert_eq!(
+        usage.prompt_tokens,
+        312 + 2048,
+        "cache reads count as prompt"
+    );
+    assert_eq!(usage.completion_tokens, 61);
+    assert_eq!(usage.total_tokens, 312 + 2048 + 61);
+}
+
+#[test]
+fn anthropic_text_response_maps_end_turn_to_stop() {
+    let response = anthropic::parse_response(ANTHROPIC_TEXT_RESPONSE).unwrap();
+    let choice = &response.choices[0];
+    assert_eq!(choice.finish_reason.as_deref(), Some("stop"));
+    assert_eq!(choice.message.content_text(), Some("The dialog is closed."));
+    assert!(choice.message.tool_calls.is_none());
+}
+
+#[tokio::test]
+async fn anthropic_provider_posts_to_messages_with_api_key_headers() {
+    let (base_url, hits, requests) =
+        recording_mock_server(vec![MockReply::Json(ANTHROPIC_TOOL_USE_RESPONSE)]).await;
+    let client = LlmClient::new(
+        LlmConfig {
+            base_url,
+            api_key: Some("sk-ant-test".into()),
+            model: "claude-sonnet-4-5".into(),
+            ..LlmConfig::default()
+        }
+        .with_provider(LlmProvider::Anthropic),
+    );
+
+    // Streaming requests fall back to a single non-streamed call.
+    let sink = |_| panic!("Anthropic responses are not streamed");
+    let response = client
+        .chat_stream_with_options(
+            &anthropic_transcript(),
+            Some(&[click_tool()]),
+            &ChatOptions::default(),
+            &sink,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        response.choices[0].message.tool_calls.as_ref().unwrap()[0]
+            .function
+            .name,
+        "click"
+    );
+    let request = requests.lock().unwrap()[0].to_ascii_lowercase();
+    assert!(request.starts_with("post /v1/messages "), "{request}");
+    assert!(request.contains("x-api-key: sk-ant-test"));
+    assert!(request.contains(&format!("anthropic-version: {}", anthropic::API_VERSION)));
+    assert!(!request.contains("authorization:"));
+    assert!(request.contains(r#""cache_control""#));
+    assert!(client.fetch_model_info().await.unwrap().is_none());
+}
+
+// ---- context window ----
+
+#[tokio::test]
+async fn length_rejections_are_classified_as_context_overflow() {
+    let (base_url, hits) = mock_chat_server(vec![MockReply::Rejected(
+        400,
+        r#"{"error":{"message":"This model's maximum context length is 8192 tokens. However, you requested 9120 tokens."}}"#,
+    )])
+    .await;
+    let (client, _) = retrying_client(base_url, fast_retry());
+
+    let error = client.chat(&[Message::user("q")], None).await.unwrap_err();
+
+    assert!(is_context_overflow(&error), "{error:#}");
+    assert!(error.to_string().contains("maximum context length is 8192"));
+    assert_eq!(
+        hits.load(Ordering::SeqCst),
+        1,
+        "length rejections are not retried"
+    );
+
+    let (base_url, _) = mock_chat_server(vec![status(400)]).await;
+    let (client, _) = retrying_client(base_url, fast_retry());
+    let error = client.chat(&[Message::user("q")], None).await.unwrap_err();
+    assert!(!is_context_overflow(&error));
+}
+
+#[tokio::test]
+async fn a_server_error_is_only_an_overflow_when_it_says_the_prompt_exceeded() {
+    let (base_url, hits) = mock_chat_server(vec![MockReply::Rejected(
+        500,
+        r#"{"error":{"message":"the request exceeds the available context size, try increasing it"}}"#,
+    )])
+    .await;
+    let (client, _) = retrying_client(base_url, fast_retry());
+    let error = client.chat(&[Message::user("q")], None).await.unwrap_err();
+    assert!(is_context_overflow(&error), "{error:#}");
+    assert_eq!(hits.load(Ordering::SeqCst), 1, "overflows are not retried");
+
+    let (base_url, _) = mock_chat_server(vec![MockReply::Rejected(
+        500,
+        r#"{"error":{"message":"failed to allocate the context window buffer"}}"#,
+    )])
+    .await;
+    let (client, _) = retrying_client(base_url, fast_retry());
+    let error = client.chat(&[Message::user("q")], None).await.unwrap_err();
+    assert!(!is_context_overflow(&error), "{error:#}");
+}
+
+#[tokio::test]
+async fn count_tokens_uses_the_server_tokenize_endpoint() {
+    for body in [r#"{"count":7}"#, r#"{"tokens":[1,2,3,4,5,6,7]}"#] {
+        let (base_url, _, requests) = recording_mock_server(vec![MockReply::Json(body)]).await;
+        let client = LlmClient::new(LlmConfig {
+            base_url,
+            model: "local-model".into(),
+            ..LlmConfig::default()
+        });
+
+        assert_eq!(client.count_tokens("hello there").await.unwrap(), Some(7));
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.starts_with("POST /tokenize "), "{request}");
+        assert!(request.contains(r#""prompt":"hello there""#));
+    }
+}
+
+#[tokio::test]
+async fn missing_tokenize_endpoint_is_asked_only_once() {
+    let (base_url, hits) = mock_chat_server(vec![status(404)]).await;
+    let client = LlmClient::new(LlmConfig {
+        base_url,
+        ..LlmConfig::default()
+    });
+
+    assert_eq!(client.count_tokens("a").await.unwrap(), None);
+    assert_eq!(client.count_tokens("b").await.unwrap(), None);
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn a_failed_tokenize_request_is_tried_again() {
+    let (base_url, hits) =
+        mock_chat_server(vec![status(503), MockReply::Json(r#"{"count":3}"#)]).await;
+    let client = LlmClient::new(LlmConfig {
+        base_url,
+        ..LlmConfig::default()
+    });
+
+    assert_eq!(client.count_tokens("a").await.unwrap(), None);
+    assert_eq!(client.count_tokens("b").await.unwrap(), Some(3));
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
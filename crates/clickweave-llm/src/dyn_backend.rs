@@ -21,7 +21,7 @@ use std::pin::Pin;
 use anyhow::Result;
 use serde_json::Value;
 
-use crate::client::{ChatBackend, ChatOptions};
+use crate::client::{ChatBackend, ChatOptions, DeltaSink};
 use crate::types::{ChatResponse, Message, ModelInfo};
 
 /// Object-safe `dyn`-compatible mirror of [`ChatBackend`]. Returns boxed
@@ -40,6 +40,14 @@ pub trait DynChatBackend: Send + Sync {
         tools: Option<&'a [Value]>,
     ) -> Pin<Box<dyn Future<Output = Result<ChatResponse>> + Send + 'a>>;
 
+    fn chat_stream_with_options_boxed<'a>(
+        &'a self,
+        messages: &'a [Message],
+        tools: Option<&'a [Value]>,
+        options: &'a ChatOptions,
+        on_delta: DeltaSink<'a>,
+    ) -> Pin<Box<dyn Future<Output = Result<ChatResponse>> + Send + 'a>>;
+
     fn fetch_model_info_boxed<'a>(
         &'a self,
     ) -> Pin<Box<dyn Future<Output = Result<Option<ModelInfo>>> + Send + 'a>>;
@@ -65,6 +73,16 @@ impl<B: ChatBackend + Send + Sync> DynChatBackend for B {
         Box::pin(self.chat(messages, tools))
     }
 
+    fn chat_stream_with_options_boxed<'a>(
+        &'a self,
+        messages: &'a [Message],
+        tools: Option<&'a [Value]>,
+        options: &'a ChatOptions,
+        on_delta: DeltaSink<'a>,
+    ) -> Pin<Box<dyn Future<Output = Result<ChatResponse>> + Send + 'a>> {
+        Box::pin(self.chat_stream_with_options(messages, tools, options, on_delta))
+    }
+
     fn fetch_model_info_boxed<'a>(
         &'a self,
     ) -> Pin<Box<dyn Future<Output = Result<Option<ModelInfo>>> + Send + 'a>> {
@@ -98,6 +116,7 @@ mod tests {
         ) -> Result<ChatResponse> {
             Ok(ChatResponse {
                 id: "test".to_string(),
+                model: None,
                 choices: vec![crate::types::Choice {
                     index: 0,
                     message: Message::assistant("hi"),
@@ -118,5 +137,16 @@ mod tests {
             .expect("boxed call ok");
         assert_eq!(resp.choices.len(), 1);
         assert_eq!(backend.model_name(), "stub-model");
+
+        // Non-streaming backends answer the streaming entry point with
+        // one ordinary call and no deltas.
+        let deltas = std::sync::Mutex::new(Vec::new());
+        let sink = |d| deltas.lock().unwrap().push(d);
+        let resp = backend
+            .chat_stream_with_options_boxed(&[], None, &opts, &sink)
+            .await
+            .expect("boxed stream call ok");
+        assert_eq!(resp.choices[0].message.content_text(), Some("hi"));
+        assert!(deltas.lock().unwrap().is_empty());
     }
 }
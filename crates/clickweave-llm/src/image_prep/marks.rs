@@ -0,0 +1,525 @@
+//! Set-of-marks annotation: numbered boxes over known elements.
+//!
+//! A VLM that has to describe a location in prose ("the blue button near
+//! the top right") is hard to map back to something clickable. Drawing a
+//! numbered box over every element we already know about lets the model
+//! answer with a number instead, and [`SetOfMarks::resolve_reply`] turns
+//! that number back into the element's uid or coordinates.
+
+use clickweave_core::cdp::CdpViewportRect;
+use clickweave_core::walkthrough::ScreenshotMeta;
+use image::{Rgba, RgbaImage};
+
+use super::{DEFAULT_MAX_DIMENSION, encode_jpeg, fit_to_max_dimension};
+
+/// Most marks drawn on one image; beyond this the labels bury the UI.
+pub const MAX_MARKS: usize = 99;
+
+/// Candidates whose boxes overlap more than this (intersection over union)
+/// are treated as the same element, and only the first is marked.
+const DUPLICATE_IOU: f64 = 0.85;
+
+/// What a mark stands for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkTarget {
+    /// A CDP element uid from `find_elements`.
+    Cdp { uid: String },
+    /// A native accessibility element uid from `take_ax_snapshot`.
+    Ax { uid: String },
+    /// An OCR text match; acted on by its coordinates.
+    Ocr { text: String },
+    /// A bare position, such as a recorded click; acted on by its
+    /// coordinates.
+    Point,
+}
+
+/// An axis-aligned box in the screenshot's source coordinate space
+/// (screen points for AX and OCR, viewport pixels for CDP).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarkRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl MarkRect {
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    fn area(&self) -> f64 {
+        self.width.max(0.0) * self.height.max(0.0)
+    }
+
+    fn iou(&self, other: &MarkRect) -> f64 {
+        let ix = (self.x + self.width).min(other.x + other.width) - self.x.max(other.x);
+        let iy = (self.y + self.height).min(other.y + other.height) - self.y.max(other.y);
+        if ix <= 0.0 || iy <= 0.0 {
+            return 0.0;
+        }
+        let intersection = ix * iy;
+        intersection / (self.area() + other.area() - intersection)
+    }
+}
+
+impl From<&CdpViewportRect> for MarkRect {
+    fn from(rect: &CdpViewportRect) -> Self {
+        Self {
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+        }
+    }
+}
+
+/// An element to mark, before numbering.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkCandidate {
+    pub target: MarkTarget,
+    pub rect: MarkRect,
+}
+
+impl MarkCandidate {
+    pub fn cdp(uid: impl Into<String>, rect: &CdpViewportRect) -> Self {
+        Self {
+            target: MarkTarget::Cdp { uid: uid.into() },
+            rect: rect.into(),
+        }
+    }
+
+    pub fn ax(uid: impl Into<String>, rect: MarkRect) -> Self {
+        Self {
+            target: MarkTarget::Ax { uid: uid.into() },
+            rect,
+        }
+    }
+
+    pub fn ocr(text: impl Into<String>, rect: MarkRect) -> Self {
+        Self {
+            target: MarkTarget::Ocr { text: text.into() },
+            rect,
+        }
+    }
+
+    /// A square of `half_size` around `(x, y)`.
+    pub fn point(x: f64, y: f64, half_size: f64) -> Self {
+        Self {
+            target: MarkTarget::Point,
+            rect: MarkRect {
+                x: x - half_size,
+                y: y - half_size,
+                width: 2.0 * half_size,
+                height: 2.0 * half_size,
+            },
+        }
+    }
+}
+
+/// A numbered mark drawn on the image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mark {
+    pub number: u32,
+    pub target: MarkTarget,
+    /// Box in source coordinates, as passed in.
+    pub rect: MarkRect,
+}
+
+impl Mark {
+    /// The CDP or AX uid behind this mark, if it has one.
+    pub fn uid(&self) -> Option<&str> {
+        match &self.target {
+            MarkTarget::Cdp { uid } | MarkTarget::Ax { uid } => Some(uid),
+            MarkTarget::Ocr { .. } | MarkTarget::Point => None,
+        }
+    }
+
+    /// Centre of the box in source coordinates.
+    pub fn center(&self) -> (f64, f64) {
+        self.rect.center()
+    }
+
+    /// What we already know about the marked element, e.g. `[3] text "Send"`.
+    pub fn describe(&self) -> String {
+        match &self.target {
+            MarkTarget::Cdp { .. } => format!("[{}] page element", self.number),
+            MarkTarget::Ax { .. } => format!("[{}] accessibility element", self.number),
+            MarkTarget::Ocr { text } => format!("[{}] text \"{text}\"", self.number),
+            MarkTarget::Point => format!("[{}] click position", self.number),
+        }
+    }
+}
+
+/// The number-to-element mapping of one annotated image.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SetOfMarks {
+    pub marks: Vec<Mark>,
+}
+
+impl SetOfMarks {
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    pub fn get(&self, number: u32) -> Option<&Mark> {
+        self.marks.iter().find(|mark| mark.number == number)
+    }
+
+    /// Resolve a VLM answer naming a mark ("mark 7", "[7]", "#7" or a bare
+    /// "7"). An answer naming no mark, or a number that was not drawn,
+    /// resolves to `None`.
+    pub fn resolve_reply(&self, reply: &str) -> Option<&Mark> {
+        self.get(parse_mark_number(reply)?)
+    }
+
+    /// One line per mark for the prompt, so the model can match numbers to
+    /// what we already know about each element.
+    pub fn legend(&self) -> String {
+        self.marks
+            .iter()
+            .map(Mark::describe)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Number `candidates` and draw their boxes onto `rgba`.
+///
+/// `meta` maps source coordinates to the image's pixels. Candidates that
+/// are empty, fall outside the image, or duplicate an earlier candidate's
+/// box are skipped, so pass them in order of preference (CDP before AX
+/// before OCR). Numbers start at 1 and follow the surviving candidates.
+pub fn draw_marks(
+    rgba: &mut RgbaImage,
+    candidates: &[MarkCandidate],
+    meta: ScreenshotMeta,
+) -> SetOfMarks {
+    let (img_w, img_h) = (rgba.width() as f64, rgba.height() as f64);
+    let mut kept: Vec<(&MarkCandidate, MarkRect)> = Vec::new();
+    for candidate in candidates {
+        if kept.len() == MAX_MARKS {
+            break;
+        }
+        let (x0, y0) = meta.screen_to_pixel(candidate.rect.x, candidate.rect.y);
+        let (x1, y1) = meta.screen_to_pixel(
+            candidate.rect.x + candidate.rect.width,
+            candidate.rect.y + candidate.rect.height,
+        );
+        let (x0, y0) = (x0.max(0.0), y0.max(0.0));
+        let (x1, y1) = (x1.min(img_w - 1.0), y1.min(img_h - 1.0));
+        if x1 - x0 < 2.0 || y1 - y0 < 2.0 {
+            continue;
+        }
+        let pixels = MarkRect {
+            x: x0,
+            y: y0,
+            width: x1 - x0,
+            height: y1 - y0,
+        };
+        if kept
+            .iter()
+            .any(|(_, seen)| seen.iou(&pixels) > DUPLICATE_IOU)
+        {
+            continue;
+        }
+        kept.push((candidate, pixels));
+    }
+
+    let scale = (img_w.max(img_h) / DEFAULT_MAX_DIMENSION as f64).max(1.0);
+    let mut marks = Vec::with_capacity(kept.len());
+    for (index, (candidate, pixels)) in kept.into_iter().enumerate() {
+        let number = index as u32 + 1;
+        let color = PALETTE[index % PALETTE.len()];
+        draw_box(rgba, &pixels, (2.0 * scale).round() as u32, color);
+        draw_tag(rgba, &pixels, number, scale, color);
+        marks.push(Mark {
+            number,
+            target: candidate.target.clone(),
+            rect: candidate.rect,
+        });
+    }
+    SetOfMarks { marks }
+}
+
+/// Decode, downscale, annotate with marks and JPEG-encode an image for a
+/// VLM call. Returns `(base64_data, "image/jpeg", marks)`, or `None` if
+/// the image cannot be decoded or encoded.
+///
+/// Marks are drawn after downscaling so the numbers stay legible.
+pub fn prepare_marked_image_for_vlm(
+    image_bytes: &[u8],
+    candidates: &[MarkCandidate],
+    meta: ScreenshotMeta,
+    max_dimension: u32,
+) -> Option<(String, String, SetOfMarks)> {
+    let img = image::load_from_memory(image_bytes).ok()?;
+    let source_width = img.width();
+    let mut rgba = fit_to_max_dimension(img, max_dimension).to_rgba8();
+    let resize = rgba.width() as f64 / source_width as f64;
+    let meta = ScreenshotMeta {
+        scale: meta.scale * resize,
+        ..meta
+    };
+    let marks = draw_marks(&mut rgba, candidates, meta);
+    let (b64, mime) = encode_jpeg(image::DynamicImage::ImageRgba8(rgba)).ok()?;
+    Some((b64, mime, marks))
+}
+
+/// Pull the mark number out of a model reply. Prefers an explicit "mark N"
+/// or "[N]" and falls back to a reply that is only a number. "mark" only
+/// counts as a whole word, so "bookmark 3" names no mark.
+fn parse_mark_number(reply: &str) -> Option<u32> {
+    let lower = reply.to_lowercase();
+    for prefix in ["mark", "["] {
+        for (at, _) in lower.match_indices(prefix) {
+            let word = prefix.starts_with(|c: char| c.is_alphabetic());
+            if word && lower[..at].ends_with(|c: char| c.is_alphanumeric()) {
+                continue;
+            }
+            let rest = &lower[at + prefix.len()..];
+            if word && rest.starts_with(|c: char| c.is_alphanumeric()) {
+                continue;
+            }
+            let rest = rest.trim_start_matches([' ', '#', ':']);
+            if let Some(number) = leading_number(rest) {
+                return Some(number);
+            }
+        }
+    }
+    let bare = reply
+        .trim()
+        .trim_matches(|c: char| !c.is_ascii_alphanumeric());
+    bare.parse().ok()
+}
+
+fn leading_number(text: &str) -> Option<u32> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    text[..end].parse().ok()
+}
+
+/// Box colours, cycled so neighbouring marks are easy to tell apart.
+const PALETTE: [Rgba<u8>; 6] = [
+    Rgba([230, 25, 75, 255]),
+    Rgba([0, 130, 200, 255]),
+    Rgba([60, 180, 75, 255]),
+    Rgba([245, 130, 48, 255]),
+    Rgba([145, 30, 180, 255]),
+    Rgba([0, 128, 128, 255]),
+];
+
+/// 3x5 bitmap digits, one row per byte, most significant of the low three
+/// bits on the left.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+fn fill_rect(rgba: &mut RgbaImage, x0: i64, y0: i64, x1: i64, y1: i64, color: Rgba<u8>) {
+    let (img_w, img_h) = (rgba.width() as i64, rgba.height() as i64);
+    for y in y0.max(0)..=y1.min(img_h - 1) {
+        for x in x0.max(0)..=x1.min(img_w - 1) {
+            rgba.put_pixel(x as u32, y as u32, color);
+        }
+    }
+}
+
+fn draw_box(rgba: &mut RgbaImage, rect: &MarkRect, thickness: u32, color: Rgba<u8>) {
+    let t = thickness.max(1) as i64 - 1;
+    let (x0, y0) = (rect.x as i64, rect.y as i64);
+    let (x1, y1) = ((rect.x + rect.width) as i64, (rect.y + rect.height) as i64);
+    fill_rect(rgba, x0, y0, x1, y0 + t, color);
+    fill_rect(rgba, x0, y1 - t, x1, y1, color);
+    fill_rect(rgba, x0, y0, x0 + t, y1, color);
+    fill_rect(rgba, x1 - t, y0, x1, y1, color);
+}
+
+/// Draw the mark number in white on a filled tag at the box's top-left
+/// corner: above the box when there is room, inside it otherwise.
+fn draw_tag(rgba: &mut RgbaImage, rect: &MarkRect, number: u32, scale: f64, color: Rgba<u8>) {
+    let cell = (3.0 * scale).round().max(1.0) as i64;
+    let pad = cell;
+    let digits: Vec<usize> = number
+        .to_string()
+        .bytes()
+        .map(|b| (b - b'0') as usize)
+        .collect();
+    let glyph_w = 3 * cell;
+    let tag_w = digits.len() as i64 * (glyph_w + cell) - cell + 2 * pad;
+    let tag_h = 5 * cell + 2 * pad;
+
+    let x0 = rect.x as i64;
+    let y0 = if rect.y as i64 >= tag_h {
+        rect.y as i64 - tag_h
+    } else {
+        rect.y as i64
+    };
+    fill_rect(rgba, x0, y0, x0 + tag_w - 1, y0 + tag_h - 1, color);
+
+    let white = Rgba([255, 255, 255, 255]);
+    for (i, digit) in digits.into_iter().enumerate() {
+        let gx = x0 + pad + i as i64 * (glyph_w + cell);
+        for (row, bits) in DIGITS[digit].iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) != 0 {
+                    let px = gx + col * cell;
+                    let py = y0 + pad + row as i64 * cell;
+                    fill_rect(rgba, px, py, px + cell - 1, py + cell - 1, white);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::engine::{Engine, general_purpose::STANDARD};
+
+    const IDENTITY: ScreenshotMeta = ScreenshotMeta {
+        origin_x: 0.0,
+        origin_y: 0.0,
+        scale: 1.0,
+    };
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> MarkRect {
+        MarkRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn blank(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255]))
+    }
+
+    #[test]
+    fn numbers_candidates_in_order_and_skips_duplicates_and_offscreen() {
+        let mut img = blank(400, 300);
+        let candidates = vec![
+            MarkCandidate::cdp(
+                "e12",
+                &CdpViewportRect {
+                    x: 20.0,
+                    y: 40.0,
+                    width: 100.0,
+                    height: 30.0,
+                },
+            ),
+            // The same button seen again by OCR.
+            MarkCandidate::ocr("Send", rect(22.0, 41.0, 98.0, 29.0)),
+            MarkCandidate::ax("a7g2", rect(900.0, 900.0, 50.0, 20.0)),
+            MarkCandidate::ocr("Inbox", rect(200.0, 150.0, 60.0, 20.0)),
+        ];
+
+        let marks = draw_marks(&mut img, &candidates, IDENTITY);
+
+        assert_eq!(marks.marks.len(), 2);
+        assert_eq!(marks.get(1).unwrap().uid(), Some("e12"));
+        assert_eq!(
+            marks.get(2).unwrap().target,
+            MarkTarget::Ocr {
+                text: "Inbox".into()
+            }
+        );
+        assert_eq!(marks.get(2).unwrap().center(), (230.0, 160.0));
+        // The box edge is painted in the first palette colour.
+        assert_eq!(*img.get_pixel(60, 40), PALETTE[0]);
+        assert_eq!(*img.get_pixel(60, 55), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn screenshot_meta_maps_source_coordinates_to_pixels() {
+        let mut img = blank(400, 300);
+        let meta = ScreenshotMeta {
+            origin_x: 100.0,
+            origin_y: 50.0,
+            scale: 2.0,
+        };
+        let marks = draw_marks(
+            &mut img,
+            &[MarkCandidate::ax("a1", rect(110.0, 70.0, 40.0, 20.0))],
+            meta,
+        );
+
+        // (110, 70) lands on pixel (20, 40); the mark keeps source coords.
+        assert_eq!(*img.get_pixel(50, 40), PALETTE[0]);
+        assert_eq!(marks.get(1).unwrap().center(), (130.0, 80.0));
+    }
+
+    #[test]
+    fn resolves_mark_references_in_replies() {
+        let marks = SetOfMarks {
+            marks: (1..=12)
+                .map(|number| Mark {
+                    number,
+                    target: MarkTarget::Cdp {
+                        uid: format!("e{number}"),
+                    },
+                    rect: rect(0.0, 0.0, 10.0, 10.0),
+                })
+                .collect(),
+        };
+        let uid = |reply: &str| marks.resolve_reply(reply).and_then(Mark::uid);
+
+        assert_eq!(uid("Mark 7"), Some("e7"));
+        assert_eq!(uid("The Send button is mark #12."), Some("e12"));
+        assert_eq!(uid("[3] Search field"), Some("e3"));
+        assert_eq!(uid(" 4. "), Some("e4"));
+        assert_eq!(uid("mark 40"), None, "not drawn");
+        assert_eq!(uid("the button at the top right"), None);
+    }
+
+    #[test]
+    fn mark_is_only_matched_as_a_whole_word() {
+        let marks = SetOfMarks {
+            marks: vec![Mark {
+                number: 3,
+                target: MarkTarget::Cdp { uid: "e3".into() },
+                rect: rect(0.0, 0.0, 10.0, 10.0),
+            }],
+        };
+
+        assert_eq!(marks.resolve_reply("Open bookmark 3 in the sidebar"), None);
+        assert_eq!(marks.resolve_reply("remarks 3"), None);
+        assert_eq!(marks.resolve_reply("markers: 3"), None);
+        assert_eq!(
+            marks.resolve_reply("(mark 3)").and_then(Mark::uid),
+            Some("e3")
+        );
+    }
+
+    #[test]
+    fn prepared_image_is_downscaled_and_marked() {
+        let mut buf = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgba8(blank(2560, 1440))
+            .write_to(&mut buf, image::ImageFormat::Png)
+            .unwrap();
+        let candidates = [MarkCandidate::ocr("OK", rect(400.0, 400.0, 200.0, 80.0))];
+
+        let (b64, mime, marks) =
+            prepare_marked_image_for_vlm(&buf.into_inner(), &candidates, IDENTITY, 1280).unwrap();
+
+        assert_eq!(mime, "image/jpeg");
+        assert_eq!(marks.legend(), "[1] text \"OK\"");
+        let img = image::load_from_memory(&STANDARD.decode(b64).unwrap())
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(img.dimensions(), (1280, 720));
+        // Box top edge at half the source coordinates; JPEG is lossy.
+        let edge = img.get_pixel(250, 200);
+        assert!(edge[0] > 180 && edge[1] < 90, "edge pixel {edge:?}");
+    }
+}
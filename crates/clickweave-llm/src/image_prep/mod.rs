@@ -1,5 +1,9 @@
 use base64::engine::{Engine, general_purpose::STANDARD};
 
+mod marks;
+
+pub use marks::*;
+
 /// Default maximum dimension (longest edge) for VLM images.
 pub const DEFAULT_MAX_DIMENSION: u32 = 1280;
 
@@ -28,10 +32,15 @@ pub fn prepare_dynimage_for_vlm(
     img: image::DynamicImage,
     max_dimension: u32,
 ) -> Result<(String, String), image::ImageError> {
+    encode_jpeg(fit_to_max_dimension(img, max_dimension))
+}
+
+/// Downscale proportionally so the longest edge <= `max_dimension`.
+fn fit_to_max_dimension(img: image::DynamicImage, max_dimension: u32) -> image::DynamicImage {
     let (w, h) = (img.width(), img.height());
     let longest = w.max(h);
 
-    let img = if longest > max_dimension {
+    if longest > max_dimension {
         let scale = max_dimension as f64 / longest as f64;
         let new_w = (w as f64 * scale).round() as u32;
         let new_h = (h as f64 * scale).round() as u32;
@@ -43,8 +52,10 @@ pub fn prepare_dynimage_for_vlm(
         ))
     } else {
         img
-    };
+    }
+}
 
+fn encode_jpeg(img: image::DynamicImage) -> Result<(String, String), image::ImageError> {
     let mut buf = std::io::Cursor::new(Vec::new());
     img.write_to(&mut buf, image::ImageFormat::Jpeg)?;
 
@@ -1,9 +1,15 @@
+mod cassette;
 mod client;
 mod dyn_backend;
 mod image_prep;
+mod response_cache;
+mod router;
 mod types;
 
+pub use cassette::{CassetteBackend, CassetteDivergence, ReplayMatching};
 pub use client::*;
 pub use dyn_backend::DynChatBackend;
 pub use image_prep::*;
+pub use response_cache::{CachedBackend, ResponseCache, ResponseCacheSettings, ResponseCacheStats};
+pub use router::RoutingBackend;
 pub use types::*;
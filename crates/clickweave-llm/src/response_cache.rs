@@ -0,0 +1,535 @@
+//! Content-addressed disk cache of LLM replies.
+//!
+//! [`CachedBackend`] wraps any [`ChatBackend`] and answers a request it
+//! has seen before from a [`ResponseCache`] directory instead of the
+//! endpoint. It is meant for the side roles (completion checks, VLM click
+//! labelling) that re-send identical screenshots and prompts, not for the
+//! agent itself.
+//!
+//! The key is a BLAKE3 digest of the model name and the request normalized
+//! as for cassettes: no `reasoning_content`, image data reduced to digests,
+//! tools sorted by name, and the sampling options. Only deterministic
+//! calls are cached: a request whose effective temperature is above zero,
+//! or unknown, goes straight to the endpoint unless the cache is forced.
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tracing::{debug, warn};
+
+use crate::cassette::NormalizedRequest;
+use crate::client::{ChatBackend, ChatOptions, DeltaSink, ToolMode};
+use crate::types::{ChatResponse, Message, ModelInfo};
+
+/// User-facing cache settings.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(default)]
+pub struct ResponseCacheSettings {
+    /// Entries older than this are ignored and replaced.
+    pub ttl_hours: u32,
+    /// Total size the cache directory is trimmed to, least recently used
+    /// entries first.
+    pub max_mb: u32,
+    /// Cache sampled (temperature > 0) calls too.
+    pub force: bool,
+}
+
+impl Default for ResponseCacheSettings {
+    fn default() -> Self {
+        Self {
+            ttl_hours: 24 * 7,
+            max_mb: 256,
+            force: false,
+        }
+    }
+}
+
+/// Counters of one [`ResponseCache`] since it was opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ResponseCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Sampled calls that went to the endpoint without touching the cache.
+    pub bypassed: u64,
+    /// Entries removed to stay under the size cap.
+    pub evicted: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<R = ChatResponse> {
+    model: String,
+    /// Seconds since the Unix epoch.
+    stored_at: u64,
+    response: R,
+}
+
+/// A cache directory: one JSON file per key. Several backends may share
+/// one cache; the model name keeps their entries apart.
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_bytes: u64,
+    force: bool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    bypassed: AtomicU64,
+    evicted: AtomicU64,
+    /// Serializes writes so concurrent stores trim the directory once.
+    write_lock: Mutex<()>,
+}
+
+impl ResponseCache {
+    /// Open (creating if needed) the cache at `dir`.
+    pub fn open(dir: impl Into<PathBuf>, settings: ResponseCacheSettings) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create response cache {}", dir.display()))?;
+        Ok(Self {
+            dir,
+            ttl: Duration::from_secs(u64::from(settings.ttl_hours) * 3600),
+            max_bytes: u64::from(settings.max_mb) * 1024 * 1024,
+            force: settings.force,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            bypassed: AtomicU64::new(0),
+            evicted: AtomicU64::new(0),
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    /// Keep entries for `ttl` instead of the configured hours.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Cap the directory at `max_bytes` instead of the configured size.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn stats(&self) -> ResponseCacheStats {
+        ResponseCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            bypassed: self.bypassed.load(Ordering::Relaxed),
+            evicted: self.evicted.load(Ordering::Relaxed),
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    /// A fresh entry for `key`, refreshing its recency for eviction.
+    fn load(&self, key: &str) -> Option<ChatResponse> {
+        let path = self.entry_path(key);
+        let text = std::fs::read_to_string(&path).ok()?;
+        let entry: CacheEntry = match serde_json::from_str(&text) {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Discarding malformed response cache entry");
+                let _ = std::fs::remove_file(&path);
+                return None;
+            }
+        };
+        if now_secs().saturating_sub(entry.stored_at) > self.ttl.as_secs() {
+            return None;
+        }
+        if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(entry.response)
+    }
+
+    fn store(&self, key: &str, model: &str, response: &ChatResponse) {
+        let entry = CacheEntry {
+            model: model.to_string(),
+            stored_at: now_secs(),
+            response,
+        };
+        let _guard = self.write_lock.lock().unwrap();
+        let path = self.entry_path(key);
+        let written = serde_json::to_vec(&entry)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| Ok(std::fs::write(&path, bytes)?));
+        if let Err(e) = written {
+            warn!(path = %path.display(), error = %e, "Failed to write response cache entry");
+            return;
+        }
+        self.trim();
+    }
+
+    /// Delete least recently used entries until the directory fits the
+    /// size cap.
+    fn trim(&self) {
+        let Ok(read_dir) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = read_dir
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                Some((meta.modified().ok()?, meta.len(), entry.path()))
+            })
+            .collect();
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total <= self.max_bytes {
+            return;
+        }
+        entries.sort_by_key(|(modified, _, _)| *modified);
+        for (_, len, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                total -= len;
+                self.evicted.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// [`ChatBackend`] wrapper that serves repeated deterministic requests
+/// from a [`ResponseCache`].
+pub struct CachedBackend<B> {
+    inner: B,
+    cache: Option<Arc<ResponseCache>>,
+    /// Temperature the endpoint uses when a call sets none.
+    default_temperature: Option<f32>,
+}
+
+impl<B: ChatBackend> CachedBackend<B> {
+    /// `default_temperature` is the wrapped endpoint's configured
+    /// temperature, used to judge calls that do not set their own.
+    pub fn new(inner: B, cache: Arc<ResponseCache>, default_temperature: Option<f32>) -> Self {
+        Self {
+            inner,
+            cache: Some(cache),
+            default_temperature,
+        }
+    }
+
+    /// Forward every call to `inner` untouched.
+    pub fn passthrough(inner: B) -> Self {
+        Self {
+            inner,
+            cache: None,
+            default_temperature: None,
+        }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// The cache and key for the request, or `None` when it goes straight
+    /// to the endpoint.
+    fn key(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+    ) -> Option<(&ResponseCache, String)> {
+        let cache = self.cache.as_deref()?;
+        let temperature = options.temperature.or(self.default_temperature);
+        if !cache.force && !temperature.is_some_and(|t| t <= 0.0) {
+            cache.bypassed.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        let request = NormalizedRequest::new(messages, tools, options);
+        let keyed = json!({ "model": self.inner.model_name(), "request": request.body });
+        let key = blake3::hash(keyed.to_string().as_bytes()).to_hex();
+        Some((cache, key.to_string()))
+    }
+
+    fn lookup(&self, cache: &ResponseCache, key: &str) -> Option<ChatResponse> {
+        let hit = cache.load(key);
+        if hit.is_some() {
+            cache.hits.fetch_add(1, Ordering::Relaxed);
+            debug!(key, model = self.inner.model_name(), "Response cache hit");
+        } else {
+            cache.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        hit
+    }
+
+    /// Store a live reply. Errors and replies cut short by the token
+    /// limit are not kept, so a retry reaches the endpoint.
+    fn remember(&self, cache: &ResponseCache, key: &str, result: &Result<ChatResponse>) {
+        let Ok(response) = result else {
+            return;
+        };
+        if response
+            .choices
+            .iter()
+            .any(|choice| choice.finish_reason.as_deref() == Some("length"))
+        {
+            return;
+        }
+        cache.store(key, self.inner.model_name(), response);
+    }
+}
+
+impl<B: ChatBackend> ChatBackend for CachedBackend<B> {
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    fn tool_mode(&self) -> ToolMode {
+        self.inner.tool_mode()
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+    ) -> Result<ChatResponse> {
+        let Some((cache, key)) = self.key(messages, tools, options) else {
+            return self.inner.chat_with_options(messages, tools, options).await;
+        };
+        if let Some(response) = self.lookup(cache, &key) {
+            return Ok(response);
+        }
+        let result = self.inner.chat_with_options(messages, tools, options).await;
+        self.remember(cache, &key, &result);
+        result
+    }
+
+    /// Cached responses are returned whole, without deltas.
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+        on_delta: DeltaSink<'_>,
+    ) -> Result<ChatResponse> {
+        let Some((cache, key)) = self.key(messages, tools, options) else {
+            return self
+                .inner
+                .chat_stream_with_options(messages, tools, options, on_delta)
+                .await;
+        };
+        if let Some(response) = self.lookup(cache, &key) {
+            return Ok(response);
+        }
+        let result = self
+            .inner
+            .chat_stream_with_options(messages, tools, options, on_delta)
+            .await;
+        self.remember(cache, &key, &result);
+        result
+    }
+
+    fn fetch_model_info(&self) -> impl Future<Output = Result<Option<ModelInfo>>> + Send {
+        self.inner.fetch_model_info()
+    }
+
+    fn count_tokens(&self, text: &str) -> impl Future<Output = Result<Option<usize>>> + Send {
+        self.inner.count_tokens(text)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Choice;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Echoes the last message's text and counts calls. A message
+    /// containing "long" is reported as cut short by the token limit.
+    #[derive(Default)]
+    struct EchoBackend {
+        calls: AtomicUsize,
+    }
+
+    impl ChatBackend for EchoBackend {
+        fn model_name(&self) -> &str {
+            "echo-vlm"
+        }
+
+        async fn chat_with_options(
+            &self,
+            messages: &[Message],
+            _tools: Option<&[Value]>,
+            _options: &ChatOptions,
+        ) -> Result<ChatResponse> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            let text = messages
+                .last()
+                .and_then(Message::content_text)
+                .unwrap_or_default();
+            let finish_reason = if text.contains("long") {
+                "length"
+            } else {
+                "stop"
+            };
+            Ok(ChatResponse {
+                id: format!("live-{n}"),
+                model: None,
+                choices: vec![Choice {
+                    index: 0,
+                    message: Message::assistant(format!("echo: {text}")),
+                    finish_reason: Some(finish_reason.to_string()),
+                }],
+                usage: None,
+            })
+        }
+    }
+
+    fn screenshot(data: &str, prompt: &str) -> Vec<Message> {
+        vec![Message::user_with_images(
+            prompt,
+            vec![(data.to_string(), "image/png".into())],
+        )]
+    }
+
+    fn open(dir: &Path, force: bool) -> Arc<ResponseCache> {
+        let settings = ResponseCacheSettings {
+            force,
+            ..ResponseCacheSettings::default()
+        };
+        Arc::new(ResponseCache::open(dir, settings).unwrap())
+    }
+
+    async fn ask(backend: &impl ChatBackend, messages: &[Message]) -> String {
+        let response = backend.chat(messages, None).await.unwrap();
+        response.id
+    }
+
+    #[tokio::test]
+    async fn repeated_deterministic_calls_are_served_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open(dir.path(), false);
+        let backend = CachedBackend::new(EchoBackend::default(), cache.clone(), Some(0.0));
+
+        let first = ask(&backend, &screenshot("AAAA", "Is the goal met?")).await;
+        let again = ask(&backend, &screenshot("AAAA", "Is the goal met?")).await;
+        let other = ask(&backend, &screenshot("BBBB", "Is the goal met?")).await;
+
+        assert_eq!(first, again);
+        assert_ne!(first, other, "a different screenshot is a different key");
+        assert_eq!(backend.inner().calls.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            cache.stats(),
+            ResponseCacheStats {
+                hits: 1,
+                misses: 2,
+                ..ResponseCacheStats::default()
+            }
+        );
+
+        // A fresh process sharing the directory sees the same entries.
+        let reopened =
+            CachedBackend::new(EchoBackend::default(), open(dir.path(), false), Some(0.0));
+        assert_eq!(
+            ask(&reopened, &screenshot("AAAA", "Is the goal met?")).await,
+            first
+        );
+        assert_eq!(reopened.inner().calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn sampled_calls_bypass_the_cache_unless_forced() {
+        let dir = tempfile::tempdir().unwrap();
+        let messages = screenshot("AAAA", "What is at the crosshair?");
+
+        let sampled =
+            CachedBackend::new(EchoBackend::default(), open(dir.path(), false), Some(0.7));
+        ask(&sampled, &messages).await;
+        ask(&sampled, &messages).await;
+        assert_eq!(sampled.inner().calls.load(Ordering::SeqCst), 2);
+        assert_eq!(sampled.cache.as_ref().unwrap().stats().bypassed, 2);
+
+        // A per-call temperature overrides the endpoint default.
+        let options = ChatOptions::with_temperature(0.0);
+        sampled
+            .chat_with_options(&messages, None, &options)
+            .await
+            .unwrap();
+        sampled
+            .chat_with_options(&messages, None, &options)
+            .await
+            .unwrap();
+        assert_eq!(sampled.inner().calls.load(Ordering::SeqCst), 3);
+
+        let forced = CachedBackend::new(EchoBackend::default(), open(dir.path(), true), Some(0.7));
+        ask(&forced, &messages).await;
+        ask(&forced, &messages).await;
+        assert_eq!(forced.inner().calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn truncated_replies_and_expired_entries_are_not_served() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend =
+            CachedBackend::new(EchoBackend::default(), open(dir.path(), false), Some(0.0));
+        ask(&backend, &screenshot("AAAA", "a long answer")).await;
+        ask(&backend, &screenshot("AAAA", "a long answer")).await;
+        assert_eq!(backend.inner().calls.load(Ordering::SeqCst), 2);
+
+        let expiring = Arc::new(
+            ResponseCache::open(dir.path(), ResponseCacheSettings::default())
+                .unwrap()
+                .with_ttl(Duration::ZERO),
+        );
+        let entry = expiring.entry_path("stale");
+        std::fs::write(
+            &entry,
+            serde_json::to_vec(&CacheEntry {
+                model: "echo-vlm".into(),
+                stored_at: now_secs() - 10,
+                response: json!({"id": "old", "choices": [], "usage": null}),
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(expiring.load("stale").is_none());
+    }
+
+    #[tokio::test]
+    async fn size_cap_evicts_least_recently_used_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Arc::new(
+            ResponseCache::open(dir.path(), ResponseCacheSettings::default())
+                .unwrap()
+                .with_max_bytes(600),
+        );
+        let backend = CachedBackend::new(EchoBackend::default(), cache.clone(), Some(0.0));
+        for n in 0..6 {
+            ask(&backend, &screenshot("AAAA", &format!("label {n}"))).await;
+        }
+
+        let size: u64 = std::fs::read_dir(dir.path())
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.metadata().unwrap().len())
+            .sum();
+        assert!(size <= 600, "cache holds {size} bytes");
+        assert!(cache.stats().evicted > 0);
+        // The newest entry survives.
+        ask(&backend, &screenshot("AAAA", "label 5")).await;
+        assert_eq!(cache.stats().hits, 1);
+    }
+}
@@ -0,0 +1,401 @@
+//! Model routing and fallback across endpoints.
+//!
+//! [`RoutingBackend`] holds an ordered chain of backends: a primary and
+//! its fallbacks, typically a small local model followed by a large hosted
+//! one. Each call goes to the first route; when that route errors, times
+//! out or replies with something unusable, the same request moves on to
+//! the next one. A call with [`ChatOptions::escalate`] set skips the
+//! primary and tries the fallbacks first, keeping the primary as a last
+//! resort. Deciding *when* to escalate is the caller's business.
+//!
+//! Every route is asked in the primary's [`ToolMode`], since the caller
+//! shapes the request for it.
+
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::client::{ChatBackend, ChatOptions, DeltaSink, RetryNotice, RetryObserver, ToolMode};
+use crate::types::{ChatResponse, Message, ModelInfo};
+
+/// A [`ChatBackend`] that falls back through a chain of backends.
+pub struct RoutingBackend<B> {
+    /// Primary first. Never empty.
+    routes: Vec<B>,
+    attempt_timeout: Option<Duration>,
+    observer: Option<RetryObserver>,
+}
+
+impl<B: ChatBackend> RoutingBackend<B> {
+    pub fn new(primary: B) -> Self {
+        Self {
+            routes: vec![primary],
+            attempt_timeout: None,
+            observer: None,
+        }
+    }
+
+    /// Append a fallback route (chainable). The first fallback is also
+    /// where escalated calls start.
+    pub fn with_fallback(mut self, fallback: B) -> Self {
+        self.routes.push(fallback);
+        self
+    }
+
+    /// Give up on a route after `timeout` and move to the next one
+    /// (chainable). Without it a route may take as long as its own
+    /// client timeout allows.
+    pub fn with_attempt_timeout(mut self, timeout: Duration) -> Self {
+        self.attempt_timeout = Some(timeout);
+        self
+    }
+
+    /// Report every fallback as a [`RetryNotice::FellBack`] (chainable).
+    pub fn with_retry_observer(mut self, observer: RetryObserver) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    pub fn primary(&self) -> &B {
+        &self.routes[0]
+    }
+
+    /// Route indices in the order a call tries them.
+    fn order(&self, escalate: bool) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.routes.len()).collect();
+        if escalate && order.len() > 1 {
+            order.rotate_left(1);
+        }
+        order
+    }
+
+    async fn route<'a, F, Fut>(&'a self, options: &ChatOptions, call: F) -> Result<ChatResponse>
+    where
+        F: Fn(&'a B) -> Fut,
+        Fut: Future<Output = Result<ChatResponse>>,
+    {
+        let order = self.order(options.escalate);
+        let mut failures = Vec::new();
+        let mut last_error = None;
+        for (n, &index) in order.iter().enumerate() {
+            let backend = &self.routes[index];
+            let attempt = call(backend);
+            let outcome = match self.attempt_timeout {
+                Some(limit) => tokio::time::timeout(limit, attempt)
+                    .await
+                    .unwrap_or_else(|_| Err(anyhow!("timed out after {}s", limit.as_secs_f64()))),
+                None => attempt.await,
+            };
+            // The last route's reply is returned even when unusable: the
+            // caller copes with a bad reply better than with an error.
+            let next = order.get(n + 1).copied();
+            let outcome = match next {
+                Some(_) => outcome.and_then(|response| check_reply(response, options)),
+                None => outcome,
+            };
+            let error = match outcome {
+                Ok(mut response) => {
+                    response
+                        .model
+                        .get_or_insert_with(|| backend.model_name().to_string());
+                    return Ok(response);
+                }
+                Err(error) if failures.is_empty() && next.is_none() => return Err(error),
+                Err(error) => error,
+            };
+            let reason = format!("{error:#}");
+            if let Some(next) = next {
+                let notice = RetryNotice::FellBack {
+                    from: backend.model_name().to_string(),
+                    to: self.routes[next].model_name().to_string(),
+                    reason: reason.clone(),
+                };
+                warn!("{notice}");
+                if let Some(observer) = &self.observer {
+                    observer(&notice);
+                }
+            }
+            failures.push(format!("{}: {reason}", backend.model_name()));
+            last_error = Some(error);
+        }
+        // Keep the last error as the source so callers can still tell
+        // what kind of failure ended the chain.
+        let summary = format!("Every LLM route failed ({})", failures.join("; "));
+        Err(match last_error {
+            Some(error) => error.context(summary),
+            None => anyhow!(summary),
+        })
+    }
+}
+
+/// Reject replies no caller can act on, so the next route gets a chance.
+fn check_reply(response: ChatResponse, options: &ChatOptions) -> Result<ChatResponse> {
+    let Some(message) = response.choices.first().map(|choice| &choice.message) else {
+        bail!("reply has no choices");
+    };
+    let tool_calls = message.tool_calls.as_deref().unwrap_or_default();
+    if let Some(call) = tool_calls
+        .iter()
+        .find(|call| call.function.arguments.is_string())
+    {
+        bail!("tool call `{}` has malformed arguments", call.function.name);
+    }
+    if tool_calls.is_empty() {
+        if message
+            .content_text()
+            .is_none_or(|text| text.trim().is_empty())
+        {
+            bail!("reply is empty");
+        }
+        if options.response_format.is_some() && !is_json(message) {
+            bail!("reply is not valid JSON");
+        }
+    }
+    Ok(response)
+}
+
+fn is_json(message: &Message) -> bool {
+    serde_json::from_str::<Value>(message.json_text()).is_ok()
+}
+
+impl<B: ChatBackend> ChatBackend for RoutingBackend<B> {
+    fn model_name(&self) -> &str {
+        self.primary().model_name()
+    }
+
+    fn tool_mode(&self) -> ToolMode {
+        self.primary().tool_mode()
+    }
+
+    async fn chat_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+    ) -> Result<ChatResponse> {
+        self.route(options, |backend| {
+            backend.chat_with_options(messages, tools, options)
+        })
+        .await
+    }
+
+    /// Deltas from a route that fails partway have already been reported
+    /// by the time the next route starts over.
+    async fn chat_stream_with_options(
+        &self,
+        messages: &[Message],
+        tools: Option<&[Value]>,
+        options: &ChatOptions,
+        on_delta: DeltaSink<'_>,
+    ) -> Result<ChatResponse> {
+        self.route(options, |backend| {
+            backend.chat_stream_with_options(messages, tools, options, on_delta)
+        })
+        .await
+    }
+
+    fn fetch_model_info(&self) -> impl Future<Output = Result<Option<ModelInfo>>> + Send {
+        self.primary().fetch_model_info()
+    }
+
+    fn count_tokens(&self, text: &str) -> impl Future<Output = Result<Option<usize>>> + Send {
+        self.primary().count_tokens(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CallType, Choice, FunctionCall, ToolCall};
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    /// Answers from a script and counts the calls it served.
+    struct ScriptedRoute {
+        name: &'static str,
+        replies: Mutex<VecDeque<Result<Message>>>,
+        delay: Option<Duration>,
+        hits: Mutex<usize>,
+    }
+
+    impl ScriptedRoute {
+        fn new(name: &'static str, replies: Vec<Result<Message>>) -> Self {
+            Self {
+                name,
+                replies: Mutex::new(replies.into()),
+                delay: None,
+                hits: Mutex::new(0),
+            }
+        }
+
+        fn hits(&self) -> usize {
+            *self.hits.lock().unwrap()
+        }
+    }
+
+    impl ChatBackend for ScriptedRoute {
+        fn model_name(&self) -> &str {
+            self.name
+        }
+
+        async fn chat_with_options(
+            &self,
+            _messages: &[Message],
+            _tools: Option<&[Value]>,
+            _options: &ChatOptions,
+        ) -> Result<ChatResponse> {
+            *self.hits.lock().unwrap() += 1;
+            if let Some(delay) = self.delay {
+                tokio::time::sleep(delay).await;
+            }
+            let message = self.replies.lock().unwrap().pop_front().expect("script")?;
+            Ok(ChatResponse {
+                id: "r".to_string(),
+                model: None,
+                choices: vec![Choice {
+                    index: 0,
+                    message,
+                    finish_reason: Some("stop".to_string()),
+                }],
+                usage: None,
+            })
+        }
+    }
+
+    fn answer(text: &str) -> Result<Message> {
+        Ok(Message::assistant(text))
+    }
+
+    fn reply_text(response: &ChatResponse) -> &str {
+        response.choices[0].message.content_text().unwrap()
+    }
+
+    #[tokio::test]
+    async fn falls_back_on_error_and_records_the_serving_model() {
+        let notices = Arc::new(Mutex::new(Vec::new()));
+        let sink = notices.clone();
+        let router = RoutingBackend::new(ScriptedRoute::new(
+            "small",
+            vec![Err(anyhow!("connection refused"))],
+        ))
+        .with_fallback(ScriptedRoute::new("large", vec![answer("hi")]))
+        .with_retry_observer(Arc::new(move |notice| {
+            sink.lock().unwrap().push(notice.to_string())
+        }));
+
+        let response = router.chat(&[Message::user("q")], None).await.unwrap();
+
+        assert_eq!(reply_text(&response), "hi");
+        assert_eq!(response.model.as_deref(), Some("large"));
+        assert_eq!(
+            *notices.lock().unwrap(),
+            ["LLM small failed (connection refused); falling back to large"]
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_replies_fall_back() {
+        let bad_arguments = Message::assistant_tool_calls(vec![ToolCall {
+            id: "c1".to_string(),
+            call_type: CallType::Function,
+            function: FunctionCall {
+                name: "click".to_string(),
+                arguments: Value::String("{\"uid\": ".to_string()),
+            },
+        }]);
+        let router = RoutingBackend::new(ScriptedRoute::new(
+            "small",
+            vec![answer("  "), Ok(bad_arguments), answer("not json")],
+        ))
+        .with_fallback(ScriptedRoute::new(
+            "large",
+            vec![
+                answer("ok"),
+                answer("ok"),
+                answer("```json\n{\"a\": 1}\n```"),
+            ],
+        ));
+        let json = ChatOptions::default().with_json_schema("t", serde_json::json!({}));
+
+        for options in [ChatOptions::default(), ChatOptions::default(), json] {
+            let response = router
+                .chat_with_options(&[Message::user("q")], None, &options)
+                .await
+                .unwrap();
+            assert_eq!(response.model.as_deref(), Some("large"));
+        }
+        assert_eq!(router.routes[0].hits(), 3);
+    }
+
+    #[tokio::test]
+    async fn escalated_calls_start_at_the_fallback_and_keep_the_primary_last() {
+        let router = RoutingBackend::new(ScriptedRoute::new("small", vec![answer("from small")]))
+            .with_fallback(ScriptedRoute::new(
+                "large",
+                vec![answer("from large"), Err(anyhow!("overloaded"))],
+            ));
+        let escalate = ChatOptions {
+            escalate: true,
+            ..ChatOptions::default()
+        };
+
+        let first = router
+            .chat_with_options(&[Message::user("q")], None, &escalate)
+            .await
+            .unwrap();
+        assert_eq!(reply_text(&first), "from large");
+        assert_eq!(router.routes[0].hits(), 0);
+
+        let second = router
+            .chat_with_options(&[Message::user("q")], None, &escalate)
+            .await
+            .unwrap();
+        assert_eq!(reply_text(&second), "from small");
+    }
+
+    #[tokio::test]
+    async fn slow_route_times_out_to_the_next() {
+        let mut slow = ScriptedRoute::new("small", vec![answer("late")]);
+        slow.delay = Some(Duration::from_secs(30));
+        let router = RoutingBackend::new(slow)
+            .with_fallback(ScriptedRoute::new("large", vec![answer("on time")]))
+            .with_attempt_timeout(Duration::from_millis(20));
+
+        let response = router.chat(&[Message::user("q")], None).await.unwrap();
+
+        assert_eq!(reply_text(&response), "on time");
+    }
+
+    #[tokio::test]
+    async fn exhausted_chain_names_every_failure() {
+        let router =
+            RoutingBackend::new(ScriptedRoute::new("small", vec![answer("")])).with_fallback(
+                ScriptedRoute::new("large", vec![Err(anyhow!("overloaded"))]),
+            );
+
+        let error = router.chat(&[Message::user("q")], None).await.unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Every LLM route failed (small: reply is empty; large: overloaded)"
+        );
+        assert_eq!(error.root_cause().to_string(), "overloaded");
+    }
+
+    #[tokio::test]
+    async fn single_route_passes_its_reply_or_error_through() {
+        let router = RoutingBackend::new(ScriptedRoute::new(
+            "only",
+            vec![answer(""), Err(anyhow!("boom"))],
+        ));
+
+        let empty = router.chat(&[Message::user("q")], None).await.unwrap();
+        let error = router.chat(&[Message::user("q")], None).await.unwrap_err();
+
+        assert_eq!(reply_text(&empty), "");
+        assert_eq!(error.to_string(), "boom");
+    }
+}
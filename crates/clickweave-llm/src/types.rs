@@ -12,12 +12,26 @@ pub struct ChatRequest<'a> {
     pub temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u32>,
+    /// Ask for a Server-Sent Events stream of completion chunks.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<&'a Value>,
     /// Extra provider-specific fields flattened into the request body
     /// (e.g. `{"chat_template_kwargs": {"enable_thinking": false}}`).
     #[serde(flatten, skip_serializing_if = "serde_json::Map::is_empty")]
     pub extra_body: &'a serde_json::Map<String, Value>,
 }
 
+/// `stream_options` for streamed requests.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct StreamOptions {
+    /// Have the server append a final chunk carrying token usage.
+    pub include_usage: bool,
+}
+
 /// OpenAI chat message role.
 ///
 /// Serializes to lowercase strings on the wire (`"system"`, `"user"`,
@@ -240,6 +254,19 @@ impl Message {
     pub fn content_text(&self) -> Option<&str> {
         self.content.as_ref().and_then(|c| c.as_text())
     }
+
+    /// Content text of a reply that should be a bare JSON document, minus
+    /// the Markdown code fence servers without constrained decoding
+    /// sometimes wrap it in.
+    pub fn json_text(&self) -> &str {
+        let trimmed = self.content_text().unwrap_or_default().trim();
+        trimmed
+            .strip_prefix("```json")
+            .or_else(|| trimmed.strip_prefix("```"))
+            .and_then(|rest| rest.strip_suffix("```"))
+            .map(str::trim)
+            .unwrap_or(trimmed)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -316,21 +343,25 @@ where
     deserializer.deserialize_any(ArgsVisitor)
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ChatResponse {
     pub id: String,
+    /// The model that produced the reply, as reported by the provider
+    /// (or stamped by a routing backend when the provider omits it).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
     pub choices: Vec<Choice>,
     pub usage: Option<Usage>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Choice {
     pub index: u32,
     pub message: Message,
     pub finish_reason: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Usage {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
@@ -346,7 +377,7 @@ pub struct Usage {
 /// - OpenAI standard: only `id`, `object`, `created`, `owned_by`
 ///
 /// Extra fields are captured in `extra` for forward compatibility.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
     pub id: String,
     pub owned_by: Option<String>,
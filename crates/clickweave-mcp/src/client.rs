@@ -1,28 +1,32 @@
 use crate::protocol::*;
+use crate::transport::{HttpTransport, McpEndpoint, StdioTransport, Transport};
 use anyhow::{Context, Result, anyhow};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::time::Duration;
 use thiserror::Error;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout};
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, oneshot};
 use tokio::task::JoinHandle;
 use tokio::time::Instant;
 use tracing::{debug, error, info, trace, warn};
 
 const INITIALIZE_TIMEOUT: Duration = Duration::from_secs(30);
 const TOOLS_LIST_TIMEOUT: Duration = Duration::from_secs(30);
+const RESOURCES_TIMEOUT: Duration = Duration::from_secs(30);
+const PROMPTS_TIMEOUT: Duration = Duration::from_secs(30);
 const DEFAULT_TOOL_CALL_TIMEOUT: Duration = Duration::from_secs(60);
 
-const MAX_SKIPPED_LINES: usize = 64;
-const MAX_MISMATCHED_IDS: usize = 8;
+/// Notifications buffered per subscriber before the oldest are dropped.
+/// Shared by the supervisor and the multiplexer, which rebroadcast them.
+pub(crate) const NOTIFICATION_CAPACITY: usize = 256;
 
 /// Typed errors the MCP client surfaces so supervision layers can decide between
-/// respawning the subprocess (Timeout, SubprocessClosed) and failing the call
-/// (Protocol).
-#[derive(Debug, Error)]
+/// respawning the server ([`server_gone`](Self::server_gone)) and failing the
+/// call (Timeout, Protocol).
+#[derive(Debug, Clone, Error)]
 pub enum McpError {
     #[error("MCP request `{method}` timed out after {timeout:?}")]
     Timeout { method: String, timeout: Duration },
@@ -30,8 +34,38 @@ pub enum McpError {
     #[error("MCP subprocess closed stdout (EOF)")]
     SubprocessClosed,
 
+    /// The server process exited; `stderr_tail` holds its last stderr
+    /// lines, which usually say why.
+    #[error("MCP server exited ({status}){}", stderr_suffix(stderr_tail))]
+    ServerExited { status: String, stderr_tail: String },
+
     #[error("MCP server error {code}: {message}")]
     Protocol { code: i64, message: String },
+
+    #[error("MCP server returned HTTP {status}: {body}")]
+    Http { status: u16, body: String },
+
+    #[error("MCP server connection closed")]
+    ConnectionClosed,
+}
+
+impl McpError {
+    /// Whether the server is gone for good, so that only a new one can
+    /// answer further requests.
+    pub fn server_gone(&self) -> bool {
+        matches!(
+            self,
+            Self::SubprocessClosed | Self::ServerExited { .. } | Self::ConnectionClosed
+        )
+    }
+}
+
+fn stderr_suffix(stderr_tail: &str) -> String {
+    if stderr_tail.is_empty() {
+        String::new()
+    } else {
+        format!("; stderr:\n{stderr_tail}")
+    }
 }
 
 impl JsonRpcResponse {
@@ -47,149 +81,387 @@ impl JsonRpcResponse {
     }
 }
 
-/// JSON-RPC 2.0 client over a subprocess spawned on construction.
+/// Compile each tool's `outputSchema`. A schema that does not compile is
+/// skipped with a warning, so that tool's results go unchecked.
+fn compile_output_schemas(tools: &[Tool]) -> HashMap<String, Arc<jsonschema::Validator>> {
+    tools
+        .iter()
+        .filter_map(|tool| {
+            let schema = tool.output_schema.as_ref()?;
+            match jsonschema::validator_for(schema) {
+                Ok(validator) => Some((tool.name.clone(), Arc::new(validator))),
+                Err(e) => {
+                    warn!(tool = %tool.name, "ignoring invalid outputSchema: {e}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// JSON-RPC 2.0 client over a [`Transport`]: a subprocess spawned on
+/// construction, or a Streamable HTTP server.
 ///
-/// Correctness relies on `io_lock` serializing each request-write -> response-read
-/// pair. `tools` is a write-rare cache rebuildable via `refresh_tools`, so its
-/// `RwLock` poisoning is recovered silently.
+/// A background reader owns the receiving side: responses are handed to
+/// the request awaiting their id, server notifications are broadcast to
+/// [`notifications`](Self::notifications) subscribers, and server pings
+/// are answered. Requests do not wait for each other: any number can be
+/// in flight, each with its own deadline.
 pub struct McpClient {
-    process: Child,
-    stdin: Mutex<ChildStdin>,
-    stdout: Mutex<BufReader<ChildStdout>>,
-    io_lock: Mutex<()>,
+    shared: Arc<Shared>,
+    reader: JoinHandle<()>,
+}
+
+struct Shared {
+    transport: Transport,
     request_id: AtomicU64,
+    /// Write-rare cache rebuildable via `refresh_tools`, so poisoning is
+    /// recovered silently.
     tools: RwLock<Vec<Tool>>,
-    stderr_task: Option<JoinHandle<()>>,
+    /// Bumped on every successful `refresh_tools`.
+    tools_generation: AtomicU64,
+    /// Compiled `outputSchema` per tool, rebuilt with `tools`.
+    output_schemas: RwLock<HashMap<String, Arc<jsonschema::Validator>>>,
+    /// What the server declared in `initialize`.
+    capabilities: RwLock<ServerCapabilities>,
+    /// The revision agreed in `initialize`.
+    protocol_version: RwLock<String>,
+    pending: Mutex<Pending>,
+    notifications: broadcast::Sender<McpNotification>,
+}
+
+/// Requests awaiting a response, and why no more will arrive once the
+/// reader stopped.
+#[derive(Default)]
+struct Pending {
+    waiters: HashMap<u64, Waiter>,
+    closed: Option<McpError>,
+}
+
+struct Waiter {
+    /// `tools/call click`, for labelling progress.
+    label: String,
+    reply: oneshot::Sender<JsonRpcResponse>,
 }
 
 impl McpClient {
     /// Spawn native-devtools-mcp and initialize the connection.
     pub async fn spawn(command: &str, args: &[&str]) -> Result<Self> {
-        info!("Spawning MCP server: {} {:?}", command, args);
-
-        let mut process = tokio::process::Command::new(command)
-            .args(args)
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .kill_on_drop(true)
-            .spawn()
-            .context("Failed to spawn MCP server")?;
-
-        let stdin = process.stdin.take().ok_or_else(|| anyhow!("No stdin"))?;
-        let stdout = process.stdout.take().ok_or_else(|| anyhow!("No stdout"))?;
-        let stderr = process.stderr.take().ok_or_else(|| anyhow!("No stderr"))?;
-
-        let stderr_task = Some(tokio::spawn(forward_stderr(stderr)));
-
-        let mut client = Self {
-            process,
-            stdin: Mutex::new(stdin),
-            stdout: Mutex::new(BufReader::new(stdout)),
-            io_lock: Mutex::new(()),
+        let stdio = StdioTransport::spawn(command, args)?;
+        Self::start(Transport::Stdio(Box::new(stdio))).await
+    }
+
+    /// Connect to an MCP server over Streamable HTTP at `url` and
+    /// initialize the session.
+    pub async fn connect_http(url: &str) -> Result<Self> {
+        Self::start(Transport::Http(HttpTransport::connect(url)?)).await
+    }
+
+    /// Spawn or connect to `endpoint`, whichever it names.
+    pub async fn connect(endpoint: &McpEndpoint) -> Result<Self> {
+        match endpoint {
+            McpEndpoint::Command { command, args } => {
+                let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                Self::spawn(command, &args).await
+            }
+            McpEndpoint::Url(url) => Self::connect_http(url).await,
+        }
+    }
+
+    async fn start(transport: Transport) -> Result<Self> {
+        let (notifications, _) = broadcast::channel(NOTIFICATION_CAPACITY);
+        let shared = Arc::new(Shared {
+            transport,
             request_id: AtomicU64::new(1),
             tools: RwLock::new(Vec::new()),
-            stderr_task,
-        };
+            tools_generation: AtomicU64::new(0),
+            output_schemas: RwLock::new(HashMap::new()),
+            capabilities: RwLock::new(ServerCapabilities::default()),
+            protocol_version: RwLock::new(PROTOCOL_VERSIONS[0].to_string()),
+            pending: Mutex::new(Pending::default()),
+            notifications,
+        });
+        let reader = tokio::spawn(read_messages(Arc::clone(&shared)));
+        let client = Self { shared, reader };
 
-        client.initialize().await?;
-        client.refresh_tools().await?;
+        client.shared.initialize().await?;
+        client.shared.refresh_tools().await?;
 
         Ok(client)
     }
 
-    fn next_id(&self) -> u64 {
-        self.request_id.fetch_add(1, Ordering::SeqCst)
+    /// Subscribe to the server's notifications from now on. A subscriber
+    /// that falls more than a few hundred behind loses the oldest.
+    pub fn notifications(&self) -> broadcast::Receiver<McpNotification> {
+        self.shared.notifications.subscribe()
     }
 
-    async fn write_message(&self, json: &str) -> Result<()> {
-        let mut buf = Vec::with_capacity(json.len() + 1);
-        buf.extend_from_slice(json.as_bytes());
-        buf.push(b'\n');
-        let mut stdin = self.stdin.lock().await;
-        stdin.write_all(&buf).await?;
-        stdin.flush().await?;
-        Ok(())
+    /// Re-fetch the tool list from the MCP server.
+    /// Call after operations that change the server's available tools
+    /// (e.g., `cdp_connect` exposes new CDP inspection tools). Servers
+    /// that send `notifications/tools/list_changed` get this for free.
+    pub async fn refresh_tools(&self) -> Result<()> {
+        self.shared.refresh_tools().await
     }
 
-    async fn read_response(&self) -> Result<JsonRpcResponse> {
-        let mut stdout = self.stdout.lock().await;
-        let mut skipped = 0usize;
+    /// Counts tool-list refreshes, so holders of a derived view can tell
+    /// when to rebuild it.
+    pub(crate) fn tools_generation(&self) -> u64 {
+        self.shared.tools_generation.load(Ordering::SeqCst)
+    }
+
+    /// Get available tool count.
+    pub fn tool_count(&self) -> usize {
+        self.shared.tools_read().len()
+    }
+
+    /// Check whether a tool with the given name is available.
+    pub fn has_tool(&self, name: &str) -> bool {
+        self.shared.tools_read().iter().any(|t| t.name == name)
+    }
+
+    /// Call a tool by name with arguments, using the default timeout.
+    pub async fn call_tool(&self, name: &str, arguments: Option<Value>) -> Result<ToolCallResult> {
+        self.call_tool_with_timeout(name, arguments, DEFAULT_TOOL_CALL_TIMEOUT)
+            .await
+    }
+
+    /// On timeout, returns [`McpError::Timeout`] so supervision layers can
+    /// decide to respawn the subprocess.
+    ///
+    /// `structured_content` that does not match the tool's `outputSchema`
+    /// is dropped with a warning, leaving callers the text blocks.
+    pub async fn call_tool_with_timeout(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+        timeout: Duration,
+    ) -> Result<ToolCallResult> {
+        let params = ToolCallParams {
+            name: name.to_string(),
+            arguments,
+        };
+
+        let response = self
+            .shared
+            .send_request("tools/call", Some(serde_json::to_value(params)?), timeout)
+            .await?;
+
+        let result = response
+            .into_result()?
+            .ok_or_else(|| anyhow!("tools/call response missing both `result` and `error`"))?;
+        let mut tool_result: ToolCallResult = serde_json::from_value(result)?;
+        self.shared.check_structured_content(name, &mut tool_result);
+        Ok(tool_result)
+    }
+
+    /// Convert MCP tools to OpenAI-compatible tool format.
+    pub fn tools_as_openai(&self) -> Vec<Value> {
+        crate::tools_to_openai(&self.shared.tools_read())
+    }
 
+    /// What the server declared it supports during `initialize`.
+    pub fn server_capabilities(&self) -> ServerCapabilities {
+        self.shared
+            .capabilities
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The protocol revision agreed with the server in `initialize`.
+    pub fn protocol_version(&self) -> String {
+        self.shared
+            .protocol_version
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Every resource the server lists, across all pages.
+    pub async fn list_resources(&self) -> Result<Vec<Resource>> {
+        let mut resources = Vec::new();
+        let mut cursor = None;
         loop {
-            let line = read_mcp_stdout_line(&mut stdout).await?;
-            let Some(value) = parse_mcp_stdout_line(&line)? else {
-                skipped += 1;
-                ensure_skipped_line_budget(skipped)?;
-                continue;
-            };
-
-            if value.get("method").is_some() && value.get("id").is_none() {
-                debug!(
-                    "Skipping server notification: {}",
-                    value
-                        .get("method")
-                        .and_then(|m| m.as_str())
-                        .unwrap_or("unknown")
-                );
-                continue;
+            let page: ResourcesListResult = self
+                .shared
+                .request(
+                    "resources/list",
+                    PaginatedParams { cursor },
+                    RESOURCES_TIMEOUT,
+                )
+                .await?;
+            resources.extend(page.resources);
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                return Ok(resources);
             }
+        }
+    }
 
-            return serde_json::from_value(value).context("Failed to parse MCP response");
+    /// Fetch a resource's contents, e.g. the target of a `resource_link`
+    /// a tool returned.
+    pub async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult> {
+        let params = ResourceParams { uri: uri.into() };
+        self.shared
+            .request("resources/read", params, RESOURCES_TIMEOUT)
+            .await
+    }
+
+    /// Ask for `notifications/resources/updated` whenever `uri` changes.
+    pub async fn subscribe_resource(&self, uri: &str) -> Result<()> {
+        let params = ResourceParams { uri: uri.into() };
+        self.shared
+            .request::<Value>("resources/subscribe", params, RESOURCES_TIMEOUT)
+            .await
+            .map(drop)
+    }
+
+    pub async fn unsubscribe_resource(&self, uri: &str) -> Result<()> {
+        let params = ResourceParams { uri: uri.into() };
+        self.shared
+            .request::<Value>("resources/unsubscribe", params, RESOURCES_TIMEOUT)
+            .await
+            .map(drop)
+    }
+
+    /// Every prompt the server offers, across all pages.
+    pub async fn list_prompts(&self) -> Result<Vec<Prompt>> {
+        let mut prompts = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page: PromptsListResult = self
+                .shared
+                .request("prompts/list", PaginatedParams { cursor }, PROMPTS_TIMEOUT)
+                .await?;
+            prompts.extend(page.prompts);
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                return Ok(prompts);
+            }
         }
     }
 
-    async fn send_request(
+    /// Render prompt `name` with `arguments`.
+    pub async fn get_prompt(
         &self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<GetPromptResult> {
+        let params = GetPromptParams {
+            name: name.into(),
+            arguments,
+        };
+        self.shared
+            .request("prompts/get", params, PROMPTS_TIMEOUT)
+            .await
+    }
+
+    /// Why the connection is gone, once it is. Every request fails with
+    /// this error from then on.
+    pub fn connection_lost(&self) -> Option<McpError> {
+        self.shared.pending().closed.clone()
+    }
+
+    /// Sends `SIGKILL` to a subprocess server, or ends the session with an
+    /// HTTP one. Reaping is handled by Tokio's `kill_on_drop` machinery
+    /// when the `McpClient` is dropped.
+    pub fn kill(&self) -> Result<()> {
+        self.shared.transport.kill()
+    }
+}
+
+impl Drop for McpClient {
+    fn drop(&mut self) {
+        self.reader.abort();
+        // Release anything still waiting (a list_changed refresh) so the
+        // transport, and with it the subprocess, goes away now.
+        self.shared.close(McpError::ConnectionClosed);
+        if let Err(e) = self.shared.transport.kill() {
+            debug!("MCP kill on drop failed: {e}");
+        }
+    }
+}
+
+impl Shared {
+    fn next_id(&self) -> u64 {
+        self.request_id.fetch_add(1, Ordering::SeqCst)
+    }
+
+    async fn send_request(
+        self: &Arc<Self>,
         method: &str,
         params: Option<Value>,
         timeout: Duration,
     ) -> Result<JsonRpcResponse> {
-        let _guard = self.io_lock.lock().await;
-
         let id = self.next_id();
+        let label = request_label(method, params.as_ref());
+        let params = match params {
+            // Ask for progress on tool calls; the token is the request id.
+            Some(Value::Object(mut map)) if method == "tools/call" => {
+                map.insert("_meta".into(), serde_json::json!({ "progressToken": id }));
+                Some(Value::Object(map))
+            }
+            other => other,
+        };
         let request = JsonRpcRequest::new(id, method, params);
         let json = serde_json::to_string(&request)?;
 
         trace!("MCP request: {}", json);
-        self.write_message(&json).await?;
 
-        // Enforce a cumulative deadline across id-mismatch retries so a server
-        // emitting stray ids can't keep the request alive past `timeout`.
+        // One deadline covers the write (an HTTP round trip for remote
+        // servers) and the wait for the reader to hand back the response.
         let deadline = Instant::now() + timeout;
-        let mut mismatched = 0usize;
-        loop {
-            let response = match tokio::time::timeout_at(deadline, self.read_response()).await {
-                Ok(r) => r?,
-                Err(_) => {
-                    return Err(McpError::Timeout {
-                        method: method.to_string(),
-                        timeout,
-                    }
-                    .into());
-                }
-            };
-
-            if response.id != Some(id) {
-                mismatched += 1;
-                error!(
-                    "MCP response id mismatch: expected {}, got {:?}",
-                    id, response.id
-                );
-                if mismatched > MAX_MISMATCHED_IDS {
-                    return Err(anyhow!(
-                        "Too many mismatched response ids on MCP stdout (>{MAX_MISMATCHED_IDS})"
-                    ));
-                }
-                continue;
+        let mut in_flight = self.register(id, label)?;
+        // The spec forbids cancelling `initialize`.
+        let cancellable = method != "initialize";
+        let outcome = tokio::time::timeout_at(deadline, async {
+            // A write cut short by the deadline may still have reached the
+            // server, so it counts as sent; a write that failed did not.
+            in_flight.sent = cancellable;
+            if let Err(e) = self.transport.send(&json).await {
+                in_flight.sent = false;
+                return Err(e);
             }
+            (&mut in_flight.reply)
+                .await
+                .map_err(|_| anyhow::Error::from(self.closed_error()))
+        })
+        .await;
 
-            if let Some(err) = &response.error {
-                error!("MCP error: {} (code {})", err.message, err.code);
+        let response = match outcome {
+            Ok(response) => response?,
+            Err(_) => {
+                in_flight.reason = Some(format!("timed out after {timeout:?}"));
+                return Err(McpError::Timeout {
+                    method: method.to_string(),
+                    timeout,
+                }
+                .into());
             }
+        };
 
-            return Ok(response);
+        if let Some(err) = &response.error {
+            error!("MCP error: {} (code {})", err.message, err.code);
         }
+
+        Ok(response)
+    }
+
+    /// Send `method` and decode its result as `T`.
+    async fn request<T: DeserializeOwned>(
+        self: &Arc<Self>,
+        method: &str,
+        params: impl serde::Serialize,
+        timeout: Duration,
+    ) -> Result<T> {
+        let response = self
+            .send_request(method, Some(serde_json::to_value(params)?), timeout)
+            .await?;
+        let result = response
+            .into_result()?
+            .ok_or_else(|| anyhow!("{method} response missing both `result` and `error`"))?;
+        serde_json::from_value(result).with_context(|| format!("Malformed {method} result"))
     }
 
     async fn send_notification(&self, method: &str) -> Result<()> {
@@ -200,12 +472,12 @@ impl McpClient {
         let json = serde_json::to_string(&notification)?;
 
         debug!("MCP notification: {}", json);
-        self.write_message(&json).await
+        self.transport.send(&json).await
     }
 
-    async fn initialize(&mut self) -> Result<()> {
+    async fn initialize(self: &Arc<Self>) -> Result<()> {
         let params = InitializeParams {
-            protocol_version: "2024-11-05".to_string(),
+            protocol_version: PROTOCOL_VERSIONS[0].to_string(),
             capabilities: ClientCapabilities::default(),
             client_info: ClientInfo {
                 name: "clickweave".to_string(),
@@ -230,20 +502,37 @@ impl McpClient {
             init_result.protocol_version,
             init_result.server_info.as_ref().map(|s| &s.name)
         );
+        let version = init_result.protocol_version;
+        // The server answers with the revision it wants; one this client
+        // does not speak cannot be used.
+        if !PROTOCOL_VERSIONS.contains(&version.as_str()) {
+            anyhow::bail!("MCP server wants unsupported protocol version {version}");
+        }
+        *self.capabilities.write().unwrap_or_else(|e| e.into_inner()) = init_result.capabilities;
+        if version.as_str() >= STRUCTURED_CONTENT_VERSION {
+            self.transport.set_protocol_version(&version);
+        }
+        *self
+            .protocol_version
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = version;
 
         self.send_notification("notifications/initialized").await
     }
 
-    /// Re-fetch the tool list from the MCP server.
-    /// Call after operations that change the server's available tools
-    /// (e.g., `cdp_connect` exposes new CDP inspection tools).
-    pub async fn refresh_tools(&self) -> Result<()> {
+    async fn refresh_tools(self: &Arc<Self>) -> Result<()> {
         let response = self
             .send_request("tools/list", None, TOOLS_LIST_TIMEOUT)
             .await?;
 
         if let Some(result) = response.into_result()? {
-            let tools_result: ToolsListResult = serde_json::from_value(result)?;
+            let mut tools_result: ToolsListResult = serde_json::from_value(result)?;
+            if !self.has_structured_content() {
+                // Not part of this revision; a stray one is not relied on.
+                for tool in &mut tools_result.tools {
+                    tool.output_schema = None;
+                }
+            }
             info!(
                 "Refreshed MCP tools: {} available",
                 tools_result.tools.len()
@@ -251,55 +540,62 @@ impl McpClient {
             for tool in &tools_result.tools {
                 debug!("  - {}: {:?}", tool.name, tool.description);
             }
+            *self
+                .output_schemas
+                .write()
+                .unwrap_or_else(|e| e.into_inner()) = compile_output_schemas(&tools_result.tools);
             *self.tools_write() = tools_result.tools;
+            self.tools_generation.fetch_add(1, Ordering::SeqCst);
         }
 
         Ok(())
     }
 
-    /// Get available tool count.
-    pub fn tool_count(&self) -> usize {
-        self.tools_read().len()
-    }
-
-    /// Check whether a tool with the given name is available.
-    pub fn has_tool(&self, name: &str) -> bool {
-        self.tools_read().iter().any(|t| t.name == name)
-    }
-
-    /// Call a tool by name with arguments, using the default timeout.
-    pub async fn call_tool(&self, name: &str, arguments: Option<Value>) -> Result<ToolCallResult> {
-        self.call_tool_with_timeout(name, arguments, DEFAULT_TOOL_CALL_TIMEOUT)
-            .await
-    }
-
-    /// On timeout, returns [`McpError::Timeout`] so supervision layers can
-    /// decide to respawn the subprocess.
-    pub async fn call_tool_with_timeout(
-        &self,
-        name: &str,
-        arguments: Option<Value>,
-        timeout: Duration,
-    ) -> Result<ToolCallResult> {
-        let params = ToolCallParams {
-            name: name.to_string(),
-            arguments,
+    /// Validate a successful result's `structured_content` against the
+    /// tool's `outputSchema`, dropping it when it does not match. Error
+    /// results are exempt: the spec only binds successful ones.
+    fn check_structured_content(&self, name: &str, result: &mut ToolCallResult) {
+        if !self.has_structured_content() {
+            result.structured_content = None;
+            return;
+        }
+        if result.is_error == Some(true) {
+            return;
+        }
+        let Some(validator) = self
+            .output_schemas
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+        else {
+            return;
         };
-
-        let response = self
-            .send_request("tools/call", Some(serde_json::to_value(params)?), timeout)
-            .await?;
-
-        let result = response
-            .into_result()?
-            .ok_or_else(|| anyhow!("tools/call response missing both `result` and `error`"))?;
-        let tool_result: ToolCallResult = serde_json::from_value(result)?;
-        Ok(tool_result)
+        match &result.structured_content {
+            None => debug!(
+                tool = name,
+                "tool declares an outputSchema but returned no structuredContent"
+            ),
+            Some(structured) => {
+                if let Err(e) = validator.validate(structured) {
+                    warn!(
+                        tool = name,
+                        "dropping structuredContent that does not match the tool's outputSchema: {e}"
+                    );
+                    result.structured_content = None;
+                }
+            }
+        }
     }
 
-    /// Convert MCP tools to OpenAI-compatible tool format.
-    pub fn tools_as_openai(&self) -> Vec<Value> {
-        crate::tools_to_openai(&self.tools_read())
+    /// Whether the agreed revision has `outputSchema` and
+    /// `structuredContent`; older sessions get neither.
+    fn has_structured_content(&self) -> bool {
+        self.protocol_version
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_str()
+            >= STRUCTURED_CONTENT_VERSION
     }
 
     fn tools_read(&self) -> RwLockReadGuard<'_, Vec<Tool>> {
@@ -310,82 +606,681 @@ impl McpClient {
         self.tools.write().unwrap_or_else(|e| e.into_inner())
     }
 
-    /// Sends `SIGKILL` to the subprocess. Reaping is handled by Tokio's
-    /// `kill_on_drop` machinery when the `McpClient` is dropped.
-    pub fn kill(&mut self) -> Result<()> {
-        self.process
-            .start_kill()
-            .context("Failed to kill MCP server")?;
-        Ok(())
+    fn pending(&self) -> MutexGuard<'_, Pending> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
     }
-}
 
-async fn read_mcp_stdout_line(stdout: &mut BufReader<ChildStdout>) -> Result<String> {
-    let mut line = String::new();
-    let bytes = stdout
-        .read_line(&mut line)
-        .await
-        .context("Failed to read MCP response line")?;
-    if bytes == 0 {
-        return Err(McpError::SubprocessClosed.into());
+    /// Reserve a slot for the response to `id`, unless the reader is gone.
+    fn register(self: &Arc<Self>, id: u64, label: String) -> Result<InFlight> {
+        let mut pending = self.pending();
+        if let Some(closed) = &pending.closed {
+            return Err(closed.clone().into());
+        }
+        let (reply, rx) = oneshot::channel();
+        pending.waiters.insert(id, Waiter { label, reply });
+        Ok(InFlight {
+            shared: Arc::clone(self),
+            id,
+            reply: rx,
+            sent: false,
+            reason: None,
+        })
     }
-    Ok(line)
-}
 
-fn parse_mcp_stdout_line(line: &str) -> Result<Option<Value>> {
-    let trimmed = line.trim();
-    if trimmed.is_empty() {
-        warn!("MCP produced blank stdout line; skipping");
-        return Ok(None);
+    fn closed_error(&self) -> McpError {
+        self.pending()
+            .closed
+            .clone()
+            .unwrap_or(McpError::ConnectionClosed)
     }
 
-    trace!("MCP response: {}", trimmed);
+    /// Fail every waiting request with `error`, and every later one.
+    fn close(&self, error: McpError) {
+        let mut pending = self.pending();
+        pending.closed.get_or_insert(error);
+        // Dropping the senders wakes the waiters, which read `closed`.
+        pending.waiters.clear();
+    }
 
-    match serde_json::from_str(trimmed) {
-        Ok(value) => Ok(Some(value)),
-        Err(e) => {
-            let preview: String = trimmed.chars().take(200).collect();
-            warn!(
-                "Non-JSON line on MCP stdout (skipping): {} -- {}",
-                e, preview
-            );
-            Ok(None)
+    fn dispatch(self: &Arc<Self>, message: Value) {
+        let method = message.get("method").and_then(Value::as_str);
+        let id = message.get("id").filter(|id| !id.is_null());
+        match (method, id) {
+            (Some(method), Some(id)) => self.answer_server_request(method, id.clone()),
+            (Some(method), None) => {
+                self.handle_notification(method, message.get("params").cloned())
+            }
+            (None, _) => match serde_json::from_value::<JsonRpcResponse>(message) {
+                Ok(response) => self.deliver(response),
+                Err(e) => warn!("Malformed MCP response: {e}"),
+            },
         }
     }
-}
 
-fn ensure_skipped_line_budget(skipped: usize) -> Result<()> {
-    if skipped > MAX_SKIPPED_LINES {
-        return Err(anyhow!(
-            "Too many malformed lines on MCP stdout (>{MAX_SKIPPED_LINES})"
-        ));
+    fn deliver(&self, response: JsonRpcResponse) {
+        let waiter = response
+            .id
+            .and_then(|id| self.pending().waiters.remove(&id));
+        match waiter {
+            Some(waiter) => {
+                let _ = waiter.reply.send(response);
+            }
+            None => warn!(
+                "MCP response for no pending request (id {:?}); dropping",
+                response.id
+            ),
+        }
+    }
+
+    /// Server-to-client requests: `ping` is answered, anything else is
+    /// refused, since the client advertises no capabilities.
+    fn answer_server_request(self: &Arc<Self>, method: &str, id: Value) {
+        let reply = if method == "ping" {
+            serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": {} })
+        } else {
+            debug!("Refusing MCP server request `{method}`");
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32601, "message": format!("Method not found: {method}") },
+            })
+        };
+        let shared = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Err(e) = shared.transport.send(&reply.to_string()).await {
+                debug!("Failed to answer MCP server request: {e:#}");
+            }
+        });
+    }
+
+    fn handle_notification(self: &Arc<Self>, method: &str, params: Option<Value>) {
+        debug!("MCP server notification: {method}");
+        let notification = match McpNotification::parse(method, params) {
+            McpNotification::ToolsListChanged => {
+                // Subscribers hear about the change once the cache has it.
+                let shared = Arc::clone(self);
+                tokio::spawn(async move {
+                    match shared.refresh_tools().await {
+                        Ok(()) => {
+                            let _ = shared.notifications.send(McpNotification::ToolsListChanged);
+                        }
+                        Err(e) => warn!("MCP tool-list refresh after list_changed failed: {e:#}"),
+                    }
+                });
+                return;
+            }
+            McpNotification::Progress { progress, .. } => {
+                let request = progress
+                    .progress_token
+                    .as_u64()
+                    .and_then(|id| self.pending().waiters.get(&id).map(|w| w.label.clone()));
+                McpNotification::Progress { request, progress }
+            }
+            other => other,
+        };
+        let _ = self.notifications.send(notification);
     }
-    Ok(())
 }
 
-impl Drop for McpClient {
+/// A registered request. Dropping it withdraws the registration, so a
+/// request that timed out or whose caller went away leaves nothing
+/// behind; a late response for it is logged and dropped. If the server
+/// had the request and had not answered yet, it is sent
+/// `notifications/cancelled` so it can stop working on it.
+struct InFlight {
+    shared: Arc<Shared>,
+    id: u64,
+    reply: oneshot::Receiver<JsonRpcResponse>,
+    /// Set while the request is going out and once it has, unless it may
+    /// not be cancelled; cleared again if the write fails.
+    sent: bool,
+    /// Why the request was abandoned; defaults to the caller going away.
+    reason: Option<String>,
+}
+
+impl Drop for InFlight {
     fn drop(&mut self) {
-        if let Some(handle) = self.stderr_task.take() {
-            handle.abort();
-        }
-        if let Err(e) = self.process.start_kill() {
-            debug!("MCP kill on drop failed: {e}");
+        let unanswered = self.shared.pending().waiters.remove(&self.id).is_some();
+        if !(unanswered && self.sent) {
+            return;
         }
+        let reason = self
+            .reason
+            .take()
+            .unwrap_or_else(|| "request abandoned by the client".to_string());
+        debug!("Cancelling MCP request {}: {reason}", self.id);
+        let cancelled = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/cancelled",
+            "params": { "requestId": self.id, "reason": reason },
+        });
+        // Drop cannot await; without a runtime there is no one to tell.
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let shared = Arc::clone(&self.shared);
+        runtime.spawn(async move {
+            if let Err(e) = shared.transport.send(&cancelled.to_string()).await {
+                debug!("Failed to send MCP cancellation: {e:#}");
+            }
+        });
     }
 }
 
-async fn forward_stderr(stderr: ChildStderr) {
-    let mut reader = BufReader::new(stderr).lines();
+/// Route every message from the server until the transport fails, then
+/// fail whatever is still waiting.
+async fn read_messages(shared: Arc<Shared>) {
     loop {
-        match reader.next_line().await {
-            Ok(Some(line)) => {
-                debug!(target: "mcp.stderr", "{line}");
-            }
-            Ok(None) => break,
+        match shared.transport.recv().await {
+            Ok(message) => shared.dispatch(message),
             Err(e) => {
-                warn!(target: "mcp.stderr", "error reading MCP stderr: {e}");
-                break;
+                debug!("MCP reader stopped: {e:#}");
+                let error = e
+                    .downcast_ref::<McpError>()
+                    .cloned()
+                    .unwrap_or(McpError::ConnectionClosed);
+                shared.close(error);
+                return;
+            }
+        }
+    }
+}
+
+/// `tools/call click` for tool calls, the bare method otherwise.
+fn request_label(method: &str, params: Option<&Value>) -> String {
+    match params.and_then(|p| p.get("name")).and_then(Value::as_str) {
+        Some(name) if method == "tools/call" => format!("{method} {name}"),
+        _ => method.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+    use serde_json::json;
+    use std::sync::atomic::AtomicBool;
+
+    #[tokio::test]
+    async fn notifications_are_broadcast_and_list_changes_refresh_the_cache() {
+        let changed = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&changed);
+        let (url, _) = stub_server(move |request| {
+            let id = &request.body["id"];
+            match request.rpc_method() {
+                "initialize" => StubReply::Json(result(
+                    id,
+                    json!({"protocolVersion": "2025-06-18", "capabilities": {}}),
+                )),
+                "tools/list" => {
+                    let mut tools = vec![json!({"name": "cdp_connect", "inputSchema": {}})];
+                    if flag.load(Ordering::SeqCst) {
+                        tools.push(json!({"name": "cdp_find_elements", "inputSchema": {}}));
+                    }
+                    StubReply::Json(result(id, json!({ "tools": tools })))
+                }
+                "tools/call" => {
+                    flag.store(true, Ordering::SeqCst);
+                    let token = request.body["params"]["_meta"]["progressToken"].clone();
+                    assert_eq!(&token, id);
+                    let notify = |method: &str, params: Value| {
+                        event(
+                            None,
+                            &json!({"jsonrpc": "2.0", "method": method, "params": params}),
+                        )
+                    };
+                    StubReply::Events(
+                        notify(
+                            "notifications/progress",
+                            json!({"progressToken": token, "progress": 1, "total": 2}),
+                        ) + &notify(
+                            "notifications/message",
+                            json!({"level": "info", "data": "attached to port 9222"}),
+                        ) + &notify("notifications/tools/list_changed", json!({}))
+                            + &event(
+                                None,
+                                &result(id, json!({"content": [{"type": "text", "text": "ok"}]})),
+                            ),
+                    )
+                }
+                _ => StubReply::Accepted,
             }
+        })
+        .await;
+
+        let client = McpClient::connect_http(&url).await.unwrap();
+        let mut notifications = client.notifications();
+        assert!(!client.has_tool("cdp_find_elements"));
+        client.call_tool("cdp_connect", None).await.unwrap();
+
+        let mut seen = Vec::new();
+        while seen.len() < 3 {
+            let next = tokio::time::timeout(Duration::from_secs(5), notifications.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            seen.push(next);
         }
+        let McpNotification::Progress { request, progress } = &seen[0] else {
+            panic!("expected progress first, got {seen:?}");
+        };
+        assert_eq!(request.as_deref(), Some("tools/call cdp_connect"));
+        assert_eq!(progress.total, Some(2.0));
+        assert!(
+            matches!(&seen[1], McpNotification::Message(m) if m.text() == "attached to port 9222")
+        );
+        assert_eq!(seen[2], McpNotification::ToolsListChanged);
+        assert!(client.has_tool("cdp_find_elements"));
+    }
+
+    #[tokio::test]
+    async fn resources_and_prompts_are_listed_read_and_subscribed() {
+        let (url, requests) = stub_server(|request| {
+            let id = &request.body["id"];
+            let params = &request.body["params"];
+            match request.rpc_method() {
+                "initialize" => StubReply::Json(result(
+                    id,
+                    json!({
+                        "protocolVersion": "2025-06-18",
+                        "capabilities": {"resources": {"subscribe": true}, "prompts": {}}
+                    }),
+                )),
+                "tools/list" => StubReply::Json(result(id, json!({"tools": []}))),
+                "resources/list" => StubReply::Json(if params["cursor"].is_null() {
+                    result(
+                        id,
+                        json!({"resources": [{"uri": "ax://a", "name": "a"}], "nextCursor": "2"}),
+                    )
+                } else {
+                    result(id, json!({"resources": [{"uri": "ax://b", "name": "b"}]}))
+                }),
+                "resources/read" => StubReply::Json(result(
+                    id,
+                    json!({"contents": [{"uri": params["uri"], "text": "button OK"}]}),
+                )),
+                "resources/subscribe" => StubReply::Events(
+                    event(
+                        None,
+                        &json!({
+                            "jsonrpc": "2.0",
+                            "method": "notifications/resources/updated",
+                            "params": {"uri": params["uri"]}
+                        }),
+                    ) + &event(None, &result(id, json!({}))),
+                ),
+                "prompts/list" => StubReply::Json(result(
+                    id,
+                    json!({"prompts": [{"name": "login", "arguments": [{"name": "user"}]}]}),
+                )),
+                "prompts/get" => StubReply::Json(result(
+                    id,
+                    json!({"messages": [{"role": "user", "content": {
+                        "type": "text",
+                        "text": format!("log in as {}", params["arguments"]["user"].as_str().unwrap())
+                    }}]}),
+                )),
+                _ => StubReply::Accepted,
+            }
+        })
+        .await;
+        let client = McpClient::connect_http(&url).await.unwrap();
+        let capabilities = client.server_capabilities();
+        assert_eq!(capabilities.resources.unwrap().subscribe, Some(true));
+
+        let uris: Vec<String> = client
+            .list_resources()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.uri)
+            .collect();
+        assert_eq!(uris, ["ax://a", "ax://b"]);
+        let read = client.read_resource("ax://a").await.unwrap();
+        assert_eq!(read.contents[0].text.as_deref(), Some("button OK"));
+
+        let mut notifications = client.notifications();
+        client.subscribe_resource("ax://a").await.unwrap();
+        let updated = tokio::time::timeout(Duration::from_secs(5), notifications.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            updated,
+            McpNotification::ResourceUpdated {
+                uri: "ax://a".into()
+            }
+        );
+
+        let prompts = client.list_prompts().await.unwrap();
+        assert_eq!(prompts[0].name, "login");
+        let rendered = client
+            .get_prompt("login", HashMap::from([("user".into(), "ada".into())]))
+            .await
+            .unwrap();
+        assert_eq!(
+            rendered.messages[0].content.as_text(),
+            Some("log in as ada")
+        );
+
+        let methods: Vec<String> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.rpc_method().to_string())
+            .collect();
+        assert_eq!(
+            methods.iter().filter(|m| *m == "resources/list").count(),
+            2,
+            "{methods:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn structured_content_that_breaks_the_output_schema_is_dropped() {
+        let (url, _) = stub_server(|request| {
+            let id = &request.body["id"];
+            let params = &request.body["params"];
+            match request.rpc_method() {
+                "initialize" => StubReply::Json(result(
+                    id,
+                    json!({"protocolVersion": "2025-06-18", "capabilities": {}}),
+                )),
+                "tools/list" => StubReply::Json(result(
+                    id,
+                    json!({"tools": [{
+                        "name": "find_text",
+                        "inputSchema": {"type": "object"},
+                        "outputSchema": {
+                            "type": "object",
+                            "properties": {"matches": {"type": "array"}},
+                            "required": ["matches"]
+                        }
+                    }]}),
+                )),
+                "tools/call" => {
+                    let structured = if params["arguments"]["text"] == "OK" {
+                        json!({"matches": [{"text": "OK"}]})
+                    } else {
+                        json!({"matches": "none"})
+                    };
+                    StubReply::Json(result(
+                        id,
+                        json!({
+                            "content": [{"type": "text", "text": structured.to_string()}],
+                            "structuredContent": structured
+                        }),
+                    ))
+                }
+                _ => StubReply::Accepted,
+            }
+        })
+        .await;
+        let client = McpClient::connect_http(&url).await.unwrap();
+
+        let valid = client
+            .call_tool("find_text", Some(json!({"text": "OK"})))
+            .await
+            .unwrap();
+        assert_eq!(
+            valid.structured_content,
+            Some(json!({"matches": [{"text": "OK"}]}))
+        );
+
+        let invalid = client
+            .call_tool("find_text", Some(json!({"text": "Cancel"})))
+            .await
+            .unwrap();
+        assert_eq!(invalid.structured_content, None);
+        assert_eq!(invalid.content[0].as_text(), Some(r#"{"matches":"none"}"#));
+    }
+
+    #[tokio::test]
+    async fn an_older_session_goes_without_structured_output() {
+        let (url, requests) = stub_server(|request| {
+            let id = &request.body["id"];
+            match request.rpc_method() {
+                "initialize" => StubReply::Json(result(
+                    id,
+                    json!({"protocolVersion": "2025-03-26", "capabilities": {}}),
+                )),
+                "tools/list" => StubReply::Json(result(
+                    id,
+                    json!({"tools": [{
+                        "name": "find_text",
+                        "inputSchema": {"type": "object"},
+                        "outputSchema": {"type": "object"}
+                    }]}),
+                )),
+                "tools/call" => StubReply::Json(result(
+                    id,
+                    json!({
+                        "content": [{"type": "text", "text": "{}"}],
+                        "structuredContent": {}
+                    }),
+                )),
+                _ => StubReply::Accepted,
+            }
+        })
+        .await;
+        let client = McpClient::connect_http(&url).await.unwrap();
+
+        assert_eq!(client.protocol_version(), "2025-03-26");
+        assert_eq!(client.shared.tools_read()[0].output_schema, None);
+        let called = client.call_tool("find_text", None).await.unwrap();
+        assert_eq!(called.structured_content, None);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].body["params"]["protocolVersion"], "2025-06-18");
+        assert!(
+            requests
+                .iter()
+                .all(|r| r.header("mcp-protocol-version").is_none())
+        );
+    }
+
+    #[tokio::test]
+    async fn an_unknown_protocol_version_is_refused() {
+        let (url, _) = stub_server(|request| {
+            let id = &request.body["id"];
+            match request.rpc_method() {
+                "initialize" => StubReply::Json(result(
+                    id,
+                    json!({"protocolVersion": "2099-01-01", "capabilities": {}}),
+                )),
+                _ => StubReply::Accepted,
+            }
+        })
+        .await;
+
+        let Err(err) = McpClient::connect_http(&url).await else {
+            panic!("connected with an unknown protocol version");
+        };
+        assert!(err.to_string().contains("2099-01-01"), "{err:#}");
+    }
+
+    #[tokio::test]
+    async fn requests_overlap_and_abandoned_ones_leave_nothing_behind() {
+        let (url, _) = stub_server(|request| {
+            let id = &request.body["id"];
+            match request.rpc_method() {
+                "initialize" => StubReply::Json(result(
+                    id,
+                    json!({"protocolVersion": "2025-06-18", "capabilities": {}}),
+                )),
+                "tools/list" => StubReply::Json(result(id, json!({"tools": []}))),
+                "tools/call" => {
+                    let name = request.body["params"]["name"].clone();
+                    let reply = StubReply::Json(result(
+                        id,
+                        json!({"content": [{"type": "text", "text": name}]}),
+                    ));
+                    if name == "cdp_wait_for_page_change" {
+                        StubReply::Delayed(Duration::from_millis(400), Box::new(reply))
+                    } else {
+                        reply
+                    }
+                }
+                _ => StubReply::Accepted,
+            }
+        })
+        .await;
+        let client = McpClient::connect_http(&url).await.unwrap();
+
+        // A screenshot does not queue behind a slow wait.
+        let slow = client.call_tool("cdp_wait_for_page_change", None);
+        tokio::pin!(slow);
+        let fast = tokio::select! {
+            result = &mut slow => panic!("slow call finished first: {result:?}"),
+            result = client.call_tool("take_screenshot", None) => result.unwrap(),
+        };
+        assert_eq!(fast.content[0].as_text(), Some("take_screenshot"));
+        let slow = slow.await.unwrap();
+        assert_eq!(slow.content[0].as_text(), Some("cdp_wait_for_page_change"));
+
+        let err = client
+            .call_tool_with_timeout("cdp_wait_for_page_change", None, Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<McpError>(),
+            Some(McpError::Timeout { .. })
+        ));
+        let abandoned = client.call_tool("cdp_wait_for_page_change", None);
+        let _ = tokio::time::timeout(Duration::from_millis(50), abandoned).await;
+        assert!(client.shared.pending().waiters.is_empty());
+
+        // The late responses are dropped; the client carries on.
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let next = client.call_tool("take_screenshot", None).await.unwrap();
+        assert_eq!(next.content[0].as_text(), Some("take_screenshot"));
+    }
+
+    #[tokio::test]
+    async fn abandoned_requests_are_cancelled_on_the_server() {
+        let (url, requests) = stub_server(|request| {
+            let id = &request.body["id"];
+            match request.rpc_method() {
+                "initialize" => StubReply::Json(result(
+                    id,
+                    json!({"protocolVersion": "2025-06-18", "capabilities": {}}),
+                )),
+                "tools/list" => StubReply::Json(result(id, json!({"tools": []}))),
+                "tools/call" => StubReply::Delayed(
+                    Duration::from_millis(300),
+                    Box::new(StubReply::Json(result(id, json!({"content": []})))),
+                ),
+                _ => StubReply::Accepted,
+            }
+        })
+        .await;
+        let client = McpClient::connect_http(&url).await.unwrap();
+
+        client
+            .call_tool_with_timeout("cdp_wait_for_page_change", None, Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        let abandoned = client.call_tool("cdp_wait_for_page_change", None);
+        let _ = tokio::time::timeout(Duration::from_millis(50), abandoned).await;
+        // Answered requests are not cancelled.
+        client
+            .call_tool("cdp_wait_for_page_change", None)
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap().clone();
+        let call_ids: Vec<&Value> = requests
+            .iter()
+            .filter(|r| r.rpc_method() == "tools/call")
+            .map(|r| &r.body["id"])
+            .collect();
+        let cancelled: Vec<&Value> = requests
+            .iter()
+            .filter(|r| r.rpc_method() == "notifications/cancelled")
+            .map(|r| &r.body["params"])
+            .collect();
+        assert_eq!(cancelled.len(), 2, "{cancelled:?}");
+        assert_eq!(&cancelled[0]["requestId"], call_ids[0]);
+        assert!(
+            cancelled[0]["reason"]
+                .as_str()
+                .unwrap()
+                .starts_with("timed out")
+        );
+        assert_eq!(&cancelled[1]["requestId"], call_ids[1]);
+    }
+
+    #[tokio::test]
+    async fn failed_writes_are_not_cancelled_on_the_server() {
+        let (url, requests) = stub_server(|request| {
+            let id = &request.body["id"];
+            match request.rpc_method() {
+                "initialize" => StubReply::Json(result(
+                    id,
+                    json!({"protocolVersion": "2025-06-18", "capabilities": {}}),
+                )),
+                "tools/list" => StubReply::Json(result(id, json!({"tools": []}))),
+                "tools/call" => StubReply::Status(500),
+                _ => StubReply::Accepted,
+            }
+        })
+        .await;
+        let client = McpClient::connect_http(&url).await.unwrap();
+
+        client.call_tool("click", None).await.unwrap_err();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let requests = requests.lock().unwrap().clone();
+        assert!(
+            requests
+                .iter()
+                .all(|r| r.rpc_method() != "notifications/cancelled"),
+            "{requests:?}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn a_crashed_server_is_reported_with_its_stderr() {
+        let client = McpClient::connect(&crashing_stdio_server()).await.unwrap();
+        let pong = client.call_tool("echo", None).await.unwrap();
+        assert_eq!(pong.content[0].as_text(), Some("pong"));
+
+        let err = client.call_tool("crash", None).await.unwrap_err();
+        let Some(McpError::ServerExited {
+            status,
+            stderr_tail,
+        }) = err.downcast_ref::<McpError>()
+        else {
+            panic!("expected ServerExited, got {err:#}");
+        };
+        assert!(status.contains("139"), "{status}");
+        assert_eq!(stderr_tail, "segfault");
+        assert!(client.connection_lost().is_some_and(|e| e.server_gone()));
+
+        // Later calls fail at once with the same error.
+        let err = client.call_tool("echo", None).await.unwrap_err();
+        assert!(err.to_string().contains("segfault"), "{err:#}");
+    }
+
+    #[tokio::test]
+    async fn requests_fail_fast_once_the_connection_is_gone() {
+        let (url, _) = tool_server(&["click"], |_, _| None).await;
+        let client = McpClient::connect_http(&url).await.unwrap();
+        // The server hangs up on the call: the POST itself fails.
+        assert!(client.call_tool("click", None).await.is_err());
+
+        client.shared.close(McpError::SubprocessClosed);
+        let err = client
+            .call_tool_with_timeout("click", None, Duration::from_secs(30))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                err.downcast_ref::<McpError>(),
+                Some(McpError::SubprocessClosed)
+            ),
+            "{err:#}"
+        );
     }
 }
@@ -1,5 +1,16 @@
 mod client;
+mod multiplex;
 mod protocol;
+mod server;
+mod supervisor;
+mod transport;
 
 pub use client::*;
+pub use multiplex::*;
 pub use protocol::*;
+pub use server::*;
+pub use supervisor::*;
+pub use transport::McpEndpoint;
+
+#[cfg(test)]
+mod test_support;
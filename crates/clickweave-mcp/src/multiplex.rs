@@ -0,0 +1,931 @@
+//! Several MCP servers behind one tool surface.
+//!
+//! [`McpMultiplexer`] starts every configured server, merges their tool
+//! lists and routes each `tools/call` to the server that owns the tool.
+//! The primary server (native-devtools-mcp) keeps its tool names, which
+//! the engine refers to directly; every other server's tools are exposed
+//! as `{server}__{tool}`. Annotations travel with each tool, so the
+//! permission policy still sees the owning server's hints, and a rule on
+//! `fs__*` covers exactly one server.
+//!
+//! Servers fail independently: an optional server that cannot start, or
+//! whose connection is lost mid-run, is marked unhealthy and its tools
+//! disappear while the others keep working. A server with a
+//! [`RestartPolicy`] is restarted instead, for as long as its budget
+//! lasts.
+//!
+//! Resources keep their URIs. A URI is routed to the server that listed
+//! it or linked it from a tool result, and otherwise to the first server
+//! that serves resources. Prompts are namespaced like tools.
+
+use crate::client::NOTIFICATION_CAPACITY;
+use crate::{
+    GetPromptResult, McpEndpoint, McpError, McpNotification, McpSupervisor, Prompt,
+    ReadResourceResult, Resource, RestartPolicy, ServerCapabilities, ToolCallResult, ToolContent,
+};
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Joins a server name and a tool name in an exposed tool name.
+pub const NAMESPACE_SEPARATOR: &str = "__";
+
+/// One server to start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpServerConfig {
+    /// Short identifier, used as the tool-name prefix. Letters, digits,
+    /// `-` and `_` only, so prefixed names stay valid function names.
+    pub name: String,
+    pub endpoint: McpEndpoint,
+    /// Expose tools as `{name}__{tool}` rather than under their own names.
+    pub namespaced: bool,
+    /// Fail startup when the server cannot be started; otherwise continue
+    /// without it.
+    pub required: bool,
+    /// Restart the server when it goes away mid-run. Off by default.
+    pub restart: RestartPolicy,
+}
+
+impl McpServerConfig {
+    /// The desktop-automation server: bare tool names, must start.
+    pub fn primary(name: impl Into<String>, endpoint: McpEndpoint) -> Self {
+        Self {
+            name: name.into(),
+            endpoint,
+            namespaced: false,
+            required: true,
+            restart: RestartPolicy::NEVER,
+        }
+    }
+
+    /// An auxiliary server: namespaced tool names, optional.
+    pub fn namespaced(name: impl Into<String>, endpoint: McpEndpoint) -> Self {
+        Self {
+            name: name.into(),
+            endpoint,
+            namespaced: true,
+            required: false,
+            restart: RestartPolicy::NEVER,
+        }
+    }
+
+    pub fn with_restart(mut self, policy: RestartPolicy) -> Self {
+        self.restart = policy;
+        self
+    }
+}
+
+/// Status of one server, for surfacing to the user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct McpServerHealth {
+    pub name: String,
+    pub healthy: bool,
+    pub tool_count: usize,
+    /// Times the server was restarted; see [`McpSupervisor::restarts`].
+    pub restarts: u64,
+    /// Why the server is unhealthy.
+    pub error: Option<String>,
+}
+
+struct Server {
+    config: McpServerConfig,
+    client: Option<McpSupervisor>,
+    /// Set once the server failed to start or lost its connection.
+    failure: RwLock<Option<String>>,
+}
+
+impl Server {
+    fn healthy(&self) -> Option<&McpSupervisor> {
+        if self
+            .failure
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some()
+        {
+            return None;
+        }
+        self.client.as_ref()
+    }
+
+    fn mark_failed(&self, error: &anyhow::Error) {
+        warn!(server = %self.config.name, "MCP server marked unhealthy: {error:#}");
+        *self.failure.write().unwrap_or_else(|e| e.into_inner()) = Some(format!("{error:#}"));
+    }
+}
+
+/// Where an exposed tool name leads.
+#[derive(Debug, Clone)]
+struct Route {
+    server: usize,
+    tool: String,
+}
+
+#[derive(Default)]
+struct Surface {
+    routes: HashMap<String, Route>,
+    /// OpenAI-format tools under their exposed names, in server order.
+    tools: Vec<(usize, Value)>,
+    /// Each server's tool-list generation this surface was built from.
+    generations: Vec<Option<(u64, u64)>>,
+}
+
+/// [`McpClient`](crate::McpClient)-shaped front for several servers.
+pub struct McpMultiplexer {
+    servers: Vec<Server>,
+    surface: RwLock<Surface>,
+    /// Server that listed or linked each resource URI seen so far.
+    resource_owners: RwLock<HashMap<String, usize>>,
+    notifications: broadcast::Sender<McpNotification>,
+    forwarders: Vec<JoinHandle<()>>,
+}
+
+impl McpMultiplexer {
+    /// Start and initialize every server concurrently.
+    pub async fn start(configs: Vec<McpServerConfig>) -> Result<Self> {
+        validate(&configs)?;
+
+        let mut set = tokio::task::JoinSet::new();
+        for (index, config) in configs.iter().enumerate() {
+            let endpoint = config.endpoint.clone();
+            let policy = config.restart;
+            set.spawn(async move { (index, McpSupervisor::start(endpoint, policy).await) });
+        }
+        let mut started: Vec<Option<Result<McpSupervisor>>> =
+            configs.iter().map(|_| None).collect();
+        while let Some(joined) = set.join_next().await {
+            let (index, result) = joined.context("MCP server start task failed")?;
+            started[index] = Some(result);
+        }
+
+        let mut servers = Vec::with_capacity(configs.len());
+        for (config, result) in configs.into_iter().zip(started) {
+            let result = result.unwrap_or_else(|| Err(anyhow!("MCP server never started")));
+            let (client, failure) = match result {
+                Ok(client) => {
+                    info!(server = %config.name, tools = client.tool_count(), "MCP server ready");
+                    (Some(client), None)
+                }
+                Err(e) if config.required => {
+                    return Err(e.context(format!("MCP server `{}` failed to start", config.name)));
+                }
+                Err(e) => {
+                    warn!(server = %config.name, "Continuing without MCP server: {e:#}");
+                    (None, Some(format!("{e:#}")))
+                }
+            };
+            servers.push(Server {
+                config,
+                client,
+                failure: RwLock::new(failure),
+            });
+        }
+
+        let (notifications, _) = broadcast::channel(NOTIFICATION_CAPACITY);
+        let forwarders = servers
+            .iter()
+            .filter_map(|server| {
+                let client = server.client.as_ref()?;
+                let logger = server.config.namespaced.then(|| server.config.name.clone());
+                Some(tokio::spawn(forward_notifications(
+                    client.notifications(),
+                    notifications.clone(),
+                    logger,
+                )))
+            })
+            .collect();
+        let mux = Self {
+            servers,
+            surface: RwLock::new(Surface::default()),
+            resource_owners: RwLock::new(HashMap::new()),
+            notifications,
+            forwarders,
+        };
+        mux.rebuild_surface();
+        Ok(mux)
+    }
+
+    /// Re-fetch every healthy server's tool list. A required server's
+    /// failure is returned; an optional one is marked unhealthy.
+    pub async fn refresh_tools(&self) -> Result<()> {
+        let mut first_error = None;
+        for server in &self.servers {
+            let Some(client) = server.healthy() else {
+                continue;
+            };
+            let restarts = client.restarts();
+            if let Err(e) = client.refresh_tools().await {
+                if lost_connection(&e) && client.restarts() == restarts {
+                    server.mark_failed(&e);
+                }
+                if server.config.required && first_error.is_none() {
+                    first_error = Some(e.context(format!(
+                        "Failed to refresh tools of MCP server `{}`",
+                        server.config.name
+                    )));
+                }
+            }
+        }
+        self.rebuild_surface();
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Whether `name` is an exposed tool of a healthy server.
+    pub fn has_tool(&self, name: &str) -> bool {
+        self.route(name).is_some()
+    }
+
+    /// Exposed tool count across healthy servers.
+    pub fn tool_count(&self) -> usize {
+        self.tools_as_openai().len()
+    }
+
+    /// Merged tool list in OpenAI format, each under its exposed name.
+    pub fn tools_as_openai(&self) -> Vec<Value> {
+        self.surface_read()
+            .tools
+            .iter()
+            .filter(|(server, _)| self.servers[*server].healthy().is_some())
+            .map(|(_, tool)| tool.clone())
+            .collect()
+    }
+
+    /// Notifications from every server. Log messages from namespaced
+    /// servers that name no logger carry the server name as their logger.
+    pub fn notifications(&self) -> broadcast::Receiver<McpNotification> {
+        self.notifications.subscribe()
+    }
+
+    /// Name of the server that owns the exposed tool `name`.
+    pub fn server_for_tool(&self, name: &str) -> Option<&str> {
+        let server = self.surface_read().routes.get(name)?.server;
+        Some(&self.servers[server].config.name)
+    }
+
+    /// Restarts of the primary server, whose session state (CDP, window
+    /// and snapshot ids) callers keep; see [`McpSupervisor::restarts`].
+    /// An auxiliary server restarting leaves that state alone, so it is
+    /// only reported per server by [`Self::health`].
+    pub fn primary_restarts(&self) -> u64 {
+        self.servers
+            .iter()
+            .filter(|server| !server.config.namespaced)
+            .filter_map(|server| server.client.as_ref())
+            .map(McpSupervisor::restarts)
+            .sum()
+    }
+
+    pub fn health(&self) -> Vec<McpServerHealth> {
+        let surface = self.surface_read();
+        self.servers
+            .iter()
+            .enumerate()
+            .map(|(index, server)| McpServerHealth {
+                name: server.config.name.clone(),
+                healthy: server.healthy().is_some(),
+                tool_count: surface.tools.iter().filter(|(s, _)| *s == index).count(),
+                restarts: server.client.as_ref().map_or(0, McpSupervisor::restarts),
+                error: server
+                    .failure
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clone(),
+            })
+            .collect()
+    }
+
+    pub async fn call_tool(&self, name: &str, arguments: Option<Value>) -> Result<ToolCallResult> {
+        let (server, tool) = self.route_or_err(name)?;
+        let client = self.servers[server]
+            .healthy()
+            .ok_or_else(|| self.unavailable(server))?;
+        let restarts = client.restarts();
+        let result = client.call_tool(&tool, arguments).await;
+        let result = self.observe(server, restarts, result)?;
+        self.note_resource_links(server, &result);
+        Ok(result)
+    }
+
+    pub async fn call_tool_with_timeout(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+        timeout: Duration,
+    ) -> Result<ToolCallResult> {
+        let (server, tool) = self.route_or_err(name)?;
+        let client = self.servers[server]
+            .healthy()
+            .ok_or_else(|| self.unavailable(server))?;
+        let restarts = client.restarts();
+        let result = client
+            .call_tool_with_timeout(&tool, arguments, timeout)
+            .await;
+        let result = self.observe(server, restarts, result)?;
+        self.note_resource_links(server, &result);
+        Ok(result)
+    }
+
+    /// Resources of every healthy server that serves them. A required
+    /// server's failure is returned; an optional one's is logged.
+    pub async fn list_resources(&self) -> Result<Vec<Resource>> {
+        let listed = self
+            .gather(
+                "resources",
+                |c| c.resources.is_some(),
+                async |client| client.list_resources().await,
+            )
+            .await?;
+        let mut owners = self.owners_write();
+        let mut resources = Vec::new();
+        for (server, listed) in listed {
+            for resource in listed {
+                owners.insert(resource.uri.clone(), server);
+                resources.push(resource);
+            }
+        }
+        Ok(resources)
+    }
+
+    pub async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult> {
+        let (server, client) = self.resource_server(uri)?;
+        let restarts = client.restarts();
+        let result = client.read_resource(uri).await;
+        self.observe(server, restarts, result)
+    }
+
+    pub async fn subscribe_resource(&self, uri: &str) -> Result<()> {
+        let (server, client) = self.resource_server(uri)?;
+        let restarts = client.restarts();
+        let result = client.subscribe_resource(uri).await;
+        self.observe(server, restarts, result)
+    }
+
+    pub async fn unsubscribe_resource(&self, uri: &str) -> Result<()> {
+        let (server, client) = self.resource_server(uri)?;
+        let restarts = client.restarts();
+        let result = client.unsubscribe_resource(uri).await;
+        self.observe(server, restarts, result)
+    }
+
+    /// Prompts of every healthy server that offers them, named like tools:
+    /// a namespaced server's as `{server}__{prompt}`.
+    pub async fn list_prompts(&self) -> Result<Vec<Prompt>> {
+        let listed = self
+            .gather(
+                "prompts",
+                |c| c.prompts.is_some(),
+                async |client| client.list_prompts().await,
+            )
+            .await?;
+        let mut prompts = Vec::new();
+        for (server, listed) in listed {
+            let config = &self.servers[server].config;
+            prompts.extend(listed.into_iter().map(|mut prompt| {
+                if config.namespaced {
+                    prompt.name = format!("{}{NAMESPACE_SEPARATOR}{}", config.name, prompt.name);
+                }
+                prompt
+            }));
+        }
+        Ok(prompts)
+    }
+
+    /// Render a prompt by the name [`list_prompts`](Self::list_prompts)
+    /// gave it.
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<GetPromptResult> {
+        let namespaced = name
+            .split_once(NAMESPACE_SEPARATOR)
+            .and_then(|(prefix, rest)| {
+                let server = self
+                    .servers
+                    .iter()
+                    .position(|s| s.config.namespaced && s.config.name == prefix)?;
+                Some((server, rest))
+            });
+        let (server, prompt) = match namespaced {
+            Some(found) => found,
+            None => {
+                let server = self
+                    .servers
+                    .iter()
+                    .position(|s| {
+                        !s.config.namespaced
+                            && s.healthy()
+                                .is_some_and(|c| c.server_capabilities().prompts.is_some())
+                    })
+                    .ok_or_else(|| anyhow!("No MCP server offers prompt `{name}`"))?;
+                (server, name)
+            }
+        };
+        let client = self.servers[server]
+            .healthy()
+            .ok_or_else(|| self.unavailable(server))?;
+        let restarts = client.restarts();
+        let result = client.get_prompt(prompt, arguments).await;
+        self.observe(server, restarts, result)
+    }
+
+    /// Kill every subprocess server and end every HTTP session.
+    pub fn kill(&mut self) -> Result<()> {
+        for server in &self.servers {
+            if let Some(client) = &server.client {
+                client.kill()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn route(&self, name: &str) -> Option<(usize, String)> {
+        let route = self.surface_read().routes.get(name).cloned()?;
+        self.servers[route.server].healthy()?;
+        Some((route.server, route.tool))
+    }
+
+    fn route_or_err(&self, name: &str) -> Result<(usize, String)> {
+        let surface = self.surface_read();
+        let route = surface
+            .routes
+            .get(name)
+            .ok_or_else(|| anyhow!("No MCP server provides tool `{name}`"))?;
+        Ok((route.server, route.tool.clone()))
+    }
+
+    fn unavailable(&self, server: usize) -> anyhow::Error {
+        let server = &self.servers[server];
+        let reason = server
+            .failure
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+            .unwrap_or_default();
+        anyhow!(
+            "MCP server `{}` is unavailable: {reason}",
+            server.config.name
+        )
+    }
+
+    /// Mark the server unhealthy when a call failed because it is gone,
+    /// unless its supervisor has since replaced it (`restarts` is the
+    /// count from before the call).
+    fn observe<T>(&self, server: usize, restarts: u64, result: Result<T>) -> Result<T> {
+        if let Err(e) = &result
+            && lost_connection(e)
+            && self.servers[server]
+                .client
+                .as_ref()
+                .is_none_or(|client| client.restarts() == restarts)
+        {
+            self.servers[server].mark_failed(e);
+        }
+        result
+    }
+
+    /// Run `list` on every healthy server whose capabilities pass
+    /// `declared`, pairing each result with the server's index.
+    async fn gather<T>(
+        &self,
+        what: &str,
+        declared: impl Fn(&ServerCapabilities) -> bool,
+        list: impl AsyncFn(&McpSupervisor) -> Result<Vec<T>>,
+    ) -> Result<Vec<(usize, Vec<T>)>> {
+        let mut gathered = Vec::new();
+        for (index, server) in self.servers.iter().enumerate() {
+            let Some(client) = server.healthy() else {
+                continue;
+            };
+            if !declared(&client.server_capabilities()) {
+                continue;
+            }
+            let restarts = client.restarts();
+            match self.observe(index, restarts, list(client).await) {
+                Ok(items) => gathered.push((index, items)),
+                Err(e) if server.config.required => {
+                    return Err(e.context(format!(
+                        "Failed to list {what} of MCP server `{}`",
+                        server.config.name
+                    )));
+                }
+                Err(e) => warn!(server = %server.config.name, "Skipping MCP {what}: {e:#}"),
+            }
+        }
+        Ok(gathered)
+    }
+
+    /// The server `uri` was seen on, or else the first that serves
+    /// resources.
+    fn resource_server(&self, uri: &str) -> Result<(usize, &McpSupervisor)> {
+        let owner = self
+            .resource_owners
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(uri)
+            .copied();
+        let server = match owner {
+            Some(server) => server,
+            None => self
+                .servers
+                .iter()
+                .position(|s| {
+                    s.healthy()
+                        .is_some_and(|c| c.server_capabilities().resources.is_some())
+                })
+                .ok_or_else(|| anyhow!("No MCP server serves resource `{uri}`"))?,
+        };
+        let client = self.servers[server]
+            .healthy()
+            .ok_or_else(|| self.unavailable(server))?;
+        Ok((server, client))
+    }
+
+    /// Remember which server linked or embedded each resource in `result`.
+    fn note_resource_links(&self, server: usize, result: &ToolCallResult) {
+        let uris: Vec<&str> = result
+            .content
+            .iter()
+            .filter_map(|content| match content {
+                ToolContent::ResourceLink(link) => Some(link.uri.as_str()),
+                ToolContent::Resource { resource } => Some(resource.uri.as_str()),
+                _ => None,
+            })
+            .collect();
+        if uris.is_empty() {
+            return;
+        }
+        let mut owners = self.owners_write();
+        for uri in uris {
+            owners.insert(uri.to_string(), server);
+        }
+    }
+
+    fn owners_write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, usize>> {
+        self.resource_owners
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Recompute exposed names and routes from the servers' tool caches.
+    /// A name already taken gets a numeric suffix, so no tool shadows
+    /// another.
+    fn rebuild_surface(&self) {
+        let mut surface = Surface {
+            generations: self.generations(),
+            ..Surface::default()
+        };
+        for (index, server) in self.servers.iter().enumerate() {
+            let Some(client) = server.client.as_ref() else {
+                continue;
+            };
+            for mut tool in client.tools_as_openai() {
+                let Some(original) = tool
+                    .pointer("/function/name")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                else {
+                    continue;
+                };
+                let base = if server.config.namespaced {
+                    format!("{}{NAMESPACE_SEPARATOR}{original}", server.config.name)
+                } else {
+                    original.clone()
+                };
+                let mut exposed = base.clone();
+                let mut n = 2;
+                while surface.routes.contains_key(&exposed) {
+                    exposed = format!("{base}_{n}");
+                    n += 1;
+                }
+                if exposed != base {
+                    warn!(
+                        server = %server.config.name,
+                        tool = %original,
+                        exposed = %exposed,
+                        "MCP tool name collides with another server's; renamed"
+                    );
+                }
+                tool["function"]["name"] = Value::String(exposed.clone());
+                surface.routes.insert(
+                    exposed,
+                    Route {
+                        server: index,
+                        tool: original,
+                    },
+                );
+                surface.tools.push((index, tool));
+            }
+        }
+        *self.surface.write().unwrap_or_else(|e| e.into_inner()) = surface;
+    }
+
+    /// The surface, rebuilt first if a server refreshed its tools on its
+    /// own (after `notifications/tools/list_changed`).
+    fn surface_read(&self) -> RwLockReadGuard<'_, Surface> {
+        let stale = {
+            let surface = self.surface.read().unwrap_or_else(|e| e.into_inner());
+            surface.generations != self.generations()
+        };
+        if stale {
+            self.rebuild_surface();
+        }
+        self.surface.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn generations(&self) -> Vec<Option<(u64, u64)>> {
+        self.servers
+            .iter()
+            .map(|server| server.client.as_ref().map(McpSupervisor::tools_generation))
+            .collect()
+    }
+}
+
+impl Drop for McpMultiplexer {
+    fn drop(&mut self) {
+        for forwarder in &self.forwarders {
+            forwarder.abort();
+        }
+    }
+}
+
+async fn forward_notifications(
+    mut from: broadcast::Receiver<McpNotification>,
+    to: broadcast::Sender<McpNotification>,
+    logger: Option<String>,
+) {
+    loop {
+        let notification = match from.recv().await {
+            Ok(notification) => notification,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        let notification = match notification {
+            McpNotification::Message(mut message) if message.logger.is_none() => {
+                message.logger = logger.clone();
+                McpNotification::Message(message)
+            }
+            other => other,
+        };
+        let _ = to.send(notification);
+    }
+}
+
+fn validate(configs: &[McpServerConfig]) -> Result<()> {
+    if configs.is_empty() {
+        bail!("No MCP servers configured");
+    }
+    for (index, config) in configs.iter().enumerate() {
+        let valid = !config.name.is_empty()
+            && config
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            bail!(
+                "Invalid MCP server name `{}`: use letters, digits, `-` and `_`",
+                config.name
+            );
+        }
+        if configs[..index].iter().any(|c| c.name == config.name) {
+            bail!("Duplicate MCP server name `{}`", config.name);
+        }
+    }
+    Ok(())
+}
+
+/// Whether a call failed because the server is gone, as opposed to the
+/// server answering with an error or running out of time.
+fn lost_connection(error: &anyhow::Error) -> bool {
+    !matches!(
+        error.downcast_ref::<McpError>(),
+        Some(McpError::Timeout { .. } | McpError::Protocol { .. })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{StubReply, result, stub_server, tool_server};
+    use serde_json::json;
+
+    async fn server(name: &str, tools: &[&str], namespaced: bool) -> McpServerConfig {
+        let label = name.to_string();
+        let (url, _) = tool_server(tools, move |tool, args| {
+            (tool != "crash").then(|| format!("{label}:{tool}:{args}"))
+        })
+        .await;
+        let endpoint = McpEndpoint::Url(url);
+        if namespaced {
+            McpServerConfig::namespaced(name, endpoint)
+        } else {
+            McpServerConfig::primary(name, endpoint)
+        }
+    }
+
+    fn text(result: &ToolCallResult) -> &str {
+        result.content[0].as_text().unwrap()
+    }
+
+    #[tokio::test]
+    async fn merges_tools_with_namespaces_and_routes_calls() {
+        let mux = McpMultiplexer::start(vec![
+            server("desktop", &["take_screenshot", "read_file"], false).await,
+            server("fs", &["read_file", "write_file"], true).await,
+        ])
+        .await
+        .unwrap();
+
+        let names: Vec<String> = mux
+            .tools_as_openai()
+            .iter()
+            .map(|t| t["function"]["name"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "take_screenshot",
+                "read_file",
+                "fs__read_file",
+                "fs__write_file"
+            ]
+        );
+        assert_eq!(mux.server_for_tool("fs__write_file"), Some("fs"));
+        assert_eq!(mux.server_for_tool("read_file"), Some("desktop"));
+
+        let result = mux
+            .call_tool("fs__read_file", Some(json!({"path": "/tmp/a"})))
+            .await
+            .unwrap();
+        assert_eq!(text(&result), r#"fs:read_file:{"path":"/tmp/a"}"#);
+        let result = mux.call_tool("read_file", None).await.unwrap();
+        assert_eq!(text(&result), "desktop:read_file:null");
+        assert!(mux.call_tool("fs__delete", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn collisions_get_a_numeric_suffix() {
+        let mux = McpMultiplexer::start(vec![
+            server("desktop", &["db__query"], false).await,
+            server("db", &["query"], true).await,
+        ])
+        .await
+        .unwrap();
+
+        assert!(mux.has_tool("db__query"));
+        assert_eq!(mux.server_for_tool("db__query"), Some("desktop"));
+        assert_eq!(mux.server_for_tool("db__query_2"), Some("db"));
+    }
+
+    #[tokio::test]
+    async fn servers_fail_independently() {
+        let mut dead =
+            McpServerConfig::namespaced("db", McpEndpoint::Url("http://127.0.0.1:1/mcp".into()));
+        let mux = McpMultiplexer::start(vec![
+            server("desktop", &["click"], false).await,
+            server("fs", &["read_file", "crash"], true).await,
+            dead.clone(),
+        ])
+        .await
+        .unwrap();
+
+        let health = mux.health();
+        assert!(health[0].healthy && health[1].healthy);
+        assert!(!health[2].healthy);
+        assert!(health[2].error.is_some());
+
+        // A lost connection takes the server's tools away, not the others'.
+        assert!(mux.call_tool("fs__crash", None).await.is_err());
+        assert!(!mux.has_tool("fs__read_file"));
+        assert_eq!(mux.tool_count(), 1);
+        let err = mux.call_tool("fs__read_file", None).await.unwrap_err();
+        assert!(err.to_string().contains("`fs` is unavailable"), "{err}");
+        assert!(mux.call_tool("click", None).await.is_ok());
+
+        // A required server that cannot start fails the whole startup.
+        dead.required = true;
+        let err = McpMultiplexer::start(vec![dead]).await.err().unwrap();
+        assert!(err.to_string().contains("`db` failed to start"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn restarts_are_counted_per_server() {
+        let crashing =
+            McpServerConfig::namespaced("aux", crate::test_support::crashing_stdio_server())
+                .with_restart(RestartPolicy {
+                    max_restarts: 1,
+                    backoff: Duration::ZERO,
+                });
+        let mux = McpMultiplexer::start(vec![server("desktop", &["click"], false).await, crashing])
+            .await
+            .unwrap();
+
+        mux.call_tool("aux__crash", None).await.unwrap_err();
+
+        let health = mux.health();
+        assert_eq!((health[0].restarts, health[1].restarts), (0, 1));
+        assert!(health[1].healthy);
+        assert_eq!(mux.primary_restarts(), 0);
+    }
+
+    /// A server serving resources and prompts that answer with its
+    /// `label`. Its `link` tool returns a link to `{label}://linked`.
+    async fn resource_server(label: &'static str) -> String {
+        let (url, _) = stub_server(move |request| {
+            let id = &request.body["id"];
+            let params = &request.body["params"];
+            let answer = match request.rpc_method() {
+                "initialize" => json!({
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {"resources": {}, "prompts": {}}
+                }),
+                "tools/list" => json!({"tools": [{"name": "link", "inputSchema": {}}]}),
+                "tools/call" => json!({"content": [{
+                    "type": "resource_link",
+                    "uri": format!("{label}://linked"),
+                    "name": "linked"
+                }]}),
+                "resources/list" => json!({"resources": [
+                    {"uri": format!("{label}://listed"), "name": "listed"}
+                ]}),
+                "resources/read" => json!({"contents": [
+                    {"uri": params["uri"], "text": format!("{label} read {}", params["uri"].as_str().unwrap())}
+                ]}),
+                "prompts/list" => json!({"prompts": [{"name": "summarize"}]}),
+                "prompts/get" => json!({"messages": [{"role": "user", "content": {
+                    "type": "text",
+                    "text": format!("{label} {}", params["name"].as_str().unwrap())
+                }}]}),
+                _ => return StubReply::Accepted,
+            };
+            StubReply::Json(result(id, answer))
+        })
+        .await;
+        url
+    }
+
+    #[tokio::test]
+    async fn routes_resources_to_their_server_and_namespaces_prompts() {
+        let mux = McpMultiplexer::start(vec![
+            McpServerConfig::primary("desktop", McpEndpoint::Url(resource_server("ax").await)),
+            McpServerConfig::namespaced("fs", McpEndpoint::Url(resource_server("file").await)),
+        ])
+        .await
+        .unwrap();
+        let read = async |uri: &str| {
+            let read = mux.read_resource(uri).await.unwrap();
+            read.contents[0].text.clone().unwrap()
+        };
+
+        // Unknown URIs go to the first server that serves resources.
+        assert_eq!(read("file://linked").await, "ax read file://linked");
+        let listed: Vec<String> = mux
+            .list_resources()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.uri)
+            .collect();
+        assert_eq!(listed, ["ax://listed", "file://listed"]);
+        assert_eq!(read("file://listed").await, "file read file://listed");
+        mux.call_tool("fs__link", None).await.unwrap();
+        assert_eq!(read("file://linked").await, "file read file://linked");
+
+        let prompts: Vec<String> = mux
+            .list_prompts()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(prompts, ["summarize", "fs__summarize"]);
+        for (name, rendered) in [
+            ("summarize", "ax summarize"),
+            ("fs__summarize", "file summarize"),
+        ] {
+            let prompt = mux.get_prompt(name, HashMap::new()).await.unwrap();
+            assert_eq!(prompt.messages[0].content.as_text(), Some(rendered));
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_unusable_server_names() {
+        let endpoint = McpEndpoint::Url("http://127.0.0.1:1/mcp".into());
+        for configs in [
+            vec![],
+            vec![McpServerConfig::namespaced("my db", endpoint.clone())],
+            vec![
+                McpServerConfig::namespaced("db", endpoint.clone()),
+                McpServerConfig::namespaced("db", endpoint.clone()),
+            ],
+        ] {
+            assert!(McpMultiplexer::start(configs).await.is_err());
+        }
+    }
+}
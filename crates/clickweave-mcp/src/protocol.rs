@@ -33,13 +33,46 @@ pub struct JsonRpcResponse {
     pub error: Option<JsonRpcError>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonRpcError {
     pub code: i64,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub data: Option<Value>,
 }
 
+impl JsonRpcError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(Self::INVALID_PARAMS, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(Self::INTERNAL_ERROR, message)
+    }
+}
+
+/// Protocol revisions spoken here, newest first. The client asks for the
+/// newest; the server offers it to a client asking for any other.
+pub(crate) const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// First revision with tool `outputSchema` and `structuredContent`,
+/// `resource_link` content and the `MCP-Protocol-Version` header.
+pub(crate) const STRUCTURED_CONTENT_VERSION: &str = "2025-06-18";
+
 /// MCP Initialize request params
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -72,21 +105,43 @@ pub struct InitializeResult {
     pub server_info: Option<ServerInfo>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Default)]
 pub struct ServerCapabilities {
     #[serde(default)]
     pub tools: Option<ToolsCapability>,
+    #[serde(default)]
+    pub resources: Option<ResourcesCapability>,
+    #[serde(default)]
+    pub prompts: Option<PromptsCapability>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ToolsCapability {
     #[serde(default)]
     pub list_changed: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesCapability {
+    /// The server accepts `resources/subscribe`.
+    #[serde(default)]
+    pub subscribe: Option<bool>,
+    #[serde(default)]
+    pub list_changed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptsCapability {
+    #[serde(default)]
+    pub list_changed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerInfo {
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
 }
 
@@ -95,10 +150,13 @@ pub struct ServerInfo {
 #[serde(rename_all = "camelCase")]
 pub struct Tool {
     pub name: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub input_schema: Value,
-    #[serde(default)]
+    /// JSON Schema for the tool's `structuredContent`, when it returns any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub annotations: Option<Value>,
 }
 
@@ -128,13 +186,13 @@ pub fn tools_to_openai(tools: &[Tool]) -> Vec<Value> {
 }
 
 /// MCP tools/list response
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ToolsListResult {
     pub tools: Vec<Tool>,
 }
 
 /// MCP tools/call request params
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ToolCallParams {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -142,12 +200,26 @@ pub struct ToolCallParams {
 }
 
 /// MCP tools/call response
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ToolCallResult {
     pub content: Vec<ToolContent>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
+    /// The result as JSON, for tools that declare an `outputSchema`.
+    /// Servers usually repeat it as a text block for older clients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+}
+
+impl ToolCallResult {
+    /// `structured_content` deserialized as `T`; `None` when the result
+    /// has none or it does not fit `T`.
+    pub fn structured_as<T: serde::de::DeserializeOwned>(&self) -> Option<T> {
+        self.structured_content
+            .as_ref()
+            .and_then(|value| T::deserialize(value).ok())
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -161,9 +233,18 @@ pub enum ToolContent {
         #[serde(rename = "mimeType")]
         mime_type: String,
     },
-    /// Any content type not explicitly modeled above (e.g. `resource`,
-    /// `audio`, `resource_link`). The raw JSON is preserved so callers can
-    /// inspect or log the payload instead of silently discarding it.
+    /// A resource the server can serve through `resources/read`, rather
+    /// than its contents.
+    #[serde(rename = "resource_link")]
+    ResourceLink(Resource),
+    /// Resource contents embedded in the result.
+    Resource {
+        resource: ResourceContents,
+    },
+    /// Any content type not explicitly modeled above (e.g. `audio`), or a
+    /// modeled one whose fields do not fit. The raw JSON is preserved so
+    /// callers can inspect or log the payload instead of silently
+    /// discarding it.
     #[serde(skip)]
     Unknown(Value),
 }
@@ -204,6 +285,20 @@ impl<'de> Deserialize<'de> for ToolContent {
                     .to_string();
                 Ok(ToolContent::Image { data, mime_type })
             }
+            Some("resource_link") => Ok(match Resource::deserialize(&value) {
+                Ok(link) => ToolContent::ResourceLink(link),
+                Err(_) => ToolContent::Unknown(value),
+            }),
+            Some("resource") => {
+                let embedded = value
+                    .get("resource")
+                    .map(ResourceContents::deserialize)
+                    .and_then(Result::ok);
+                Ok(match embedded {
+                    Some(resource) => ToolContent::Resource { resource },
+                    None => ToolContent::Unknown(value),
+                })
+            }
             _ => Ok(ToolContent::Unknown(value)),
         }
     }
@@ -227,6 +322,239 @@ impl ToolContent {
     }
 }
 
+/// A resource a server can serve, as listed by `resources/list` or
+/// linked from a tool result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    pub uri: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Size in bytes, when the server knows it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+/// `resources/list`, `prompts/list` request params.
+#[derive(Debug, Default, Serialize)]
+pub struct PaginatedParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// MCP resources/list response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesListResult {
+    pub resources: Vec<Resource>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+/// `resources/read`, `resources/subscribe` and `resources/unsubscribe`
+/// request params, and `notifications/resources/updated` params.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceParams {
+    pub uri: String,
+}
+
+/// MCP resources/read response
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReadResourceResult {
+    pub contents: Vec<ResourceContents>,
+}
+
+/// One resource's contents: `text`, or base64 `blob` for binary data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceContents {
+    pub uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+}
+
+/// A prompt template a server offers through `prompts/get`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Prompt {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PromptArgument {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// MCP prompts/list response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptsListResult {
+    pub prompts: Vec<Prompt>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+/// MCP prompts/get request params
+#[derive(Debug, Serialize)]
+pub struct GetPromptParams {
+    pub name: String,
+    #[serde(skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub arguments: std::collections::HashMap<String, String>,
+}
+
+/// MCP prompts/get response
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetPromptResult {
+    #[serde(default)]
+    pub description: Option<String>,
+    pub messages: Vec<PromptMessage>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PromptMessage {
+    /// `user` or `assistant`.
+    pub role: String,
+    pub content: ToolContent,
+}
+
+/// A server notification, as broadcast by
+/// [`McpClient::notifications`](crate::McpClient::notifications).
+#[derive(Debug, Clone, PartialEq)]
+pub enum McpNotification {
+    /// `notifications/tools/list_changed`. Broadcast once the client has
+    /// re-fetched its tool cache, so `has_tool` already reflects it.
+    ToolsListChanged,
+    /// `notifications/progress` for an in-flight request. `request` names
+    /// it (`tools/call click`) when the token matches one of ours.
+    Progress {
+        request: Option<String>,
+        progress: ProgressParams,
+    },
+    /// `notifications/message`: a log line from the server.
+    Message(LoggingMessageParams),
+    /// `notifications/resources/list_changed`.
+    ResourcesListChanged,
+    /// `notifications/resources/updated` for a subscribed resource;
+    /// `resources/read` gets its new contents.
+    ResourceUpdated { uri: String },
+    /// `notifications/prompts/list_changed`.
+    PromptsListChanged,
+    Other {
+        method: String,
+        params: Option<Value>,
+    },
+}
+
+impl McpNotification {
+    /// Classify a notification by method. Params that do not fit the
+    /// method's shape leave it as [`McpNotification::Other`].
+    pub fn parse(method: &str, params: Option<Value>) -> Self {
+        let typed = |params: &Option<Value>| params.clone().unwrap_or(Value::Null);
+        match method {
+            "notifications/tools/list_changed" => return Self::ToolsListChanged,
+            "notifications/resources/list_changed" => return Self::ResourcesListChanged,
+            "notifications/prompts/list_changed" => return Self::PromptsListChanged,
+            "notifications/resources/updated" => {
+                if let Ok(ResourceParams { uri }) = serde_json::from_value(typed(&params)) {
+                    return Self::ResourceUpdated { uri };
+                }
+            }
+            "notifications/progress" => {
+                if let Ok(progress) = serde_json::from_value(typed(&params)) {
+                    return Self::Progress {
+                        request: None,
+                        progress,
+                    };
+                }
+            }
+            "notifications/message" => {
+                if let Ok(message) = serde_json::from_value(typed(&params)) {
+                    return Self::Message(message);
+                }
+            }
+            _ => {}
+        }
+        Self::Other {
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+/// `notifications/progress` params.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressParams {
+    pub progress_token: Value,
+    pub progress: f64,
+    #[serde(default)]
+    pub total: Option<f64>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// `notifications/message` params.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LoggingMessageParams {
+    /// Syslog severity: `debug`, `info`, `notice`, `warning`, `error`, ...
+    pub level: String,
+    #[serde(default)]
+    pub logger: Option<String>,
+    #[serde(default)]
+    pub data: Value,
+}
+
+impl LoggingMessageParams {
+    /// `data` as display text: strings verbatim, anything else as JSON.
+    pub fn text(&self) -> String {
+        match &self.data {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// `elicitation/create` params: a question a server puts to the user
+/// through the client, answered with content matching
+/// `requested_schema` (a flat object schema of primitive fields).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElicitRequestParams {
+    pub message: String,
+    pub requested_schema: Value,
+}
+
+/// How the user answered an elicitation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitAction {
+    Accept,
+    Decline,
+    Cancel,
+}
+
+/// `elicitation/create` result. `content` is set when the user accepted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElicitResult {
+    pub action: ElicitAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<Value>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -388,6 +716,56 @@ mod tests {
         assert_eq!(result.content[0].as_text(), Some("Element not found"));
     }
 
+    #[test]
+    fn tool_call_result_structured_content() {
+        let raw = json!({
+            "content": [
+                {"type": "text", "text": "{\"screenshot_id\":\"ss-1\"}"}
+            ],
+            "structuredContent": {"screenshot_id": "ss-1"}
+        });
+
+        #[derive(Deserialize)]
+        struct Screenshot {
+            screenshot_id: String,
+        }
+
+        let result: ToolCallResult = serde_json::from_value(raw).unwrap();
+        assert_eq!(
+            result.structured_as::<Screenshot>().unwrap().screenshot_id,
+            "ss-1"
+        );
+        assert!(result.structured_as::<Vec<String>>().is_none());
+    }
+
+    #[test]
+    fn tool_output_schema_round_trips_and_stays_optional() {
+        let tool: Tool = serde_json::from_value(json!({
+            "name": "find_text",
+            "inputSchema": {"type": "object"},
+            "outputSchema": {"type": "object", "required": ["matches"]}
+        }))
+        .unwrap();
+        assert_eq!(
+            tool.output_schema,
+            Some(json!({"type": "object", "required": ["matches"]}))
+        );
+        assert_eq!(
+            serde_json::to_value(&tool).unwrap()["outputSchema"]["required"],
+            json!(["matches"])
+        );
+
+        let plain: Tool =
+            serde_json::from_value(json!({"name": "click", "inputSchema": {}})).unwrap();
+        assert!(plain.output_schema.is_none());
+        assert!(
+            serde_json::to_value(&plain)
+                .unwrap()
+                .get("outputSchema")
+                .is_none()
+        );
+    }
+
     #[test]
     fn tool_call_result_with_image_content() {
         let raw = json!({
@@ -446,6 +824,71 @@ mod tests {
         assert_eq!(payload["uri"], "file:///tmp/data.json");
     }
 
+    #[test]
+    fn tool_content_resource_link_and_embedded_resource() {
+        let raw = json!({
+            "content": [
+                {
+                    "type": "resource_link",
+                    "uri": "ax://snapshot/a1g3",
+                    "name": "AX tree",
+                    "mimeType": "text/plain"
+                },
+                {
+                    "type": "resource",
+                    "resource": {"uri": "screen://main", "mimeType": "image/png", "blob": "iVBO"}
+                }
+            ]
+        });
+
+        let result: ToolCallResult = serde_json::from_value(raw).unwrap();
+        let ToolContent::ResourceLink(link) = &result.content[0] else {
+            panic!("expected a resource link, got {:?}", result.content[0]);
+        };
+        assert_eq!(link.uri, "ax://snapshot/a1g3");
+        assert_eq!(link.mime_type.as_deref(), Some("text/plain"));
+        let ToolContent::Resource { resource } = &result.content[1] else {
+            panic!("expected an embedded resource, got {:?}", result.content[1]);
+        };
+        assert_eq!(resource.blob.as_deref(), Some("iVBO"));
+        assert_eq!(resource.text, None);
+
+        // Serialization keeps the wire tags.
+        let serialized = serde_json::to_value(&result.content[0]).unwrap();
+        assert_eq!(serialized["type"], "resource_link");
+        assert_eq!(serialized["mimeType"], "text/plain");
+    }
+
+    #[test]
+    fn read_resource_and_get_prompt_results_deserialize() {
+        let read: ReadResourceResult = serde_json::from_value(json!({
+            "contents": [{"uri": "ax://snapshot/a1g3", "mimeType": "text/plain", "text": "button OK"}]
+        }))
+        .unwrap();
+        assert_eq!(read.contents[0].text.as_deref(), Some("button OK"));
+
+        let prompt: GetPromptResult = serde_json::from_value(json!({
+            "description": "Fill a form",
+            "messages": [
+                {"role": "user", "content": {"type": "text", "text": "Fill the login form"}}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(prompt.messages[0].role, "user");
+        assert_eq!(
+            prompt.messages[0].content.as_text(),
+            Some("Fill the login form")
+        );
+
+        let listed: PromptsListResult = serde_json::from_value(json!({
+            "prompts": [{"name": "login", "arguments": [{"name": "user", "required": true}]}],
+            "nextCursor": "2"
+        }))
+        .unwrap();
+        assert!(listed.prompts[0].arguments[0].required);
+        assert_eq!(listed.next_cursor.as_deref(), Some("2"));
+    }
+
     // ── tools_to_openai conversion ──────────────────────────────────────
 
     #[test]
@@ -461,6 +904,7 @@ mod tests {
                         "y": {"type": "number"}
                     }
                 }),
+                output_schema: None,
                 annotations: None,
             },
             Tool {
@@ -472,6 +916,7 @@ mod tests {
                         "text": {"type": "string"}
                     }
                 }),
+                output_schema: None,
                 annotations: None,
             },
         ];
@@ -505,6 +950,7 @@ mod tests {
             name: "ping".to_string(),
             description: None,
             input_schema: json!({"type": "object"}),
+            output_schema: None,
             annotations: None,
         }];
 
@@ -524,6 +970,7 @@ mod tests {
             name: "quit_app".to_string(),
             description: Some("Quit an app".to_string()),
             input_schema: json!({"type": "object"}),
+            output_schema: None,
             annotations: Some(json!({
                 "readOnlyHint": false,
                 "destructiveHint": true,
@@ -582,4 +1029,113 @@ mod tests {
         assert_eq!(serialized["name"], "list_windows");
         assert!(serialized.get("arguments").is_none());
     }
+
+    // ── Notifications ───────────────────────────────────────────────────
+
+    #[test]
+    fn notifications_parse_by_method() {
+        assert_eq!(
+            McpNotification::parse("notifications/tools/list_changed", None),
+            McpNotification::ToolsListChanged
+        );
+
+        let progress = McpNotification::parse(
+            "notifications/progress",
+            Some(json!({"progressToken": 7, "progress": 2, "total": 4, "message": "Loading"})),
+        );
+        let McpNotification::Progress { request, progress } = progress else {
+            panic!("expected progress, got {progress:?}");
+        };
+        assert_eq!(request, None);
+        assert_eq!(progress.progress_token, json!(7));
+        assert_eq!((progress.progress, progress.total), (2.0, Some(4.0)));
+        assert_eq!(progress.message.as_deref(), Some("Loading"));
+
+        let McpNotification::Message(message) = McpNotification::parse(
+            "notifications/message",
+            Some(json!({"level": "warning", "logger": "cdp", "data": {"port": 9222}})),
+        ) else {
+            panic!("expected a log message");
+        };
+        assert_eq!(message.level, "warning");
+        assert_eq!(message.text(), r#"{"port":9222}"#);
+    }
+
+    #[test]
+    fn malformed_or_unknown_notifications_are_kept_raw() {
+        assert_eq!(
+            McpNotification::parse("notifications/progress", Some(json!({"progress": "half"}))),
+            McpNotification::Other {
+                method: "notifications/progress".into(),
+                params: Some(json!({"progress": "half"})),
+            }
+        );
+        assert!(matches!(
+            McpNotification::parse("notifications/resources/updated", None),
+            McpNotification::Other { .. }
+        ));
+    }
+
+    #[test]
+    fn resource_notifications_are_typed() {
+        assert_eq!(
+            McpNotification::parse(
+                "notifications/resources/updated",
+                Some(json!({"uri": "ax://snapshot/a1g3"}))
+            ),
+            McpNotification::ResourceUpdated {
+                uri: "ax://snapshot/a1g3".into()
+            }
+        );
+        assert_eq!(
+            McpNotification::parse("notifications/resources/list_changed", None),
+            McpNotification::ResourcesListChanged
+        );
+        assert_eq!(
+            McpNotification::parse("notifications/prompts/list_changed", None),
+            McpNotification::PromptsListChanged
+        );
+    }
+
+    // ── Elicitation ─────────────────────────────────────────────────────
+
+    #[test]
+    fn elicitation_round_trips_in_wire_shape() {
+        let params = ElicitRequestParams {
+            message: "Delete 3 files?".into(),
+            requested_schema: json!({"type": "object", "properties": {}}),
+        };
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({
+                "message": "Delete 3 files?",
+                "requestedSchema": {"type": "object", "properties": {}}
+            })
+        );
+
+        let accepted: ElicitResult =
+            serde_json::from_value(json!({"action": "accept", "content": {"approve": true}}))
+                .unwrap();
+        assert_eq!(accepted.action, ElicitAction::Accept);
+        assert_eq!(accepted.content, Some(json!({"approve": true})));
+        let declined: ElicitResult = serde_json::from_value(json!({"action": "decline"})).unwrap();
+        assert_eq!(declined.action, ElicitAction::Decline);
+        assert_eq!(declined.content, None);
+    }
+
+    #[test]
+    fn tool_call_result_serializes_without_unset_fields() {
+        let result = ToolCallResult {
+            content: vec![ToolContent::Text { text: "ok".into() }],
+            is_error: None,
+            structured_content: Some(json!({"sent": true})),
+        };
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            json!({
+                "content": [{"type": "text", "text": "ok"}],
+                "structuredContent": {"sent": true}
+            })
+        );
+    }
 }
@@ -0,0 +1,600 @@
+//! MCP over Streamable HTTP at `/mcp`, served with hyper.
+//!
+//! Each POST carries one client message. A request is answered with a
+//! single `application/json` body, unless the server has to ask the
+//! client something first (an elicitation): then the answer becomes a
+//! `text/event-stream` carrying the server's requests and, last, the
+//! response. Notifications and responses get `202 Accepted`.
+//! `initialize` opens a session whose id (`Mcp-Session-Id`) every later
+//! request must carry. `DELETE` ends it, and so does going unused for
+//! [`HttpServeOptions::session_idle_timeout`]. There is no standalone GET
+//! stream.
+//!
+//! Requests with an `Origin` that is neither a loopback host nor listed
+//! in [`HttpServeOptions::allowed_origins`] are refused, so a web page
+//! cannot drive the server. Bound to anything but a loopback address,
+//! the server also refuses requests that carry no `Origin` at all.
+
+use super::{Incoming, Session, ToolServer, is_response, response};
+use crate::JsonRpcError;
+use anyhow::Result;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Body, Bytes, Frame, Incoming as RequestBody};
+use hyper::header::{self, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+const MCP_PATH: &str = "/mcp";
+const SESSION_HEADER: &str = "mcp-session-id";
+
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+const DEFAULT_SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+type HttpBody = BoxBody<Bytes, Infallible>;
+
+/// How [`serve_http_with`] admits clients.
+#[derive(Debug, Clone)]
+pub struct HttpServeOptions {
+    /// Browser origins accepted besides loopback ones, such as
+    /// `https://tools.example.com`.
+    pub allowed_origins: Vec<String>,
+    /// End a session that has had no request for this long.
+    pub session_idle_timeout: Duration,
+}
+
+impl Default for HttpServeOptions {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            session_idle_timeout: DEFAULT_SESSION_IDLE_TIMEOUT,
+        }
+    }
+}
+
+struct SessionEntry<S> {
+    session: Arc<Session<S>>,
+    last_used: Instant,
+}
+
+struct Shared<S> {
+    server: Arc<S>,
+    sessions: Mutex<HashMap<String, SessionEntry<S>>>,
+    options: HttpServeOptions,
+    /// Bound to a non-loopback address: requests must name their origin.
+    require_origin: bool,
+}
+
+/// Serve `server` on `listener` with the default options. Each client
+/// gets its own session.
+pub async fn serve_http<S: ToolServer>(server: Arc<S>, listener: TcpListener) -> Result<()> {
+    serve_http_with(server, listener, HttpServeOptions::default()).await
+}
+
+/// Serve `server` on `listener`, admitting clients as `options` says.
+pub async fn serve_http_with<S: ToolServer>(
+    server: Arc<S>,
+    listener: TcpListener,
+    options: HttpServeOptions,
+) -> Result<()> {
+    let local = listener.local_addr()?;
+    info!("Serving MCP over HTTP at http://{local}{MCP_PATH}");
+    let require_origin = !local.ip().is_loopback();
+    if require_origin {
+        warn!(%local, "MCP HTTP server is not bound to loopback; requests must send an allowed Origin");
+    }
+    let shared = Arc::new(Shared {
+        server,
+        sessions: Mutex::default(),
+        options,
+        require_origin,
+    });
+    let mut sweep = tokio::time::interval(
+        (shared.options.session_idle_timeout / 4).max(Duration::from_millis(10)),
+    );
+    loop {
+        let (socket, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    // Usually out of file descriptors; retry once some close.
+                    warn!("MCP HTTP accept failed: {e}");
+                    tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                    continue;
+                }
+            },
+            _ = sweep.tick() => {
+                shared.expire_idle_sessions();
+                continue;
+            }
+        };
+        let shared = Arc::clone(&shared);
+        tokio::spawn(async move {
+            let service = service_fn(move |request| {
+                let shared = Arc::clone(&shared);
+                async move { Ok::<_, Infallible>(shared.handle(request).await) }
+            });
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(socket), service)
+                .await
+            {
+                debug!(%peer, "MCP HTTP connection ended: {e}");
+            }
+        });
+    }
+}
+
+impl<S: ToolServer> Shared<S> {
+    async fn handle(&self, request: Request<RequestBody>) -> Response<HttpBody> {
+        if request.uri().path() != MCP_PATH {
+            return status(StatusCode::NOT_FOUND, "not found");
+        }
+        let origin = request
+            .headers()
+            .get(header::ORIGIN)
+            .map(|origin| origin.to_str().unwrap_or_default());
+        if !self.origin_allowed(origin) {
+            return status(StatusCode::FORBIDDEN, "origin not allowed");
+        }
+
+        match *request.method() {
+            Method::POST => self.post(request).await,
+            Method::DELETE => {
+                let ended =
+                    session_id(&request).and_then(|id| self.sessions.lock().unwrap().remove(id));
+                match ended {
+                    Some(entry) => {
+                        entry.session.close();
+                        status(StatusCode::OK, "session ended")
+                    }
+                    None => status(StatusCode::NOT_FOUND, "unknown session"),
+                }
+            }
+            _ => {
+                let mut response = status(StatusCode::METHOD_NOT_ALLOWED, "");
+                response
+                    .headers_mut()
+                    .insert(header::ALLOW, HeaderValue::from_static("POST, DELETE"));
+                response
+            }
+        }
+    }
+
+    async fn post(&self, request: Request<RequestBody>) -> Response<HttpBody> {
+        let known_session = session_id(&request).map(str::to_string);
+        let body = match Limited::new(request.into_body(), MAX_BODY_BYTES)
+            .collect()
+            .await
+        {
+            Ok(body) => body.to_bytes(),
+            Err(e) => return status(StatusCode::BAD_REQUEST, &format!("bad request body: {e}")),
+        };
+        let incoming = serde_json::from_slice::<Value>(&body)
+            .map_err(|e| JsonRpcError::new(JsonRpcError::PARSE_ERROR, format!("parse error: {e}")))
+            .and_then(Incoming::parse);
+        let incoming = match incoming {
+            Ok(incoming) => incoming,
+            Err(error) => {
+                let body = response(Value::Null, Err(error)).to_string();
+                return json(StatusCode::BAD_REQUEST, None, body);
+            }
+        };
+
+        let opens_session =
+            matches!(&incoming, Incoming::Request { method, .. } if method == "initialize");
+        let (new_session, session) = if opens_session {
+            let id = uuid::Uuid::new_v4().to_string();
+            let session = Arc::new(Session::new(Arc::clone(&self.server)));
+            self.sessions.lock().unwrap().insert(
+                id.clone(),
+                SessionEntry {
+                    session: Arc::clone(&session),
+                    last_used: Instant::now(),
+                },
+            );
+            (Some(id), session)
+        } else {
+            let Some(id) = known_session else {
+                return status(StatusCode::BAD_REQUEST, "missing Mcp-Session-Id");
+            };
+            let mut sessions = self.sessions.lock().unwrap();
+            let Some(entry) = sessions.get_mut(&id) else {
+                return status(StatusCode::NOT_FOUND, "unknown session");
+            };
+            entry.last_used = Instant::now();
+            (None, Arc::clone(&entry.session))
+        };
+
+        match incoming {
+            Incoming::Request { id, method, params } => {
+                let (outgoing, mut messages) = mpsc::unbounded_channel();
+                // Stop the request if the client hangs up before its
+                // answer: hyper drops this future, or the event stream.
+                let abort = AbortOnDrop(session.spawn_request(id, method, params, outgoing));
+                let Some(first) = messages.recv().await else {
+                    return status(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "request ended without an answer",
+                    );
+                };
+                if is_response(&first) {
+                    return json(StatusCode::OK, new_session.as_deref(), first.to_string());
+                }
+                let events = EventStream {
+                    first: Some(first),
+                    messages,
+                    done: false,
+                    _session: session,
+                    _abort: abort,
+                };
+                let mut response = Response::new(events.boxed());
+                let headers = response.headers_mut();
+                headers.insert(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static("text/event-stream"),
+                );
+                headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+                insert_session(&mut response, new_session.as_deref());
+                response
+            }
+            Incoming::Notification { method, params } => {
+                session.notify(&method, params);
+                status(StatusCode::ACCEPTED, "")
+            }
+            Incoming::Response(reply) => {
+                session.deliver(reply);
+                status(StatusCode::ACCEPTED, "")
+            }
+        }
+    }
+
+    /// A loopback host or an allowed origin; absent only when bound to
+    /// loopback.
+    fn origin_allowed(&self, origin: Option<&str>) -> bool {
+        let Some(origin) = origin else {
+            return !self.require_origin;
+        };
+        self.options
+            .allowed_origins
+            .iter()
+            .any(|allowed| allowed.trim_end_matches('/') == origin)
+            || is_loopback_origin(origin)
+    }
+
+    /// End sessions unused for longer than the idle timeout. A session
+    /// with a request still streaming is held by it and kept.
+    fn expire_idle_sessions(&self) {
+        let timeout = self.options.session_idle_timeout;
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|id, entry| {
+            let idle =
+                entry.last_used.elapsed() >= timeout && Arc::strong_count(&entry.session) == 1;
+            if idle {
+                debug!(session = %id, "MCP HTTP session expired");
+                entry.session.close();
+            }
+            !idle
+        });
+    }
+}
+
+/// The event-stream answer: the server's requests, then the response.
+/// Dropping it (the client hung up) stops the request.
+struct EventStream<S> {
+    first: Option<Value>,
+    messages: mpsc::UnboundedReceiver<Value>,
+    done: bool,
+    /// Keeps the session from expiring while the request runs.
+    _session: Arc<Session<S>>,
+    _abort: AbortOnDrop,
+}
+
+impl<S> Body for EventStream<S> {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        let message = match self.first.take() {
+            Some(first) => first,
+            None => match self.messages.poll_recv(cx) {
+                Poll::Ready(Some(message)) => message,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            },
+        };
+        self.done = is_response(&message);
+        let event = Bytes::from(format!("data: {message}\n\n"));
+        Poll::Ready(Some(Ok(Frame::data(event))))
+    }
+}
+
+struct AbortOnDrop(tokio::task::AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+fn session_id<B>(request: &Request<B>) -> Option<&str> {
+    request
+        .headers()
+        .get(SESSION_HEADER)
+        .and_then(|id| id.to_str().ok())
+}
+
+fn is_loopback_origin(origin: &str) -> bool {
+    let Some((_, rest)) = origin.split_once("://") else {
+        return false;
+    };
+    let host = rest.split('/').next().unwrap_or_default();
+    let host = match host.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+fn status(code: StatusCode, text: &str) -> Response<HttpBody> {
+    let mut response = Response::new(full(text.to_string()));
+    *response.status_mut() = code;
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+    response
+}
+
+fn json(code: StatusCode, session_id: Option<&str>, body: String) -> Response<HttpBody> {
+    let mut response = Response::new(full(body));
+    *response.status_mut() = code;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    insert_session(&mut response, session_id);
+    response
+}
+
+fn full(body: String) -> HttpBody {
+    Full::new(Bytes::from(body)).boxed()
+}
+
+fn insert_session(response: &mut Response<HttpBody>, session_id: Option<&str>) {
+    if let Some(value) = session_id.and_then(|id| HeaderValue::from_str(id).ok()) {
+        response.headers_mut().insert(SESSION_HEADER, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_server::EchoServer;
+    use super::*;
+    use crate::McpClient;
+    use serde_json::json;
+
+    async fn start() -> String {
+        start_with("127.0.0.1:0", HttpServeOptions::default()).await
+    }
+
+    /// Serve on `bind`, returning the loopback URL to reach it at.
+    async fn start_with(bind: &str, options: HttpServeOptions) -> String {
+        let listener = TcpListener::bind(bind).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(serve_http_with(Arc::new(EchoServer), listener, options));
+        format!("http://127.0.0.1:{port}{MCP_PATH}")
+    }
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+    }
+
+    #[tokio::test]
+    async fn our_own_client_can_list_and_call_tools() {
+        let url = start().await;
+        let client = McpClient::connect_http(&url).await.unwrap();
+        assert!(client.has_tool("echo"));
+        let result = client
+            .call_tool("echo", Some(json!({"text": "over http"})))
+            .await
+            .unwrap();
+        assert_eq!(result.content[0].as_text(), Some("over http"));
+    }
+
+    #[tokio::test]
+    async fn elicitation_switches_the_answer_to_an_event_stream() {
+        let url = start().await;
+        let http = reqwest::Client::new();
+        let init = http
+            .post(&url)
+            .body(
+                (request(
+                    1,
+                    "initialize",
+                    json!({"protocolVersion": "2025-06-18", "capabilities": {"elicitation": {}}}),
+                ))
+                .to_string(),
+            )
+            .send()
+            .await
+            .unwrap();
+        let session = init.headers()[SESSION_HEADER].to_str().unwrap().to_string();
+
+        let mut call = http
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .body((request(2, "tools/call", json!({"name": "confirm"}))).to_string())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(call.headers()["content-type"], "text/event-stream");
+        let mut events = String::new();
+        let mut next_event = async || -> Value {
+            loop {
+                if let Some(end) = events.find("\n\n") {
+                    let event: String = events.drain(..end + 2).collect();
+                    let data = event.trim().strip_prefix("data: ").unwrap();
+                    return serde_json::from_str(data).unwrap();
+                }
+                let chunk = call.chunk().await.unwrap().unwrap();
+                events.push_str(std::str::from_utf8(&chunk).unwrap());
+            }
+        };
+
+        let ask = next_event().await;
+        assert_eq!(ask["method"], "elicitation/create");
+        let answered = http
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .body(
+                (json!({"jsonrpc": "2.0", "id": ask["id"], "result": {"action": "accept"}}))
+                    .to_string(),
+            )
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(answered.status(), 202);
+
+        let done = next_event().await;
+        assert_eq!(done["id"], 2);
+        assert_eq!(done["result"]["content"][0]["text"], "Accept");
+    }
+
+    #[tokio::test]
+    async fn requests_need_a_known_session_and_a_local_origin() {
+        let url = start().await;
+        let http = reqwest::Client::new();
+        let status = |request: reqwest::RequestBuilder| async move {
+            request.send().await.unwrap().status().as_u16()
+        };
+
+        let list = request(2, "tools/list", json!({}));
+        assert_eq!(status(http.post(&url).body(list.to_string())).await, 400);
+        assert_eq!(
+            status(
+                http.post(&url)
+                    .header(SESSION_HEADER, "nope")
+                    .body(list.to_string())
+            )
+            .await,
+            404
+        );
+        let init = request(1, "initialize", json!({"capabilities": {}}));
+        assert_eq!(
+            status(
+                http.post(&url)
+                    .header("origin", "https://evil.example")
+                    .body(init.to_string())
+            )
+            .await,
+            403
+        );
+        assert_eq!(
+            status(
+                http.post(&url)
+                    .header("origin", "http://localhost:1420")
+                    .body(init.to_string())
+            )
+            .await,
+            200
+        );
+        assert_eq!(status(http.get(&url)).await, 405);
+    }
+
+    #[tokio::test]
+    async fn a_chunked_request_body_is_read_whole() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let url = start().await;
+        let addr = url.trim_start_matches("http://").trim_end_matches(MCP_PATH);
+        let body = request(1, "initialize", json!({"capabilities": {}})).to_string();
+        let (front, back) = body.split_at(body.len() / 2);
+        let mut socket = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let raw = format!(
+            "POST {MCP_PATH} HTTP/1.1\r\nhost: {addr}\r\ntransfer-encoding: chunked\r\n\
+             connection: close\r\n\r\n{:x}\r\n{front}\r\n{:x}\r\n{back}\r\n0\r\n\r\n",
+            front.len(),
+            back.len()
+        );
+        socket.write_all(raw.as_bytes()).await.unwrap();
+        let mut reply = String::new();
+        socket.read_to_string(&mut reply).await.unwrap();
+
+        assert!(reply.starts_with("HTTP/1.1 200"), "{reply}");
+        assert!(reply.contains("\"serverInfo\""), "{reply}");
+    }
+
+    #[tokio::test]
+    async fn idle_sessions_expire() {
+        let url = start_with(
+            "127.0.0.1:0",
+            HttpServeOptions {
+                session_idle_timeout: Duration::from_millis(100),
+                ..HttpServeOptions::default()
+            },
+        )
+        .await;
+        let http = reqwest::Client::new();
+        let init = http
+            .post(&url)
+            .body(request(1, "initialize", json!({"capabilities": {}})).to_string())
+            .send()
+            .await
+            .unwrap();
+        let session = init.headers()[SESSION_HEADER].to_str().unwrap().to_string();
+        let list = || {
+            http.post(&url)
+                .header(SESSION_HEADER, &session)
+                .body(request(2, "tools/list", json!({})).to_string())
+                .send()
+        };
+        assert_eq!(list().await.unwrap().status(), 200);
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(list().await.unwrap().status(), 404);
+    }
+
+    #[tokio::test]
+    async fn off_loopback_every_request_needs_an_allowed_origin() {
+        let url = start_with(
+            "0.0.0.0:0",
+            HttpServeOptions {
+                allowed_origins: vec!["https://tools.example.com".into()],
+                ..HttpServeOptions::default()
+            },
+        )
+        .await;
+        let http = reqwest::Client::new();
+        let init = request(1, "initialize", json!({"capabilities": {}})).to_string();
+        let status = |origin: Option<&str>| {
+            let mut post = http.post(&url).body(init.clone());
+            if let Some(origin) = origin {
+                post = post.header("origin", origin);
+            }
+            async move { post.send().await.unwrap().status().as_u16() }
+        };
+
+        assert_eq!(status(None).await, 403);
+        assert_eq!(status(Some("https://evil.example")).await, 403);
+        assert_eq!(status(Some("https://tools.example.com")).await, 200);
+    }
+}
@@ -0,0 +1,367 @@
+//! Serving tools to other MCP clients.
+//!
+//! A [`ToolServer`] publishes a tool list and answers `tools/call`;
+//! [`serve_stdio`] and [`serve_http`] speak MCP on its behalf over
+//! newline-delimited stdio or Streamable HTTP. A client's requests run
+//! concurrently, and its `notifications/cancelled` stops the request it
+//! names. While a call runs, the tool can put a question to the user
+//! through the client with [`ClientPeer::elicit`].
+
+mod http;
+mod stdio;
+
+pub use http::{HttpServeOptions, serve_http, serve_http_with};
+pub use stdio::serve_stdio;
+
+use crate::McpError;
+use crate::protocol::*;
+use anyhow::{Result, bail};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::AbortHandle;
+use tracing::debug;
+
+/// The tools a server publishes and how it runs them.
+pub trait ToolServer: Send + Sync + 'static {
+    /// Reported to clients in `initialize`.
+    fn info(&self) -> ServerInfo;
+
+    /// The current tool list, served on every `tools/list`.
+    fn list_tools(&self) -> Vec<Tool>;
+
+    /// Run one tool. A tool that ran and failed answers with
+    /// `is_error: Some(true)`; `Err` is for calls that could not start,
+    /// such as an unknown tool or arguments that do not fit its schema.
+    fn call_tool(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+        client: ClientPeer,
+    ) -> impl Future<Output = Result<ToolCallResult, JsonRpcError>> + Send;
+}
+
+/// The client a request came from, for questions back to it while the
+/// request runs.
+#[derive(Clone)]
+pub struct ClientPeer {
+    state: Arc<SessionState>,
+    outgoing: mpsc::UnboundedSender<Value>,
+}
+
+impl ClientPeer {
+    /// Whether the client declared the `elicitation` capability.
+    pub fn supports_elicitation(&self) -> bool {
+        self.state.elicitation.load(Ordering::SeqCst)
+    }
+
+    /// Ask the user a question through the client and wait for the
+    /// answer. Fails when the client cannot elicit or goes away first.
+    pub async fn elicit(&self, params: ElicitRequestParams) -> Result<ElicitResult> {
+        if !self.supports_elicitation() {
+            bail!("the MCP client does not support elicitation");
+        }
+        let result = self
+            .request("elicitation/create", serde_json::to_value(params)?)
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.state.next_id.fetch_add(1, Ordering::SeqCst);
+        let (reply, response) = oneshot::channel();
+        self.state.pending.lock().unwrap().insert(id, reply);
+        let _forget = ForgetOnDrop {
+            state: &self.state,
+            id,
+        };
+        let request = serde_json::to_value(JsonRpcRequest::new(id, method, Some(params)))?;
+        self.outgoing
+            .send(request)
+            .map_err(|_| McpError::ConnectionClosed)?;
+        let response = response.await.map_err(|_| McpError::ConnectionClosed)?;
+        if let Some(error) = response.error {
+            return Err(McpError::Protocol {
+                code: error.code,
+                message: error.message,
+            }
+            .into());
+        }
+        Ok(response.result.unwrap_or(Value::Null))
+    }
+}
+
+/// Drops a server request's waiter once nobody awaits the answer.
+struct ForgetOnDrop<'a> {
+    state: &'a SessionState,
+    id: u64,
+}
+
+impl Drop for ForgetOnDrop<'_> {
+    fn drop(&mut self) {
+        self.state.pending.lock().unwrap().remove(&self.id);
+    }
+}
+
+#[derive(Default)]
+struct SessionState {
+    /// Set from the client's `initialize` capabilities.
+    elicitation: AtomicBool,
+    next_id: AtomicU64,
+    /// The server's own requests awaiting the client's response.
+    pending: Mutex<HashMap<u64, oneshot::Sender<JsonRpcResponse>>>,
+    /// The client's requests still running, by JSON-encoded id.
+    in_flight: Mutex<HashMap<String, AbortHandle>>,
+}
+
+/// A client message, by JSON-RPC shape.
+pub(crate) enum Incoming {
+    Request {
+        id: Value,
+        method: String,
+        params: Option<Value>,
+    },
+    Notification {
+        method: String,
+        params: Option<Value>,
+    },
+    Response(JsonRpcResponse),
+}
+
+impl Incoming {
+    pub(crate) fn parse(message: Value) -> Result<Self, JsonRpcError> {
+        let invalid =
+            || JsonRpcError::new(JsonRpcError::INVALID_REQUEST, "invalid JSON-RPC message");
+        let Value::Object(mut fields) = message else {
+            return Err(invalid());
+        };
+        match fields.remove("method") {
+            Some(Value::String(method)) => {
+                let params = fields.remove("params");
+                Ok(match fields.remove("id") {
+                    Some(id) => Self::Request { id, method, params },
+                    None => Self::Notification { method, params },
+                })
+            }
+            Some(_) => Err(invalid()),
+            None => serde_json::from_value(Value::Object(fields))
+                .map(Self::Response)
+                .map_err(|_| invalid()),
+        }
+    }
+}
+
+/// A JSON-RPC response to the request with `id`.
+pub(crate) fn response(id: Value, result: Result<Value, JsonRpcError>) -> Value {
+    match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": error}),
+    }
+}
+
+/// Whether an outgoing message is a response, as opposed to one of the
+/// server's own requests.
+pub(crate) fn is_response(message: &Value) -> bool {
+    message.get("method").is_none()
+}
+
+/// One client's connection: what it declared, the requests it has
+/// running and the server's requests awaiting its answers.
+pub(crate) struct Session<S> {
+    server: Arc<S>,
+    state: Arc<SessionState>,
+}
+
+impl<S: ToolServer> Session<S> {
+    pub(crate) fn new(server: Arc<S>) -> Self {
+        Self {
+            server,
+            state: Arc::new(SessionState::default()),
+        }
+    }
+
+    /// Answer a request on its own task. Messages for the client —
+    /// server requests made while it runs, then the response — go to
+    /// `outgoing`; a cancelled request sends no response.
+    pub(crate) fn spawn_request(
+        &self,
+        id: Value,
+        method: String,
+        params: Option<Value>,
+        outgoing: mpsc::UnboundedSender<Value>,
+    ) -> AbortHandle {
+        let key = id.to_string();
+        let server = Arc::clone(&self.server);
+        let state = Arc::clone(&self.state);
+        let mut in_flight = self.state.in_flight.lock().unwrap();
+        let task = tokio::spawn({
+            let key = key.clone();
+            async move {
+                let peer = ClientPeer {
+                    state: Arc::clone(&state),
+                    outgoing: outgoing.clone(),
+                };
+                let result = answer(&*server, &state, &method, params, peer).await;
+                state.in_flight.lock().unwrap().remove(&key);
+                let _ = outgoing.send(response(id, result));
+            }
+        });
+        in_flight.insert(key, task.abort_handle());
+        task.abort_handle()
+    }
+
+    pub(crate) fn notify(&self, method: &str, params: Option<Value>) {
+        match method {
+            "notifications/cancelled" => {
+                let Some(id) = params.as_ref().and_then(|p| p.get("requestId")) else {
+                    return;
+                };
+                if let Some(task) = self.state.in_flight.lock().unwrap().remove(&id.to_string()) {
+                    debug!(request = %id, "MCP client cancelled request");
+                    task.abort();
+                }
+            }
+            _ => debug!(method, "ignoring MCP client notification"),
+        }
+    }
+
+    /// Hand the client's response to the server request awaiting it.
+    pub(crate) fn deliver(&self, response: JsonRpcResponse) {
+        let waiter = response
+            .id
+            .and_then(|id| self.state.pending.lock().unwrap().remove(&id));
+        match waiter {
+            Some(waiter) => {
+                let _ = waiter.send(response);
+            }
+            None => debug!(id = ?response.id, "MCP client answered an unknown request"),
+        }
+    }
+
+    /// Stop every running request; pending server requests fail.
+    pub(crate) fn close(&self) {
+        for (_, task) in self.state.in_flight.lock().unwrap().drain() {
+            task.abort();
+        }
+        self.state.pending.lock().unwrap().clear();
+    }
+}
+
+async fn answer<S: ToolServer>(
+    server: &S,
+    state: &SessionState,
+    method: &str,
+    params: Option<Value>,
+    peer: ClientPeer,
+) -> Result<Value, JsonRpcError> {
+    match method {
+        "initialize" => Ok(initialize(server, state, params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => to_result(&ToolsListResult {
+            tools: server.list_tools(),
+        }),
+        "tools/call" => {
+            let params: ToolCallParams = params
+                .and_then(|p| serde_json::from_value(p).ok())
+                .ok_or_else(|| JsonRpcError::invalid_params("tools/call needs a tool `name`"))?;
+            let result = server
+                .call_tool(&params.name, params.arguments, peer)
+                .await?;
+            to_result(&result)
+        }
+        other => Err(JsonRpcError::new(
+            JsonRpcError::METHOD_NOT_FOUND,
+            format!("method not found: {other}"),
+        )),
+    }
+}
+
+fn initialize<S: ToolServer>(server: &S, state: &SessionState, params: Option<Value>) -> Value {
+    let params = params.unwrap_or(Value::Null);
+    let elicitation = params["capabilities"].get("elicitation").is_some();
+    state.elicitation.store(elicitation, Ordering::SeqCst);
+    let requested = params["protocolVersion"].as_str();
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| Some(**v) == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": {"tools": {"listChanged": false}},
+        "serverInfo": server.info(),
+    })
+}
+
+fn to_result(value: &impl serde::Serialize) -> Result<Value, JsonRpcError> {
+    serde_json::to_value(value).map_err(|e| JsonRpcError::internal(e.to_string()))
+}
+
+#[cfg(test)]
+pub(crate) mod test_server {
+    use super::*;
+
+    /// Serves `echo`, which returns its `text` argument, and `confirm`,
+    /// which asks the user to approve and reports the answer.
+    pub(crate) struct EchoServer;
+
+    impl ToolServer for EchoServer {
+        fn info(&self) -> ServerInfo {
+            ServerInfo {
+                name: "echo".into(),
+                version: Some("1.0".into()),
+            }
+        }
+
+        fn list_tools(&self) -> Vec<Tool> {
+            ["echo", "confirm"]
+                .into_iter()
+                .map(|name| Tool {
+                    name: name.into(),
+                    description: None,
+                    input_schema: json!({"type": "object"}),
+                    output_schema: None,
+                    annotations: None,
+                })
+                .collect()
+        }
+
+        async fn call_tool(
+            &self,
+            name: &str,
+            arguments: Option<Value>,
+            client: ClientPeer,
+        ) -> Result<ToolCallResult, JsonRpcError> {
+            let text = match name {
+                "echo" => arguments.unwrap_or(Value::Null)["text"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                "confirm" => {
+                    let answer = client
+                        .elicit(ElicitRequestParams {
+                            message: "Proceed?".into(),
+                            requested_schema: json!({"type": "object", "properties": {}}),
+                        })
+                        .await;
+                    match answer {
+                        Ok(answer) => format!("{:?}", answer.action),
+                        Err(e) => format!("no answer: {e}"),
+                    }
+                }
+                other => {
+                    return Err(JsonRpcError::invalid_params(format!(
+                        "unknown tool: {other}"
+                    )));
+                }
+            };
+            Ok(ToolCallResult {
+                content: vec![ToolContent::Text { text }],
+                is_error: None,
+                structured_content: None,
+            })
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! MCP over newline-delimited JSON-RPC on a byte stream pair, normally
+//! the process's own stdin and stdout.
+
+use super::{Incoming, Session, ToolServer, response};
+use crate::JsonRpcError;
+use anyhow::Result;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Serve `server` to the one client on `input`/`output` until it closes
+/// `input`. Requests still running then are stopped.
+pub async fn serve_stdio<S: ToolServer>(
+    server: Arc<S>,
+    input: impl AsyncRead + Unpin,
+    output: impl AsyncWrite + Unpin + Send + 'static,
+) -> Result<()> {
+    info!("Serving MCP over stdio");
+    let session = Session::new(server);
+    let (outgoing, queue) = mpsc::unbounded_channel();
+    let writer = tokio::spawn(write_lines(output, queue));
+
+    let mut lines = BufReader::new(input).lines();
+    let read = loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(e.into()),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let incoming = serde_json::from_str::<Value>(&line)
+            .map_err(|e| JsonRpcError::new(JsonRpcError::PARSE_ERROR, format!("parse error: {e}")))
+            .and_then(Incoming::parse);
+        match incoming {
+            Ok(Incoming::Request { id, method, params }) => {
+                session.spawn_request(id, method, params, outgoing.clone());
+            }
+            Ok(Incoming::Notification { method, params }) => session.notify(&method, params),
+            Ok(Incoming::Response(reply)) => session.deliver(reply),
+            Err(error) => {
+                let _ = outgoing.send(response(Value::Null, Err(error)));
+            }
+        }
+    };
+
+    session.close();
+    drop(outgoing);
+    let _ = writer.await;
+    read
+}
+
+async fn write_lines(
+    mut output: impl AsyncWrite + Unpin,
+    mut queue: mpsc::UnboundedReceiver<Value>,
+) {
+    while let Some(message) = queue.recv().await {
+        let mut line = message.to_string();
+        line.push('\n');
+        let written = async {
+            output.write_all(line.as_bytes()).await?;
+            output.flush().await
+        };
+        if let Err(e) = written.await {
+            warn!("MCP stdio client stopped reading: {e}");
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_server::EchoServer;
+    use super::*;
+    use serde_json::json;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+
+    /// A client on the other end of an in-memory pipe.
+    struct Client {
+        input: tokio::io::DuplexStream,
+        output: Lines<BufReader<tokio::io::DuplexStream>>,
+    }
+
+    impl Client {
+        fn start() -> (Self, tokio::task::JoinHandle<Result<()>>) {
+            let (client_in, server_in) = tokio::io::duplex(64 * 1024);
+            let (server_out, client_out) = tokio::io::duplex(64 * 1024);
+            let served = tokio::spawn(serve_stdio(Arc::new(EchoServer), server_in, server_out));
+            let client = Self {
+                input: client_in,
+                output: BufReader::new(client_out).lines(),
+            };
+            (client, served)
+        }
+
+        async fn send(&mut self, message: Value) {
+            let line = format!("{message}\n");
+            self.input.write_all(line.as_bytes()).await.unwrap();
+        }
+
+        async fn recv(&mut self) -> Value {
+            let line = self.output.next_line().await.unwrap().unwrap();
+            serde_json::from_str(&line).unwrap()
+        }
+    }
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+    }
+
+    #[tokio::test]
+    async fn answers_initialize_list_and_call() {
+        let (mut client, served) = Client::start();
+        client
+            .send(request(
+                1,
+                "initialize",
+                json!({"protocolVersion": "2025-03-26", "capabilities": {}}),
+            ))
+            .await;
+        let init = client.recv().await;
+        assert_eq!(init["id"], 1);
+        assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(init["result"]["serverInfo"]["name"], "echo");
+        assert!(init["result"]["capabilities"]["tools"].is_object());
+
+        client
+            .send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .await;
+        client.send(request(2, "tools/list", json!({}))).await;
+        let list = client.recv().await;
+        assert_eq!(list["result"]["tools"][0]["name"], "echo");
+
+        client
+            .send(request(
+                3,
+                "tools/call",
+                json!({"name": "echo", "arguments": {"text": "hi"}}),
+            ))
+            .await;
+        let call = client.recv().await;
+        assert_eq!(call["result"]["content"][0]["text"], "hi");
+
+        client
+            .send(request(4, "tools/call", json!({"name": "nope"})))
+            .await;
+        let unknown = client.recv().await;
+        assert_eq!(unknown["error"]["code"], JsonRpcError::INVALID_PARAMS);
+
+        client.send(request(5, "prompts/list", json!({}))).await;
+        let missing = client.recv().await;
+        assert_eq!(missing["error"]["code"], JsonRpcError::METHOD_NOT_FOUND);
+
+        drop(client);
+        served.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn elicitation_round_trips_through_the_client() {
+        let (mut client, _served) = Client::start();
+        client
+            .send(request(
+                1,
+                "initialize",
+                json!({"protocolVersion": "2025-06-18", "capabilities": {"elicitation": {}}}),
+            ))
+            .await;
+        client.recv().await;
+
+        client
+            .send(request(2, "tools/call", json!({"name": "confirm"})))
+            .await;
+        let ask = client.recv().await;
+        assert_eq!(ask["method"], "elicitation/create");
+        assert_eq!(ask["params"]["message"], "Proceed?");
+        client
+            .send(json!({"jsonrpc": "2.0", "id": ask["id"], "result": {"action": "decline"}}))
+            .await;
+        let call = client.recv().await;
+        assert_eq!(call["id"], 2);
+        assert_eq!(call["result"]["content"][0]["text"], "Decline");
+    }
+
+    #[tokio::test]
+    async fn clients_without_elicitation_are_not_asked() {
+        let (mut client, _served) = Client::start();
+        client
+            .send(request(1, "initialize", json!({"capabilities": {}})))
+            .await;
+        let init = client.recv().await;
+        assert_eq!(init["result"]["protocolVersion"], "2025-06-18");
+
+        client
+            .send(request(2, "tools/call", json!({"name": "confirm"})))
+            .await;
+        let call = client.recv().await;
+        assert_eq!(call["id"], 2);
+        let text = call["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.starts_with("no answer"), "{text}");
+    }
+
+    #[tokio::test]
+    async fn cancelled_requests_get_no_response() {
+        let (mut client, _served) = Client::start();
+        client
+            .send(request(
+                1,
+                "initialize",
+                json!({"capabilities": {"elicitation": {}}}),
+            ))
+            .await;
+        client.recv().await;
+
+        client
+            .send(request(2, "tools/call", json!({"name": "confirm"})))
+            .await;
+        let ask = client.recv().await;
+        client
+            .send(json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/cancelled",
+                "params": {"requestId": 2}
+            }))
+            .await;
+        client
+            .send(json!({"jsonrpc": "2.0", "id": ask["id"], "result": {"action": "accept"}}))
+            .await;
+        client.send(request(3, "ping", json!({}))).await;
+        let next = client.recv().await;
+        assert_eq!(next["id"], 3, "{next}");
+    }
+
+    #[tokio::test]
+    async fn malformed_lines_get_a_parse_error() {
+        let (mut client, _served) = Client::start();
+        client.input.write_all(b"{not json\n").await.unwrap();
+        let error = client.recv().await;
+        assert_eq!(error["id"], Value::Null);
+        assert_eq!(error["error"]["code"], JsonRpcError::PARSE_ERROR);
+    }
+}
@@ -0,0 +1,378 @@
+//! Restarting an MCP server that went away.
+//!
+//! [`McpSupervisor`] fronts one server like an [`McpClient`]. When the
+//! server is found gone — a call fails with an error for which
+//! [`McpError::server_gone`] holds, or the connection closed between
+//! calls — it starts a new one from the same endpoint, which
+//! re-initializes the session and re-fetches the tool list.
+//!
+//! The call that ran into the crash still fails: it may have had effects
+//! before the server died, so it is not retried. Calls after it go to the
+//! new server. Anything the old server held (a CDP session, snapshot
+//! ids) is gone with it; holders of such state watch
+//! [`restarts`](McpSupervisor::restarts) to know when to drop theirs.
+//! Resource subscriptions are the exception: they are renewed with the
+//! new server.
+
+use crate::client::NOTIFICATION_CAPACITY;
+use crate::{
+    GetPromptResult, McpClient, McpEndpoint, McpError, McpNotification, Prompt, ReadResourceResult,
+    Resource, ServerCapabilities, ToolCallResult,
+};
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// When to start a new server after the current one went away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// Restarts allowed over the supervisor's lifetime. `0` disables
+    /// supervision.
+    pub max_restarts: u32,
+    /// Pause before each restart, so a server that dies on startup is
+    /// not respawned in a tight loop.
+    pub backoff: Duration,
+}
+
+impl RestartPolicy {
+    pub const NEVER: Self = Self {
+        max_restarts: 0,
+        backoff: Duration::ZERO,
+    };
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 3,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+/// [`McpClient`]-shaped front for one server that is restarted under a
+/// [`RestartPolicy`] when it goes away.
+pub struct McpSupervisor {
+    endpoint: McpEndpoint,
+    policy: RestartPolicy,
+    current: RwLock<Arc<McpClient>>,
+    /// Held while restarting, so concurrent callers that saw the same
+    /// crash start one server between them.
+    restarting: tokio::sync::Mutex<()>,
+    restarts: AtomicU64,
+    notifications: broadcast::Sender<McpNotification>,
+    /// Relays the current client's notifications.
+    forwarder: Mutex<Option<JoinHandle<()>>>,
+    /// Resource URIs subscribed to, renewed after a restart.
+    subscriptions: Mutex<BTreeSet<String>>,
+}
+
+impl McpSupervisor {
+    /// Start the server and initialize it.
+    pub async fn start(endpoint: McpEndpoint, policy: RestartPolicy) -> Result<Self> {
+        let client = Arc::new(McpClient::connect(&endpoint).await?);
+        let (notifications, _) = broadcast::channel(NOTIFICATION_CAPACITY);
+        let supervisor = Self {
+            endpoint,
+            policy,
+            current: RwLock::new(Arc::clone(&client)),
+            restarting: tokio::sync::Mutex::new(()),
+            restarts: AtomicU64::new(0),
+            notifications,
+            forwarder: Mutex::new(None),
+            subscriptions: Mutex::new(BTreeSet::new()),
+        };
+        supervisor.forward_from(&client);
+        Ok(supervisor)
+    }
+
+    /// Times a new server has been started in place of a lost one.
+    pub fn restarts(&self) -> u64 {
+        self.restarts.load(Ordering::SeqCst)
+    }
+
+    /// Notifications from whichever server is current.
+    pub fn notifications(&self) -> broadcast::Receiver<McpNotification> {
+        self.notifications.subscribe()
+    }
+
+    pub async fn refresh_tools(&self) -> Result<()> {
+        let client = self.live_client().await?;
+        let result = client.refresh_tools().await;
+        self.after_failure(&client, result).await
+    }
+
+    /// Counts tool-list changes, restarts included.
+    pub(crate) fn tools_generation(&self) -> (u64, u64) {
+        (self.restarts(), self.client().tools_generation())
+    }
+
+    pub fn tool_count(&self) -> usize {
+        self.client().tool_count()
+    }
+
+    pub fn has_tool(&self, name: &str) -> bool {
+        self.client().has_tool(name)
+    }
+
+    pub fn tools_as_openai(&self) -> Vec<Value> {
+        self.client().tools_as_openai()
+    }
+
+    pub async fn call_tool(&self, name: &str, arguments: Option<Value>) -> Result<ToolCallResult> {
+        let client = self.live_client().await?;
+        let result = client.call_tool(name, arguments).await;
+        self.after_failure(&client, result).await
+    }
+
+    pub async fn call_tool_with_timeout(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+        timeout: Duration,
+    ) -> Result<ToolCallResult> {
+        let client = self.live_client().await?;
+        let result = client
+            .call_tool_with_timeout(name, arguments, timeout)
+            .await;
+        self.after_failure(&client, result).await
+    }
+
+    pub fn server_capabilities(&self) -> ServerCapabilities {
+        self.client().server_capabilities()
+    }
+
+    pub async fn list_resources(&self) -> Result<Vec<Resource>> {
+        let client = self.live_client().await?;
+        let result = client.list_resources().await;
+        self.after_failure(&client, result).await
+    }
+
+    pub async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult> {
+        let client = self.live_client().await?;
+        let result = client.read_resource(uri).await;
+        self.after_failure(&client, result).await
+    }
+
+    /// Subscribe to `uri`, with this server and any that replaces it.
+    pub async fn subscribe_resource(&self, uri: &str) -> Result<()> {
+        let client = self.live_client().await?;
+        let result = client.subscribe_resource(uri).await;
+        self.after_failure(&client, result).await?;
+        self.subscriptions_lock().insert(uri.to_string());
+        Ok(())
+    }
+
+    pub async fn unsubscribe_resource(&self, uri: &str) -> Result<()> {
+        self.subscriptions_lock().remove(uri);
+        let client = self.live_client().await?;
+        let result = client.unsubscribe_resource(uri).await;
+        self.after_failure(&client, result).await
+    }
+
+    pub async fn list_prompts(&self) -> Result<Vec<Prompt>> {
+        let client = self.live_client().await?;
+        let result = client.list_prompts().await;
+        self.after_failure(&client, result).await
+    }
+
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<GetPromptResult> {
+        let client = self.live_client().await?;
+        let result = client.get_prompt(name, arguments).await;
+        self.after_failure(&client, result).await
+    }
+
+    /// Kill the current server; it is not restarted.
+    pub fn kill(&self) -> Result<()> {
+        self.client().kill()
+    }
+
+    fn subscriptions_lock(&self) -> std::sync::MutexGuard<'_, BTreeSet<String>> {
+        self.subscriptions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn client(&self) -> Arc<McpClient> {
+        Arc::clone(&self.current.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// The current client, replaced first if its server died since the
+    /// last call.
+    async fn live_client(&self) -> Result<Arc<McpClient>> {
+        let client = self.client();
+        match client.connection_lost() {
+            Some(lost) if lost.server_gone() => {
+                self.restart(&client, lost).await?;
+                Ok(self.client())
+            }
+            _ => Ok(client),
+        }
+    }
+
+    /// Pass `result` through, restarting the server first if it failed
+    /// because the server is gone.
+    async fn after_failure<T>(&self, client: &Arc<McpClient>, result: Result<T>) -> Result<T> {
+        if let Err(e) = &result
+            && let Some(lost) = e.downcast_ref::<McpError>().filter(|e| e.server_gone())
+            && let Err(restart_error) = self.restart(client, lost.clone()).await
+        {
+            warn!("MCP server not restarted: {restart_error:#}");
+        }
+        result
+    }
+
+    /// Replace `failed` with a new server, unless another caller already
+    /// did or the policy's budget is spent.
+    async fn restart(&self, failed: &Arc<McpClient>, lost: McpError) -> Result<()> {
+        let _restarting = self.restarting.lock().await;
+        if !Arc::ptr_eq(&self.client(), failed) {
+            return Ok(());
+        }
+        let restarts = self.restarts();
+        if restarts >= u64::from(self.policy.max_restarts) {
+            return Err(anyhow::Error::from(lost).context(format!(
+                "MCP server not restarted: {restarts} of {} restarts used",
+                self.policy.max_restarts
+            )));
+        }
+        warn!("MCP server lost ({lost}); restarting");
+        tokio::time::sleep(self.policy.backoff).await;
+        let client = McpClient::connect(&self.endpoint)
+            .await
+            .map_err(|e| anyhow!("MCP server restart failed: {e:#}"))?;
+        // Relay first, so updates to renewed subscriptions are not missed.
+        self.forward_from(&client);
+        let subscriptions: Vec<String> = self.subscriptions_lock().iter().cloned().collect();
+        for uri in subscriptions {
+            if let Err(e) = client.subscribe_resource(&uri).await {
+                warn!(%uri, "MCP resource subscription not renewed after restart: {e:#}");
+            }
+        }
+        let client = Arc::new(client);
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = client;
+        let restarts = self.restarts.fetch_add(1, Ordering::SeqCst) + 1;
+        info!(restarts, "MCP server restarted");
+        Ok(())
+    }
+
+    fn forward_from(&self, client: &McpClient) {
+        let mut from = client.notifications();
+        let to = self.notifications.clone();
+        let forwarder = tokio::spawn(async move {
+            loop {
+                match from.recv().await {
+                    Ok(notification) => {
+                        let _ = to.send(notification);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        });
+        let previous = self
+            .forwarder
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(forwarder);
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+    }
+}
+
+impl Drop for McpSupervisor {
+    fn drop(&mut self) {
+        if let Some(forwarder) = self
+            .forwarder
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+        {
+            forwarder.abort();
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_support::crashing_stdio_server;
+
+    fn quick(max_restarts: u32) -> RestartPolicy {
+        RestartPolicy {
+            max_restarts,
+            backoff: Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn a_crashed_server_is_replaced_for_the_next_call() {
+        let supervisor = McpSupervisor::start(crashing_stdio_server(), quick(1))
+            .await
+            .unwrap();
+
+        // The call that crashed it fails; it is not replayed.
+        let err = supervisor.call_tool("crash", None).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<McpError>(),
+            Some(McpError::ServerExited { .. })
+        ));
+        assert_eq!(supervisor.restarts(), 1);
+        assert!(supervisor.has_tool("echo"));
+        let pong = supervisor.call_tool("echo", None).await.unwrap();
+        assert_eq!(pong.content[0].as_text(), Some("pong"));
+
+        // The budget is spent: the next crash is final.
+        supervisor.call_tool("crash", None).await.unwrap_err();
+        let err = supervisor.call_tool("echo", None).await.unwrap_err();
+        assert!(
+            format!("{err:#}").contains("1 of 1 restarts used"),
+            "{err:#}"
+        );
+        assert_eq!(supervisor.restarts(), 1);
+    }
+
+    #[tokio::test]
+    async fn resource_subscriptions_are_renewed_after_a_restart() {
+        let supervisor = McpSupervisor::start(crashing_stdio_server(), quick(1))
+            .await
+            .unwrap();
+        let mut notifications = supervisor.notifications();
+        let mut next = async || {
+            tokio::time::timeout(Duration::from_secs(5), notifications.recv())
+                .await
+                .unwrap()
+                .unwrap()
+        };
+        let updated = McpNotification::ResourceUpdated {
+            uri: "ax://tree".into(),
+        };
+
+        supervisor.subscribe_resource("ax://tree").await.unwrap();
+        assert_eq!(next().await, updated);
+
+        supervisor.call_tool("crash", None).await.unwrap_err();
+        assert_eq!(supervisor.restarts(), 1);
+        // The new server confirms the renewed subscription.
+        assert_eq!(next().await, updated);
+    }
+
+    #[tokio::test]
+    async fn without_a_budget_the_server_stays_down() {
+        let supervisor = McpSupervisor::start(crashing_stdio_server(), RestartPolicy::NEVER)
+            .await
+            .unwrap();
+        supervisor.call_tool("crash", None).await.unwrap_err();
+        supervisor.call_tool("echo", None).await.unwrap_err();
+        assert_eq!(supervisor.restarts(), 0);
+    }
+}
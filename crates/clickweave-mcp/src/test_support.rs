@@ -0,0 +1,206 @@
+//! In-process MCP server stubs for transport and multiplexer tests.
+
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// One HTTP request as the stub server saw it.
+#[derive(Debug, Clone)]
+pub(crate) struct StubRequest {
+    pub(crate) method: String,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Value,
+}
+
+impl StubRequest {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub(crate) fn rpc_method(&self) -> &str {
+        self.body["method"].as_str().unwrap_or_default()
+    }
+}
+
+pub(crate) enum StubReply {
+    Accepted,
+    Json(Value),
+    /// Raw event-stream body; the connection closes after it.
+    Events(String),
+    Status(u16),
+    /// Close the connection without answering.
+    Hangup,
+    /// Send the inner reply after a pause.
+    Delayed(std::time::Duration, Box<StubReply>),
+}
+
+/// In-process MCP server stub: every connection carries one request,
+/// answered by `handler`, and connections are served concurrently.
+/// Returns the endpoint URL and the requests received.
+pub(crate) async fn stub_server(
+    handler: impl Fn(&StubRequest) -> StubReply + Send + Sync + 'static,
+) -> (String, Arc<Mutex<Vec<StubRequest>>>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&requests);
+    let handler = Arc::new(handler);
+    tokio::spawn(async move {
+        loop {
+            let Ok((socket, _)) = listener.accept().await else {
+                return;
+            };
+            tokio::spawn(serve(socket, Arc::clone(&handler), Arc::clone(&log)));
+        }
+    });
+    (url, requests)
+}
+
+async fn serve(
+    mut socket: tokio::net::TcpStream,
+    handler: Arc<impl Fn(&StubRequest) -> StubReply>,
+    log: Arc<Mutex<Vec<StubRequest>>>,
+) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let (head, body) = loop {
+        let n = socket.read(&mut chunk).await.unwrap_or(0);
+        if n == 0 {
+            break (String::from_utf8_lossy(&buf).into_owned(), String::new());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        let text = String::from_utf8_lossy(&buf).into_owned();
+        if let Some(end) = text.find("\r\n\r\n") {
+            let length = text[..end]
+                .lines()
+                .find_map(|l| {
+                    l.to_ascii_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                })
+                .unwrap_or(0);
+            if buf.len() >= end + 4 + length {
+                break (text[..end].to_string(), text[end + 4..].to_string());
+            }
+        }
+    };
+    let mut lines = head.lines();
+    let method = lines
+        .next()
+        .and_then(|l| l.split(' ').next())
+        .unwrap_or_default()
+        .to_string();
+    let headers = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(n, v)| (n.trim().to_string(), v.trim().to_string()))
+        .collect();
+    let request = StubRequest {
+        method,
+        headers,
+        body: serde_json::from_str(&body).unwrap_or(Value::Null),
+    };
+    let mut reply = handler(&request);
+    log.lock().unwrap().push(request);
+    while let StubReply::Delayed(delay, then) = reply {
+        tokio::time::sleep(delay).await;
+        reply = *then;
+    }
+    let (status, content_type, body) = match reply {
+        StubReply::Accepted => (202, "application/json", String::new()),
+        StubReply::Json(value) => (200, "application/json", value.to_string()),
+        StubReply::Events(body) => (200, "text/event-stream", body),
+        StubReply::Status(code) => (code, "text/plain", "unavailable".into()),
+        StubReply::Hangup | StubReply::Delayed(..) => return,
+    };
+    let response = format!(
+        "HTTP/1.1 {status} Stub\r\ncontent-type: {content_type}\r\n\
+         mcp-session-id: session-1\r\ncontent-length: {}\r\n\
+         connection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = socket.write_all(response.as_bytes()).await;
+}
+
+pub(crate) fn result(id: &Value, result: Value) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "result": result})
+}
+
+pub(crate) fn event(id: Option<&str>, message: &Value) -> String {
+    match id {
+        Some(id) => format!("id: {id}\ndata: {message}\n\n"),
+        None => format!("data: {message}\n\n"),
+    }
+}
+
+/// A plain MCP server over HTTP: answers `initialize`, lists `tools`
+/// (names only) and replies to `tools/call` with `on_call(name,
+/// arguments)` as a single text item, or hangs up when that is `None`.
+pub(crate) async fn tool_server(
+    tools: &[&str],
+    on_call: impl Fn(&str, &Value) -> Option<String> + Send + Sync + 'static,
+) -> (String, Arc<Mutex<Vec<StubRequest>>>) {
+    let tools: Vec<Value> = tools
+        .iter()
+        .map(|name| json!({"name": name, "inputSchema": {"type": "object"}}))
+        .collect();
+    stub_server(move |request| {
+        let id = &request.body["id"];
+        match request.rpc_method() {
+            "initialize" => StubReply::Json(result(
+                id,
+                json!({"protocolVersion": "2025-06-18", "capabilities": {}}),
+            )),
+            "tools/list" => StubReply::Json(result(id, json!({ "tools": tools }))),
+            "tools/call" => {
+                let params = &request.body["params"];
+                match on_call(
+                    params["name"].as_str().unwrap_or_default(),
+                    &params["arguments"],
+                ) {
+                    Some(text) => StubReply::Json(result(
+                        id,
+                        json!({"content": [{"type": "text", "text": text}]}),
+                    )),
+                    None => StubReply::Hangup,
+                }
+            }
+            _ => StubReply::Accepted,
+        }
+    })
+    .await
+}
+
+/// A plain MCP server over stdio, as a shell script: lists `echo` and
+/// `crash`; `echo` answers `pong` and `crash` writes `segfault` to
+/// stderr and exits with status 139. A `resources/subscribe` is answered
+/// with an immediate `notifications/resources/updated` for its URI.
+#[cfg(unix)]
+pub(crate) fn crashing_stdio_server() -> crate::McpEndpoint {
+    const SCRIPT: &str = r#"
+reply() { printf '{"jsonrpc":"2.0","id":%s,"result":%s}\n' "$1" "$2"; }
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"method":"initialize"'*)
+      reply "$id" '{"protocolVersion":"2025-06-18","capabilities":{}}' ;;
+    *'"method":"tools/list"'*)
+      reply "$id" '{"tools":[{"name":"echo","inputSchema":{}},{"name":"crash","inputSchema":{}}]}' ;;
+    *'"method":"resources/subscribe"'*)
+      uri=$(printf '%s' "$line" | sed -n 's/.*"uri":"\([^"]*\)".*/\1/p')
+      printf '{"jsonrpc":"2.0","method":"notifications/resources/updated","params":{"uri":"%s"}}\n' "$uri"
+      reply "$id" '{}' ;;
+    *'"name":"crash"'*)
+      echo segfault >&2; exit 139 ;;
+    *'"method":"tools/call"'*)
+      reply "$id" '{"content":[{"type":"text","text":"pong"}]}' ;;
+  esac
+done
+"#;
+    crate::McpEndpoint::Command {
+        command: "sh".to_string(),
+        args: vec!["-c".to_string(), SCRIPT.to_string()],
+    }
+}
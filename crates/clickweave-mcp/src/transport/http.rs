@@ -0,0 +1,646 @@
+//! MCP Streamable HTTP transport.
+//!
+//! Every client message is POSTed to the server's single endpoint. The
+//! server answers a request either with one `application/json` body or
+//! with a `text/event-stream` whose events carry the response, optionally
+//! preceded by notifications; notifications and responses sent by the
+//! client get `202 Accepted`. The session id the server assigns on
+//! `initialize` (`Mcp-Session-Id`) is echoed on every later request, along
+//! with the negotiated `MCP-Protocol-Version`. A server that no longer
+//! knows the session answers 404; the transport then replays the
+//! `initialize` handshake for a new session and sends the request again.
+//!
+//! When an event stream drops before it delivered its response, the
+//! transport resumes it with a GET carrying `Last-Event-ID`, as long as
+//! the server numbered its events. A stream that cannot be resumed fails
+//! only the request it was answering, with a JSON-RPC error response.
+
+use crate::McpError;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tokio::sync::{Mutex, mpsc};
+use tokio::task::AbortHandle;
+use tracing::{debug, info, trace, warn};
+
+const SESSION_HEADER: &str = "mcp-session-id";
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+const ACCEPT_BOTH: &str = "application/json, text/event-stream";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_RECONNECTS: u32 = 3;
+const RECONNECT_DELAY: Duration = Duration::from_millis(250);
+/// JSON-RPC error code for a request whose response stream was lost; in
+/// the range the spec leaves to implementations.
+const STREAM_LOST_CODE: i64 = -32000;
+
+/// What a stream task needs to resume its stream and hand messages back.
+#[derive(Clone)]
+struct Link {
+    http: reqwest::Client,
+    url: String,
+    session: Arc<StdMutex<Option<String>>>,
+    /// Bumped each time the session is re-established, so requests that
+    /// hit the same expired session re-initialize it only once.
+    session_epoch: Arc<AtomicU64>,
+    /// Held while the session is re-established.
+    reinitializing: Arc<Mutex<()>>,
+    /// The client's `initialize` request, replayed for a new session.
+    initialize: Arc<StdMutex<Option<String>>>,
+    /// Negotiated in `initialize`; sent on every request after it.
+    protocol_version: Arc<StdMutex<Option<String>>>,
+    inbox: mpsc::UnboundedSender<Result<Value>>,
+}
+
+impl Link {
+    fn session(&self) -> Option<String> {
+        self.session.lock().unwrap().clone()
+    }
+
+    /// Add the session id and protocol version, once there are any.
+    fn with_session(&self, mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if let Some(id) = self.session() {
+            request = request.header(SESSION_HEADER, id);
+        }
+        if let Some(version) = self.protocol_version.lock().unwrap().clone() {
+            request = request.header(PROTOCOL_VERSION_HEADER, version);
+        }
+        request
+    }
+
+    fn deliver(&self, value: Value) {
+        trace!("MCP response: {}", value);
+        let _ = self.inbox.send(Ok(value));
+    }
+
+    /// Answer request `id` with a JSON-RPC error, failing that request
+    /// alone rather than the whole connection.
+    fn fail(&self, id: &Value, message: String) {
+        warn!(%id, "{message}");
+        self.deliver(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": STREAM_LOST_CODE, "message": message },
+        }));
+    }
+
+    /// POST `json` with the session headers.
+    async fn post_message(&self, json: &str) -> Result<reqwest::Response> {
+        let request = self
+            .http
+            .post(&self.url)
+            .header(reqwest::header::ACCEPT, ACCEPT_BOTH)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(json.to_string());
+        let response = self
+            .with_session(request)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach MCP server at {}", self.url))?;
+        self.record_session(&response);
+        Ok(response)
+    }
+
+    fn record_session(&self, response: &reqwest::Response) {
+        let Some(id) = response
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        else {
+            return;
+        };
+        let mut session = self.session.lock().unwrap();
+        if session.as_deref() != Some(id) {
+            debug!(session = id, "MCP HTTP session established");
+            *session = Some(id.to_string());
+        }
+    }
+
+    /// Replace the session from `epoch`, which the server no longer knows,
+    /// by replaying the `initialize` handshake. Requests that found the
+    /// same session gone wait for the first one to do it.
+    async fn reinitialize(&self, epoch: u64) -> Result<()> {
+        let _guard = self.reinitializing.lock().await;
+        if self.session_epoch.load(Ordering::SeqCst) != epoch {
+            return Ok(());
+        }
+        let initialize = self
+            .initialize
+            .lock()
+            .unwrap()
+            .clone()
+            .context("MCP session expired before initialize")?;
+        info!("MCP HTTP session expired; re-initializing");
+        *self.session.lock().unwrap() = None;
+        let response = self.post_message(&initialize).await?;
+        let status = response.status();
+        // The handshake's response belongs to no pending request.
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(McpError::Http {
+                status: status.as_u16(),
+                body: body.chars().take(200).collect(),
+            }
+            .into());
+        }
+        self.post_message(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .await?;
+        self.session_epoch.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+pub(crate) struct HttpTransport {
+    link: Link,
+    inbox: Mutex<mpsc::UnboundedReceiver<Result<Value>>>,
+    streams: Arc<StdMutex<Vec<AbortHandle>>>,
+}
+
+impl HttpTransport {
+    pub(crate) fn connect(url: &str) -> Result<Self> {
+        info!("Connecting to MCP server at {}", url);
+        let http = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .context("Failed to build MCP HTTP client")?;
+        let (tx, rx) = mpsc::unbounded_channel();
+        Ok(Self {
+            link: Link {
+                http,
+                url: url.to_string(),
+                session: Arc::new(StdMutex::new(None)),
+                session_epoch: Arc::new(AtomicU64::new(0)),
+                reinitializing: Arc::new(Mutex::new(())),
+                initialize: Arc::new(StdMutex::new(None)),
+                protocol_version: Arc::new(StdMutex::new(None)),
+                inbox: tx,
+            },
+            inbox: Mutex::new(rx),
+            streams: Arc::new(StdMutex::new(Vec::new())),
+        })
+    }
+
+    /// POST one message. The exchange runs on its own task, so a caller
+    /// that stops waiting cannot cut a request off halfway; its response
+    /// still reaches the inbox.
+    pub(crate) async fn send(&self, json: &str) -> Result<()> {
+        let exchange = tokio::spawn(post(
+            self.link.clone(),
+            Arc::clone(&self.streams),
+            json.to_string(),
+        ));
+        exchange.await.context("MCP HTTP exchange task failed")?
+    }
+
+    /// Send `version` as `MCP-Protocol-Version` from now on.
+    pub(crate) fn set_protocol_version(&self, version: &str) {
+        *self.link.protocol_version.lock().unwrap() = Some(version.to_string());
+    }
+
+    pub(crate) async fn recv(&self) -> Result<Value> {
+        self.inbox
+            .lock()
+            .await
+            .recv()
+            .await
+            .unwrap_or_else(|| Err(McpError::ConnectionClosed.into()))
+    }
+
+    /// Stop all event streams and end the server session.
+    pub(crate) fn close(&self) {
+        for handle in self.streams.lock().unwrap().drain(..) {
+            handle.abort();
+        }
+        let Some(session) = self.link.session.lock().unwrap().take() else {
+            return;
+        };
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let request = self
+                .link
+                .http
+                .delete(&self.link.url)
+                .header(SESSION_HEADER, session);
+            runtime.spawn(async move {
+                if let Err(e) = request.send().await {
+                    debug!("MCP session DELETE failed: {e}");
+                }
+            });
+        }
+    }
+}
+
+impl Drop for HttpTransport {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// One POST exchange: send `json`, record the session id, and route the
+/// reply (a JSON body or an event stream) to the inbox.
+async fn post(link: Link, streams: Arc<StdMutex<Vec<AbortHandle>>>, json: String) -> Result<()> {
+    let message: Value = serde_json::from_str(&json).context("Invalid outgoing MCP message")?;
+    // Only requests get a reply; notifications and responses are
+    // acknowledged with 202.
+    let awaiting = message
+        .get("method")
+        .and(message.get("id"))
+        .cloned()
+        .filter(|id| !id.is_null());
+    let is_initialize = message.get("method").and_then(Value::as_str) == Some("initialize");
+    if is_initialize {
+        *link.initialize.lock().unwrap() = Some(json.clone());
+    }
+
+    let epoch = link.session_epoch.load(Ordering::SeqCst);
+    let had_session = link.session().is_some();
+    let mut response = link.post_message(&json).await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND && had_session && !is_initialize {
+        link.reinitialize(epoch).await?;
+        response = link.post_message(&json).await?;
+    }
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(McpError::Http {
+            status: status.as_u16(),
+            body: body.chars().take(200).collect(),
+        }
+        .into());
+    }
+    if status == reqwest::StatusCode::ACCEPTED || awaiting.is_none() {
+        return Ok(());
+    }
+
+    let is_stream = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"));
+    if is_stream {
+        let task = tokio::spawn(pump_events(link, response, awaiting));
+        let mut streams = streams.lock().unwrap();
+        streams.retain(|handle| !handle.is_finished());
+        streams.push(task.abort_handle());
+    } else {
+        let body = response
+            .text()
+            .await
+            .context("Failed to read MCP HTTP response")?;
+        match serde_json::from_str(&body).context("Malformed MCP HTTP response")? {
+            Value::Array(batch) => batch.into_iter().for_each(|v| link.deliver(v)),
+            value => link.deliver(value),
+        }
+    }
+    Ok(())
+}
+
+/// Forward the messages of one event stream to the inbox until the
+/// response to `awaiting` arrives, resuming the stream if it drops first.
+async fn pump_events(link: Link, mut response: reqwest::Response, awaiting: Option<Value>) {
+    let mut parser = SseParser::default();
+    let mut last_event_id: Option<String> = None;
+    let mut reconnects = 0u32;
+    loop {
+        let mut answered = false;
+        loop {
+            let bytes = match response.chunk().await {
+                Ok(Some(bytes)) => bytes,
+                Ok(None) => break,
+                Err(e) => {
+                    warn!("MCP event stream interrupted: {e}");
+                    break;
+                }
+            };
+            for event in parser.feed(&bytes) {
+                if event.id.is_some() {
+                    last_event_id = event.id;
+                }
+                if event.data.is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<Value>(&event.data) {
+                    Ok(value) => {
+                        answered |= value.get("method").is_none()
+                            && awaiting.is_some()
+                            && value.get("id") == awaiting.as_ref();
+                        link.deliver(value);
+                    }
+                    Err(e) => warn!("Non-JSON MCP event (skipping): {e}"),
+                }
+            }
+            if answered {
+                return;
+            }
+        }
+
+        // Only requests are answered over a stream, so there is an id.
+        let id = awaiting.clone().unwrap_or_default();
+        let Some(resume_from) = last_event_id.clone() else {
+            link.fail(
+                &id,
+                "MCP event stream dropped before the response and cannot be resumed".to_string(),
+            );
+            return;
+        };
+        parser = SseParser::default();
+        response = loop {
+            reconnects += 1;
+            if reconnects > MAX_RECONNECTS {
+                link.fail(
+                    &id,
+                    format!(
+                        "MCP event stream dropped and could not be resumed after {MAX_RECONNECTS} attempts"
+                    ),
+                );
+                return;
+            }
+            tokio::time::sleep(RECONNECT_DELAY * reconnects).await;
+            debug!(last_event_id = %resume_from, "Resuming MCP event stream");
+            let request = link
+                .http
+                .get(&link.url)
+                .header(reqwest::header::ACCEPT, "text/event-stream")
+                .header(LAST_EVENT_ID_HEADER, &resume_from);
+            match link.with_session(request).send().await {
+                Ok(resumed) if resumed.status().is_success() => break resumed,
+                Ok(resumed) => warn!(status = %resumed.status(), "MCP stream resume refused"),
+                Err(e) => warn!("MCP stream resume failed: {e}"),
+            }
+        };
+    }
+}
+
+/// One dispatched Server-Sent Event.
+#[derive(Debug, PartialEq)]
+struct SseEvent {
+    id: Option<String>,
+    data: String,
+}
+
+/// Splits an event-stream body into events. `event:` and `retry:` fields
+/// and `:` comments are ignored.
+#[derive(Default)]
+struct SseParser {
+    /// Bytes received after the last complete line.
+    pending: Vec<u8>,
+    data: Vec<String>,
+    id: Option<String>,
+}
+
+impl SseParser {
+    fn feed(&mut self, bytes: &[u8]) -> Vec<SseEvent> {
+        self.pending.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        while let Some(newline) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.is_empty() {
+                if !self.data.is_empty() || self.id.is_some() {
+                    events.push(SseEvent {
+                        id: self.id.take(),
+                        data: std::mem::take(&mut self.data).join("\n"),
+                    });
+                }
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "data" => self.data.push(value.to_string()),
+                "id" => self.id = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::McpClient;
+    use crate::test_support::*;
+    use serde_json::json;
+
+    #[test]
+    fn sse_parser_joins_data_lines_and_tracks_ids() {
+        let mut parser = SseParser::default();
+        let mut events = parser.feed(b": keep-alive\n\nid: 7\nevent: message\ndata: {\"a\":");
+        assert!(events.is_empty());
+        events.extend(parser.feed(b"\r\ndata: 1}\r\n\r\ndata:x\n\n"));
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    id: Some("7".into()),
+                    data: "{\"a\":\n1}".into(),
+                },
+                SseEvent {
+                    id: None,
+                    data: "x".into(),
+                },
+            ]
+        );
+    }
+
+    /// Answers `initialize` with JSON and `tools/list` over an event
+    /// stream; everything without an id is accepted.
+    fn handshake(request: &StubRequest) -> Option<StubReply> {
+        let id = &request.body["id"];
+        match request.rpc_method() {
+            "initialize" => Some(StubReply::Json(result(
+                id,
+                json!({"protocolVersion": "2025-06-18", "capabilities": {}}),
+            ))),
+            "tools/list" => Some(StubReply::Events(event(
+                None,
+                &result(
+                    id,
+                    json!({"tools": [{"name": "take_screenshot", "inputSchema": {}}]}),
+                ),
+            ))),
+            _ if id.is_null() => Some(StubReply::Accepted),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn client_speaks_streamable_http_with_a_session() {
+        let (url, requests) = stub_server(|request| {
+            handshake(request).unwrap_or_else(|| {
+                let id = &request.body["id"];
+                let progress = json!({"jsonrpc": "2.0", "method": "notifications/progress",
+                    "params": {"progressToken": 1, "progress": 0.5}});
+                let done = result(
+                    id,
+                    json!({"content": [{"type": "text", "text": "clicked"}]}),
+                );
+                StubReply::Events(event(None, &progress) + &event(None, &done))
+            })
+        })
+        .await;
+
+        let client = McpClient::connect_http(&url).await.unwrap();
+        assert!(client.has_tool("take_screenshot"));
+        let called = client
+            .call_tool("click", Some(json!({"x": 1, "y": 2})))
+            .await
+            .unwrap();
+        assert_eq!(called.content[0].as_text(), Some("clicked"));
+
+        let requests = requests.lock().unwrap();
+        let methods: Vec<&str> = requests.iter().map(StubRequest::rpc_method).collect();
+        assert_eq!(
+            methods,
+            [
+                "initialize",
+                "notifications/initialized",
+                "tools/list",
+                "tools/call"
+            ]
+        );
+        assert_eq!(requests[0].header(SESSION_HEADER), None);
+        assert!(
+            requests[1..]
+                .iter()
+                .all(|r| r.header(SESSION_HEADER) == Some("session-1"))
+        );
+        assert!(
+            requests[3]
+                .header("accept")
+                .unwrap()
+                .contains("text/event-stream")
+        );
+    }
+
+    #[tokio::test]
+    async fn dropped_event_stream_is_resumed_from_the_last_event_id() {
+        let pending = Arc::new(StdMutex::new(Value::Null));
+        let call_id = Arc::clone(&pending);
+        let (url, requests) = stub_server(move |request| {
+            if request.method == "GET" {
+                assert_eq!(request.header(LAST_EVENT_ID_HEADER), Some("e1"));
+                let id = call_id.lock().unwrap().clone();
+                let done = result(&id, json!({"content": [{"type": "text", "text": "ok"}]}));
+                return StubReply::Events(event(Some("e2"), &done));
+            }
+            handshake(request).unwrap_or_else(|| {
+                // The stream closes after one numbered notification.
+                *call_id.lock().unwrap() = request.body["id"].clone();
+                let progress = json!({"jsonrpc": "2.0", "method": "notifications/progress",
+                    "params": {"progressToken": 1, "progress": 0.1}});
+                StubReply::Events(event(Some("e1"), &progress))
+            })
+        })
+        .await;
+
+        let client = McpClient::connect_http(&url).await.unwrap();
+        let called = client.call_tool("wait", None).await.unwrap();
+        assert_eq!(called.content[0].as_text(), Some("ok"));
+        assert_eq!(requests.lock().unwrap().last().unwrap().method, "GET");
+    }
+
+    #[tokio::test]
+    async fn an_unresumable_stream_fails_only_its_request() {
+        let (url, _) = stub_server(|request| {
+            handshake(request).unwrap_or_else(|| {
+                let id = &request.body["id"];
+                if request.body["params"]["name"] == "drop" {
+                    // No event id, so the stream cannot be resumed.
+                    let progress = json!({"jsonrpc": "2.0", "method": "notifications/progress",
+                        "params": {"progressToken": 1, "progress": 0.1}});
+                    return StubReply::Events(event(None, &progress));
+                }
+                let done = result(id, json!({"content": [{"type": "text", "text": "ok"}]}));
+                StubReply::Json(done)
+            })
+        })
+        .await;
+
+        let client = McpClient::connect_http(&url).await.unwrap();
+        let err = client.call_tool("drop", None).await.unwrap_err();
+        assert!(
+            matches!(
+                err.downcast_ref::<McpError>(),
+                Some(McpError::Protocol {
+                    code: STREAM_LOST_CODE,
+                    ..
+                })
+            ),
+            "{err:#}"
+        );
+        assert!(client.connection_lost().is_none());
+        let called = client.call_tool("click", None).await.unwrap();
+        assert_eq!(called.content[0].as_text(), Some("ok"));
+    }
+
+    #[tokio::test]
+    async fn an_expired_session_is_re_initialized_and_the_request_resent() {
+        let expired = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let expire = Arc::clone(&expired);
+        let (url, requests) = stub_server(move |request| {
+            if request.rpc_method() == "tools/call" && !expire.swap(true, Ordering::SeqCst) {
+                return StubReply::Status(404);
+            }
+            handshake(request).unwrap_or_else(|| {
+                let id = &request.body["id"];
+                StubReply::Json(result(
+                    id,
+                    json!({"content": [{"type": "text", "text": "ok"}]}),
+                ))
+            })
+        })
+        .await;
+
+        let client = McpClient::connect_http(&url).await.unwrap();
+        let called = client.call_tool("click", None).await.unwrap();
+        assert_eq!(called.content[0].as_text(), Some("ok"));
+
+        let requests = requests.lock().unwrap();
+        let methods: Vec<&str> = requests.iter().map(StubRequest::rpc_method).collect();
+        assert_eq!(
+            methods[3..],
+            [
+                "tools/call",
+                "initialize",
+                "notifications/initialized",
+                "tools/call"
+            ]
+        );
+        assert_eq!(requests[4].header(SESSION_HEADER), None);
+        assert_eq!(requests[6].header(SESSION_HEADER), Some("session-1"));
+    }
+
+    #[tokio::test]
+    async fn the_negotiated_protocol_version_is_sent_after_initialize() {
+        let (url, requests) = stub_server(|request| handshake(request).unwrap()).await;
+
+        McpClient::connect_http(&url).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].header(PROTOCOL_VERSION_HEADER), None);
+        assert!(
+            requests[1..]
+                .iter()
+                .all(|r| r.header(PROTOCOL_VERSION_HEADER) == Some("2025-06-18"))
+        );
+    }
+
+    #[tokio::test]
+    async fn http_error_status_fails_the_request() {
+        let (url, _) = stub_server(|_| StubReply::Status(503)).await;
+        let err = McpClient::connect_http(&url).await.err().unwrap();
+        assert!(
+            matches!(
+                err.downcast_ref::<McpError>(),
+                Some(McpError::Http { status: 503, .. })
+            ),
+            "{err:#}"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Message transports beneath [`McpClient`](crate::McpClient).
+//!
+//! A transport moves whole JSON-RPC messages: [`Transport::send`] delivers
+//! one serialized message to the server and [`Transport::recv`] yields the
+//! next message from it, response or notification alike. Request/response
+//! pairing stays in the client.
+
+mod http;
+mod stdio;
+
+use anyhow::Result;
+use serde_json::Value;
+
+pub(crate) use http::HttpTransport;
+pub(crate) use stdio::StdioTransport;
+
+/// Where an MCP server lives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpEndpoint {
+    /// A child process spoken to over stdin/stdout.
+    Command { command: String, args: Vec<String> },
+    /// A server reached over the Streamable HTTP transport.
+    Url(String),
+}
+
+impl McpEndpoint {
+    /// Read a configured server: an `http://` or `https://` URL connects
+    /// over HTTP, anything else is a command to spawn with no arguments
+    /// (see [`with_args`](Self::with_args)).
+    pub fn parse(target: &str) -> Self {
+        let target = target.trim();
+        if target.starts_with("http://") || target.starts_with("https://") {
+            Self::Url(target.to_string())
+        } else {
+            Self::Command {
+                command: target.to_string(),
+                args: Vec::new(),
+            }
+        }
+    }
+
+    /// Spawn a command endpoint with `args`. A URL takes no arguments, so
+    /// it is returned unchanged.
+    pub fn with_args(self, args: Vec<String>) -> Self {
+        match self {
+            Self::Command { command, .. } => Self::Command { command, args },
+            url @ Self::Url(_) => url,
+        }
+    }
+}
+
+pub(crate) enum Transport {
+    Stdio(Box<StdioTransport>),
+    Http(HttpTransport),
+}
+
+impl Transport {
+    pub(crate) async fn send(&self, json: &str) -> Result<()> {
+        match self {
+            Self::Stdio(stdio) => stdio.send(json).await,
+            Self::Http(http) => http.send(json).await,
+        }
+    }
+
+    /// Record the protocol version negotiated in `initialize`. Only HTTP
+    /// carries it, as a header on every later request.
+    pub(crate) fn set_protocol_version(&self, version: &str) {
+        if let Self::Http(http) = self {
+            http.set_protocol_version(version);
+        }
+    }
+
+    pub(crate) async fn recv(&self) -> Result<Value> {
+        match self {
+            Self::Stdio(stdio) => stdio.recv().await,
+            Self::Http(http) => http.recv().await,
+        }
+    }
+
+    pub(crate) fn kill(&self) -> Result<()> {
+        match self {
+            Self::Stdio(stdio) => stdio.kill(),
+            Self::Http(http) => {
+                http.close();
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_are_http_endpoints_and_everything_else_a_command() {
+        assert_eq!(
+            McpEndpoint::parse(" http://127.0.0.1:8931/mcp "),
+            McpEndpoint::Url("http://127.0.0.1:8931/mcp".into())
+        );
+        assert_eq!(
+            McpEndpoint::parse("https://mcp.internal/mcp"),
+            McpEndpoint::Url("https://mcp.internal/mcp".into())
+        );
+        assert_eq!(
+            McpEndpoint::parse("/Applications/Clickweave.app/native-devtools-mcp"),
+            McpEndpoint::Command {
+                command: "/Applications/Clickweave.app/native-devtools-mcp".into(),
+                args: Vec::new(),
+            }
+        );
+        assert_eq!(
+            McpEndpoint::parse("npx").with_args(vec![
+                "-y".into(),
+                "@modelcontextprotocol/server-filesystem".into(),
+                "/tmp".into(),
+            ]),
+            McpEndpoint::Command {
+                command: "npx".into(),
+                args: vec![
+                    "-y".into(),
+                    "@modelcontextprotocol/server-filesystem".into(),
+                    "/tmp".into(),
+                ],
+            }
+        );
+    }
+}
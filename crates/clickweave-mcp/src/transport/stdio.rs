@@ -0,0 +1,247 @@
+//! Newline-delimited JSON-RPC over a child process's stdin/stdout.
+
+use crate::McpError;
+use anyhow::{Context, Result, anyhow};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout};
+use tokio::sync::{Mutex, mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, trace, warn};
+
+const MAX_SKIPPED_LINES: usize = 64;
+
+/// Stderr lines kept for the error reported when the server exits.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// How long to wait, after stdout closes, for the process to be reaped
+/// and its last stderr lines to arrive.
+const EXIT_GRACE: Duration = Duration::from_secs(1);
+
+/// A line for stdin and where to report how writing it went.
+type Outgoing = (Vec<u8>, oneshot::Sender<std::io::Result<()>>);
+
+pub(crate) struct StdioTransport {
+    process: StdMutex<Child>,
+    outgoing: mpsc::UnboundedSender<Outgoing>,
+    stdout: Mutex<BufReader<ChildStdout>>,
+    writer_task: Option<JoinHandle<()>>,
+    stderr_task: Option<JoinHandle<()>>,
+    stderr_tail: Arc<StdMutex<VecDeque<String>>>,
+}
+
+impl StdioTransport {
+    pub(crate) fn spawn(command: &str, args: &[&str]) -> Result<Self> {
+        info!("Spawning MCP server: {} {:?}", command, args);
+
+        let mut process = tokio::process::Command::new(command)
+            .args(args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to spawn MCP server")?;
+
+        let stdin = process.stdin.take().ok_or_else(|| anyhow!("No stdin"))?;
+        let stdout = process.stdout.take().ok_or_else(|| anyhow!("No stdout"))?;
+        let stderr = process.stderr.take().ok_or_else(|| anyhow!("No stderr"))?;
+
+        let (outgoing, queue) = mpsc::unbounded_channel();
+        let writer_task = Some(tokio::spawn(write_lines(stdin, queue)));
+        let stderr_tail = Arc::new(StdMutex::new(VecDeque::new()));
+        let stderr_task = Some(tokio::spawn(forward_stderr(
+            stderr,
+            Arc::clone(&stderr_tail),
+        )));
+
+        Ok(Self {
+            process: StdMutex::new(process),
+            outgoing,
+            stdout: Mutex::new(BufReader::new(stdout)),
+            writer_task,
+            stderr_task,
+            stderr_tail,
+        })
+    }
+
+    /// Queue one line for the writer task and wait for it to be written.
+    /// A caller that stops waiting does not leave half a line behind.
+    pub(crate) async fn send(&self, json: &str) -> Result<()> {
+        let mut buf = Vec::with_capacity(json.len() + 1);
+        buf.extend_from_slice(json.as_bytes());
+        buf.push(b'\n');
+        let (done, written) = oneshot::channel();
+        self.outgoing
+            .send((buf, done))
+            .map_err(|_| McpError::SubprocessClosed)?;
+        match written.await {
+            Ok(Ok(())) => Ok(()),
+            // A broken pipe is how a crash usually shows up first.
+            Ok(Err(e)) => match self.exited() {
+                Some(exited) => Err(exited.into()),
+                None => Err(e).context("Failed to write to MCP server stdin"),
+            },
+            Err(_) => Err(self.exited().unwrap_or(McpError::SubprocessClosed).into()),
+        }
+    }
+
+    /// The next JSON line on stdout, skipping blank and non-JSON lines up
+    /// to a budget.
+    pub(crate) async fn recv(&self) -> Result<Value> {
+        let mut stdout = self.stdout.lock().await;
+        let mut skipped = 0usize;
+
+        loop {
+            let Some(line) = read_mcp_stdout_line(&mut stdout).await? else {
+                return Err(self.exit_error().await.into());
+            };
+            match parse_mcp_stdout_line(&line) {
+                Some(value) => return Ok(value),
+                None => {
+                    skipped += 1;
+                    ensure_skipped_line_budget(skipped)?;
+                }
+            }
+        }
+    }
+
+    /// Why stdout closed: the process exited, or (rarely) it closed
+    /// stdout and kept running.
+    async fn exit_error(&self) -> McpError {
+        let deadline = tokio::time::Instant::now() + EXIT_GRACE;
+        loop {
+            let stderr_done = self
+                .stderr_task
+                .as_ref()
+                .is_none_or(|task| task.is_finished());
+            match self.exited() {
+                Some(exited) if stderr_done => return exited,
+                exited if tokio::time::Instant::now() >= deadline => {
+                    return exited.unwrap_or(McpError::SubprocessClosed);
+                }
+                _ => tokio::time::sleep(Duration::from_millis(20)).await,
+            }
+        }
+    }
+
+    /// [`McpError::ServerExited`] if the process has exited.
+    fn exited(&self) -> Option<McpError> {
+        let status = self
+            .process
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .try_wait()
+            .ok()
+            .flatten()?;
+        let tail = self.stderr_tail.lock().unwrap_or_else(|e| e.into_inner());
+        Some(McpError::ServerExited {
+            status: status.to_string(),
+            stderr_tail: Vec::from(tail.clone()).join("\n"),
+        })
+    }
+
+    /// Sends `SIGKILL` to the subprocess. Reaping is handled by Tokio's
+    /// `kill_on_drop` machinery when the transport is dropped.
+    pub(crate) fn kill(&self) -> Result<()> {
+        self.process
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .start_kill()
+            .context("Failed to kill MCP server")?;
+        Ok(())
+    }
+}
+
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        for handle in [self.writer_task.take(), self.stderr_task.take()]
+            .into_iter()
+            .flatten()
+        {
+            handle.abort();
+        }
+        let process = self.process.get_mut().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = process.start_kill() {
+            debug!("MCP kill on drop failed: {e}");
+        }
+    }
+}
+
+/// The next line, or `None` at EOF.
+async fn read_mcp_stdout_line(stdout: &mut BufReader<ChildStdout>) -> Result<Option<String>> {
+    let mut line = String::new();
+    let bytes = stdout
+        .read_line(&mut line)
+        .await
+        .context("Failed to read MCP response line")?;
+    Ok((bytes > 0).then_some(line))
+}
+
+fn parse_mcp_stdout_line(line: &str) -> Option<Value> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        warn!("MCP produced blank stdout line; skipping");
+        return None;
+    }
+
+    trace!("MCP response: {}", trimmed);
+
+    match serde_json::from_str(trimmed) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            let preview: String = trimmed.chars().take(200).collect();
+            warn!(
+                "Non-JSON line on MCP stdout (skipping): {} -- {}",
+                e, preview
+            );
+            None
+        }
+    }
+}
+
+fn ensure_skipped_line_budget(skipped: usize) -> Result<()> {
+    if skipped > MAX_SKIPPED_LINES {
+        return Err(anyhow!(
+            "Too many malformed lines on MCP stdout (>{MAX_SKIPPED_LINES})"
+        ));
+    }
+    Ok(())
+}
+
+/// Write queued lines to stdin in order, each one whole.
+async fn write_lines(mut stdin: ChildStdin, mut queue: mpsc::UnboundedReceiver<Outgoing>) {
+    while let Some((line, done)) = queue.recv().await {
+        let result = async {
+            stdin.write_all(&line).await?;
+            stdin.flush().await
+        }
+        .await;
+        let _ = done.send(result);
+    }
+}
+
+/// Log stderr, keeping the last few lines for [`McpError::ServerExited`].
+async fn forward_stderr(stderr: ChildStderr, tail: Arc<StdMutex<VecDeque<String>>>) {
+    let mut reader = BufReader::new(stderr).lines();
+    loop {
+        match reader.next_line().await {
+            Ok(Some(line)) => {
+                debug!(target: "mcp.stderr", "{line}");
+                let mut tail = tail.lock().unwrap_or_else(|e| e.into_inner());
+                if tail.len() == STDERR_TAIL_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+            }
+            Ok(None) => break,
+            Err(e) => {
+                warn!(target: "mcp.stderr", "error reading MCP stderr: {e}");
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use clickweave_engine::SkillServer;
+use clickweave_engine::agent::PermissionPolicy;
+use clickweave_engine::agent::episodic::HashedShingleEmbedder;
+use clickweave_engine::agent::skills::{SkillContext, SkillIndex};
+use clickweave_mcp::{
+    HttpServeOptions, McpClient, McpEndpoint, ToolServer, serve_http_with, serve_stdio,
+};
+use parking_lot::RwLock;
+use tokio::net::TcpListener;
+use tracing_subscriber::EnvFilter;
+
+const DEFAULT_DOWNSTREAM: &str = "native-devtools-mcp";
+/// Host for `--http` given only a port.
+const DEFAULT_HTTP_HOST: &str = "127.0.0.1";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse()?;
+    // stdout carries the stdio transport, so logs go to stderr.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let policy = match &args.policy {
+        Some(path) => {
+            let raw = fs::read_to_string(path).context("read permission policy")?;
+            serde_json::from_str(&raw).context("parse permission policy")?
+        }
+        None => PermissionPolicy::default(),
+    };
+    let context = SkillContext {
+        enabled: true,
+        project_skills_dir: args.project_skills_dir,
+        global_skills_dir: args.global_skills_dir,
+        project_id: String::new(),
+    };
+    let index = SkillIndex::build(&context, Arc::new(HashedShingleEmbedder::default()))
+        .context("load skills")?;
+
+    let downstream = McpClient::connect(&McpEndpoint::parse(&args.downstream))
+        .await
+        .with_context(|| format!("connect to downstream MCP server {}", args.downstream))?;
+    let server = Arc::new(
+        SkillServer::new(Arc::new(RwLock::new(index)), Arc::new(downstream)).with_policy(policy),
+    );
+    tracing::info!(tools = server.list_tools().len(), "serving skills");
+
+    match args.http {
+        Some(addr) => {
+            let listener = TcpListener::bind(&addr)
+                .await
+                .with_context(|| format!("bind {addr}"))?;
+            tracing::info!("listening on http://{}/mcp", listener.local_addr()?);
+            let options = HttpServeOptions {
+                allowed_origins: args.allowed_origins,
+                ..HttpServeOptions::default()
+            };
+            serve_http_with(server, listener, options).await
+        }
+        None => serve_stdio(server, tokio::io::stdin(), tokio::io::stdout()).await,
+    }
+}
+
+#[derive(Debug)]
+struct Args {
+    project_skills_dir: PathBuf,
+    global_skills_dir: Option<PathBuf>,
+    downstream: String,
+    policy: Option<PathBuf>,
+    http: Option<String>,
+    allowed_origins: Vec<String>,
+}
+
+impl Args {
+    fn parse() -> Result<Self> {
+        Self::parse_from(env::args().skip(1))
+    }
+
+    fn parse_from<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut args = args.into_iter().map(Into::into);
+        let mut project_skills_dir = None;
+        let mut global_skills_dir = None;
+        let mut downstream = DEFAULT_DOWNSTREAM.to_string();
+        let mut policy = None;
+        let mut http = None;
+        let mut allowed_origins = Vec::new();
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--project-skills-dir" => {
+                    project_skills_dir = Some(PathBuf::from(next_value(&mut args, &flag)?))
+                }
+                "--global-skills-dir" => {
+                    global_skills_dir = Some(PathBuf::from(next_value(&mut args, &flag)?))
+                }
+                "--downstream" => downstream = next_value(&mut args, &flag)?,
+                "--policy" => policy = Some(PathBuf::from(next_value(&mut args, &flag)?)),
+                "--http" => http = Some(http_addr(next_value(&mut args, &flag)?)),
+                "--allow-origin" => allowed_origins.push(next_value(&mut args, &flag)?),
+                "--help" | "-h" => {
+                    print_help();
+                    std::process::exit(0);
+                }
+                other => bail!("unknown argument: {other}"),
+            }
+        }
+        Ok(Self {
+            project_skills_dir: project_skills_dir.context("missing --project-skills-dir")?,
+            global_skills_dir,
+            downstream,
+            policy,
+            http,
+            allowed_origins,
+        })
+    }
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String> {
+    args.next()
+        .with_context(|| format!("{flag} requires a value"))
+}
+
+/// A bare port binds loopback; anything else is used as given.
+fn http_addr(value: String) -> String {
+    if value.parse::<u16>().is_ok() {
+        format!("{DEFAULT_HTTP_HOST}:{value}")
+    } else {
+        value
+    }
+}
+
+fn print_help() {
+    eprintln!("{}", help_text());
+}
+
+fn help_text() -> String {
+    format!(
+        "Usage: clickweave-skills-mcp --project-skills-dir <dir> [--global-skills-dir <dir>] \
+         [--downstream <command-or-url>] [--policy <json>] [--http <[host:]port>] \
+         [--allow-origin <origin>]...\n\n\
+         Serves confirmed skills as MCP tools over stdio, or over Streamable HTTP \
+         at http://<host:port>/mcp with --http. The host defaults to {DEFAULT_HTTP_HOST}; \
+         bound anywhere else, only requests from an --allow-origin origin are served. \
+         --downstream defaults to {DEFAULT_DOWNSTREAM}."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn help_text_wraps_without_stray_backslashes() {
+        let help = help_text();
+        assert!(!help.contains('\\'), "{help}");
+        assert!(help.contains("[--global-skills-dir <dir>] [--downstream"));
+    }
+
+    #[test]
+    fn parse_defaults_to_stdio_against_native_devtools() {
+        let args = Args::parse_from(["--project-skills-dir", "skills"]).unwrap();
+
+        assert_eq!(args.project_skills_dir, PathBuf::from("skills"));
+        assert_eq!(args.downstream, DEFAULT_DOWNSTREAM);
+        assert!(args.http.is_none());
+        assert!(args.policy.is_none());
+    }
+
+    #[test]
+    fn a_bare_http_port_binds_loopback() {
+        let args = Args::parse_from([
+            "--project-skills-dir",
+            "skills",
+            "--http",
+            "8931",
+            "--allow-origin",
+            "https://tools.example.com",
+        ])
+        .unwrap();
+        assert_eq!(args.http.as_deref(), Some("127.0.0.1:8931"));
+        assert_eq!(args.allowed_origins, ["https://tools.example.com"]);
+
+        let args =
+            Args::parse_from(["--project-skills-dir", "skills", "--http", "0.0.0.0:8931"]).unwrap();
+        assert_eq!(args.http.as_deref(), Some("0.0.0.0:8931"));
+    }
+
+    #[test]
+    fn parse_requires_a_project_skills_dir() {
+        let err = Args::parse_from(["--http", "127.0.0.1:8931"]).unwrap_err();
+
+        assert!(err.to_string().contains("--project-skills-dir"));
+    }
+}
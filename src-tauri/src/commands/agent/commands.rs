@@ -298,7 +298,66 @@ pub async fn run_agent(
     request: AgentRunRequest,
 ) -> Result<(), CommandError> {
     ensure_agent_idle(&app)?;
+    start_agent_run(app, request, None)
+}
+
+/// Resume the project's suspended agent run. `request` supplies the
+/// same project, model, and settings a fresh `run_agent` call would;
+/// the goal, run id, and transcript come from the snapshot written by
+/// `suspend_agent`. The snapshot is consumed so a run can only be
+/// resumed once.
+#[tauri::command]
+#[specta::specta]
+pub async fn resume_agent(
+    app: tauri::AppHandle,
+    request: AgentRunRequest,
+) -> Result<(), CommandError> {
+    ensure_agent_idle(&app)?;
+    if !request.store_traces.unwrap_or(true) {
+        return Err(CommandError::validation(
+            "Cannot resume a run while trace persistence is off",
+        ));
+    }
 
+    let project_id = parse_project_id(&request)?;
+    let storage = resolve_storage(
+        &app,
+        &request.project_path,
+        &request.project_name,
+        project_id,
+    );
+    let snapshot: SuspendedRun = storage
+        .load_suspended_run()
+        .map_err(|e| CommandError::io(format!("read suspended run: {e}")))?
+        .ok_or(CommandError::validation("No suspended agent run to resume"))?;
+    if snapshot.schema_version != clickweave_engine::agent::SUSPENDED_RUN_SCHEMA_VERSION {
+        return Err(CommandError::validation(
+            "Suspended run was saved by an incompatible version and cannot be resumed",
+        ));
+    }
+    storage
+        .clear_suspended_run()
+        .map_err(|e| CommandError::io(format!("clear suspended run: {e}")))?;
+
+    start_agent_run(app, request, Some(snapshot))
+}
+
+/// Suspend the running agent at its next turn boundary. The run's state
+/// is written to run storage and the run halts with reason `suspended`;
+/// `resume_agent` picks it up later.
+#[tauri::command]
+#[specta::specta]
+pub async fn suspend_agent(app: tauri::AppHandle) -> Result<(), CommandError> {
+    let handle = app.state::<Mutex<AgentHandle>>();
+    let mut guard = handle.lock().unwrap();
+    guard.request_suspend()
+}
+
+fn start_agent_run(
+    app: tauri::AppHandle,
+    request: AgentRunRequest,
+    resume: Option<SuspendedRun>,
+) -> Result<(), CommandError> {
     let mcp_binary_path =
         crate::mcp_resolve::resolve_mcp_binary().map_err(|e| CommandError::mcp(format!("{e}")))?;
 
@@ -322,7 +381,10 @@ pub async fn run_agent(
     // The frontend may supply its own run_id so the user message bubble
     // can be tagged before `agent://started` arrives — honor it when
     // present and syntactically valid.
-    let (run_id, run_uuid) = resolve_run_id(&request)?;
+    let (run_id, run_uuid) = match &resume {
+        Some(snapshot) => (snapshot.run_id.to_string(), snapshot.run_id),
+        None => resolve_run_id(&request)?,
+    };
     let anchor_uuid = parse_anchor_node_id(&request)?;
     let prior_turns = parse_prior_turns(&request)?;
 
@@ -334,6 +396,17 @@ pub async fn run_agent(
     let skills_settings_enabled = request.skills_enabled.unwrap_or(true);
     let applicable_skills_k_override = request.applicable_skills_k;
     let skills_global_participation = request.skills_global_participation.unwrap_or(false);
+    let max_duration_secs = request.max_duration_secs;
+    let tool_timeouts_secs = request.tool_timeouts_secs.clone();
+    let mcp_servers = request.mcp_servers.clone();
+    let restart_mcp_on_crash = request.restart_mcp_on_crash.unwrap_or(false);
+    let plan_first = request.plan_first.unwrap_or(false);
+    let max_sub_agent_depth = request.max_sub_agent_depth.unwrap_or(0);
+    let stream_llm = request.stream_llm.unwrap_or(false);
+    let llm_cassette = parse_llm_cassette(&request)?;
+    let record_mcp = request.record_mcp.unwrap_or(false);
+    let replay_mcp = parse_replay_mcp(&request)?;
+    let llm_escalation = request.llm_escalation.unwrap_or_default();
 
     let episodic_ctx = build_episodic_context(
         &app,
@@ -352,6 +425,13 @@ pub async fn run_agent(
         skills_global_participation,
     )?;
     let agent_config = request.agent.into_llm_config(None);
+    let fallback_config = request
+        .agent_fallback
+        .map(|endpoint| endpoint.into_llm_config(None));
+    let summarizer_config = request
+        .summarizer
+        .map(|endpoint| endpoint.into_llm_config(None));
+    let vlm_cache = crate::commands::types::open_vlm_cache(&app, request.vlm_cache)?;
     let permission_policy: Option<PermissionPolicy> = request.permissions.map(Into::into);
 
     // Capture the run-start timestamp so PromotePass scopes promotion
@@ -359,6 +439,7 @@ pub async fn run_agent(
     let run_start_utc = chrono::Utc::now();
 
     let cancel_token = CancellationToken::new();
+    let suspend_token = CancellationToken::new();
     let agent_token = cancel_token.clone();
     let forwarder_token = cancel_token.clone();
     let event_forwarder_token = cancel_token.clone();
@@ -397,19 +478,31 @@ pub async fn run_agent(
 
     // Install cancel_token and run_id before spawning so stop_agent() works
     // even during the spawn window (before task_handle is available).
-    install_agent_run_handle(&app, cancel_token, &run_id);
+    install_agent_run_handle(
+        &app,
+        cancel_token,
+        suspend_token.clone(),
+        persist_traces,
+        &run_id,
+    );
 
     // Emit agent://started so the frontend knows the run_id before any other events.
     let _ = app.emit("agent://started", serde_json::json!({ "run_id": &run_id }));
 
     let task_handle = spawn_agent_run_task(AgentRunTaskInput {
         mcp_binary_path,
+        mcp_servers,
+        restart_mcp_on_crash,
         agent_token,
+        suspend_token,
         terminal_event_tx,
         emit_handle,
         task_run_id,
         done_tx,
         agent_config: agent_config.clone(),
+        fallback_config,
+        summarizer_config,
+        vlm_cache,
         consecutive_destructive_cap,
         allow_focus_window,
         episodic_settings_enabled,
@@ -417,6 +510,15 @@ pub async fn run_agent(
         skills_settings_enabled,
         applicable_skills_k_override,
         skills_global_participation,
+        max_duration_secs,
+        tool_timeouts_secs,
+        plan_first,
+        max_sub_agent_depth,
+        stream_llm,
+        llm_escalation,
+        llm_cassette,
+        record_mcp,
+        replay_mcp,
         storage: task_storage,
         event_tx: event_tx.clone(),
         approval_tx,
@@ -431,6 +533,7 @@ pub async fn run_agent(
         promotion_episodic_ctx,
         promotion_project_id,
         run_start_utc,
+        resume,
     });
 
     spawn_agent_event_forwarder(
@@ -486,6 +589,48 @@ pub async fn approve_agent_action(
     })
 }
 
+/// Answer the question the agent raised through `ask_user`. The answer
+/// is handed back to the LLM as the tool result.
+#[tauri::command]
+#[specta::specta]
+pub async fn answer_agent_question(
+    app: tauri::AppHandle,
+    answer: String,
+) -> Result<(), CommandError> {
+    let handle = app.state::<Mutex<AgentHandle>>();
+    let mut guard = handle.lock().unwrap();
+    let tx = guard
+        .pending_question_tx
+        .take()
+        .ok_or(CommandError::validation("No pending agent question"))?;
+    drop(guard);
+
+    tx.send(answer).map_err(|_| {
+        CommandError::validation("Question expired — the agent stopped waiting for an answer")
+    })
+}
+
+/// Approve, edit, or reject the plan the agent proposed in plan-first
+/// mode. An approval may carry an edited subgoal list; it replaces the
+/// proposal verbatim.
+#[tauri::command]
+#[specta::specta]
+pub async fn review_agent_plan(
+    app: tauri::AppHandle,
+    decision: PlanDecision,
+) -> Result<(), CommandError> {
+    let handle = app.state::<Mutex<AgentHandle>>();
+    let mut guard = handle.lock().unwrap();
+    let tx = guard
+        .pending_plan_tx
+        .take()
+        .ok_or(CommandError::validation("No pending plan to review"))?;
+    drop(guard);
+
+    tx.send(decision)
+        .map_err(|_| CommandError::validation("Plan channel closed — agent task may have ended"))
+}
+
 /// Wire form for `resolve_completion_disagreement`. Mirrors
 /// `DisagreementResolutionAction` but derives `specta::Type` so the
 /// TypeScript binding picks it up.
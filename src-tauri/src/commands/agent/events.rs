@@ -9,9 +9,10 @@ use super::*;
 /// is not smeared across this helper. `GoalComplete` is deliberately a
 /// no-op: the terminal `agent://complete` is emitted from the main
 /// run-agent task after the engine returns, and the
-/// `CompletionDisagreementResolved` variant is emitted by the Tauri
-/// layer itself (see `await_disagreement_resolution`), so neither
-/// crosses this forwarder at runtime.
+/// `CompletionDisagreementResolved` and `VlmCacheStats` variants are
+/// emitted by the Tauri layer itself (see `await_disagreement_resolution`
+/// and `run_agent_task`), so none of them crosses this forwarder at
+/// runtime.
 ///
 /// Extracted as a standalone function so the rubric-10 smoke test in
 /// `run_agent_smoke_tests` can drive a scripted `AgentEvent` stream
@@ -33,9 +34,16 @@ pub(crate) fn forward_agent_event<R: tauri::Runtime>(
         | AgentEvent::SubAction { .. }
         | AgentEvent::CompletionDisagreement { .. }
         | AgentEvent::ConsecutiveDestructiveCapHit { .. }
-        | AgentEvent::CompletionDisagreementResolved { .. } => {
-            forward_lifecycle_agent_event(app, run_id, event)
-        }
+        | AgentEvent::UserQuestionAnswered { .. }
+        | AgentEvent::PlanReviewed { .. }
+        | AgentEvent::SubAgentStarted { .. }
+        | AgentEvent::SubAgentFinished { .. }
+        | AgentEvent::LlmDelta { .. }
+        | AgentEvent::McpProgress { .. }
+        | AgentEvent::McpLog { .. }
+        | AgentEvent::ToolCallCancelled { .. }
+        | AgentEvent::CompletionDisagreementResolved { .. }
+        | AgentEvent::VlmCacheStats { .. } => forward_lifecycle_agent_event(app, run_id, event),
         AgentEvent::TaskStateChanged { .. }
         | AgentEvent::WorldModelChanged { .. }
         | AgentEvent::BoundaryRecordWritten { .. } => forward_state_agent_event(app, run_id, event),
@@ -67,6 +75,7 @@ fn forward_lifecycle_agent_event<R: tauri::Runtime>(
             step_index,
             tool_name,
             summary,
+            ..
         } => {
             emit_agent_event(
                 app,
@@ -118,6 +127,7 @@ fn forward_lifecycle_agent_event<R: tauri::Runtime>(
             step_index,
             tool_name,
             error,
+            ..
         } => {
             emit_agent_event(
                 app,
@@ -175,11 +185,163 @@ fn forward_lifecycle_agent_event<R: tauri::Runtime>(
             );
             true
         }
+        AgentEvent::UserQuestionAnswered {
+            step_index,
+            outcome,
+            answer,
+            ..
+        } => {
+            emit_agent_event(
+                app,
+                "agent://user_question_answered",
+                serde_json::json!({
+                    "run_id": run_id,
+                    "step_index": step_index,
+                    "outcome": outcome,
+                    "answer": answer,
+                }),
+            );
+            true
+        }
+        AgentEvent::PlanReviewed {
+            step_index,
+            decision,
+            revision,
+            ..
+        } => {
+            emit_agent_event(
+                app,
+                "agent://plan_reviewed",
+                serde_json::json!({
+                    "run_id": run_id,
+                    "step_index": step_index,
+                    "decision": decision,
+                    "revision": revision,
+                }),
+            );
+            true
+        }
+        AgentEvent::SubAgentStarted {
+            child_run_id,
+            step_index,
+            subgoal,
+            tools,
+            ..
+        } => {
+            emit_agent_event(
+                app,
+                "agent://sub_agent_started",
+                serde_json::json!({
+                    "run_id": run_id,
+                    "child_run_id": child_run_id,
+                    "step_index": step_index,
+                    "subgoal": subgoal,
+                    "tools": tools,
+                }),
+            );
+            true
+        }
+        AgentEvent::SubAgentFinished {
+            child_run_id,
+            step_index,
+            completed,
+            summary,
+            steps_executed,
+            ..
+        } => {
+            emit_agent_event(
+                app,
+                "agent://sub_agent_finished",
+                serde_json::json!({
+                    "run_id": run_id,
+                    "child_run_id": child_run_id,
+                    "step_index": step_index,
+                    "completed": completed,
+                    "summary": summary,
+                    "steps_executed": steps_executed,
+                }),
+            );
+            true
+        }
+        AgentEvent::LlmDelta {
+            step_index,
+            kind,
+            text,
+            ..
+        } => {
+            emit_agent_event(
+                app,
+                "agent://llm_delta",
+                serde_json::json!({
+                    "run_id": run_id,
+                    "step_index": step_index,
+                    "kind": kind,
+                    "text": text,
+                }),
+            );
+            true
+        }
+        AgentEvent::McpProgress {
+            tool_name,
+            progress,
+            total,
+            message,
+        } => {
+            emit_agent_event(
+                app,
+                "agent://mcp_progress",
+                serde_json::json!({
+                    "run_id": run_id,
+                    "tool_name": tool_name,
+                    "progress": progress,
+                    "total": total,
+                    "message": message,
+                }),
+            );
+            true
+        }
+        AgentEvent::McpLog {
+            level,
+            logger,
+            message,
+        } => {
+            emit_agent_event(
+                app,
+                "agent://mcp_log",
+                serde_json::json!({
+                    "run_id": run_id,
+                    "level": level,
+                    "logger": logger,
+                    "message": message,
+                }),
+            );
+            true
+        }
+        AgentEvent::ToolCallCancelled {
+            step_index,
+            tool_name,
+            reason,
+            ..
+        } => {
+            emit_agent_event(
+                app,
+                "agent://tool_call_cancelled",
+                serde_json::json!({
+                    "run_id": run_id,
+                    "step_index": step_index,
+                    "tool_name": tool_name,
+                    "reason": reason,
+                }),
+            );
+            true
+        }
         // `CompletionDisagreementResolved` is emitted by the Tauri layer
         // (not the engine) so the agent loop never sends it through this
         // channel. Persisting it is handled in
         // `await_disagreement_resolution`.
         AgentEvent::CompletionDisagreementResolved { .. } => true,
+        // Written to the trace by `run_agent_task` after the run.
+        AgentEvent::VlmCacheStats { .. } => true,
         _ => false,
     }
 }
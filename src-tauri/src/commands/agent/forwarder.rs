@@ -51,11 +51,21 @@ fn drain_remaining_runner_outputs(
     while let Ok(output) = event_rx.try_recv() {
         match output {
             RunnerOutput::Event(event) => {
-                let _ = event_storage.lock().unwrap().append_agent_event(&event);
+                if !event.is_transient() {
+                    let _ = event_storage.lock().unwrap().append_agent_event(&event);
+                }
             }
             RunnerOutput::DrainBarrier { ack } => {
                 let _ = ack.send(());
             }
+            // The run is shutting down; dropping the reply resolves the
+            // question as no-human-available.
+            RunnerOutput::UserQuestion { .. } => {}
+            RunnerOutput::PlanApproval { reply, .. } => {
+                let _ = reply.send(PlanDecision::Reject {
+                    reason: "run stopped by operator".to_string(),
+                });
+            }
             RunnerOutput::SkillProposalNeeded {
                 skill_id, version, ..
             } => {
@@ -80,13 +90,23 @@ fn handle_runner_output(
 ) {
     match output {
         RunnerOutput::Event(event) => {
-            let _ = event_storage.lock().unwrap().append_agent_event(&event);
+            if !event.is_transient() {
+                let _ = event_storage.lock().unwrap().append_agent_event(&event);
+            }
             forward_agent_event(event_emit_handle, event_run_id, &event);
             maybe_spawn_skill_proposal_task(&event, proposal_skill_ctx, proposal_agent_config);
         }
         RunnerOutput::DrainBarrier { ack } => {
             let _ = ack.send(());
         }
+        RunnerOutput::UserQuestion { question, reply } => {
+            install_pending_question(event_emit_handle, reply);
+            emit_user_question(event_emit_handle, event_run_id, question);
+        }
+        RunnerOutput::PlanApproval { request, reply } => {
+            install_pending_plan(event_emit_handle, reply);
+            emit_plan_proposed(event_emit_handle, event_run_id, request);
+        }
         RunnerOutput::SkillProposalNeeded {
             skill_id, version, ..
         } => {
@@ -95,6 +115,42 @@ fn handle_runner_output(
     }
 }
 
+fn install_pending_question(app: &tauri::AppHandle, reply: tokio::sync::oneshot::Sender<String>) {
+    let handle = app.state::<Mutex<AgentHandle>>();
+    let mut guard = handle.lock().unwrap();
+    guard.pending_question_tx = Some(reply);
+}
+
+fn emit_user_question(app: &tauri::AppHandle, run_id: &str, question: UserQuestion) {
+    let _ = app.emit(
+        "agent://user_question",
+        serde_json::json!({
+            "run_id": run_id,
+            "step_index": question.step_index,
+            "question": question.question,
+            "choices": question.choices,
+        }),
+    );
+}
+
+fn install_pending_plan(app: &tauri::AppHandle, reply: tokio::sync::oneshot::Sender<PlanDecision>) {
+    let handle = app.state::<Mutex<AgentHandle>>();
+    let mut guard = handle.lock().unwrap();
+    guard.pending_plan_tx = Some(reply);
+}
+
+fn emit_plan_proposed(app: &tauri::AppHandle, run_id: &str, request: PlanApprovalRequest) {
+    let _ = app.emit(
+        "agent://plan_proposed",
+        serde_json::json!({
+            "run_id": run_id,
+            "step_index": request.step_index,
+            "subgoals": request.subgoals,
+            "revision": request.revision,
+        }),
+    );
+}
+
 pub(super) fn spawn_approval_forwarder(
     mut approval_rx: tokio::sync::mpsc::Receiver<(
         ApprovalRequest,
@@ -181,7 +237,11 @@ pub(super) fn spawn_agent_cleanup(
         guard.cancel_token = None;
         guard.task_handle = None;
         guard.pending_approval_tx = None;
+        guard.pending_question_tx = None;
+        guard.pending_plan_tx = None;
         guard.pending_disagreement_tx = None;
         guard.run_id = None;
+        guard.suspend_token = None;
+        guard.persist_traces = false;
     });
 }
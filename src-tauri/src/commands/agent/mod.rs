@@ -5,8 +5,9 @@ use clickweave_engine::agent::episodic::EpisodicContext;
 use clickweave_engine::agent::skills::{SkillContext, SkillScope, SkillState, SkillStore};
 use clickweave_engine::agent::{
     AgentChannels, AgentConfig, AgentEvent, AgentState, ApprovalRequest,
-    DisagreementResolutionAction, PermissionAction, PermissionPolicy, PermissionRule, RunnerOutput,
-    TerminalReason,
+    DisagreementResolutionAction, LlmEscalation, PermissionAction, PermissionPolicy,
+    PermissionRule, PlanApprovalRequest, PlanDecision, RunnerOutput, SuspendedRun, TerminalReason,
+    UserQuestion,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
@@ -189,6 +190,109 @@ pub struct AgentRunRequest {
     /// participate in retrieval for this run.
     #[serde(default)]
     pub skills_global_participation: Option<bool>,
+    /// Wall-clock deadline for the run, in seconds. `0` disables the
+    /// deadline. `None` uses the engine default (15 minutes).
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+    /// Per-tool MCP call timeouts from project settings, in seconds:
+    /// `{ "cdp_wait_for_page_change": 20 }`. Override the engine's
+    /// family defaults for the named tools only; `0` entries are ignored.
+    #[serde(default)]
+    pub tool_timeouts_secs: std::collections::HashMap<String, u64>,
+    /// Plan-first mode: the agent proposes a subgoal plan and waits for
+    /// approval (via `review_agent_plan`) before acting. Default off.
+    #[serde(default)]
+    pub plan_first: Option<bool>,
+    /// How many levels of `delegate_subgoal` sub-agents the run may
+    /// spawn. `None`/`0` keeps the tool hidden.
+    #[serde(default)]
+    pub max_sub_agent_depth: Option<usize>,
+    /// Stream LLM turns and forward text/reasoning fragments as
+    /// `agent://llm_delta`. Default off.
+    #[serde(default)]
+    pub stream_llm: Option<bool>,
+    /// Record every LLM request and response to `cassettes/*.jsonl` in
+    /// the run's execution directory. Default off.
+    #[serde(default)]
+    pub record_llm: Option<bool>,
+    /// Answer LLM calls from the cassettes of this earlier execution of
+    /// the workflow (its execution directory name) instead of the
+    /// endpoint. Takes precedence over `record_llm`.
+    #[serde(default)]
+    pub replay_llm_from: Option<String>,
+    /// How strictly replayed requests must match the recording.
+    /// Default strict.
+    #[serde(default)]
+    pub replay_matching: Option<clickweave_llm::ReplayMatching>,
+    /// Record every MCP tool call and result to `cassettes/mcp.jsonl` in
+    /// the run's execution directory, with images saved alongside.
+    /// Default off.
+    #[serde(default)]
+    pub record_mcp: Option<bool>,
+    /// Answer MCP tool calls from the `replay_llm_from` execution's MCP
+    /// cassette instead of the desktop, so a recorded run replays end to
+    /// end. Requires `replay_llm_from`. Default off.
+    #[serde(default)]
+    pub replay_mcp: Option<bool>,
+    /// Endpoint the agent and VLM fall back to when the primary errors,
+    /// times out or replies with something unusable; also the target of
+    /// `llm_escalation`. `None` keeps a single endpoint.
+    #[serde(default)]
+    pub agent_fallback: Option<EndpointConfig>,
+    /// Which turns go straight to `agent_fallback`. Default never.
+    #[serde(default)]
+    pub llm_escalation: Option<clickweave_engine::agent::LlmEscalation>,
+    /// Endpoint, typically a cheap model, that folds the steps compaction
+    /// collapses into a narrative summary. `None` keeps the one-line
+    /// stand-ins.
+    #[serde(default)]
+    pub summarizer: Option<EndpointConfig>,
+    /// Serve repeated completion-check VLM calls from the on-disk
+    /// response cache. `None` leaves it off.
+    #[serde(default)]
+    pub vlm_cache: Option<clickweave_llm::ResponseCacheSettings>,
+    /// Extra MCP servers from settings, started next to the
+    /// desktop server. Their tools reach the agent as `{name}__{tool}`.
+    #[serde(default)]
+    pub mcp_servers: Vec<McpServerWire>,
+    /// Restart the desktop MCP server if it crashes mid-run, instead of
+    /// failing every later tool call. Default off.
+    #[serde(default)]
+    pub restart_mcp_on_crash: Option<bool>,
+}
+
+/// An auxiliary MCP server for an agent run.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct McpServerWire {
+    /// Tool-name prefix: letters, digits, `-` and `_`.
+    pub name: String,
+    /// Command to spawn, or an `http(s)://` URL to connect to.
+    pub target: String,
+    /// Arguments for a spawned command. Ignored for a URL.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Refuse to start the run without this server. Default off: the
+    /// run continues with a warning.
+    #[serde(default)]
+    pub required: bool,
+    /// Restart the server if it goes away mid-run. Default off: its
+    /// tools are dropped for the rest of the run.
+    #[serde(default)]
+    pub restart_on_crash: bool,
+}
+
+impl From<McpServerWire> for clickweave_mcp::McpServerConfig {
+    fn from(w: McpServerWire) -> Self {
+        let mut config = clickweave_mcp::McpServerConfig::namespaced(
+            w.name,
+            clickweave_mcp::McpEndpoint::parse(&w.target).with_args(w.args),
+        );
+        config.required = w.required;
+        if w.restart_on_crash {
+            config.restart = clickweave_mcp::RestartPolicy::default();
+        }
+        config
+    }
 }
 
 /// Wire form of a prior-turn entry (matches
@@ -216,6 +320,12 @@ pub struct AgentHandle {
     task_handle: Option<tauri::async_runtime::JoinHandle<()>>,
     /// Pending approval oneshot sender — set when the agent is waiting for approval.
     pending_approval_tx: Option<tokio::sync::oneshot::Sender<bool>>,
+    /// Pending `ask_user` answer sender — set while the agent waits for
+    /// the operator to answer a question via `answer_agent_question`.
+    pending_question_tx: Option<tokio::sync::oneshot::Sender<String>>,
+    /// Pending plan-first decision sender — set while the agent waits
+    /// for the operator to review its plan via `review_agent_plan`.
+    pending_plan_tx: Option<tokio::sync::oneshot::Sender<PlanDecision>>,
     /// Pending disagreement-resolution oneshot sender — set after the
     /// engine halts on `CompletionDisagreement` and the Tauri task is
     /// waiting for the operator to confirm or cancel via
@@ -225,6 +335,12 @@ pub struct AgentHandle {
     pending_disagreement_tx: Option<tokio::sync::oneshot::Sender<DisagreementResolutionAction>>,
     /// Generation ID for the current run. Used to tag events and reject stale ones.
     run_id: Option<String>,
+    /// Suspend request for the current run. Cancelled by `suspend_agent`;
+    /// the engine snapshots the run at its next turn boundary and halts.
+    suspend_token: Option<CancellationToken>,
+    /// Whether the current run persists traces. A suspended run's
+    /// snapshot lives in run storage, so suspension requires it.
+    persist_traces: bool,
 }
 
 impl AgentHandle {
@@ -244,6 +360,18 @@ impl AgentHandle {
         if let Some(tx) = self.pending_approval_tx.take() {
             let _ = tx.send(false);
         }
+        // An open question has no "cancel" answer; dropping the sender
+        // resolves it as no-human-available and the cancel token halts
+        // the run right after.
+        self.pending_question_tx = None;
+        // A pending plan gets an explicit rejection for the same reason
+        // approvals get `false`: a dropped sender reads as
+        // `approval_unavailable` rather than a stop.
+        if let Some(tx) = self.pending_plan_tx.take() {
+            let _ = tx.send(PlanDecision::Reject {
+                reason: "run stopped by operator".to_string(),
+            });
+        }
         // Same contract for the pending disagreement-resolution oneshot:
         // send an explicit Cancel so the Tauri task records a truthful
         // `DisagreementCancelled` terminal reason. Dropping the sender
@@ -255,6 +383,21 @@ impl AgentHandle {
         }
         had_task
     }
+
+    /// Ask the running agent to suspend. Fails when no run is active or
+    /// when the run could not persist its snapshot.
+    pub fn request_suspend(&mut self) -> Result<(), CommandError> {
+        let Some(token) = self.suspend_token.as_ref() else {
+            return Err(CommandError::validation("No agent is running"));
+        };
+        if !self.persist_traces {
+            return Err(CommandError::validation(
+                "Cannot suspend a run while trace persistence is off",
+            ));
+        }
+        token.cancel();
+        Ok(())
+    }
 }
 
 // ── Disagreement resolution ─────────────────────────────────────
@@ -282,8 +425,8 @@ mod task;
 mod tests;
 
 pub use commands::{
-    add_run_to_skill, approve_agent_action, resolve_completion_disagreement, run_agent,
-    save_run_as_skill, stop_agent,
+    add_run_to_skill, answer_agent_question, approve_agent_action, resolve_completion_disagreement,
+    resume_agent, review_agent_plan, run_agent, save_run_as_skill, stop_agent, suspend_agent,
 };
 
 use disagreement::await_disagreement_resolution;
@@ -41,6 +41,31 @@ pub(super) fn parse_anchor_node_id(
     }
 }
 
+pub(super) fn parse_llm_cassette(
+    request: &AgentRunRequest,
+) -> Result<super::task::LlmCassette, CommandError> {
+    use super::task::LlmCassette;
+    match request.replay_llm_from.as_deref() {
+        Some(dir) if dir.is_empty() || dir.contains(['/', '\\']) || dir.starts_with('.') => Err(
+            CommandError::validation("replay_llm_from must be an execution directory name"),
+        ),
+        Some(dir) => Ok(LlmCassette::Replay {
+            execution_dir: dir.to_string(),
+            matching: request.replay_matching.unwrap_or_default(),
+        }),
+        None if request.record_llm.unwrap_or(false) => Ok(LlmCassette::Record),
+        None => Ok(LlmCassette::Off),
+    }
+}
+
+pub(super) fn parse_replay_mcp(request: &AgentRunRequest) -> Result<bool, CommandError> {
+    let replay = request.replay_mcp.unwrap_or(false);
+    if replay && request.replay_llm_from.is_none() {
+        return Err(CommandError::validation("replay_mcp needs replay_llm_from"));
+    }
+    Ok(replay)
+}
+
 pub(super) fn parse_prior_turns(
     request: &AgentRunRequest,
 ) -> Result<Vec<clickweave_engine::agent::PriorTurn>, CommandError> {
@@ -118,6 +143,7 @@ pub(super) fn build_skill_context(
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn agent_config_from_request(
     consecutive_destructive_cap: Option<usize>,
     allow_focus_window: Option<bool>,
@@ -126,6 +152,12 @@ pub(super) fn agent_config_from_request(
     skills_settings_enabled: bool,
     applicable_skills_k_override: Option<usize>,
     skills_global_participation: bool,
+    max_duration_secs: Option<u64>,
+    tool_timeouts_secs: std::collections::HashMap<String, u64>,
+    plan_first: bool,
+    max_sub_agent_depth: usize,
+    stream_llm: bool,
+    llm_escalation: LlmEscalation,
 ) -> AgentConfig {
     let mut config = AgentConfig::default();
     if let Some(cap) = consecutive_destructive_cap {
@@ -143,17 +175,34 @@ pub(super) fn agent_config_from_request(
         config.applicable_skills_k = k.clamp(1, 10);
     }
     config.skills_global_participation = skills_global_participation;
+    if let Some(secs) = max_duration_secs {
+        config.max_duration = (secs > 0).then(|| std::time::Duration::from_secs(secs));
+    }
+    config.tool_timeouts.overrides.extend(
+        tool_timeouts_secs
+            .into_iter()
+            .filter(|(_, secs)| *secs > 0)
+            .map(|(tool, secs)| (tool, std::time::Duration::from_secs(secs))),
+    );
+    config.plan_first = plan_first;
+    config.max_sub_agent_depth = max_sub_agent_depth;
+    config.stream_llm = stream_llm;
+    config.llm_escalation = llm_escalation;
     config
 }
 
 pub(super) fn install_agent_run_handle(
     app: &tauri::AppHandle,
     cancel_token: CancellationToken,
+    suspend_token: CancellationToken,
+    persist_traces: bool,
     run_id: &str,
 ) {
     let handle = app.state::<Mutex<AgentHandle>>();
     let mut guard = handle.lock().unwrap();
     guard.cancel_token = Some(cancel_token);
+    guard.suspend_token = Some(suspend_token);
+    guard.persist_traces = persist_traces;
     guard.run_id = Some(run_id.to_string());
 }
 
@@ -54,6 +54,11 @@ const AGENT_TOPICS: &[&str] = &[
     "agent://episodes_retrieved",
     "agent://episode_written",
     "agent://episode_promoted",
+    "agent://user_question_answered",
+    "agent://plan_reviewed",
+    "agent://sub_agent_started",
+    "agent://sub_agent_finished",
+    "agent://llm_delta",
 ];
 
 fn agent_event_line_count(events_path: &std::path::Path) -> usize {
@@ -122,6 +127,8 @@ async fn runner_output_forwarder_skips_drain_barrier_and_persists_events() {
                     let _ = ack.send(());
                 }
                 RunnerOutput::SkillProposalNeeded { .. } => {}
+                RunnerOutput::UserQuestion { .. } => {}
+                RunnerOutput::PlanApproval { .. } => {}
             }
         }
     });
@@ -202,6 +209,8 @@ async fn terminal_emit_waits_for_prior_runner_output_drain() {
                     let _ = ack.send(());
                 }
                 RunnerOutput::SkillProposalNeeded { .. } => {}
+                RunnerOutput::UserQuestion { .. } => {}
+                RunnerOutput::PlanApproval { .. } => {}
             }
         }
     });
@@ -325,6 +334,8 @@ async fn run_smoke_test_body() {
     let channels = AgentChannels {
         event_tx,
         approval_tx,
+        suspend_signal: None,
+        cancel_signal: None,
     };
 
     let run_id = uuid::Uuid::new_v4().to_string();
@@ -1,13 +1,34 @@
 use super::*;
 
+use clickweave_llm::{
+    CachedBackend, CassetteBackend, ChatBackend, LlmClient, ReplayMatching, ResponseCache,
+    RoutingBackend,
+};
+
+/// Whether the run's LLM traffic is taped or served from tape.
+pub(super) enum LlmCassette {
+    Off,
+    Record,
+    Replay {
+        execution_dir: String,
+        matching: ReplayMatching,
+    },
+}
+
 pub(super) struct AgentRunTaskInput {
     pub(super) mcp_binary_path: String,
+    pub(super) mcp_servers: Vec<McpServerWire>,
+    pub(super) restart_mcp_on_crash: bool,
     pub(super) agent_token: CancellationToken,
+    pub(super) suspend_token: CancellationToken,
     pub(super) terminal_event_tx: tokio::sync::mpsc::Sender<RunnerOutput>,
     pub(super) emit_handle: tauri::AppHandle,
     pub(super) task_run_id: String,
     pub(super) done_tx: tokio::sync::oneshot::Sender<()>,
     pub(super) agent_config: clickweave_llm::LlmConfig,
+    pub(super) fallback_config: Option<clickweave_llm::LlmConfig>,
+    pub(super) summarizer_config: Option<clickweave_llm::LlmConfig>,
+    pub(super) vlm_cache: Option<Arc<ResponseCache>>,
     pub(super) consecutive_destructive_cap: Option<usize>,
     pub(super) allow_focus_window: Option<bool>,
     pub(super) episodic_settings_enabled: bool,
@@ -15,6 +36,15 @@ pub(super) struct AgentRunTaskInput {
     pub(super) skills_settings_enabled: bool,
     pub(super) applicable_skills_k_override: Option<usize>,
     pub(super) skills_global_participation: bool,
+    pub(super) max_duration_secs: Option<u64>,
+    pub(super) tool_timeouts_secs: std::collections::HashMap<String, u64>,
+    pub(super) plan_first: bool,
+    pub(super) max_sub_agent_depth: usize,
+    pub(super) stream_llm: bool,
+    pub(super) llm_escalation: LlmEscalation,
+    pub(super) llm_cassette: LlmCassette,
+    pub(super) record_mcp: bool,
+    pub(super) replay_mcp: bool,
     pub(super) storage: Arc<Mutex<clickweave_core::storage::RunStorage>>,
     pub(super) event_tx: tokio::sync::mpsc::Sender<RunnerOutput>,
     pub(super) approval_tx:
@@ -30,6 +60,8 @@ pub(super) struct AgentRunTaskInput {
     pub(super) promotion_episodic_ctx: EpisodicContext,
     pub(super) promotion_project_id: String,
     pub(super) run_start_utc: chrono::DateTime<chrono::Utc>,
+    /// Snapshot to continue instead of starting a fresh run.
+    pub(super) resume: Option<SuspendedRun>,
 }
 
 pub(super) fn spawn_agent_run_task(
@@ -43,12 +75,18 @@ pub(super) fn spawn_agent_run_task(
 async fn run_agent_task(input: AgentRunTaskInput) {
     let AgentRunTaskInput {
         mcp_binary_path,
+        mcp_servers,
+        restart_mcp_on_crash,
         agent_token,
+        suspend_token,
         terminal_event_tx,
         emit_handle,
         task_run_id,
         done_tx,
         agent_config,
+        fallback_config,
+        summarizer_config,
+        vlm_cache,
         consecutive_destructive_cap,
         allow_focus_window,
         episodic_settings_enabled,
@@ -56,6 +94,15 @@ async fn run_agent_task(input: AgentRunTaskInput) {
         skills_settings_enabled,
         applicable_skills_k_override,
         skills_global_participation,
+        max_duration_secs,
+        tool_timeouts_secs,
+        plan_first,
+        max_sub_agent_depth,
+        stream_llm,
+        llm_escalation,
+        llm_cassette,
+        record_mcp,
+        replay_mcp,
         storage,
         event_tx,
         approval_tx,
@@ -70,11 +117,13 @@ async fn run_agent_task(input: AgentRunTaskInput) {
         promotion_episodic_ctx,
         promotion_project_id,
         run_start_utc,
+        resume,
     } = input;
 
     let Some(mcp) = spawn_mcp_for_agent(
-        &mcp_binary_path,
+        agent_mcp_servers(&mcp_binary_path, restart_mcp_on_crash, mcp_servers),
         &agent_token,
+        &event_tx,
         &terminal_event_tx,
         &emit_handle,
         &task_run_id,
@@ -85,11 +134,7 @@ async fn run_agent_task(input: AgentRunTaskInput) {
         return;
     };
 
-    let llm = clickweave_llm::LlmClient::new(agent_config.clone().with_thinking(false));
-    let vision: Arc<dyn clickweave_llm::DynChatBackend> = Arc::new(clickweave_llm::LlmClient::new(
-        agent_config.with_thinking(false).with_max_tokens(512),
-    ));
-    let config = agent_config_from_request(
+    let mut config = agent_config_from_request(
         consecutive_destructive_cap,
         allow_focus_window,
         episodic_settings_enabled,
@@ -97,7 +142,18 @@ async fn run_agent_task(input: AgentRunTaskInput) {
         skills_settings_enabled,
         applicable_skills_k_override,
         skills_global_participation,
+        max_duration_secs,
+        tool_timeouts_secs,
+        plan_first,
+        max_sub_agent_depth,
+        stream_llm,
+        llm_escalation,
     );
+    // Greedy completion checks, so the same screenshot and summary get
+    // the same verdict and repeats can be served from the cache.
+    if vlm_cache.is_some() {
+        config.completion_check_temperature = Some(0.0);
+    }
 
     let (variant_context, verification_artifacts_dir) = match initialize_agent_storage(&storage) {
         Ok(v) => v,
@@ -108,6 +164,63 @@ async fn run_agent_task(input: AgentRunTaskInput) {
         }
     };
 
+    // Built after `begin_execution` so a recording lands in this run's
+    // execution directory.
+    let mcp = match with_mcp_cassette(mcp, record_mcp, replay_mcp, &llm_cassette, &storage) {
+        Ok(mcp) => mcp,
+        Err(message) => {
+            emit_agent_task_error(&terminal_event_tx, &emit_handle, &task_run_id, message).await;
+            let _ = done_tx.send(());
+            return;
+        }
+    };
+    let llm = route_llm(
+        &agent_config,
+        fallback_config.as_ref(),
+        &event_tx,
+        |config| config.with_thinking(false),
+    );
+    let vision = route_llm(
+        &agent_config,
+        fallback_config.as_ref(),
+        &event_tx,
+        |config| config.with_thinking(false).with_max_tokens(512),
+    );
+    // Inside the cassette, so a recording still sees every call.
+    let vision = match &vlm_cache {
+        Some(cache) => CachedBackend::new(vision, cache.clone(), agent_config.temperature),
+        None => CachedBackend::passthrough(vision),
+    };
+    let taped = with_cassette(llm, "agent", &llm_cassette, &storage).and_then(|llm| {
+        with_cassette(vision, "vision", &llm_cassette, &storage).map(|vision| (llm, vision))
+    });
+    let (llm, vision) = match taped {
+        Ok(v) => v,
+        Err(message) => {
+            emit_agent_task_error(&terminal_event_tx, &emit_handle, &task_run_id, message).await;
+            let _ = done_tx.send(());
+            return;
+        }
+    };
+    let vision: Arc<dyn clickweave_llm::DynChatBackend> = Arc::new(vision);
+    let summarizer = summarizer_config
+        .map(|config| {
+            let summarizer = route_llm(&config, None, &event_tx, |config| {
+                config.with_thinking(false)
+            });
+            with_cassette(summarizer, "summarizer", &llm_cassette, &storage)
+        })
+        .transpose();
+    let summarizer = match summarizer {
+        Ok(summarizer) => summarizer
+            .map(|summarizer| Arc::new(summarizer) as Arc<dyn clickweave_llm::DynChatBackend>),
+        Err(message) => {
+            emit_agent_task_error(&terminal_event_tx, &emit_handle, &task_run_id, message).await;
+            let _ = done_tx.send(());
+            return;
+        }
+    };
+
     let goal_block = clickweave_engine::agent::build_goal_block(
         &goal,
         &prior_turns,
@@ -121,24 +234,55 @@ async fn run_agent_task(input: AgentRunTaskInput) {
     let channels = AgentChannels {
         event_tx: event_tx.clone(),
         approval_tx,
+        suspend_signal: Some(suspend_token),
+        cancel_signal: Some(agent_token.clone()),
+    };
+
+    // A resumed run carries its own goal block and run id in the
+    // snapshot; everything else is wired exactly like a fresh run.
+    let run = async {
+        match resume {
+            Some(snapshot) => {
+                clickweave_engine::agent::resume_agent_workflow(
+                    &llm,
+                    config,
+                    snapshot,
+                    &mcp,
+                    Some(channels),
+                    Some(vision.clone()),
+                    summarizer.clone(),
+                    permission_policy,
+                    verification_artifacts_dir,
+                    Some(storage.clone()),
+                    Some(episodic_ctx.clone()),
+                    Some(skill_ctx.clone()),
+                )
+                .await
+            }
+            None => {
+                clickweave_engine::agent::run_agent_workflow(
+                    &llm,
+                    config,
+                    goal_block,
+                    &mcp,
+                    Some(channels),
+                    Some(vision.clone()),
+                    summarizer.clone(),
+                    permission_policy,
+                    run_uuid,
+                    anchor_uuid,
+                    verification_artifacts_dir,
+                    Some(storage.clone()),
+                    Some(episodic_ctx.clone()),
+                    Some(skill_ctx.clone()),
+                )
+                .await
+            }
+        }
     };
 
     let result = tokio::select! {
-        res = clickweave_engine::agent::run_agent_workflow(
-            &llm,
-            config,
-            goal_block,
-            &mcp,
-            Some(channels),
-            Some(vision.clone()),
-            permission_policy,
-            run_uuid,
-            anchor_uuid,
-            verification_artifacts_dir,
-            Some(storage.clone()),
-            Some(episodic_ctx.clone()),
-            Some(skill_ctx.clone()),
-        ) => res,
+        res = run => res,
         _ = agent_token.cancelled() => {
             emit_after_agent_event_drain(
                 &terminal_event_tx,
@@ -180,26 +324,71 @@ async fn run_agent_task(input: AgentRunTaskInput) {
         }
     }
 
+    if let Some(cache) = &vlm_cache {
+        let stats = cache.stats();
+        let _ = storage
+            .lock()
+            .unwrap()
+            .append_agent_event(&AgentEvent::VlmCacheStats {
+                hits: stats.hits,
+                misses: stats.misses,
+                bypassed: stats.bypassed,
+                evicted: stats.evicted,
+            });
+    }
     let _ = done_tx.send(());
 }
 
-async fn spawn_mcp_for_agent(
+/// The desktop server, first, then the configured extras.
+fn agent_mcp_servers(
     mcp_binary_path: &str,
+    restart_on_crash: bool,
+    mcp_servers: Vec<McpServerWire>,
+) -> Vec<clickweave_mcp::McpServerConfig> {
+    let mut desktop = clickweave_mcp::McpServerConfig::primary(
+        "desktop",
+        clickweave_mcp::McpEndpoint::parse(mcp_binary_path),
+    );
+    if restart_on_crash {
+        desktop = desktop.with_restart(clickweave_mcp::RestartPolicy::default());
+    }
+    let mut configs = vec![desktop];
+    configs.extend(mcp_servers.into_iter().map(Into::into));
+    configs
+}
+
+/// Start the desktop server plus any configured extras. Extras that fail
+/// to start are reported as warnings and the run goes on without them.
+async fn spawn_mcp_for_agent(
+    configs: Vec<clickweave_mcp::McpServerConfig>,
     agent_token: &CancellationToken,
+    event_tx: &tokio::sync::mpsc::Sender<RunnerOutput>,
     terminal_event_tx: &tokio::sync::mpsc::Sender<RunnerOutput>,
     emit_handle: &tauri::AppHandle,
     task_run_id: &str,
-) -> Option<clickweave_mcp::McpClient> {
+) -> Option<clickweave_mcp::McpMultiplexer> {
+    let start = clickweave_mcp::McpMultiplexer::start(configs);
     tokio::select! {
-        res = clickweave_mcp::McpClient::spawn(mcp_binary_path, &[]) => {
+        res = start => {
             match res {
-                Ok(m) => Some(m),
+                Ok(m) => {
+                    for server in m.health().into_iter().filter(|s| !s.healthy) {
+                        let _ = event_tx.try_send(RunnerOutput::Event(AgentEvent::Warning {
+                            message: format!(
+                                "MCP server `{}` is unavailable: {}",
+                                server.name,
+                                server.error.unwrap_or_default()
+                            ),
+                        }));
+                    }
+                    Some(m)
+                }
                 Err(e) => {
                     emit_agent_task_error(
                         terminal_event_tx,
                         emit_handle,
                         task_run_id,
-                        format!("MCP spawn failed: {e}"),
+                        format!("MCP spawn failed: {e:#}"),
                     )
                     .await;
                     None
@@ -219,6 +408,101 @@ async fn spawn_mcp_for_agent(
     }
 }
 
+/// The primary endpoint, falling back to `fallback` when configured. Both
+/// get the same `adjust`ments, and retries and fallbacks surface as agent
+/// warnings.
+fn route_llm(
+    primary: &clickweave_llm::LlmConfig,
+    fallback: Option<&clickweave_llm::LlmConfig>,
+    event_tx: &tokio::sync::mpsc::Sender<RunnerOutput>,
+    adjust: impl Fn(clickweave_llm::LlmConfig) -> clickweave_llm::LlmConfig,
+) -> RoutingBackend<LlmClient> {
+    let client = |config: &clickweave_llm::LlmConfig| {
+        LlmClient::new(adjust(config.clone())).with_retry_observer(
+            clickweave_engine::agent::llm_retry_warnings(event_tx.clone()),
+        )
+    };
+    let router = RoutingBackend::new(client(primary)).with_retry_observer(
+        clickweave_engine::agent::llm_retry_warnings(event_tx.clone()),
+    );
+    match fallback {
+        Some(fallback) => router.with_fallback(client(fallback)),
+        None => router,
+    }
+}
+
+/// Wrap `client` for the requested cassette mode. Recording with run
+/// traces disabled has nowhere to write, so it degrades to passthrough.
+fn with_cassette<B: ChatBackend>(
+    client: B,
+    name: &str,
+    cassette: &LlmCassette,
+    storage: &Arc<Mutex<clickweave_core::storage::RunStorage>>,
+) -> Result<CassetteBackend<B>, String> {
+    let storage = storage.lock().unwrap();
+    match cassette {
+        LlmCassette::Off => Ok(CassetteBackend::passthrough(client)),
+        LlmCassette::Record => match storage.cassette_path(name) {
+            Some(path) => CassetteBackend::record(client, path).map_err(|e| format!("{e:#}")),
+            None => {
+                tracing::warn!("LLM recording requested but run traces are disabled");
+                Ok(CassetteBackend::passthrough(client))
+            }
+        },
+        LlmCassette::Replay {
+            execution_dir,
+            matching,
+        } => {
+            let path = storage.cassette_path_in(execution_dir, name);
+            CassetteBackend::replay(client, &path, *matching).map_err(|e| format!("{e:#}"))
+        }
+    }
+}
+
+/// Tape the run's MCP tool calls to `cassettes/mcp.jsonl` when `record`
+/// is set. As with LLM recording, disabled run traces degrade it to
+/// passthrough. `replay` serves them from the MCP cassette of the
+/// execution the LLM replays, matched as loosely as the LLM calls.
+fn with_mcp_cassette<M: clickweave_engine::Mcp>(
+    mcp: M,
+    record: bool,
+    replay: bool,
+    llm_cassette: &LlmCassette,
+    storage: &Arc<Mutex<clickweave_core::storage::RunStorage>>,
+) -> Result<clickweave_engine::McpCassette<M>, String> {
+    if let (
+        true,
+        LlmCassette::Replay {
+            execution_dir,
+            matching,
+        },
+    ) = (replay, llm_cassette)
+    {
+        let path = storage
+            .lock()
+            .unwrap()
+            .cassette_path_in(execution_dir, "mcp");
+        let matching = match matching {
+            ReplayMatching::Strict => clickweave_engine::McpReplayMatching::Sequence,
+            ReplayMatching::Lenient => clickweave_engine::McpReplayMatching::ToolAndArguments,
+        };
+        return clickweave_engine::McpCassette::replay(mcp, &path, matching)
+            .map_err(|e| format!("{e:#}"));
+    }
+    if !record {
+        return Ok(clickweave_engine::McpCassette::passthrough(mcp));
+    }
+    match storage.lock().unwrap().cassette_path("mcp") {
+        Some(path) => {
+            clickweave_engine::McpCassette::record(mcp, path).map_err(|e| format!("{e:#}"))
+        }
+        None => {
+            tracing::warn!("MCP recording requested but run traces are disabled");
+            Ok(clickweave_engine::McpCassette::passthrough(mcp))
+        }
+    }
+}
+
 fn initialize_agent_storage(
     storage: &Arc<Mutex<clickweave_core::storage::RunStorage>>,
 ) -> Result<(String, Option<std::path::PathBuf>), String> {
@@ -323,7 +607,9 @@ fn append_variant_entry(
     persist_traces: bool,
     resolved_terminal: &Option<TerminalReason>,
 ) {
-    if !persist_traces {
+    // A suspended run has not ended yet; its variant entry is written
+    // when the resumed run reaches a real terminal state.
+    if !persist_traces || matches!(resolved_terminal, Some(TerminalReason::Suspended { .. })) {
         return;
     }
     let (divergence_summary, success) = match resolved_terminal {
@@ -187,3 +187,63 @@ fn resolver_critical_section_closes_confirm_vs_force_stop_window() {
              had no pending sender to overwrite it with Cancel"
     );
 }
+
+/// `request_suspend` only fires when a run is active and persisting —
+/// otherwise the snapshot would have nowhere to go.
+#[test]
+fn request_suspend_requires_active_persisting_run() {
+    let mut idle = AgentHandle::default();
+    assert!(idle.request_suspend().is_err());
+
+    let token = CancellationToken::new();
+    let mut ephemeral = AgentHandle {
+        suspend_token: Some(token.clone()),
+        persist_traces: false,
+        ..Default::default()
+    };
+    assert!(ephemeral.request_suspend().is_err());
+    assert!(!token.is_cancelled());
+
+    let mut persisting = AgentHandle {
+        suspend_token: Some(token.clone()),
+        persist_traces: true,
+        ..Default::default()
+    };
+    assert!(persisting.request_suspend().is_ok());
+    assert!(token.is_cancelled());
+}
+
+/// `force_stop` releases a pending `ask_user` question so the engine's
+/// wait resolves immediately instead of running out its timeout.
+#[test]
+fn force_stop_releases_pending_question() {
+    let (tx, rx) = tokio::sync::oneshot::channel::<String>();
+    let mut handle = AgentHandle {
+        cancel_token: Some(CancellationToken::new()),
+        pending_question_tx: Some(tx),
+        ..Default::default()
+    };
+
+    assert!(handle.force_stop());
+    assert!(handle.pending_question_tx.is_none());
+    assert!(rx.blocking_recv().is_err());
+}
+
+/// A pending plan review is rejected explicitly on stop, so the engine
+/// sees a decision rather than a closed channel (which would surface as
+/// `approval_unavailable`).
+#[test]
+fn force_stop_rejects_pending_plan() {
+    let (tx, rx) = tokio::sync::oneshot::channel::<PlanDecision>();
+    let mut handle = AgentHandle {
+        cancel_token: Some(CancellationToken::new()),
+        pending_plan_tx: Some(tx),
+        ..Default::default()
+    };
+
+    assert!(handle.force_stop());
+    assert!(matches!(
+        rx.blocking_recv(),
+        Ok(PlanDecision::Reject { .. })
+    ));
+}
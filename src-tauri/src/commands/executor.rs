@@ -2,8 +2,11 @@ use super::error::CommandError;
 use super::types::*;
 use clickweave_engine::agent::skills::{ActionSketchStep, Skill, SkillStore};
 use clickweave_engine::executor::skill_runner::{SkillRunContext, run_skill_steps};
-use clickweave_engine::{ExecutorCommand, ExecutorEvent, ExecutorState};
-use clickweave_mcp::McpClient;
+use clickweave_engine::{
+    ExecutorCommand, ExecutorError, ExecutorEvent, ExecutorState, McpCassette,
+    forward_notifications_as_logs,
+};
+use clickweave_mcp::{McpClient, McpEndpoint};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::collections::{HashMap, HashSet};
@@ -70,6 +73,11 @@ pub struct RunSkillRequest {
     /// Privacy kill switch — `Some(false)` disables run/skill artifact
     /// persistence (D31). `None` falls back to settings.
     pub store_traces: Option<bool>,
+    /// Record every MCP tool call and result to `cassettes/mcp.jsonl` in
+    /// the run's events directory, for replay through `McpCassette`.
+    /// Default off.
+    #[serde(default)]
+    pub record_mcp: Option<bool>,
 }
 
 fn default_supervision_delay_ms() -> u64 {
@@ -139,6 +147,8 @@ pub async fn run_skill(
     let run_record = storage
         .create_skill_run(&skill.id)
         .map_err(|e| CommandError::io(format!("create skill run: {e}")))?;
+    let mcp_cassette =
+        skill_mcp_cassette(&storage, request.record_mcp.unwrap_or(false), &run_record);
 
     let run_generation = {
         let handle = app.state::<Mutex<ExecutorHandle>>();
@@ -160,6 +170,7 @@ pub async fn run_skill(
             &skill,
             &request.variables,
             &mcp_binary_path,
+            mcp_cassette,
             &cancel_token,
             &event_tx,
         )
@@ -207,6 +218,24 @@ pub async fn run_skill(
     Ok(())
 }
 
+/// Where to tape a skill run's MCP calls when `record` is set. With run
+/// traces disabled there is nowhere to record to, so the run goes
+/// untaped.
+fn skill_mcp_cassette(
+    storage: &clickweave_core::storage::RunStorage,
+    record: bool,
+    run: &clickweave_core::SkillRun,
+) -> Option<std::path::PathBuf> {
+    if !record {
+        return None;
+    }
+    let path = storage.skill_run_cassette_path(&run.skill_id, run.run_id, "mcp");
+    if path.is_none() {
+        warn!("MCP recording requested but run traces are disabled");
+    }
+    path
+}
+
 fn load_skill_by_id(store: &SkillStore, skill_id: &str) -> Result<Skill, CommandError> {
     let files = store
         .list_files()
@@ -229,6 +258,7 @@ async fn run_skill_dispatch(
     skill: &Skill,
     variables: &HashMap<String, serde_json::Value>,
     mcp_binary_path: &str,
+    mcp_cassette: Option<std::path::PathBuf>,
     cancel_token: &CancellationToken,
     event_tx: &tokio::sync::mpsc::Sender<ExecutorEvent>,
 ) -> anyhow::Result<()> {
@@ -237,34 +267,55 @@ async fn run_skill_dispatch(
         _ = cancel_token.cancelled() => {
             anyhow::bail!("Cancelled before MCP spawn");
         }
-        res = McpClient::spawn(mcp_binary_path, &[]) => res?,
+        res = McpClient::connect(&McpEndpoint::parse(mcp_binary_path)) => res?,
     };
 
-    let mut ctx = SkillRunContext::new(&mcp, variables.clone());
+    // Server progress and log lines go to the run log. The forwarder
+    // ends when `mcp` is dropped.
+    tauri::async_runtime::spawn(forward_notifications_as_logs(
+        mcp.notifications(),
+        event_tx.clone(),
+    ));
+    let mcp = match mcp_cassette {
+        Some(path) => McpCassette::record(mcp, path)?,
+        None => McpCassette::passthrough(mcp),
+    };
+    let mut ctx =
+        SkillRunContext::new(&mcp, variables.clone()).with_cancel_signal(cancel_token.clone());
 
-    tokio::select! {
-        biased;
-        _ = cancel_token.cancelled() => {
-            anyhow::bail!("Cancelled");
+    match run_skill_steps(&mut ctx, &skill.action_sketch).await {
+        Ok(()) => {
+            let _ = event_tx
+                .send(ExecutorEvent::Log(format!(
+                    "Skill '{}' completed ({} steps)",
+                    skill.name,
+                    ctx.completed_steps.len()
+                )))
+                .await;
+            Ok(())
         }
-        res = run_skill_steps(&mut ctx, &skill.action_sketch) => {
-            match res {
-                Ok(()) => {
-                    let _ = event_tx
-                        .send(ExecutorEvent::Log(format!(
-                            "Skill '{}' completed ({} steps)",
-                            skill.name,
-                            ctx.completed_steps.len()
-                        )))
-                        .await;
-                    Ok(())
-                }
-                Err(e) => Err(anyhow::anyhow!(format!("{e}"))),
-            }
+        Err(e) => {
+            log_cancelled_call(&e, event_tx).await;
+            Err(anyhow::anyhow!(format!("{e}")))
         }
     }
 }
 
+/// Record which call a stop abandoned; the server was already told to
+/// stop working on it when the call was dropped.
+async fn log_cancelled_call(
+    error: &ExecutorError,
+    event_tx: &tokio::sync::mpsc::Sender<ExecutorEvent>,
+) {
+    if let ExecutorError::ToolCallCancelled { step_id, tool } = error {
+        let _ = event_tx
+            .send(ExecutorEvent::Log(format!(
+                "Cancelled in-flight tool call {tool} (step {step_id})"
+            )))
+            .await;
+    }
+}
+
 fn spawn_executor_event_forwarder(
     emit_handle: tauri::AppHandle,
     mut event_rx: tokio::sync::mpsc::Receiver<ExecutorEvent>,
@@ -438,6 +489,8 @@ pub struct ResumeSkillFromFailureRequest {
     #[serde(default = "default_supervision_delay_ms")]
     pub supervision_delay_ms: u64,
     pub store_traces: Option<bool>,
+    #[serde(default)]
+    pub record_mcp: Option<bool>,
     /// The section ID to resume from. All sections before this section are skipped.
     pub from_section_id: String,
 }
@@ -526,6 +579,8 @@ pub async fn resume_skill_from_failure(
     let run_record = storage
         .create_skill_run(&skill.id)
         .map_err(|e| CommandError::io(format!("create skill run: {e}")))?;
+    let mcp_cassette =
+        skill_mcp_cassette(&storage, request.record_mcp.unwrap_or(false), &run_record);
 
     let run_generation = {
         let handle = app.state::<Mutex<ExecutorHandle>>();
@@ -549,6 +604,7 @@ pub async fn resume_skill_from_failure(
             &filtered_sketch,
             &variables,
             &mcp_binary_path,
+            mcp_cassette,
             &cancel_token,
             &event_tx,
             &skill_name,
@@ -596,6 +652,7 @@ async fn run_skill_steps_from_filtered(
     filtered_sketch: &[ActionSketchStep],
     variables: &HashMap<String, serde_json::Value>,
     mcp_binary_path: &str,
+    mcp_cassette: Option<std::path::PathBuf>,
     cancel_token: &CancellationToken,
     event_tx: &tokio::sync::mpsc::Sender<ExecutorEvent>,
     skill_name: &str,
@@ -605,30 +662,36 @@ async fn run_skill_steps_from_filtered(
         _ = cancel_token.cancelled() => {
             anyhow::bail!("Cancelled before MCP spawn");
         }
-        res = McpClient::spawn(mcp_binary_path, &[]) => res?,
+        res = McpClient::connect(&McpEndpoint::parse(mcp_binary_path)) => res?,
     };
 
-    let mut ctx = SkillRunContext::new(&mcp, variables.clone());
+    // Server progress and log lines go to the run log. The forwarder
+    // ends when `mcp` is dropped.
+    tauri::async_runtime::spawn(forward_notifications_as_logs(
+        mcp.notifications(),
+        event_tx.clone(),
+    ));
+    let mcp = match mcp_cassette {
+        Some(path) => McpCassette::record(mcp, path)?,
+        None => McpCassette::passthrough(mcp),
+    };
+    let mut ctx =
+        SkillRunContext::new(&mcp, variables.clone()).with_cancel_signal(cancel_token.clone());
 
-    tokio::select! {
-        biased;
-        _ = cancel_token.cancelled() => {
-            anyhow::bail!("Cancelled");
+    match run_skill_steps(&mut ctx, filtered_sketch).await {
+        Ok(()) => {
+            let _ = event_tx
+                .send(ExecutorEvent::Log(format!(
+                    "Skill '{}' resume completed ({} steps)",
+                    skill_name,
+                    ctx.completed_steps.len()
+                )))
+                .await;
+            Ok(())
         }
-        res = run_skill_steps(&mut ctx, filtered_sketch) => {
-            match res {
-                Ok(()) => {
-                    let _ = event_tx
-                        .send(ExecutorEvent::Log(format!(
-                            "Skill '{}' resume completed ({} steps)",
-                            skill_name,
-                            ctx.completed_steps.len()
-                        )))
-                        .await;
-                    Ok(())
-                }
-                Err(e) => Err(anyhow::anyhow!(format!("{e}"))),
-            }
+        Err(e) => {
+            log_cancelled_call(&e, event_tx).await;
+            Err(anyhow::anyhow!(format!("{e}")))
         }
     }
 }
@@ -13,8 +13,9 @@ mod walkthrough_enrichment;
 mod walkthrough_session;
 
 pub use agent::{
-    AgentHandle, add_run_to_skill, approve_agent_action, resolve_completion_disagreement,
-    run_agent, save_run_as_skill, stop_agent,
+    AgentHandle, add_run_to_skill, answer_agent_question, approve_agent_action,
+    resolve_completion_disagreement, resume_agent, review_agent_plan, run_agent, save_run_as_skill,
+    stop_agent, suspend_agent,
 };
 pub use chrome_profiles::{
     create_chrome_profile, get_chrome_profile_path, is_chrome_profile_configured,
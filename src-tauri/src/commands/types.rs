@@ -1,6 +1,6 @@
 use clickweave_core::ProjectManifest;
 use clickweave_core::storage::RunStorage;
-use clickweave_llm::LlmConfig;
+use clickweave_llm::{LlmConfig, LlmProvider, ResponseCache, ResponseCacheSettings, ToolMode};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::path::PathBuf;
@@ -27,6 +27,20 @@ pub fn resolve_storage(
     }
 }
 
+/// Open the app-wide VLM response cache when `settings` enable it.
+pub fn open_vlm_cache(
+    app: &tauri::AppHandle,
+    settings: Option<ResponseCacheSettings>,
+) -> Result<Option<std::sync::Arc<ResponseCache>>, super::error::CommandError> {
+    let Some(settings) = settings else {
+        return Ok(None);
+    };
+    let dir = app.state::<AppDataDir>().0.join("vlm_cache");
+    ResponseCache::open(dir, settings)
+        .map(|cache| Some(std::sync::Arc::new(cache)))
+        .map_err(|e| super::error::CommandError::io(format!("{e:#}")))
+}
+
 pub fn project_dir(path: &str) -> PathBuf {
     let p = PathBuf::from(path);
     if p.extension().is_some() {
@@ -58,16 +72,22 @@ pub struct EndpointConfig {
     pub base_url: String,
     pub model: String,
     pub api_key: Option<String>,
+    #[serde(default)]
+    pub provider: LlmProvider,
+    #[serde(default)]
+    pub tool_mode: ToolMode,
 }
 
 impl EndpointConfig {
     pub fn into_llm_config(self, temperature: Option<f32>) -> LlmConfig {
         LlmConfig {
+            provider: self.provider,
             base_url: self.base_url,
             api_key: self.api_key.filter(|k| !k.is_empty()),
             model: self.model,
             temperature,
             max_tokens: None,
+            tool_mode: self.tool_mode,
             ..LlmConfig::default()
         }
     }
@@ -376,7 +376,9 @@ pub async fn stop_walkthrough(
     app: tauri::AppHandle,
     supervisor: Option<super::types::EndpointConfig>,
     hover_dwell_threshold: Option<u64>,
+    vlm_cache: Option<clickweave_llm::ResponseCacheSettings>,
 ) -> Result<(), CommandError> {
+    let vlm_cache = super::types::open_vlm_cache(&app, vlm_cache)?;
     let (task, storage, session_dir, _project_id, session_id) = {
         let handle = app.state::<Mutex<WalkthroughHandle>>();
         let mut guard = handle.lock().unwrap();
@@ -463,7 +465,7 @@ pub async fn stop_walkthrough(
 
             // VLM: resolve click and hover targets using vision (parallel).
             if let Some(ref supervisor_cfg) = supervisor {
-                resolve_click_targets_with_vlm(&mut actions, supervisor_cfg).await;
+                resolve_click_targets_with_vlm(&mut actions, supervisor_cfg, vlm_cache).await;
             }
 
             // Clean up raw recording frames — they're no longer needed after
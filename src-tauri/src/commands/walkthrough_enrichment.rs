@@ -171,10 +171,15 @@ pub(super) fn parse_screenshot_metadata(
 pub(super) struct VlmClickRequest {
     pub(super) image_b64: String,
     pub(super) prompt: String,
+    /// The set-of-marks box drawn around the click; empty when the image
+    /// fell back to a crosshair.
+    pub(super) marks: clickweave_llm::SetOfMarks,
 }
 
-/// Prepare a VLM request for a single click: read screenshot, mark crosshair,
-/// build prompt with context hints. Returns `None` if prerequisites are missing.
+/// Prepare a VLM request for a single click: read screenshot, draw a
+/// numbered box around the click (or a crosshair when the box cannot be
+/// placed), build prompt with context hints. Returns `None` if
+/// prerequisites are missing.
 pub(super) fn prepare_vlm_click_request(
     screenshot_path: &str,
     click_x: f64,
@@ -192,22 +197,56 @@ pub(super) fn prepare_vlm_click_request(
         }
     };
 
-    // Compute click position in image pixel coordinates.
-    let (px, py) = meta.screen_to_pixel(click_x, click_y);
-
-    let image_b64 = mark_click_point(&image_bytes, px, py)?;
+    let candidates = [clickweave_llm::MarkCandidate::point(
+        click_x,
+        click_y,
+        CROP_HALF_SIZE_PTS,
+    )];
+    let (image_b64, marks) = match clickweave_llm::prepare_marked_image_for_vlm(
+        &image_bytes,
+        &candidates,
+        meta,
+        clickweave_llm::DEFAULT_MAX_DIMENSION,
+    ) {
+        Some((b64, _mime, marks)) if !marks.is_empty() => (b64, marks),
+        _ => {
+            // Compute click position in image pixel coordinates.
+            let (px, py) = meta.screen_to_pixel(click_x, click_y);
+            let b64 = mark_click_point(&image_bytes, px, py)?;
+            (b64, clickweave_llm::SetOfMarks::default())
+        }
+    };
+    let click_mark = marks.marks.first().map(|mark| mark.number);
 
     // Delegate prompt construction to the library crate.
-    let prompt = enrichment::build_vlm_click_prompt(ax_label, ocr_text, app_name);
+    let prompt = enrichment::build_vlm_click_prompt(ax_label, ocr_text, app_name, click_mark);
 
-    Some(VlmClickRequest { image_b64, prompt })
+    Some(VlmClickRequest {
+        image_b64,
+        prompt,
+        marks,
+    })
+}
+
+/// Whether a VLM reply only points back at the click's box ("mark 1",
+/// "[1]") instead of naming the element. A reply that is just the number
+/// is kept: "1" is a real label on a keypad.
+fn reply_only_cites_mark(reply: &str, marks: &clickweave_llm::SetOfMarks) -> bool {
+    if marks.resolve_reply(reply).is_none() || reply.trim().parse::<u32>().is_ok() {
+        return false;
+    }
+    !reply
+        .to_lowercase()
+        .replace("mark", "")
+        .chars()
+        .any(char::is_alphabetic)
 }
 
 /// Execute a VLM request and return the resolved label, or `None` on failure.
 ///
 /// Retries once if the model exhausts its token budget on reasoning.
 pub(super) async fn execute_vlm_click_request(
-    backend: &clickweave_llm::LlmClient,
+    backend: &impl clickweave_llm::ChatBackend,
     request: &VlmClickRequest,
 ) -> Option<String> {
     let make_messages = || {
@@ -239,6 +278,7 @@ pub(super) async fn execute_vlm_click_request(
             .choices
             .first()
             .and_then(|c| c.message.content_text())
+            .filter(|reply| !reply_only_cites_mark(reply, &request.marks))
             .and_then(enrichment::clean_vlm_label),
         Err(_) => None,
     }
@@ -260,10 +300,13 @@ pub(super) fn attach_recording_frames(
 /// For each Click or Hover action that lacks an actionable AX label or VLM label,
 /// draws a crosshair on the screenshot and sends it to the VLM asking what UI
 /// element is at that point. For hovers with before/after recording frames,
-/// uses the before frame (element unobscured by hover effects).
+/// uses the before frame (element unobscured by hover effects). With
+/// `vlm_cache`, labels already resolved for the same crosshair image and
+/// prompt are reused.
 pub(super) async fn resolve_click_targets_with_vlm(
     actions: &mut [WalkthroughAction],
     supervisor_cfg: &super::types::EndpointConfig,
+    vlm_cache: Option<std::sync::Arc<clickweave_llm::ResponseCache>>,
 ) {
     use clickweave_core::walkthrough::{TargetCandidate, WalkthroughActionKind};
 
@@ -350,12 +393,20 @@ pub(super) async fn resolve_click_targets_with_vlm(
         inputs.len()
     );
 
+    // Greedy decoding when caching: the cache only answers deterministic
+    // calls, and the same marked image should get the same label.
+    let temperature = if vlm_cache.is_some() { 0.0 } else { 0.1 };
     let llm_config = supervisor_cfg
         .clone()
-        .into_llm_config(Some(0.1))
+        .into_llm_config(Some(temperature))
         .with_max_tokens(2048)
         .with_thinking(false);
-    let backend = std::sync::Arc::new(clickweave_llm::LlmClient::new(llm_config));
+    let temperature = llm_config.temperature;
+    let client = clickweave_llm::LlmClient::new(llm_config);
+    let backend = std::sync::Arc::new(match vlm_cache {
+        Some(cache) => clickweave_llm::CachedBackend::new(client, cache, temperature),
+        None => clickweave_llm::CachedBackend::passthrough(client),
+    });
 
     let mut join_set = tokio::task::JoinSet::new();
 
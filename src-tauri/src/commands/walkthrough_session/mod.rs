@@ -16,7 +16,7 @@ use clickweave_core::walkthrough::{
     ScreenshotKind, ScreenshotMeta, WalkthroughEvent, WalkthroughEventKind,
     WalkthroughSessionRuntime, WalkthroughStatus, WalkthroughStorage,
 };
-use clickweave_mcp::McpClient;
+use clickweave_mcp::{McpClient, McpEndpoint};
 use tauri::{Emitter, Manager};
 use uuid::Uuid;
 
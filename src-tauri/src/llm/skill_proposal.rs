@@ -65,6 +65,7 @@ pub async fn propose_skill_refinement_with_backend<B: ChatBackend + ?Sized>(
     let options = ChatOptions {
         temperature: Some(0.0),
         max_tokens: Some(2048),
+        ..ChatOptions::default()
     };
 
     let response = llm_client
@@ -294,6 +295,7 @@ mod tests {
                 match &self.response {
                     Ok(text) => Ok(ChatResponse {
                         id: "stub".into(),
+                        model: None,
                         choices: vec![Choice {
                             index: 0,
                             message: Message::assistant(text),
@@ -129,7 +129,11 @@ fn main() {
             list_models,
             run_agent,
             stop_agent,
+            suspend_agent,
+            resume_agent,
             approve_agent_action,
+            answer_agent_question,
+            review_agent_plan,
             resolve_completion_disagreement,
             save_run_as_skill,
             add_run_to_skill,
@@ -1,9 +1,23 @@
 /// Resolve the path to the native-devtools-mcp binary as a UTF-8 string.
 ///
-/// In debug builds, checks `CLICKWEAVE_MCP_BINARY` env var first.
-/// Otherwise resolves relative to the current executable (where Tauri
-/// places sidecar binaries: `Contents/MacOS/` on macOS, install dir on Windows).
+/// `CLICKWEAVE_MCP_URL`, when set to an `http(s)://` URL, wins: the result
+/// is that URL and `McpEndpoint::parse` turns it into a Streamable HTTP
+/// connection instead of a spawn. In debug builds, checks
+/// `CLICKWEAVE_MCP_BINARY` env var next. Otherwise resolves relative to the
+/// current executable (where Tauri places sidecar binaries:
+/// `Contents/MacOS/` on macOS, install dir on Windows).
 pub fn resolve_mcp_binary() -> anyhow::Result<String> {
+    if let Ok(url) = std::env::var("CLICKWEAVE_MCP_URL") {
+        if let clickweave_mcp::McpEndpoint::Url(url) = clickweave_mcp::McpEndpoint::parse(&url) {
+            tracing::info!("Using MCP server at {}", url);
+            return Ok(url);
+        }
+        tracing::warn!(
+            "CLICKWEAVE_MCP_URL='{}' is not an http(s) URL, ignoring",
+            url
+        );
+    }
+
     #[cfg(debug_assertions)]
     if let Ok(path) = std::env::var("CLICKWEAVE_MCP_BINARY") {
         if let Ok(path) = validated_mcp_binary_path(std::path::Path::new(&path)) {
@@ -187,9 +187,9 @@ async resumeWalkthrough() : Promise<Result<null, CommandError>> {
     else return { status: "error", error: e  as any };
 }
 },
-async stopWalkthrough(supervisor: EndpointConfig | null, hoverDwellThreshold: number | null) : Promise<Result<null, CommandError>> {
+async stopWalkthrough(supervisor: EndpointConfig | null, hoverDwellThreshold: number | null, vlmCache: ResponseCacheSettings | null) : Promise<Result<null, CommandError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("stop_walkthrough", { supervisor, hoverDwellThreshold }) };
+    return { status: "ok", data: await TAURI_INVOKE("stop_walkthrough", { supervisor, hoverDwellThreshold, vlmCache }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -326,6 +326,43 @@ async stopAgent() : Promise<Result<null, CommandError>> {
     else return { status: "error", error: e  as any };
 }
 },
+async suspendAgent() : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("suspend_agent") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async resumeAgent(request: AgentRunRequest) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resume_agent", { request }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async answerAgentQuestion(answer: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("answer_agent_question", { answer }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Approve, edit, or reject the plan the agent proposed in plan-first
+ * mode. An approval may carry an edited subgoal list; it replaces the
+ * proposal verbatim.
+ */
+async reviewAgentPlan(decision: PlanDecision) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("review_agent_plan", { decision }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async approveAgentAction(approved: boolean) : Promise<Result<null, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("approve_agent_action", { approved }) };
@@ -614,7 +651,92 @@ applicable_skills_k?: number | null;
  * Spec 3 privacy opt-in: when `true`, confirmed global skills may
  * participate in retrieval for this run.
  */
-skills_global_participation?: boolean | null }
+skills_global_participation?: boolean | null; 
+/**
+ * Wall-clock deadline for the run, in seconds. `0` disables the
+ * deadline. `None` uses the engine default (15 minutes).
+ */
+max_duration_secs?: number | null; 
+/**
+ * Per-tool MCP call timeouts from project settings, in seconds:
+ * `{ "cdp_wait_for_page_change": 20 }`. Override the engine's
+ * family defaults for the named tools only; `0` entries are ignored.
+ */
+tool_timeouts_secs?: Partial<{ [key in string]: number }>; 
+/**
+ * Plan-first mode: the agent proposes a subgoal plan and waits for
+ * approval (via `review_agent_plan`) before acting. Default off.
+ */
+plan_first?: boolean | null; 
+/**
+ * How many levels of `delegate_subgoal` sub-agents the run may
+ * spawn. `None`/`0` keeps the tool hidden.
+ */
+max_sub_agent_depth?: number | null; 
+/**
+ * Stream LLM turns and forward text/reasoning fragments as
+ * `agent://llm_delta`. Default off.
+ */
+stream_llm?: boolean | null; 
+/**
+ * Record every LLM request and response to `cassettes/*.jsonl` in
+ * the run's execution directory. Default off.
+ */
+record_llm?: boolean | null; 
+/**
+ * Answer LLM calls from the cassettes of this earlier execution of
+ * the workflow (its execution directory name) instead of the
+ * endpoint. Takes precedence over `record_llm`.
+ */
+replay_llm_from?: string | null; 
+/**
+ * How strictly replayed requests must match the recording.
+ * Default strict.
+ */
+replay_matching?: ReplayMatching | null; 
+/**
+ * Record every MCP tool call and result to `cassettes/mcp.jsonl` in
+ * the run's execution directory, with images saved alongside.
+ * Default off.
+ */
+record_mcp?: boolean | null; 
+/**
+ * Answer MCP tool calls from the `replay_llm_from` execution's MCP
+ * cassette instead of the desktop, so a recorded run replays end to
+ * end. Requires `replay_llm_from`. Default off.
+ */
+replay_mcp?: boolean | null; 
+/**
+ * Endpoint the agent and VLM fall back to when the primary errors,
+ * times out or replies with something unusable; also the target of
+ * `llm_escalation`. `None` keeps a single endpoint.
+ */
+agent_fallback?: EndpointConfig | null; 
+/**
+ * Which turns go straight to `agent_fallback`. Default never.
+ */
+llm_escalation?: LlmEscalation | null; 
+/**
+ * Endpoint, typically a cheap model, that folds the steps compaction
+ * collapses into a narrative summary. `None` keeps the one-line
+ * stand-ins.
+ */
+summarizer?: EndpointConfig | null; 
+/**
+ * Serve repeated completion-check VLM calls from the on-disk
+ * response cache. `None` leaves it off.
+ */
+vlm_cache?: ResponseCacheSettings | null; 
+/**
+ * Extra MCP servers from settings, started next to the
+ * desktop server. Their tools reach the agent as `{name}__{tool}`.
+ */
+mcp_servers?: McpServerWire[]; 
+/**
+ * Restart the desktop MCP server if it crashes mid-run, instead of
+ * failing every later tool call. Default off.
+ */
+restart_mcp_on_crash?: boolean | null }
 /**
  * One agent step sent from the frontend for skill materialisation.
  * `args_json` is the JSON-serialised tool arguments; empty string is
@@ -675,7 +797,7 @@ export type DeleteSkillRequest = { skill_id: string; version: number; scope: Ski
  * A running app detected as Electron or Chrome, returned to the frontend for CDP selection.
  */
 export type DetectedCdpApp = { name: string; pid: number; app_kind: AppKind }
-export type EndpointConfig = { base_url: string; model: string; api_key: string | null }
+export type EndpointConfig = { base_url: string; model: string; api_key: string | null; provider?: LlmProvider; tool_mode?: ToolMode }
 export type ErrorKind = "Validation" | "Io" | "Mcp" | "AlreadyRunning" | "Internal"
 export type ExecutionMode = "Test" | "Run"
 /**
@@ -699,6 +821,25 @@ export type Hypothesis = { text: string; recorded_at_step: number; refuted: bool
 export type ImportedAsset = { relative_path: string; absolute_path: string }
 export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
 export type ListSkillsRequest = { scope: SkillScope; project_path: string | null; project_name: string; project_id: string; store_traces: boolean }
+/**
+ * Escalation policy for [`clickweave_llm::RoutingBackend`]: which LLM
+ * calls set `ChatOptions::escalate`. Backends without routes ignore it.
+ */
+export type LlmEscalation = { 
+/**
+ * Escalate once this many steps in a row have failed. `None` or `0`
+ * never escalates on errors alone.
+ */
+after_consecutive_errors?: number | null; 
+/**
+ * Escalate every turn taken in `Phase::Recovering`.
+ */
+while_recovering?: boolean; 
+/**
+ * Escalate the VLM check that confirms `agent_done`.
+ */
+completion_check?: boolean }
+export type LlmProvider = "open_ai_compatible" | "anthropic"
 export type LoadAgentChatRequest = { project_path: string | null; project_name: string; project_id: string }
 export type LoadLatestRunTraceRequest = { project_path: string | null; project_name: string; project_id: string; store_traces: boolean }
 /**
@@ -714,6 +855,32 @@ export type LoopPredicate = { type: "world_model_delta"; expr: string } | { type
  * with serde/specta derives).
  */
 export type MarkdownReplacementDto = { old_text: string; new_text: string }
+/**
+ * An auxiliary MCP server for an agent run.
+ */
+export type McpServerWire = { 
+/**
+ * Tool-name prefix: letters, digits, `-` and `_`.
+ */
+name: string; 
+/**
+ * Command to spawn, or an `http(s)://` URL to connect to.
+ */
+target: string; 
+/**
+ * Arguments for a spawned command. Ignored for a URL.
+ */
+args?: string[]; 
+/**
+ * Refuse to start the run without this server. Default off: the
+ * run continues with a warning.
+ */
+required?: boolean; 
+/**
+ * Restart the server if it goes away mid-run. Default off: its
+ * tools are dropped for the rest of the run.
+ */
+restart_on_crash?: boolean }
 export type Milestone = { subgoal_id: SubgoalId; text: string; summary: string; pushed_at_step: number; completed_at_step: number }
 export type MouseButton = "Left" | "Right" | "Center"
 export type NodeRename = { node_id: string; new_name: string }
@@ -755,6 +922,29 @@ export type Phase =
  * Consecutive errors or an `agent_replan` this step.
  */
 "recovering"
+/**
+ * Operator's answer to a proposed plan.
+ */
+export type PlanDecision = 
+/**
+ * Approve the plan. `subgoals` is the plan as the operator left it;
+ * an empty list approves the proposal unchanged.
+ */
+{ decision: "approve"; subgoals: PlannedSubgoal[] } | 
+/**
+ * Send the plan back; `reason` is shown to the LLM.
+ */
+{ decision: "reject"; reason: string }
+/**
+ * One subgoal of an approved plan-first plan, linked to the stack
+ * entry it was seeded as so progress can be read off `milestones`.
+ */
+export type PlanItem = { subgoal_id: SubgoalId; text: string; expected_apps: string[]; expected_tools: string[] }
+/**
+ * One subgoal as proposed by the LLM (and possibly edited by the
+ * operator).
+ */
+export type PlannedSubgoal = { text: string; expected_apps?: string[]; expected_tools?: string[] }
 /**
  * Wire form of a prior-turn entry (matches
  * `clickweave_engine::agent::PriorTurn` with string UUIDs for JSON).
@@ -770,15 +960,36 @@ export type ProvenanceEntry = { run_id: string; step_index: number; completed_at
 export type PruneSkillLineageRequest = { project_path: string | null; project_name: string; project_id: string; node_ids: string[]; store_traces: boolean }
 export type ReadArtifactQuery = { project_path: string | null; project_id: string; project_name: string; skill_id: string; run_id: string; artifact_path: string }
 export type RejectSkillProposalRequest = { skill_id: string; version: number; project_path: string | null; project_name: string; project_id: string; store_traces: boolean }
+/**
+ * How replay pairs incoming requests with recorded ones.
+ */
+export type ReplayMatching = "strict" | "lenient"
 /**
  * Wire-format `ReplaySidecarMutation` for Tauri IPC.
  */
 export type ReplaySidecarMutationDto = { type: "clear_signals"; step_id: string } | { type: "append_section_history"; retired: string; split_into: string[]; at_version: number } | { type: "delete_step_bundle"; step_id: string } | { type: "update_requires_approval"; step_id: string; value: boolean | null }
+/**
+ * User-facing cache settings.
+ */
+export type ResponseCacheSettings = { 
+/**
+ * Entries older than this are ignored and replaced.
+ */
+ttl_hours?: number; 
+/**
+ * Total size the cache directory is trimmed to, least recently used
+ * entries first.
+ */
+max_mb?: number; 
+/**
+ * Cache sampled (temperature > 0) calls too.
+ */
+force?: boolean }
 /**
  * Request body for `resume_skill_from_failure`.
  * Inherits all fields from `RunSkillRequest` and adds the section to resume from.
  */
-export type ResumeSkillFromFailureRequest = { project_path: string | null; project_id: string; project_name: string; skill_id: string; variables?: Partial<{ [key in string]: JsonValue }>; agent: EndpointConfig; fast: EndpointConfig | null; supervisor: EndpointConfig | null; execution_mode: ExecutionMode; supervision_delay_ms?: number; store_traces: boolean | null; 
+export type ResumeSkillFromFailureRequest = { project_path: string | null; project_id: string; project_name: string; skill_id: string; variables?: Partial<{ [key in string]: JsonValue }>; agent: EndpointConfig; fast: EndpointConfig | null; supervisor: EndpointConfig | null; execution_mode: ExecutionMode; supervision_delay_ms?: number; store_traces: boolean | null; record_mcp?: boolean | null; 
 /**
  * The section ID to resume from. All sections before this section are skipped.
  */
@@ -811,7 +1022,13 @@ supervisor: EndpointConfig | null; execution_mode: ExecutionMode; supervision_de
  * Privacy kill switch — `Some(false)` disables run/skill artifact
  * persistence (D31). `None` falls back to settings.
  */
-store_traces: boolean | null }
+store_traces: boolean | null; 
+/**
+ * Record every MCP tool call and result to `cassettes/mcp.jsonl` in
+ * the run's events directory, for replay through `McpCassette`.
+ * Default off.
+ */
+record_mcp?: boolean | null }
 export type RunStatus = "Ok" | "Failed" | "Stopped" | "Cancelled"
 export type RunsQuery = { project_path: string | null; project_id: string; project_name: string; 
 /**
@@ -954,7 +1171,13 @@ export type TargetCandidate = { type: "AccessibilityLabel"; label: string; role:
  */
 { type: "WindowControl"; action: WindowControlAction }
 export type TargetOverride = { node_id: string; chosen_candidate_index: number }
-export type TaskState = { goal: string; subgoal_stack: Subgoal[]; watch_slots: WatchSlot[]; hypotheses: Hypothesis[]; phase: Phase; milestones: Milestone[] }
+export type TaskState = { goal: string; subgoal_stack: Subgoal[]; watch_slots: WatchSlot[]; hypotheses: Hypothesis[]; phase: Phase; milestones: Milestone[]; 
+/**
+ * Approved plan-first plan, in order. Empty when the run is not in
+ * plan-first mode or no plan has been approved yet.
+ */
+plan?: PlanItem[] }
+export type ToolMode = "native_tools" | "json_schema"
 export type TraceEvent = { timestamp: number; event_type: TraceEventKind; payload: JsonValue }
 /**
  * The canonical set of trace event kinds emitted by the executor.
@@ -1,3 +1,11 @@
+import type { McpServerWire } from "../bindings";
+import {
+  formatMcpServers,
+  formatToolTimeouts,
+  parseMcpServers,
+  parseToolTimeouts,
+} from "../store/settings";
+
 const inputClass =
   "w-full rounded bg-[var(--bg-input)] px-2.5 py-1.5 text-xs text-[var(--text-primary)] outline-none focus:ring-1 focus:ring-[var(--accent-coral)]";
 
@@ -10,6 +18,11 @@ interface ExecutionTabProps {
   skillsEnabled: boolean;
   applicableSkillsK: number;
   skillsGlobalParticipation: boolean;
+  vlmCacheEnabled: boolean;
+  maxRunDurationSecs: number;
+  toolTimeoutsSecs: Record<string, number>;
+  mcpServers: McpServerWire[];
+  recordRuns: boolean;
   onMaxRepairAttemptsChange: (n: number) => void;
   onSupervisionDelayMsChange: (ms: number) => void;
   onEpisodicEnabledChange: (enabled: boolean) => void;
@@ -18,6 +31,11 @@ interface ExecutionTabProps {
   onSkillsEnabledChange: (enabled: boolean) => void;
   onApplicableSkillsKChange: (n: number) => void;
   onSkillsGlobalParticipationChange: (enabled: boolean) => void;
+  onVlmCacheEnabledChange: (enabled: boolean) => void;
+  onMaxRunDurationSecsChange: (secs: number) => void;
+  onToolTimeoutsSecsChange: (timeouts: Record<string, number>) => void;
+  onMcpServersChange: (servers: McpServerWire[]) => void;
+  onRecordRunsChange: (enabled: boolean) => void;
 }
 
 export function ExecutionTab({
@@ -29,6 +47,11 @@ export function ExecutionTab({
   skillsEnabled,
   applicableSkillsK,
   skillsGlobalParticipation,
+  vlmCacheEnabled,
+  maxRunDurationSecs,
+  toolTimeoutsSecs,
+  mcpServers,
+  recordRuns,
   onMaxRepairAttemptsChange,
   onSupervisionDelayMsChange,
   onEpisodicEnabledChange,
@@ -37,6 +60,11 @@ export function ExecutionTab({
   onSkillsEnabledChange,
   onApplicableSkillsKChange,
   onSkillsGlobalParticipationChange,
+  onVlmCacheEnabledChange,
+  onMaxRunDurationSecsChange,
+  onToolTimeoutsSecsChange,
+  onMcpServersChange,
+  onRecordRunsChange,
 }: ExecutionTabProps) {
   return (
     <div className="space-y-4 p-4">
@@ -95,6 +123,107 @@ export function ExecutionTab({
             How long to wait before capturing the per-step supervision screenshot, giving the UI time to settle (0-10000ms).
           </p>
         </div>
+
+        <div className="mt-3">
+          <label className="flex items-center gap-2 text-xs text-[var(--text-secondary)]">
+            <input
+              type="checkbox"
+              checked={vlmCacheEnabled}
+              onChange={(e) => onVlmCacheEnabledChange(e.target.checked)}
+              className="accent-[var(--accent-coral)]"
+            />
+            Cache vision model replies
+          </label>
+          <p className="ml-5 text-[10px] text-[var(--text-muted)]">
+            Reuse earlier answers for identical completion-check and
+            walkthrough-labelling screenshots. Those calls then run at
+            temperature 0.
+          </p>
+        </div>
+
+        <div className="mt-3">
+          <label className="flex items-center gap-2 text-xs text-[var(--text-secondary)]">
+            <input
+              type="checkbox"
+              checked={recordRuns}
+              onChange={(e) => onRecordRunsChange(e.target.checked)}
+              className="accent-[var(--accent-coral)]"
+            />
+            Record runs for replay
+          </label>
+          <p className="ml-5 text-[10px] text-[var(--text-muted)]">
+            Save every model call and MCP tool call of agent and skill runs
+            to the run's trace folder. Needs run traces to be stored.
+          </p>
+        </div>
+      </div>
+
+      <div>
+        <h3 className="mb-2 text-xs font-semibold uppercase tracking-wider text-[var(--text-muted)]">
+          Agent Limits
+        </h3>
+        <p className="mb-2 text-[10px] text-[var(--text-muted)]">
+          Stops agent runs and tool calls that take too long.
+        </p>
+
+        <div className="mb-3">
+          <label className="mb-1 block text-xs text-[var(--text-secondary)]">
+            Run time limit (seconds)
+          </label>
+          <input
+            type="number"
+            min={0}
+            max={86400}
+            step={60}
+            value={maxRunDurationSecs}
+            onChange={(e) => {
+              const clamped = Math.max(0, Math.min(86400, Math.floor(Number(e.target.value) || 0)));
+              onMaxRunDurationSecsChange(clamped);
+            }}
+            className={inputClass}
+          />
+          <p className="mt-1 text-[10px] text-[var(--text-muted)]">
+            The run stops once it has been going this long. 0 = no limit. Default: 900.
+          </p>
+        </div>
+
+        <div className="mb-3">
+          <label className="mb-1 block text-xs text-[var(--text-secondary)]">
+            Tool timeouts (seconds)
+          </label>
+          <textarea
+            key={formatToolTimeouts(toolTimeoutsSecs)}
+            defaultValue={formatToolTimeouts(toolTimeoutsSecs)}
+            onBlur={(e) => onToolTimeoutsSecsChange(parseToolTimeouts(e.target.value))}
+            placeholder="cdp_wait_for_page_change = 20"
+            rows={3}
+            className={`${inputClass} font-mono`}
+          />
+          <p className="mt-1 text-[10px] text-[var(--text-muted)]">
+            One <code>tool = seconds</code> per line. Tools not listed keep
+            their default timeout.
+          </p>
+        </div>
+
+        <div className="mb-3">
+          <label className="mb-1 block text-xs text-[var(--text-secondary)]">
+            Extra MCP servers
+          </label>
+          <textarea
+            key={formatMcpServers(mcpServers)}
+            defaultValue={formatMcpServers(mcpServers)}
+            onBlur={(e) => onMcpServersChange(parseMcpServers(e.target.value))}
+            placeholder="fs [required] = npx -y @modelcontextprotocol/server-filesystem /tmp"
+            rows={3}
+            className={`${inputClass} font-mono`}
+          />
+          <p className="mt-1 text-[10px] text-[var(--text-muted)]">
+            One <code>name = command args</code> or <code>name = URL</code> per
+            line. Their tools reach the agent as <code>name__tool</code>. Add
+            <code>[required]</code> after the name to refuse runs without the
+            server, <code>[restart]</code> to restart it if it crashes.
+          </p>
+        </div>
       </div>
 
       <div>
@@ -1,5 +1,5 @@
 import { useCallback, useEffect, useRef, useState } from "react";
-import type { ChromeProfile } from "../bindings";
+import type { ChromeProfile, LlmProvider, McpServerWire, ToolMode } from "../bindings";
 import { commands } from "../bindings";
 import type { EndpointConfig } from "../store/useAppStore";
 import type { PermissionLevel, ToolPermissions } from "../store/state";
@@ -74,6 +74,11 @@ interface SettingsModalProps {
   skillsEnabled: boolean;
   applicableSkillsK: number;
   skillsGlobalParticipation: boolean;
+  vlmCacheEnabled: boolean;
+  maxRunDurationSecs: number;
+  toolTimeoutsSecs: Record<string, number>;
+  mcpServers: McpServerWire[];
+  recordRuns: boolean;
   onClose: () => void;
   onSupervisorConfigChange: (config: EndpointConfig) => void;
   onAgentConfigChange: (config: EndpointConfig) => void;
@@ -92,6 +97,11 @@ interface SettingsModalProps {
   onSkillsEnabledChange: (enabled: boolean) => void;
   onApplicableSkillsKChange: (n: number) => void;
   onSkillsGlobalParticipationChange: (enabled: boolean) => void;
+  onVlmCacheEnabledChange: (enabled: boolean) => void;
+  onMaxRunDurationSecsChange: (secs: number) => void;
+  onToolTimeoutsSecsChange: (timeouts: Record<string, number>) => void;
+  onMcpServersChange: (servers: McpServerWire[]) => void;
+  onRecordRunsChange: (enabled: boolean) => void;
 }
 
 const inputClass =
@@ -303,6 +313,17 @@ function EndpointFields({
 }) {
   return (
     <div className="space-y-2">
+      <div>
+        <label className="mb-1 block text-xs text-[var(--text-secondary)]">API Format</label>
+        <select
+          value={config.provider ?? "open_ai_compatible"}
+          onChange={(e) => onChange({ ...config, provider: e.target.value as LlmProvider })}
+          className={inputClass}
+        >
+          <option value="open_ai_compatible">OpenAI-compatible</option>
+          <option value="anthropic">Anthropic Messages</option>
+        </select>
+      </div>
       <div>
         <label className="mb-1 block text-xs text-[var(--text-secondary)]">Base URL</label>
         <input
@@ -313,6 +334,19 @@ function EndpointFields({
         />
       </div>
       <ModelDropdown config={config} onChange={onChange} />
+      {(config.provider ?? "open_ai_compatible") === "open_ai_compatible" && (
+        <div>
+          <label className="mb-1 block text-xs text-[var(--text-secondary)]">Agent Actions</label>
+          <select
+            value={config.toolMode ?? "native_tools"}
+            onChange={(e) => onChange({ ...config, toolMode: e.target.value as ToolMode })}
+            className={inputClass}
+          >
+            <option value="native_tools">Native tool calls</option>
+            <option value="json_schema">JSON schema (constrained decoding)</option>
+          </select>
+        </div>
+      )}
       <div>
         <label className="mb-1 block text-xs text-[var(--text-secondary)]">API Key</label>
         <input
@@ -474,6 +508,11 @@ export function SettingsModal({
   skillsEnabled,
   applicableSkillsK,
   skillsGlobalParticipation,
+  vlmCacheEnabled,
+  maxRunDurationSecs,
+  toolTimeoutsSecs,
+  mcpServers,
+  recordRuns,
   onClose,
   onSupervisorConfigChange,
   onAgentConfigChange,
@@ -492,6 +531,11 @@ export function SettingsModal({
   onSkillsEnabledChange,
   onApplicableSkillsKChange,
   onSkillsGlobalParticipationChange,
+  onVlmCacheEnabledChange,
+  onMaxRunDurationSecsChange,
+  onToolTimeoutsSecsChange,
+  onMcpServersChange,
+  onRecordRunsChange,
 }: SettingsModalProps) {
   const [tab, setTab] = useState<SettingsTab>("general");
 
@@ -542,6 +586,11 @@ export function SettingsModal({
             skillsEnabled={skillsEnabled}
             applicableSkillsK={applicableSkillsK}
             skillsGlobalParticipation={skillsGlobalParticipation}
+            vlmCacheEnabled={vlmCacheEnabled}
+            maxRunDurationSecs={maxRunDurationSecs}
+            toolTimeoutsSecs={toolTimeoutsSecs}
+            mcpServers={mcpServers}
+            recordRuns={recordRuns}
             onMaxRepairAttemptsChange={onMaxRepairAttemptsChange}
             onSupervisionDelayMsChange={onSupervisionDelayMsChange}
             onEpisodicEnabledChange={onEpisodicEnabledChange}
@@ -554,6 +603,11 @@ export function SettingsModal({
             onSkillsGlobalParticipationChange={
               onSkillsGlobalParticipationChange
             }
+            onVlmCacheEnabledChange={onVlmCacheEnabledChange}
+            onMaxRunDurationSecsChange={onMaxRunDurationSecsChange}
+            onToolTimeoutsSecsChange={onToolTimeoutsSecsChange}
+            onMcpServersChange={onMcpServersChange}
+            onRecordRunsChange={onRecordRunsChange}
           />
         ) : tab === "privacy" ? (
           <PrivacyTab
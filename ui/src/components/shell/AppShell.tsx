@@ -200,6 +200,11 @@ function useSettingsModalProps() {
       skillsEnabled: s.skillsEnabled,
       applicableSkillsK: s.applicableSkillsK,
       skillsGlobalParticipation: s.skillsGlobalParticipation,
+      vlmCacheEnabled: s.vlmCacheEnabled,
+      maxRunDurationSecs: s.maxRunDurationSecs,
+      toolTimeoutsSecs: s.toolTimeoutsSecs,
+      mcpServers: s.mcpServers,
+      recordRuns: s.recordRuns,
       onSupervisorConfigChange: s.setSupervisorConfig,
       onAgentConfigChange: s.setAgentConfig,
       onFastConfigChange: s.setFastConfig,
@@ -217,6 +222,11 @@ function useSettingsModalProps() {
       onSkillsEnabledChange: s.setSkillsEnabled,
       onApplicableSkillsKChange: s.setApplicableSkillsK,
       onSkillsGlobalParticipationChange: s.setSkillsGlobalParticipation,
+      onVlmCacheEnabledChange: s.setVlmCacheEnabled,
+      onMaxRunDurationSecsChange: s.setMaxRunDurationSecs,
+      onToolTimeoutsSecsChange: s.setToolTimeoutsSecs,
+      onMcpServersChange: s.setMcpServers,
+      onRecordRunsChange: s.setRecordRuns,
     })),
   );
 }
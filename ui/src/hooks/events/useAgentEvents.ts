@@ -1,6 +1,12 @@
 import { useEffect } from "react";
 import { listen } from "@tauri-apps/api/event";
-import type { BoundaryKind, TaskState, WorldModelDiff } from "../../bindings";
+import type {
+  BoundaryKind,
+  PlanDecision,
+  PlannedSubgoal,
+  TaskState,
+  WorldModelDiff,
+} from "../../bindings";
 import { useStore } from "../../store/useAppStore";
 import type { AgentStatus } from "../../store/slices/agentSlice";
 import type { AgentPhase, TerminalFrame } from "../../store/slices/assistantSlice";
@@ -45,6 +51,7 @@ interface AgentStoppedPayload extends RunScoped {
   reason: string;
   steps_executed?: number;
   consecutive_errors?: number;
+  max_duration_secs?: number;
 }
 
 interface StepFailedPayload extends RunScoped {
@@ -60,6 +67,51 @@ interface ApprovalRequiredPayload extends RunScoped {
   description: string;
 }
 
+interface UserQuestionPayload extends RunScoped {
+  step_index: number;
+  question: string;
+  choices: string[];
+}
+
+interface UserQuestionAnsweredPayload extends RunScoped {
+  step_index: number;
+  outcome: "answered" | "timed_out" | "no_human_available" | "interrupted";
+  answer: string | null;
+}
+
+interface PlanProposedPayload extends RunScoped {
+  step_index: number;
+  subgoals: PlannedSubgoal[];
+  revision: boolean;
+}
+
+interface PlanReviewedPayload extends RunScoped {
+  step_index: number;
+  decision: PlanDecision;
+  revision: boolean;
+}
+
+interface LlmDeltaPayload extends RunScoped {
+  step_index: number;
+  kind: "text" | "reasoning";
+  text: string;
+}
+
+interface SubAgentStartedPayload extends RunScoped {
+  child_run_id: string;
+  step_index: number;
+  subgoal: string;
+  tools: string[];
+}
+
+interface SubAgentFinishedPayload extends RunScoped {
+  child_run_id: string;
+  step_index: number;
+  completed: boolean;
+  summary: string | null;
+  steps_executed: number;
+}
+
 interface CompletionDisagreementPayload extends RunScoped {
   screenshot_b64: string;
   vlm_reasoning: string;
@@ -85,12 +137,35 @@ interface BoundaryRecordWrittenPayload extends RunScoped {
   milestone_text: string | null;
 }
 
+interface McpProgressPayload extends RunScoped {
+  tool_name: string | null;
+  progress: number;
+  total: number | null;
+  message: string | null;
+}
+
+interface McpLogPayload extends RunScoped {
+  level: string;
+  logger: string | null;
+  message: string;
+}
+
+interface ToolCallCancelledPayload extends RunScoped {
+  step_index: number;
+  tool_name: string;
+  reason: string;
+}
+
 /**
  * Subscribe to agent backend events:
  * agent://started, agent://step, agent://complete,
  * agent://completion_disagreement, agent://stopped, agent://error,
- * agent://warning, agent://approval_required, agent://cdp_connected,
- * agent://step_failed, agent://sub_action.
+ * agent://warning, agent://approval_required, agent://user_question,
+ * agent://user_question_answered, agent://plan_proposed,
+ * agent://plan_reviewed, agent://sub_agent_started,
+ * agent://sub_agent_finished, agent://llm_delta, agent://cdp_connected,
+ * agent://step_failed, agent://sub_action, agent://mcp_progress,
+ * agent://mcp_log, agent://tool_call_cancelled.
  *
  * All run-scoped events carry a `run_id` generation ID. Events whose
  * run_id does not match the active run are silently dropped to prevent
@@ -139,6 +214,7 @@ export function useAgentEvents() {
     sub(
       listen<AgentStepPayload>("agent://step", (e) => {
         if (isStale(e.payload.run_id)) return;
+        useStore.getState().clearLlmStream();
         const phase = currentTracePhase(e.payload.run_id);
         useStore.getState().addAgentStep({
           summary: e.payload.summary,
@@ -162,6 +238,15 @@ export function useAgentEvents() {
       }),
     );
 
+    sub(
+      listen<LlmDeltaPayload>("agent://llm_delta", (e) => {
+        if (isStale(e.payload.run_id)) return;
+        useStore
+          .getState()
+          .appendLlmDelta(e.payload.step_index, e.payload.kind, e.payload.text);
+      }),
+    );
+
     sub(
       listen<ApprovalRequiredPayload>("agent://approval_required", (e) => {
         if (isStale(e.payload.run_id)) return;
@@ -180,6 +265,84 @@ export function useAgentEvents() {
       }),
     );
 
+    sub(
+      listen<UserQuestionPayload>("agent://user_question", (e) => {
+        if (isStale(e.payload.run_id)) return;
+        const choices = e.payload.choices.length
+          ? ` [${e.payload.choices.join(" / ")}]`
+          : "";
+        useStore
+          .getState()
+          .pushLog(`Agent asks: ${e.payload.question}${choices}`);
+      }),
+    );
+
+    sub(
+      listen<UserQuestionAnsweredPayload>(
+        "agent://user_question_answered",
+        (e) => {
+          if (isStale(e.payload.run_id)) return;
+          const text =
+            e.payload.outcome === "answered"
+              ? `Answered: ${e.payload.answer ?? ""}`
+              : e.payload.outcome === "timed_out"
+                ? "Question timed out — agent continues without an answer"
+                : e.payload.outcome === "interrupted"
+                  ? "Run paused before the question was answered"
+                  : "No operator available — agent continues without an answer";
+          useStore.getState().pushLog(text);
+        },
+      ),
+    );
+
+    sub(
+      listen<PlanProposedPayload>("agent://plan_proposed", (e) => {
+        if (isStale(e.payload.run_id)) return;
+        const label = e.payload.revision ? "revised plan" : "plan";
+        const steps = e.payload.subgoals
+          .map((s, i) => `${i + 1}. ${s.text}`)
+          .join("; ");
+        useStore
+          .getState()
+          .pushLog(`Agent proposes a ${label} for approval: ${steps}`);
+      }),
+    );
+
+    sub(
+      listen<PlanReviewedPayload>("agent://plan_reviewed", (e) => {
+        if (isStale(e.payload.run_id)) return;
+        const text =
+          e.payload.decision.decision === "approve"
+            ? "Plan approved"
+            : `Plan rejected: ${e.payload.decision.reason}`;
+        useStore.getState().pushLog(text);
+      }),
+    );
+
+    sub(
+      listen<SubAgentStartedPayload>("agent://sub_agent_started", (e) => {
+        if (isStale(e.payload.run_id)) return;
+        useStore
+          .getState()
+          .pushLog(
+            `Delegating to sub-agent: ${e.payload.subgoal} (tools: ${e.payload.tools.join(", ")})`,
+          );
+      }),
+    );
+
+    sub(
+      listen<SubAgentFinishedPayload>("agent://sub_agent_finished", (e) => {
+        if (isStale(e.payload.run_id)) return;
+        const outcome = e.payload.completed ? "finished" : "stopped";
+        const summary = e.payload.summary ? `: ${e.payload.summary}` : "";
+        useStore
+          .getState()
+          .pushLog(
+            `Sub-agent ${outcome} after ${e.payload.steps_executed} steps${summary}`,
+          );
+      }),
+    );
+
     sub(
       listen<CdpConnectedPayload>("agent://cdp_connected", (e) => {
         if (isStale(e.payload.run_id)) return;
@@ -358,7 +521,11 @@ export function useAgentEvents() {
                   ? "user cancelled after VLM disagreement"
                   : e.payload.reason === "loop_detected"
                     ? "the same tool call kept failing — stopped to avoid looping"
-                    : e.payload.reason;
+                    : e.payload.reason === "deadline_exceeded"
+                      ? `run deadline of ${e.payload.max_duration_secs}s exceeded`
+                      : e.payload.reason === "suspended"
+                        ? "suspended — resume to continue where it left off"
+                        : e.payload.reason;
         const frameKind: TerminalFrame["kind"] =
           e.payload.reason === "user_cancelled_disagreement"
             ? "disagreement_cancelled"
@@ -405,6 +572,39 @@ export function useAgentEvents() {
       }),
     );
 
+    sub(
+      listen<McpProgressPayload>("agent://mcp_progress", (e) => {
+        if (isStale(e.payload.run_id)) return;
+        const { tool_name, progress, total, message } = e.payload;
+        const amount = total == null ? `${progress}` : `${progress}/${total}`;
+        useStore
+          .getState()
+          .pushLog(
+            `MCP progress${tool_name ? ` (${tool_name})` : ""}: ${amount}${message ? ` ${message}` : ""}`,
+          );
+      }),
+    );
+
+    sub(
+      listen<McpLogPayload>("agent://mcp_log", (e) => {
+        if (isStale(e.payload.run_id)) return;
+        const { level, logger, message } = e.payload;
+        useStore
+          .getState()
+          .pushLog(`MCP [${level}]${logger ? ` ${logger}:` : ""} ${message}`);
+      }),
+    );
+
+    sub(
+      listen<ToolCallCancelledPayload>("agent://tool_call_cancelled", (e) => {
+        if (isStale(e.payload.run_id)) return;
+        const { step_index, tool_name, reason } = e.payload;
+        useStore
+          .getState()
+          .pushLog(`Cancelled ${tool_name} (step ${step_index}): ${reason}`);
+      }),
+    );
+
     sub(
       listen<AgentErrorPayload>("agent://error", (e) => {
         if (isStale(e.payload.run_id)) return;
@@ -34,6 +34,11 @@ function makeSettings(overrides: Partial<PersistedSettings> = {}): PersistedSett
         skillsEnabled: true,
         applicableSkillsK: 2,
         skillsGlobalParticipation: false,
+        vlmCacheEnabled: false,
+        maxRunDurationSecs: 900,
+        toolTimeoutsSecs: {},
+        mcpServers: [],
+        recordRuns: false,
         ...overrides,
     };
 }
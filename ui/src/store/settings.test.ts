@@ -5,7 +5,11 @@ import {
   DEFAULT_SKILLS_GLOBAL_PARTICIPATION,
   DEFAULT_STORE_TRACES,
   DEFAULT_TRACE_RETENTION_DAYS,
+  formatMcpServers,
+  formatToolTimeouts,
   normalizeToolPermissions,
+  parseMcpServers,
+  parseToolTimeouts,
   toEndpoint,
 } from "./settings";
 
@@ -20,9 +24,21 @@ describe("toEndpoint", () => {
       base_url: "http://localhost:1234/v1",
       api_key: "sk-test",
       model: "gpt-4",
+      provider: "open_ai_compatible",
+      tool_mode: "native_tools",
     });
   });
 
+  it("passes the provider through", () => {
+    const result = toEndpoint({
+      baseUrl: "https://api.anthropic.com/v1",
+      apiKey: "sk-ant",
+      model: "claude-sonnet-4-5",
+      provider: "anthropic",
+    });
+    expect(result.provider).toBe("anthropic");
+  });
+
   it("converts empty apiKey to null", () => {
     const result = toEndpoint({
       baseUrl: "http://localhost:1234/v1",
@@ -88,3 +104,55 @@ describe("procedural-skill defaults", () => {
     expect(DEFAULT_SKILLS_GLOBAL_PARTICIPATION).toBe(false);
   });
 });
+
+describe("tool timeout overrides", () => {
+  it("parses tool = seconds lines and drops malformed ones", () => {
+    const parsed = parseToolTimeouts(
+      "cdp_wait_for_page_change = 20\n\nclick=5\nwait = 0\nbroken\nfind_text = soon",
+    );
+    expect(parsed).toEqual({ cdp_wait_for_page_change: 20, click: 5 });
+  });
+
+  it("round-trips through the text form", () => {
+    const timeouts = { cdp_wait_for_page_change: 20, click: 5 };
+    expect(parseToolTimeouts(formatToolTimeouts(timeouts))).toEqual(timeouts);
+  });
+});
+
+describe("MCP server settings", () => {
+  it("parses a command with arguments, a URL and flags", () => {
+    const parsed = parseMcpServers(
+      'fs [required] = npx -y @modelcontextprotocol/server-filesystem "/Users/ada/My Files"\n' +
+        "db [restart, required] = http://127.0.0.1:8931/mcp\n\nbroken line\nbad name = x",
+    );
+    expect(parsed).toEqual([
+      {
+        name: "fs",
+        target: "npx",
+        args: ["-y", "@modelcontextprotocol/server-filesystem", "/Users/ada/My Files"],
+        required: true,
+        restart_on_crash: false,
+      },
+      {
+        name: "db",
+        target: "http://127.0.0.1:8931/mcp",
+        args: [],
+        required: true,
+        restart_on_crash: true,
+      },
+    ]);
+  });
+
+  it("round-trips through the text form", () => {
+    const servers = [
+      {
+        name: "fs",
+        target: "npx",
+        args: ["-y", "server-filesystem", "/tmp/a b"],
+        required: false,
+        restart_on_crash: true,
+      },
+    ];
+    expect(parseMcpServers(formatMcpServers(servers))).toEqual(servers);
+  });
+});
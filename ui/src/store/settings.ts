@@ -1,4 +1,5 @@
 import { load } from "@tauri-apps/plugin-store";
+import type { McpServerWire, ResponseCacheSettings } from "../bindings";
 import type { EndpointConfig, ToolPermissions } from "./state";
 import {
   DEFAULT_ENDPOINT,
@@ -52,6 +53,28 @@ export interface PersistedSettings {
   applicableSkillsK: number;
   /** Spec 3 privacy opt-in for the global cross-workflow skills tier. */
   skillsGlobalParticipation: boolean;
+  /**
+   * Answer repeated completion-check and walkthrough-labelling VLM calls
+   * from the on-disk response cache.
+   */
+  vlmCacheEnabled: boolean;
+  /** Wall-clock limit for an agent run, in seconds. 0 = no limit. */
+  maxRunDurationSecs: number;
+  /**
+   * Per-tool MCP call timeouts in seconds, overriding the engine's
+   * defaults for the named tools only.
+   */
+  toolTimeoutsSecs: Record<string, number>;
+  /**
+   * Extra MCP servers started next to the desktop server for agent runs.
+   * Their tools reach the agent as `{name}__{tool}`.
+   */
+  mcpServers: McpServerWire[];
+  /**
+   * Record agent and skill runs to cassettes in the execution directory
+   * so they can be replayed later.
+   */
+  recordRuns: boolean;
 }
 
 export const DEFAULT_TRACE_RETENTION_DAYS = 30;
@@ -62,6 +85,11 @@ export const DEFAULT_EPISODIC_GLOBAL_PARTICIPATION = false;
 export const DEFAULT_SKILLS_ENABLED = true;
 export const DEFAULT_APPLICABLE_SKILLS_K = 2;
 export const DEFAULT_SKILLS_GLOBAL_PARTICIPATION = false;
+export const DEFAULT_VLM_CACHE_ENABLED = false;
+export const DEFAULT_MAX_RUN_DURATION_SECS = 900;
+export const DEFAULT_TOOL_TIMEOUTS_SECS: Record<string, number> = {};
+export const DEFAULT_MCP_SERVERS: McpServerWire[] = [];
+export const DEFAULT_RECORD_RUNS = false;
 
 const SETTINGS_DEFAULTS: PersistedSettings = {
   supervisorConfig: DEFAULT_ENDPOINT,
@@ -80,6 +108,11 @@ const SETTINGS_DEFAULTS: PersistedSettings = {
   skillsEnabled: DEFAULT_SKILLS_ENABLED,
   applicableSkillsK: DEFAULT_APPLICABLE_SKILLS_K,
   skillsGlobalParticipation: DEFAULT_SKILLS_GLOBAL_PARTICIPATION,
+  vlmCacheEnabled: DEFAULT_VLM_CACHE_ENABLED,
+  maxRunDurationSecs: DEFAULT_MAX_RUN_DURATION_SECS,
+  toolTimeoutsSecs: DEFAULT_TOOL_TIMEOUTS_SECS,
+  mcpServers: DEFAULT_MCP_SERVERS,
+  recordRuns: DEFAULT_RECORD_RUNS,
 };
 
 export async function loadSettings(): Promise<PersistedSettings> {
@@ -159,6 +192,12 @@ export async function loadSettings(): Promise<PersistedSettings> {
   const skillsGlobalParticipation = await store.get<boolean>(
     "skillsGlobalParticipation",
   );
+  const vlmCacheEnabled = await store.get<boolean>("vlmCacheEnabled");
+  const maxRunDurationSecs = await store.get<number>("maxRunDurationSecs");
+  const toolTimeoutsSecs =
+    await store.get<Record<string, number>>("toolTimeoutsSecs");
+  const mcpServers = await store.get<McpServerWire[]>("mcpServers");
+  const recordRuns = await store.get<boolean>("recordRuns");
 
   return {
     supervisorConfig,
@@ -184,6 +223,12 @@ export async function loadSettings(): Promise<PersistedSettings> {
     applicableSkillsK: applicableSkillsK ?? SETTINGS_DEFAULTS.applicableSkillsK,
     skillsGlobalParticipation:
       skillsGlobalParticipation ?? SETTINGS_DEFAULTS.skillsGlobalParticipation,
+    vlmCacheEnabled: vlmCacheEnabled ?? SETTINGS_DEFAULTS.vlmCacheEnabled,
+    maxRunDurationSecs:
+      maxRunDurationSecs ?? SETTINGS_DEFAULTS.maxRunDurationSecs,
+    toolTimeoutsSecs: toolTimeoutsSecs ?? SETTINGS_DEFAULTS.toolTimeoutsSecs,
+    mcpServers: mcpServers ?? SETTINGS_DEFAULTS.mcpServers,
+    recordRuns: recordRuns ?? SETTINGS_DEFAULTS.recordRuns,
   };
 }
 
@@ -201,5 +246,85 @@ export function toEndpoint(c: EndpointConfig) {
     base_url: c.baseUrl,
     model: c.model,
     api_key: c.apiKey || null,
+    provider: c.provider ?? "open_ai_compatible",
+    tool_mode: c.toolMode ?? "native_tools",
   };
 }
+
+/** Wire form of the VLM cache toggle: default cache settings, or off. */
+export function toVlmCache(enabled: boolean): ResponseCacheSettings | null {
+  return enabled ? {} : null;
+}
+
+/** One `tool = seconds` line per override, for the settings text box. */
+export function formatToolTimeouts(timeouts: Record<string, number>): string {
+  return Object.entries(timeouts)
+    .map(([tool, secs]) => `${tool} = ${secs}`)
+    .join("\n");
+}
+
+/**
+ * Parse `tool = seconds` lines. Blank or malformed lines and non-positive
+ * timeouts are dropped.
+ */
+export function parseToolTimeouts(text: string): Record<string, number> {
+  const timeouts: Record<string, number> = {};
+  for (const line of text.split("\n")) {
+    const [tool, secs, ...rest] = line.split("=").map((part) => part.trim());
+    const n = Number(secs);
+    if (tool && rest.length === 0 && Number.isInteger(n) && n > 0) {
+      timeouts[tool] = n;
+    }
+  }
+  return timeouts;
+}
+
+/**
+ * One `name [required, restart] = command args…` line per server, for the
+ * settings text box. Arguments containing spaces are double-quoted.
+ */
+export function formatMcpServers(servers: McpServerWire[]): string {
+  return servers
+    .map((server) => {
+      const flags = [
+        server.required ? "required" : null,
+        server.restart_on_crash ? "restart" : null,
+      ].filter((flag) => flag !== null);
+      const name = flags.length
+        ? `${server.name} [${flags.join(", ")}]`
+        : server.name;
+      const words = [server.target, ...(server.args ?? [])].map((word) =>
+        /\s/.test(word) ? `"${word}"` : word,
+      );
+      return `${name} = ${words.join(" ")}`;
+    })
+    .join("\n");
+}
+
+/**
+ * Parse `name [required, restart] = command args…` lines. The bracketed
+ * flags are optional. Blank or malformed lines are dropped.
+ */
+export function parseMcpServers(text: string): McpServerWire[] {
+  const servers: McpServerWire[] = [];
+  for (const line of text.split("\n")) {
+    const eq = line.indexOf("=");
+    if (eq < 0) continue;
+    const head = /^\s*([A-Za-z0-9_-]+)\s*(?:\[([^\]]*)\])?\s*$/.exec(
+      line.slice(0, eq),
+    );
+    const words = [...line.slice(eq + 1).matchAll(/"([^"]*)"|(\S+)/g)].map(
+      (m) => m[1] ?? m[2],
+    );
+    if (!head || words.length === 0) continue;
+    const flags = (head[2] ?? "").split(",").map((flag) => flag.trim());
+    servers.push({
+      name: head[1],
+      target: words[0],
+      args: words.slice(1),
+      required: flags.includes("required"),
+      restart_on_crash: flags.includes("restart"),
+    });
+  }
+  return servers;
+}
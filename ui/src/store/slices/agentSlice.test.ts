@@ -97,6 +97,34 @@ describe("agentSlice.startAgent", () => {
     expect(state.pendingApproval).toBeNull();
   });
 
+  it("sends the run time limit and tool timeouts from settings", async () => {
+    invokeMock.mockResolvedValueOnce(undefined);
+    useStore.setState({
+      maxRunDurationSecs: 600,
+      toolTimeoutsSecs: { cdp_wait_for_page_change: 20 },
+    });
+
+    await useStore.getState().startAgent("open the inbox");
+
+    const [command, args] = invokeMock.mock.calls[0];
+    expect(command).toBe("run_agent");
+    expect(args.request.max_duration_secs).toBe(600);
+    expect(args.request.tool_timeouts_secs).toEqual({
+      cdp_wait_for_page_change: 20,
+    });
+  });
+
+  it("asks for LLM and MCP recording when runs are recorded", async () => {
+    invokeMock.mockResolvedValueOnce(undefined);
+    useStore.setState({ recordRuns: true });
+
+    await useStore.getState().startAgent("open the inbox");
+
+    const [, args] = invokeMock.mock.calls[0];
+    expect(args.request.record_llm).toBe(true);
+    expect(args.request.record_mcp).toBe(true);
+  });
+
   it("does not overwrite an agentRunId installed by agent://started during invoke", async () => {
     // Simulate the backend emitting agent://started (which calls
     // setAgentRunId) *before* the invoke promise resolves — the listener
@@ -1,7 +1,7 @@
 import type { StateCreator } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
 import { commands, type Skill } from "../../bindings";
-import { toEndpoint } from "../settings";
+import { toEndpoint, toVlmCache } from "../settings";
 import type { PermissionRule, ToolPermissions } from "../state";
 import type { StoreState } from "./types";
 
@@ -13,6 +13,16 @@ export interface AgentStep {
   pageTransitioned: boolean;
 }
 
+/**
+ * The LLM turn currently streaming in via `agent://llm_delta`. Replaced
+ * when a delta for a later step arrives; cleared when the step lands.
+ */
+export interface LlmStream {
+  stepIndex: number;
+  text: string;
+  reasoning: string;
+}
+
 export type AgentStatus = "idle" | "running" | "complete" | "stopped" | "error";
 
 export interface PendingApproval {
@@ -163,6 +173,8 @@ export interface AgentSlice {
   consecutiveDestructiveCapHit: ConsecutiveDestructiveCapHit | null;
   /** Generation ID for the active run — used to reject stale events. */
   agentRunId: string | null;
+  /** Live text of the turn being streamed, when `stream_llm` is on. */
+  llmStream: LlmStream | null;
   /** Epoch ms when `startAgent` flipped the slice into the running state.
    *  Used by `LiveRuntimeCard` to compute the Elapsed metric. Cleared on
    *  the next `startAgent` (or `clearConversationFlow`) — never on terminal
@@ -259,6 +271,12 @@ export interface AgentSlice {
   setAgentStatus: (status: AgentStatus) => void;
   setAgentError: (error: string | null) => void;
   setAgentRunId: (runId: string) => void;
+  appendLlmDelta: (
+    stepIndex: number,
+    kind: "text" | "reasoning",
+    text: string,
+  ) => void;
+  clearLlmStream: () => void;
   resetAgent: () => void;
   addAmbiguityResolution: (resolution: AmbiguityResolution) => void;
   openAmbiguityModal: (id: string) => void;
@@ -279,6 +297,7 @@ export const createAgentSlice: StateCreator<StoreState, [], [], AgentSlice> = (
   completionDisagreement: null,
   consecutiveDestructiveCapHit: null,
   agentRunId: null,
+  llmStream: null,
   ambiguityResolutions: [],
   activeAmbiguityId: null,
   agentRunCollapsed: {},
@@ -304,6 +323,11 @@ export const createAgentSlice: StateCreator<StoreState, [], [], AgentSlice> = (
       skillsEnabled,
       applicableSkillsK,
       skillsGlobalParticipation,
+      vlmCacheEnabled,
+      maxRunDurationSecs,
+      toolTimeoutsSecs,
+      mcpServers,
+      recordRuns,
       pushAssistantMessage,
     } = priorState;
     // If a run is already active, do not touch run-scoped state: the
@@ -361,6 +385,7 @@ export const createAgentSlice: StateCreator<StoreState, [], [], AgentSlice> = (
         // and any late in-flight events from the prior run (which
         // carry a different run_id) get rejected by `isStaleRunId`.
         agentRunId: runId,
+        llmStream: null,
         // D24 — both elapsed fields zero together on every fresh
         // start. The "cleared together only on the next start" rule
         // is achieved by writing both fields here; terminal events
@@ -395,6 +420,12 @@ export const createAgentSlice: StateCreator<StoreState, [], [], AgentSlice> = (
           skills_enabled: skillsEnabled,
           applicable_skills_k: applicableSkillsK,
           skills_global_participation: skillsGlobalParticipation,
+          vlm_cache: toVlmCache(vlmCacheEnabled),
+          max_duration_secs: maxRunDurationSecs,
+          tool_timeouts_secs: toolTimeoutsSecs,
+          mcp_servers: mcpServers,
+          record_llm: recordRuns,
+          record_mcp: recordRuns,
         },
       });
     } catch (err) {
@@ -675,6 +706,22 @@ export const createAgentSlice: StateCreator<StoreState, [], [], AgentSlice> = (
 
   setAgentRunId: (runId) => set({ agentRunId: runId }),
 
+  appendLlmDelta: (stepIndex, kind, text) =>
+    set((s) => {
+      const current =
+        s.llmStream && s.llmStream.stepIndex === stepIndex
+          ? s.llmStream
+          : { stepIndex, text: "", reasoning: "" };
+      return {
+        llmStream:
+          kind === "text"
+            ? { ...current, text: current.text + text }
+            : { ...current, reasoning: current.reasoning + text },
+      };
+    }),
+
+  clearLlmStream: () => set({ llmStream: null }),
+
   resetAgent: () =>
     set({
       agentStatus: "idle",
@@ -686,6 +733,7 @@ export const createAgentSlice: StateCreator<StoreState, [], [], AgentSlice> = (
       completionDisagreement: null,
       consecutiveDestructiveCapHit: null,
       agentRunId: null,
+      llmStream: null,
       agentRunCollapsed: {},
       agentRunStartedAt: null,
       agentRunFinishedAt: null,
@@ -97,6 +97,7 @@ export const createExecutionSlice: StateCreator<StoreState, [], [], ExecutionSli
       executionMode,
       supervisionDelayMs,
       storeTraces,
+      recordRuns,
       pushLog,
     } = get();
 
@@ -116,6 +117,7 @@ export const createExecutionSlice: StateCreator<StoreState, [], [], ExecutionSli
       execution_mode: executionMode,
       supervision_delay_ms: supervisionDelayMs,
       store_traces: storeTraces,
+      record_mcp: recordRuns,
     };
     const result = await commands.runSkill(request);
     if (result.status === "error") {
@@ -143,6 +145,7 @@ export const createExecutionSlice: StateCreator<StoreState, [], [], ExecutionSli
       executionMode,
       supervisionDelayMs,
       storeTraces,
+      recordRuns,
       pushLog,
     } = get();
     const request: RunSkillRequest = {
@@ -157,6 +160,7 @@ export const createExecutionSlice: StateCreator<StoreState, [], [], ExecutionSli
       execution_mode: executionMode,
       supervision_delay_ms: supervisionDelayMs,
       store_traces: storeTraces,
+      record_mcp: recordRuns,
     };
     const result = await commands.runSkill(request);
     if (result.status === "error") {
@@ -176,6 +180,7 @@ export const createExecutionSlice: StateCreator<StoreState, [], [], ExecutionSli
       executionMode,
       supervisionDelayMs,
       storeTraces,
+      recordRuns,
       pushLog,
     } = get();
     const request: ResumeSkillFromFailureRequest = {
@@ -190,6 +195,7 @@ export const createExecutionSlice: StateCreator<StoreState, [], [], ExecutionSli
       execution_mode: executionMode,
       supervision_delay_ms: supervisionDelayMs,
       store_traces: storeTraces,
+      record_mcp: recordRuns,
       from_section_id: fromSectionId,
     };
     const result = await commands.resumeSkillFromFailure(request);
@@ -1,4 +1,5 @@
 import type { StateCreator } from "zustand";
+import type { McpServerWire } from "../../bindings";
 import type { EndpointConfig, PermissionLevel, ToolPermissions } from "../state";
 import { DEFAULT_ENDPOINT, DEFAULT_TOOL_PERMISSIONS, DEFAULT_FAST_ENABLED } from "../state";
 import { formatModelStatus, verifyConfiguredModels } from "../modelAvailability";
@@ -6,11 +7,16 @@ import {
   DEFAULT_APPLICABLE_SKILLS_K,
   DEFAULT_EPISODIC_ENABLED,
   DEFAULT_EPISODIC_GLOBAL_PARTICIPATION,
+  DEFAULT_MAX_RUN_DURATION_SECS,
+  DEFAULT_MCP_SERVERS,
+  DEFAULT_RECORD_RUNS,
   DEFAULT_RETRIEVED_EPISODES_K,
   DEFAULT_SKILLS_ENABLED,
   DEFAULT_SKILLS_GLOBAL_PARTICIPATION,
   DEFAULT_STORE_TRACES,
+  DEFAULT_TOOL_TIMEOUTS_SECS,
   DEFAULT_TRACE_RETENTION_DAYS,
+  DEFAULT_VLM_CACHE_ENABLED,
   loadSettings,
   saveSetting,
 } from "../settings";
@@ -34,6 +40,11 @@ export interface SettingsSlice {
   skillsEnabled: boolean;
   applicableSkillsK: number;
   skillsGlobalParticipation: boolean;
+  vlmCacheEnabled: boolean;
+  maxRunDurationSecs: number;
+  toolTimeoutsSecs: Record<string, number>;
+  mcpServers: McpServerWire[];
+  recordRuns: boolean;
   _settingsLoaded: boolean;
 
   loadSettingsFromDisk: () => void;
@@ -54,6 +65,11 @@ export interface SettingsSlice {
   setSkillsEnabled: (enabled: boolean) => void;
   setApplicableSkillsK: (n: number) => void;
   setSkillsGlobalParticipation: (enabled: boolean) => void;
+  setVlmCacheEnabled: (enabled: boolean) => void;
+  setMaxRunDurationSecs: (secs: number) => void;
+  setToolTimeoutsSecs: (timeouts: Record<string, number>) => void;
+  setMcpServers: (servers: McpServerWire[]) => void;
+  setRecordRuns: (enabled: boolean) => void;
 }
 
 function persistSetting<K extends keyof PersistedSettings>(
@@ -90,6 +106,11 @@ export const createSettingsSlice: StateCreator<StoreState, [], [], SettingsSlice
   skillsEnabled: DEFAULT_SKILLS_ENABLED,
   applicableSkillsK: DEFAULT_APPLICABLE_SKILLS_K,
   skillsGlobalParticipation: DEFAULT_SKILLS_GLOBAL_PARTICIPATION,
+  vlmCacheEnabled: DEFAULT_VLM_CACHE_ENABLED,
+  maxRunDurationSecs: DEFAULT_MAX_RUN_DURATION_SECS,
+  toolTimeoutsSecs: DEFAULT_TOOL_TIMEOUTS_SECS,
+  mcpServers: DEFAULT_MCP_SERVERS,
+  recordRuns: DEFAULT_RECORD_RUNS,
   _settingsLoaded: false,
 
   loadSettingsFromDisk: () => {
@@ -129,6 +150,16 @@ export const createSettingsSlice: StateCreator<StoreState, [], [], SettingsSlice
             DEFAULT_APPLICABLE_SKILLS_K,
           ),
           skillsGlobalParticipation: s.skillsGlobalParticipation,
+          vlmCacheEnabled: s.vlmCacheEnabled,
+          maxRunDurationSecs: clampInt(
+            s.maxRunDurationSecs,
+            0,
+            86400,
+            DEFAULT_MAX_RUN_DURATION_SECS,
+          ),
+          toolTimeoutsSecs: s.toolTimeoutsSecs,
+          mcpServers: s.mcpServers,
+          recordRuns: s.recordRuns,
         });
         verifyConfiguredModels(s)
           .then((results) => {
@@ -181,4 +212,16 @@ export const createSettingsSlice: StateCreator<StoreState, [], [], SettingsSlice
     ),
   setSkillsGlobalParticipation: (enabled) =>
     persistSetting("skillsGlobalParticipation", enabled, set),
+  setVlmCacheEnabled: (enabled) =>
+    persistSetting("vlmCacheEnabled", enabled, set),
+  setMaxRunDurationSecs: (secs) =>
+    persistSetting(
+      "maxRunDurationSecs",
+      clampInt(secs, 0, 86400, DEFAULT_MAX_RUN_DURATION_SECS),
+      set,
+    ),
+  setToolTimeoutsSecs: (timeouts) =>
+    persistSetting("toolTimeoutsSecs", timeouts, set),
+  setMcpServers: (servers) => persistSetting("mcpServers", servers, set),
+  setRecordRuns: (enabled) => persistSetting("recordRuns", enabled, set),
 });
@@ -3,7 +3,7 @@ import { commands } from "../../bindings";
 import type { CdpAppConfig, WalkthroughAction, WalkthroughAnnotations } from "../../bindings";
 import type { CdpSetupProgress } from "../../components/CdpAppSelectModal";
 import { errorMessage } from "../../utils/commandError";
-import { toEndpoint } from "../settings";
+import { toEndpoint, toVlmCache } from "../settings";
 import { WebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { currentMonitor } from "@tauri-apps/api/window";
 import type { StoreState } from "./types";
@@ -263,11 +263,15 @@ export const createWalkthroughSlice: StateCreator<StoreState, [], [], Walkthroug
   },
 
   stopWalkthrough: async () => {
-    const { pushLog, supervisorConfig, hoverDwellThreshold } = get();
+    const { pushLog, supervisorConfig, hoverDwellThreshold, vlmCacheEnabled } = get();
     const supervisor = supervisorConfig.baseUrl && supervisorConfig.model
       ? toEndpoint(supervisorConfig)
       : null;
-    const result = await commands.stopWalkthrough(supervisor, hoverDwellThreshold);
+    const result = await commands.stopWalkthrough(
+      supervisor,
+      hoverDwellThreshold,
+      toVlmCache(vlmCacheEnabled),
+    );
     if (result.status === "error") {
       pushLog(`Walkthrough stop failed: ${errorMessage(result.error)}`);
     }
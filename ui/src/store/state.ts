@@ -1,9 +1,15 @@
+import type { LlmProvider, ToolMode } from "../bindings";
+
 export type DetailTab = "setup" | "trace" | "runs";
 
 export interface EndpointConfig {
   baseUrl: string;
   apiKey: string;
   model: string;
+  /** Wire protocol; absent in settings saved before it existed. */
+  provider?: LlmProvider;
+  /** Native tool calls or schema-constrained JSON turns. */
+  toolMode?: ToolMode;
 }
 
 export const DEFAULT_ENDPOINT: EndpointConfig = {